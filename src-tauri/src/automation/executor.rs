@@ -1,18 +1,21 @@
 use crate::automation::{
-    file_ops, image_match, keyboard, mouse, ocr_match, power, screenshot, start_menu,
-    system_settings, uia, window, process,
+    feature_match, file_journal, file_ops, image_match, image_ops, keyboard, mouse, ocr_layout,
+    ocr_match, ocr_models, pixel_color, power, process, redaction, screen_change, screenshot,
+    start_menu, system_settings, text_similarity, uia, visual_assert, window,
 };
 use crate::error::{CommandFlowError, CommandResult};
 use crate::secure_settings::{
-    load_input_recording_presets, InputRecordingAction, InputRecordingPreset, RecordedCursorPoint,
+    load_input_recording_presets, load_secret, InputRecordingAction, InputRecordingPreset,
+    RecordedCursorPoint,
 };
+use crate::workflow::capability::{self, Capability};
 use crate::workflow::graph::WorkflowGraph;
 use crate::workflow::node::{NodeKind, WorkflowNode};
 use arboard::{Clipboard, ImageData};
 use base64::engine::general_purpose;
 use base64::Engine as _;
 use chrono::{Datelike, Local, SecondsFormat, Timelike, Utc};
-use image::{ImageBuffer, Rgba, RgbaImage};
+use image::{DynamicImage, GrayImage, ImageBuffer, Rgba, RgbaImage};
 use regex::Regex;
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
 use rfd::{MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
@@ -32,26 +35,45 @@ use tokio::time::{sleep, Duration};
 const DEFAULT_POST_DELAY_MS: u64 = 1000;
 const IMAGE_MATCH_DEBUG_SAVE_EVERY: u64 = 15;
 const OCR_MATCH_DEBUG_SAVE_EVERY: u64 = 5;
+const PIXEL_COLOR_MAX_SAMPLE_RADIUS: u32 = 16;
+const WAIT_ANY_HANDLE_PREFIX: &str = "target:";
 const GUI_AGENT_MAX_SCREENSHOTS: usize = 5;
 const GUI_AGENT_DEFAULT_MAX_STEPS: u64 = 20;
 const GUI_AGENT_ACTION_PARSE_RETRIES: u64 = 3;
 const PARAM_INPUT_PREFIX: &str = "param:";
 const PARAM_INPUT_SUFFIX: &str = ":in";
 
-#[derive(Debug, Default)]
-pub struct WorkflowExecutor;
+#[derive(Debug)]
+pub struct WorkflowExecutor {
+    run_id: String,
+    safe_mode: bool,
+}
+
+impl Default for WorkflowExecutor {
+    fn default() -> Self {
+        Self {
+            run_id: file_journal::new_run_id(),
+            safe_mode: false,
+        }
+    }
+}
 
 #[derive(Debug, Default)]
 struct ExecutionContext {
+    run_id: String,
     variables: HashMap<String, Value>,
     loop_remaining: HashMap<String, u64>,
     while_iterations: HashMap<String, u64>,
     node_outputs: HashMap<String, HashMap<String, Value>>,
+    redactor: redaction::Redactor,
+    file_journal: Option<file_journal::RunJournal>,
 }
 
 enum NextDirective {
     Default,
     Branch(&'static str),
+    // 运行时命名的分支（如 WaitAny 的 target:<name>）
+    NamedBranch(String),
 }
 
 struct CommandExecutionResult {
@@ -73,6 +95,15 @@ struct ClipboardWriteImage {
 }
 
 impl WorkflowExecutor {
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    pub fn with_safe_mode(mut self, enabled: bool) -> Self {
+        self.safe_mode = enabled;
+        self
+    }
+
     pub async fn execute(&self, graph: &WorkflowGraph) -> CommandResult<()> {
         let mut noop = |_node: &WorkflowNode| {};
         let mut noop_vars = |_variables: &HashMap<String, Value>| {};
@@ -115,6 +146,10 @@ impl WorkflowExecutor {
             ));
         }
 
+        for node in &graph.nodes {
+            self.ensure_node_capabilities(node, graph)?;
+        }
+
         let node_map: HashMap<&str, &WorkflowNode> = graph
             .nodes
             .iter()
@@ -171,26 +206,111 @@ impl WorkflowExecutor {
             }
         };
 
+        let redactor = redaction::Redactor::default();
+        for node in graph
+            .nodes
+            .iter()
+            .filter(|node| redaction::is_secret_typed_node(node))
+        {
+            let value = resolve_typed_param_value(node, "value");
+            match node.kind {
+                NodeKind::VarDefine | NodeKind::VarSet => {
+                    redactor.register_variable(&get_string(node, "name", ""), &value);
+                }
+                _ => redactor.register_variable_value(&value),
+            }
+        }
+        let mut redacted_node_start = |node: &WorkflowNode| {
+            on_node_start(&redactor.redact_node(node));
+        };
+        let mut redacted_variables_update = |variables: &HashMap<String, Value>| {
+            on_variables_update(&redactor.redact_variables(variables));
+        };
+        let mut redacted_log = |level: &str, message: String| {
+            on_log(level, redactor.redact_text(&message));
+        };
+        let mut redacted_node_complete =
+            |node: &WorkflowNode,
+             outputs: &HashMap<String, Value>,
+             selected_control_output: Option<&str>| {
+                on_node_complete(
+                    &redactor.redact_node(node),
+                    &redactor.redact_outputs(node, outputs),
+                    selected_control_output,
+                );
+            };
+
+        match file_journal::prune_journals(&self.run_id) {
+            Ok(report) if report.removed_runs > 0 => redacted_log(
+                "info",
+                format!(
+                    "已按保留策略清理 {} 个历史运行的文件回收区，释放 {:.1} MB。",
+                    report.removed_runs,
+                    report.freed_bytes as f64 / (1024.0 * 1024.0)
+                ),
+            ),
+            Ok(_) => {}
+            Err(error) => redacted_log("warn", format!("清理历史文件回收区失败：{}", error)),
+        }
+
         let mut visited_entry = HashSet::<String>::new();
-        let mut ctx = ExecutionContext::default();
+        let mut ctx = ExecutionContext {
+            run_id: self.run_id.clone(),
+            redactor: redactor.clone(),
+            ..ExecutionContext::default()
+        };
+        let mut result = Ok(());
         for start in starts {
             if visited_entry.insert(start.to_string()) {
-                self.execute_from_node(
-                    start,
-                    graph,
-                    &node_map,
-                    &mut ctx,
-                    on_node_start,
-                    on_variables_update,
-                    on_log,
-                    on_node_complete,
-                    should_cancel,
-                )
-                .await?;
+                result = self
+                    .execute_from_node(
+                        start,
+                        graph,
+                        &node_map,
+                        &mut ctx,
+                        &mut redacted_node_start,
+                        &mut redacted_variables_update,
+                        &mut redacted_log,
+                        &mut redacted_node_complete,
+                        should_cancel,
+                    )
+                    .await
+                    .map_err(|error| redactor.redact_error(error));
+                if result.is_err() {
+                    break;
+                }
             }
         }
 
-        Ok(())
+        if let Err(error) = image_ops::remove_temp_images(&self.run_id) {
+            redacted_log("warn", format!("清理本次运行的临时图像失败：{}", error));
+        }
+
+        result
+    }
+
+    fn ensure_node_capabilities(
+        &self,
+        node: &WorkflowNode,
+        graph: &WorkflowGraph,
+    ) -> CommandResult<()> {
+        // 未声明权限等同于声明为空，避免省略字段即可绕过校验；编辑器会在执行前列出所需权限供确认
+        let declared: &[Capability] = graph.capabilities.as_deref().unwrap_or(&[]);
+
+        let missing = capability::missing_capabilities(node, declared);
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let names = missing
+            .iter()
+            .map(|capability| format!("{}（{}）", capability.key(), capability.label()))
+            .collect::<Vec<_>>()
+            .join("、");
+        Err(CommandFlowError::Validation(format!(
+            "node '{}' ({}) requires undeclared capability: {}; declare it in the workflow capabilities before running",
+            node.label, node.id, names
+        )))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -629,8 +749,14 @@ impl WorkflowExecutor {
                 return Ok(());
             }
 
+            self.ensure_node_capabilities(&effective_node, graph)?;
+            if self.safe_mode && capability::requires_confirmation(&effective_node) {
+                confirm_sensitive_node(&effective_node, on_log)?;
+            }
+
+            let secret_node = resolve_secret_params(&effective_node, graph, &ctx.redactor)?;
             let directive = self
-                .execute_single_node(&effective_node, node, graph, ctx, on_log, should_cancel)
+                .execute_single_node(&secret_node, node, graph, ctx, on_log, should_cancel)
                 .await?;
             let outputs_snapshot = ctx
                 .node_outputs
@@ -640,6 +766,7 @@ impl WorkflowExecutor {
             let selected_control_output = match &directive {
                 NextDirective::Default => Some("next"),
                 NextDirective::Branch(handle) => Some(*handle),
+                NextDirective::NamedBranch(handle) => Some(handle.as_str()),
             };
             on_node_complete(&effective_node, &outputs_snapshot, selected_control_output);
             sleep_after_node(&effective_node, should_cancel).await?;
@@ -657,12 +784,12 @@ impl WorkflowExecutor {
                             )
                     })
                     .collect(),
-                NextDirective::Branch(handle) => graph
+                NextDirective::Branch(_) | NextDirective::NamedBranch(_) => graph
                     .edges
                     .iter()
                     .filter(|edge| {
                         edge.source == current_id
-                            && edge.source_handle.as_deref() == Some(handle)
+                            && edge.source_handle.as_deref() == selected_control_output
                             && is_control_flow_edge(
                                 edge.source_handle.as_deref(),
                                 edge.target_handle.as_deref(),
@@ -757,25 +884,75 @@ impl WorkflowExecutor {
             NodeKind::Screenshot => {
                 let output_path = resolve_screenshot_output_path(node)?;
                 let fullscreen = get_bool(node, "fullscreen", false);
+                let capture_target = resolve_capture_target(node)?;
 
-                let (rgba, width, height) = if fullscreen {
-                    screenshot::capture_fullscreen_rgba()?
-                } else {
+                let (rgba, width, height, origin) = if fullscreen {
+                    let capture = screenshot::capture_target_rgba(&capture_target)?;
+                    (capture.rgba, capture.width, capture.height, capture.origin)
+                } else if capture_target == screenshot::CaptureTarget::Primary {
                     let start_x = get_u32(node, "startX", 0);
                     let start_y = get_u32(node, "startY", 0);
                     let width = get_u32(node, "width", 320);
                     let height = get_u32(node, "height", 240);
-                    screenshot::capture_region_rgba(start_x, start_y, width.max(1), height.max(1))?
+                    let (rgba, width, height) = screenshot::capture_region_rgba(
+                        start_x,
+                        start_y,
+                        width.max(1),
+                        height.max(1),
+                    )?;
+                    (rgba, width, height, (start_x as i32, start_y as i32))
+                } else {
+                    let region = SearchRegion {
+                        left: get_f32(node, "startX", 0.0).round() as i32,
+                        top: get_f32(node, "startY", 0.0).round() as i32,
+                        width: get_u32(node, "width", 320).max(1),
+                        height: get_u32(node, "height", 240).max(1),
+                    };
+                    let capture = screenshot::capture_target_rgba(&capture_target)?;
+                    // 窗口截图时区域相对窗口左上角，其余情况按虚拟屏幕坐标解释
+                    let region = if matches!(capture_target, screenshot::CaptureTarget::Window(_)) {
+                        region
+                    } else {
+                        region.relative_to(capture.origin)
+                    };
+                    let (rgba, width, height, crop_origin) = crop_rgba_to_region(
+                        node,
+                        capture.rgba,
+                        capture.width,
+                        capture.height,
+                        region,
+                    )?;
+                    (
+                        rgba,
+                        width,
+                        height,
+                        offset_point(crop_origin, capture.origin),
+                    )
                 };
 
                 let screenshot_base64 =
                     screenshot::encode_rgba_to_png_base64(&rgba, width, height)?;
+                let image_value = image_ops::data_url_image_value(
+                    format!("data:image/png;base64,{}", screenshot_base64),
+                    image_ops::EncodeFormat::Png,
+                    width,
+                    height,
+                );
                 set_node_output(ctx, node, "screenshot", Value::String(screenshot_base64));
+                set_node_output(ctx, node, "offsetX", value_from_i32(origin.0));
+                set_node_output(ctx, node, "offsetY", value_from_i32(origin.1));
 
                 if let Some(path) = output_path {
                     screenshot::save_rgba_image(&path, rgba, width, height)?;
+                    set_node_output(
+                        ctx,
+                        node,
+                        "image",
+                        image_ops::file_image_value(Path::new(&path), width, height),
+                    );
                     set_node_output(ctx, node, "path", Value::String(path));
                 } else {
+                    set_node_output(ctx, node, "image", image_value);
                     set_node_output(ctx, node, "path", Value::String(String::new()));
                 }
                 Ok(NextDirective::Default)
@@ -850,10 +1027,35 @@ impl WorkflowExecutor {
             }
             NodeKind::TerminateProcess => execute_terminate_process(node, ctx, on_log),
             NodeKind::LaunchApplication => execute_launch_application(node, ctx, on_log),
-            NodeKind::FileOperation => execute_file_operation(node, ctx, None, on_log),
-            NodeKind::FileCopy => execute_file_operation(node, ctx, Some("copy"), on_log),
-            NodeKind::FileMove => execute_file_operation(node, ctx, Some("move"), on_log),
-            NodeKind::FileDelete => execute_file_operation(node, ctx, Some("delete"), on_log),
+            NodeKind::FileOperation => {
+                execute_file_operation(node, ctx, None, on_log, should_cancel).await
+            }
+            NodeKind::FileCopy => {
+                execute_file_operation(node, ctx, Some("copy"), on_log, should_cancel).await
+            }
+            NodeKind::FileMove => {
+                execute_file_operation(node, ctx, Some("move"), on_log, should_cancel).await
+            }
+            NodeKind::FileDelete => {
+                execute_file_operation(node, ctx, Some("delete"), on_log, should_cancel).await
+            }
+            NodeKind::ListFiles => {
+                execute_file_operation(node, ctx, Some("listFiles"), on_log, should_cancel).await
+            }
+            NodeKind::FileInfo => {
+                execute_file_operation(node, ctx, Some("fileInfo"), on_log, should_cancel).await
+            }
+            NodeKind::WaitForFile => {
+                execute_file_operation(node, ctx, Some("waitForFile"), on_log, should_cancel).await
+            }
+            NodeKind::ArchiveCreate => {
+                execute_file_operation(node, ctx, Some("archiveCreate"), on_log, should_cancel)
+                    .await
+            }
+            NodeKind::ArchiveExtract => {
+                execute_file_operation(node, ctx, Some("archiveExtract"), on_log, should_cancel)
+                    .await
+            }
             NodeKind::RunCommand => execute_system_operation(node, ctx, Some("runCommand")).await,
             NodeKind::PythonCode => {
                 run_python_code(node, on_log).await?;
@@ -953,18 +1155,22 @@ impl WorkflowExecutor {
                 let structured_value = Value::Object(structured);
                 set_node_output(ctx, node, "content", structured_value.clone());
 
+                // 剪贴板常用于粘贴密码，读取结果按密钥处理：变量整体遮蔽，文本值参与全文替换
                 let output_var = get_string(node, "outputVar", "clipboardContent")
                     .trim()
                     .to_string();
                 if !output_var.is_empty() {
+                    ctx.redactor.register_variable_name(&output_var);
                     ctx.variables.insert(output_var, structured_value);
                 }
 
                 if let Some(text) = text {
+                    ctx.redactor.register_value(&text);
                     let output_text_var = get_string(node, "outputTextVar", "clipboardText")
                         .trim()
                         .to_string();
                     if !output_text_var.is_empty() {
+                        ctx.redactor.register_variable_name(&output_text_var);
                         ctx.variables.insert(output_text_var, Value::String(text));
                     }
                 }
@@ -974,6 +1180,7 @@ impl WorkflowExecutor {
                         .trim()
                         .to_string();
                     if !output_image_var.is_empty() {
+                        ctx.redactor.register_variable_name(&output_image_var);
                         ctx.variables
                             .insert(output_image_var, Value::String(image.data_url));
                     }
@@ -1026,7 +1233,8 @@ impl WorkflowExecutor {
                         );
                     }
                     "image" => {
-                        let image = resolve_clipboard_write_image(node, &ctx.variables)?;
+                        let image =
+                            resolve_clipboard_write_image(node, &ctx.variables, &ctx.run_id)?;
                         clipboard
                             .set_image(ImageData {
                                 width: image.width,
@@ -1057,8 +1265,13 @@ impl WorkflowExecutor {
 
                 Ok(NextDirective::Default)
             }
-            NodeKind::FileReadText => execute_file_operation(node, ctx, Some("readText"), on_log),
-            NodeKind::FileWriteText => execute_file_operation(node, ctx, Some("writeText"), on_log),
+            NodeKind::ImageOperation => execute_image_operation(node, ctx, on_log),
+            NodeKind::FileReadText => {
+                execute_file_operation(node, ctx, Some("readText"), on_log, should_cancel).await
+            }
+            NodeKind::FileWriteText => {
+                execute_file_operation(node, ctx, Some("writeText"), on_log, should_cancel).await
+            }
             NodeKind::ShowMessage => {
                 let title_raw = get_string(node, "title", "CommandFlow");
                 let title = resolve_text_template(&title_raw, &ctx.variables);
@@ -1165,24 +1378,95 @@ impl WorkflowExecutor {
             }
             NodeKind::TryCatch => Ok(NextDirective::Default),
             NodeKind::ImageMatch => {
-                let template_path = get_string(node, "templatePath", "");
+                let template_path = get_image_path(node, "templatePath", &ctx.run_id)?;
                 if template_path.trim().is_empty() {
                     return Err(CommandFlowError::Validation(format!(
                         "node '{}' templatePath cannot be empty",
                         node.id
                     )));
                 }
+                if let Some(detector) = feature_match::FeatureDetector::parse(&get_string(
+                    node,
+                    "algorithm",
+                    "template",
+                )) {
+                    return execute_feature_image_match(
+                        node,
+                        ctx,
+                        on_log,
+                        should_cancel,
+                        &template_path,
+                        detector,
+                    )
+                    .await;
+                }
 
-                let source_path = get_string(node, "sourcePath", "");
+                let source_path = get_image_path(node, "sourcePath", &ctx.run_id)?;
                 let threshold = get_f32(node, "threshold", 0.99).clamp(0.0, 1.0);
                 let timeout_ms = get_u64(node, "timeoutMs", 10_000);
                 let poll_ms = get_u64(node, "pollMs", 16).max(1);
                 let click_on_match = get_bool(node, "clickOnMatch", false);
                 let click_times = get_u64(node, "clickTimes", 1).max(1) as usize;
                 let confirm_frames = get_u64(node, "confirmFrames", 2).max(1);
+                let wait_disappear =
+                    get_string(node, "waitFor", "appear").eq_ignore_ascii_case("disappear");
+                let timeout_reason = if wait_disappear {
+                    "目标仍未消失"
+                } else {
+                    "未命中"
+                };
                 let debug_save_every = IMAGE_MATCH_DEBUG_SAVE_EVERY;
-                let mut matcher =
-                    image_match::TemplateMatcher::from_path(&template_path, threshold)?;
+                let match_options = image_match::MatchOptions {
+                    color_mode: image_match::ColorMode::parse(&get_string(
+                        node,
+                        "colorMode",
+                        "gray",
+                    )),
+                    method: image_match::MatchMethod::parse(&get_string(
+                        node,
+                        "matchMethod",
+                        "ssd",
+                    )),
+                    use_alpha_mask: get_bool(node, "useAlphaMask", true),
+                };
+                let mut matcher = image_match::TemplateMatcher::from_path_with_options(
+                    &template_path,
+                    threshold,
+                    match_options,
+                )?;
+                if get_bool(node, "multiScale", false) {
+                    matcher = matcher.with_scale_range(image_match::ScaleRange {
+                        min: get_f32(node, "scaleMin", 0.5),
+                        max: get_f32(node, "scaleMax", 2.0),
+                        step: get_f32(node, "scaleStep", 0.1),
+                    })?;
+                }
+                if let Some(reason) = matcher.gpu_fallback_reason() {
+                    on_log(
+                        "info",
+                        format!("图像匹配节点 '{}' 回退到 CPU：{}。", node.label, reason),
+                    );
+                }
+                let find_all = get_string(node, "matchMode", "best").eq_ignore_ascii_case("all");
+                if find_all {
+                    let sort_by = get_string(node, "sortBy", "score");
+                    matcher = matcher.with_find_all(image_match::FindAllOptions {
+                        sort: image_match::MatchSortOrder::parse(&sort_by),
+                        max_results: get_u64(node, "maxMatches", 50).max(1) as usize,
+                        select_index: get_u64(node, "matchIndex", 1).max(1) as usize - 1,
+                    });
+                }
+                let mut search_region = resolve_search_region(node)?;
+                if let Some(region) = search_region {
+                    on_log(
+                        "info",
+                        format!(
+                            "图像匹配节点 '{}' 搜索区域：({}, {}) {}x{}。",
+                            node.label, region.left, region.top, region.width, region.height
+                        ),
+                    );
+                }
+                let capture_target = resolve_capture_target(node)?;
                 let debug_dir = prepare_image_match_debug_dir(node)?;
 
                 let started = tokio::time::Instant::now();
@@ -1195,9 +1479,9 @@ impl WorkflowExecutor {
 
                 if !source_path.trim().is_empty() {
                     let source = image::open(&source_path)
-                        .map_err(|error| CommandFlowError::Automation(error.to_string()))?
-                        .to_luma8();
-                    let evaluation = matcher.evaluate(&source);
+                        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+                    let evaluation =
+                        evaluate_image_match_in_region(node, &mut matcher, &source, search_region)?;
                     best_similarity_seen = best_similarity_seen.max(evaluation.best_similarity);
                     set_node_output(
                         ctx,
@@ -1205,14 +1489,54 @@ impl WorkflowExecutor {
                         "similarity",
                         value_from_f64(evaluation.best_similarity as f64),
                     );
+                    set_node_output(
+                        ctx,
+                        node,
+                        "matchScale",
+                        value_from_f64(evaluation.best_scale as f64),
+                    );
+                    if find_all {
+                        set_image_match_list_outputs(ctx, node, &evaluation, (0, 0));
+                    }
                     on_log(
                         "info",
                         format!(
-                            "图像匹配节点 '{}'：静态源图匹配，bestSimilarity={:.4}，threshold={:.2}。",
-                            node.label, evaluation.best_similarity, threshold
+                            "图像匹配节点 '{}'：静态源图匹配，bestSimilarity={:.4}，scale={:.2}，threshold={:.2}。",
+                            node.label, evaluation.best_similarity, evaluation.best_scale, threshold
                         ),
                     );
 
+                    if wait_disappear {
+                        let debug_path = debug_dir.join("static-source.png");
+                        let rect = evaluation.best_top_left.map(|(x, y)| {
+                            (x, y, evaluation.template_size.0, evaluation.template_size.1)
+                        });
+                        let _ = screenshot::save_image_with_box(
+                            path_to_string(&debug_path)?,
+                            &source,
+                            rect,
+                            evaluation.target_present(),
+                        );
+                        if !evaluation.target_present() {
+                            on_log(
+                                "info",
+                                format!(
+                                    "图像匹配节点 '{}' 静态源图中未检测到目标，判定已消失。",
+                                    node.label
+                                ),
+                            );
+                            return Ok(NextDirective::Branch("true"));
+                        }
+                        on_log(
+                            "warn",
+                            format!(
+                                "图像匹配节点 '{}' 静态源图中目标仍存在（bestSimilarity={:.4}），已走 false 分支。",
+                                node.label, best_similarity_seen
+                            ),
+                        );
+                        return Ok(NextDirective::Branch("false"));
+                    }
+
                     if let Some((x, y)) = evaluation.matched_point {
                         set_node_output(ctx, node, "matchX", value_from_i32(x));
                         set_node_output(ctx, node, "matchY", value_from_i32(y));
@@ -1229,11 +1553,11 @@ impl WorkflowExecutor {
                         return Ok(NextDirective::Branch("true"));
                     }
 
-                    let debug_path = debug_dir.join("static-source-gray.png");
+                    let debug_path = debug_dir.join("static-source.png");
                     let rect = evaluation.best_top_left.map(|(x, y)| {
                         (x, y, evaluation.template_size.0, evaluation.template_size.1)
                     });
-                    let _ = screenshot::save_gray_with_box(
+                    let _ = screenshot::save_image_with_box(
                         path_to_string(&debug_path)?,
                         &source,
                         rect,
@@ -1250,7 +1574,7 @@ impl WorkflowExecutor {
                     return Ok(NextDirective::Branch("false"));
                 }
 
-                screenshot::ensure_primary_frame_stream().map_err(|error| {
+                let origin = screenshot::ensure_frame_stream(&capture_target).map_err(|error| {
                     let bt = Backtrace::force_capture();
                     CommandFlowError::Automation(format!(
                         "imageMatch xcap stream init failed at node '{}': {}\nbacktrace:\n{}",
@@ -1260,7 +1584,10 @@ impl WorkflowExecutor {
 
                 on_log(
                     "info",
-                    format!("图像匹配节点 '{}' 已启用 xcap 实时帧流匹配。", node.label),
+                    format!(
+                        "图像匹配节点 '{}' 已启用 xcap 实时帧流匹配，画面原点=({}, {})。",
+                        node.label, origin.0, origin.1
+                    ),
                 );
 
                 let mut stream_recover_attempted = false;
@@ -1279,7 +1606,7 @@ impl WorkflowExecutor {
                     };
 
                     let recv_result = tokio::task::block_in_place(|| {
-                        screenshot::recv_primary_frame_gray_timeout(stream_recv_timeout)
+                        recv_image_match_frame(match_options.color_mode, stream_recv_timeout)
                     });
 
                     let frame = match recv_result {
@@ -1298,7 +1625,7 @@ impl WorkflowExecutor {
                                 let _ = screenshot::reset_primary_frame_stream(
                                     "image_match_recv_failed",
                                 );
-                                screenshot::ensure_primary_frame_stream().map_err(|reinit_error| {
+                                screenshot::ensure_frame_stream(&capture_target).map_err(|reinit_error| {
                                     let bt = Backtrace::force_capture();
                                     CommandFlowError::Automation(format!(
                                         "imageMatch xcap stream recover failed at node '{}': recv_error={}, reinit_error={}\nbacktrace:\n{}",
@@ -1322,8 +1649,8 @@ impl WorkflowExecutor {
                             on_log(
                                 "warn",
                                 format!(
-                                    "图像匹配节点 '{}' 在 {}ms 内未命中，bestSimilarity={:.4}，已走 false 分支。",
-                                    node.label, timeout_ms, best_similarity_seen
+                                    "图像匹配节点 '{}' 在 {}ms 内{}，bestSimilarity={:.4}，已走 false 分支。",
+                                    node.label, timeout_ms, timeout_reason, best_similarity_seen
                                 ),
                             );
                             return Ok(NextDirective::Branch("false"));
@@ -1332,8 +1659,12 @@ impl WorkflowExecutor {
                     };
 
                     attempts += 1;
+                    let origin = screenshot::frame_stream_origin()?;
+                    search_region = refresh_search_region(node, search_region);
+                    let frame_region = search_region.map(|region| region.relative_to(origin));
 
-                    let evaluation = matcher.evaluate(&frame);
+                    let evaluation =
+                        evaluate_image_match_in_region(node, &mut matcher, &frame, frame_region)?;
                     best_similarity_seen = best_similarity_seen.max(evaluation.best_similarity);
                     let elapsed_ms = started.elapsed().as_millis();
 
@@ -1345,7 +1676,7 @@ impl WorkflowExecutor {
                         let rect = evaluation.best_top_left.map(|(x, y)| {
                             (x, y, evaluation.template_size.0, evaluation.template_size.1)
                         });
-                        let _ = screenshot::save_gray_with_box(
+                        let _ = screenshot::save_image_with_box(
                             path_to_string(&frame_path)?,
                             &frame,
                             rect,
@@ -1356,18 +1687,24 @@ impl WorkflowExecutor {
                     on_log(
                         "info",
                         format!(
-                            "图像匹配节点 '{}' 第 {} 帧匹配，elapsed={}ms，bestSimilarity={:.4}，threshold={:.2}，confirm={}/{}。",
+                            "图像匹配节点 '{}' 第 {} 帧匹配，elapsed={}ms，bestSimilarity={:.4}，scale={:.2}，threshold={:.2}，confirm={}/{}。",
                             node.label,
                             attempts,
                             elapsed_ms,
                             evaluation.best_similarity,
+                            evaluation.best_scale,
                             threshold,
                             matched_streak,
                             confirm_frames
                         ),
                     );
 
-                    if evaluation.matched_point.is_some() {
+                    let hit = if wait_disappear {
+                        !evaluation.target_present()
+                    } else {
+                        evaluation.matched_point.is_some()
+                    };
+                    if hit {
                         matched_streak += 1;
                     } else {
                         matched_streak = 0;
@@ -1379,11 +1716,49 @@ impl WorkflowExecutor {
                         "similarity",
                         value_from_f64(evaluation.best_similarity as f64),
                     );
+                    set_node_output(
+                        ctx,
+                        node,
+                        "matchScale",
+                        value_from_f64(evaluation.best_scale as f64),
+                    );
+                    if find_all {
+                        set_image_match_list_outputs(ctx, node, &evaluation, origin);
+                    }
+
+                    if matched_streak >= confirm_frames && wait_disappear {
+                        let _ = screenshot::stop_primary_frame_stream();
+                        on_log(
+                            "info",
+                            format!(
+                                "图像匹配节点 '{}' 连续 {} 帧未检测到目标，已确认消失。",
+                                node.label, confirm_frames
+                            ),
+                        );
+
+                        let frame_path = debug_dir.join(format!(
+                            "gone-{:05}-sim-{:.4}.png",
+                            attempts, evaluation.best_similarity
+                        ));
+                        let rect = evaluation.best_top_left.map(|(x, y)| {
+                            (x, y, evaluation.template_size.0, evaluation.template_size.1)
+                        });
+                        let _ = screenshot::save_image_with_box(
+                            path_to_string(&frame_path)?,
+                            &frame,
+                            rect,
+                            false,
+                        );
+                        return Ok(NextDirective::Branch("true"));
+                    }
 
                     if matched_streak >= confirm_frames {
-                        let (x, y) = evaluation.matched_point.ok_or_else(|| {
-                            CommandFlowError::Automation("matched point missing".to_string())
-                        })?;
+                        let (x, y) = evaluation
+                            .matched_point
+                            .map(|point| offset_point(point, origin))
+                            .ok_or_else(|| {
+                                CommandFlowError::Automation("matched point missing".to_string())
+                            })?;
                         set_node_output(ctx, node, "matchX", value_from_i32(x));
                         set_node_output(ctx, node, "matchY", value_from_i32(y));
                         let _ = screenshot::stop_primary_frame_stream();
@@ -1402,7 +1777,7 @@ impl WorkflowExecutor {
                         let rect = evaluation.best_top_left.map(|(x, y)| {
                             (x, y, evaluation.template_size.0, evaluation.template_size.1)
                         });
-                        let _ = screenshot::save_gray_with_box(
+                        let _ = screenshot::save_image_with_box(
                             path_to_string(&frame_path)?,
                             &frame,
                             rect,
@@ -1420,8 +1795,8 @@ impl WorkflowExecutor {
                         on_log(
                             "warn",
                             format!(
-                                "图像匹配节点 '{}' 在 {}ms 内未命中（peakSimilarity={:.4}），已走 false 分支。",
-                                node.label, timeout_ms, best_similarity_seen
+                                "图像匹配节点 '{}' 在 {}ms 内{}（peakSimilarity={:.4}），已走 false 分支。",
+                                node.label, timeout_ms, timeout_reason, best_similarity_seen
                             ),
                         );
                         return Ok(NextDirective::Branch("false"));
@@ -1429,10 +1804,18 @@ impl WorkflowExecutor {
                 }
             }
             NodeKind::OcrMatch => execute_ocr_match(node, ctx, on_log, should_cancel).await,
+            NodeKind::OcrRead => execute_ocr_read(node, ctx, on_log).await,
+            NodeKind::PixelColor => execute_pixel_color(node, ctx, on_log, should_cancel).await,
+            NodeKind::ScreenChange => execute_screen_change(node, ctx, on_log, should_cancel).await,
+            NodeKind::WaitAny => execute_wait_any(node, ctx, on_log, should_cancel).await,
+            NodeKind::VisualAssert => execute_visual_assert(node, ctx, on_log, should_cancel).await,
             NodeKind::VarDefine => {
                 let name = get_string(node, "name", "");
                 if !name.trim().is_empty() {
                     let value = resolve_typed_param_value(node, "value");
+                    if redaction::is_secret_typed_node(node) {
+                        ctx.redactor.register_variable(&name, &value);
+                    }
                     set_node_output(ctx, node, "value", value.clone());
                     ctx.variables.entry(name).or_insert(value);
                 }
@@ -1442,6 +1825,11 @@ impl WorkflowExecutor {
                 let name = get_string(node, "name", "");
                 if !name.trim().is_empty() {
                     let value = resolve_typed_param_value(node, "value");
+                    if redaction::is_secret_typed_node(node)
+                        || ctx.redactor.is_secret_variable(&name)
+                    {
+                        ctx.redactor.register_variable(&name, &value);
+                    }
                     set_node_output(ctx, node, "value", value.clone());
                     ctx.variables.insert(name, value);
                 }
@@ -1768,7 +2156,9 @@ fn is_control_source_handle(handle: Option<&str>) -> bool {
             | Some("success")
             | Some("error")
             | Some("finally")
-    )
+    ) || handle
+        .map(|value| value.starts_with(WAIT_ANY_HANDLE_PREFIX))
+        .unwrap_or(false)
 }
 
 fn is_control_target_handle(handle: Option<&str>) -> bool {
@@ -1855,6 +2245,140 @@ fn resolve_node_with_data_inputs(
     resolved
 }
 
+fn secret_reference_regex() -> &'static Regex {
+    static SECRET_REFERENCE: OnceLock<Regex> = OnceLock::new();
+    SECRET_REFERENCE.get_or_init(|| {
+        Regex::new(r"\{\{\s*secret:([A-Za-z0-9_.\-]+)\s*\}\}").expect("valid secret regex")
+    })
+}
+
+fn confirm_sensitive_node(
+    node: &WorkflowNode,
+    on_log: &mut impl FnMut(&str, String),
+) -> CommandResult<()> {
+    let actions = capability::required_capabilities(node)
+        .iter()
+        .map(|capability| capability.label())
+        .collect::<Vec<_>>()
+        .join("、");
+    let answer = MessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title("CommandFlow 安全模式确认")
+        .set_description(format!(
+            "工作流即将执行节点「{}」（{}）。\n\n确定继续吗？",
+            node.label, actions
+        ))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+
+    if matches!(answer, MessageDialogResult::Yes | MessageDialogResult::Ok) {
+        on_log(
+            "warn",
+            format!("安全模式：已确认执行节点 '{}'（{}）。", node.label, actions),
+        );
+        return Ok(());
+    }
+
+    Err(CommandFlowError::Validation(format!(
+        "safe mode: execution of node '{}' ({}) was declined by the user",
+        node.label, node.id
+    )))
+}
+
+// 只解析作者编写的参数；经数据端口传入的运行时文本（OCR、剪贴板、文件内容、LLM 输出等）
+// 即使包含 {{secret:…}} 也保持原样，避免被借道读出密钥
+fn resolve_secret_params<'a>(
+    node: &'a WorkflowNode,
+    graph: &WorkflowGraph,
+    redactor: &redaction::Redactor,
+) -> CommandResult<Cow<'a, WorkflowNode>> {
+    let data_input_keys = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.target == node.id)
+        .filter_map(|edge| edge.target_handle.as_deref())
+        .filter_map(extract_param_key_from_input_handle)
+        .collect::<HashSet<_>>();
+    let has_reference = node.params.iter().any(|(key, value)| {
+        !data_input_keys.contains(key) && value_contains_secret_reference(value)
+    });
+    if !has_reference {
+        return Ok(Cow::Borrowed(node));
+    }
+
+    let mut cache = HashMap::<String, String>::new();
+    let mut resolved = node.clone();
+    for (key, value) in resolved.params.iter_mut() {
+        if !data_input_keys.contains(key) {
+            resolve_secret_value(value, &node.id, &mut cache)?;
+        }
+    }
+    for secret in cache.values() {
+        redactor.register_value(secret);
+    }
+    Ok(Cow::Owned(resolved))
+}
+
+fn value_contains_secret_reference(value: &Value) -> bool {
+    match value {
+        Value::String(text) => secret_reference_regex().is_match(text),
+        Value::Array(items) => items.iter().any(value_contains_secret_reference),
+        Value::Object(map) => map.values().any(value_contains_secret_reference),
+        _ => false,
+    }
+}
+
+fn resolve_secret_value(
+    value: &mut Value,
+    node_id: &str,
+    cache: &mut HashMap<String, String>,
+) -> CommandResult<()> {
+    match value {
+        Value::String(text) => {
+            if !secret_reference_regex().is_match(text) {
+                return Ok(());
+            }
+
+            let mut output = String::with_capacity(text.len());
+            let mut last_end = 0;
+            for captures in secret_reference_regex().captures_iter(text) {
+                let (Some(whole), Some(name)) = (captures.get(0), captures.get(1)) else {
+                    continue;
+                };
+                output.push_str(&text[last_end..whole.start()]);
+                if !cache.contains_key(name.as_str()) {
+                    let secret = load_secret(name.as_str())
+                        .map_err(CommandFlowError::Automation)?
+                        .ok_or_else(|| {
+                            CommandFlowError::Validation(format!(
+                                "node '{}' references undefined secret '{}'",
+                                node_id,
+                                name.as_str()
+                            ))
+                        })?;
+                    cache.insert(name.as_str().to_string(), secret);
+                }
+                output.push_str(&cache[name.as_str()]);
+                last_end = whole.end();
+            }
+            output.push_str(&text[last_end..]);
+            *text = output;
+        }
+        Value::Array(items) => {
+            for item in items {
+                resolve_secret_value(item, node_id, cache)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                resolve_secret_value(item, node_id, cache)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 fn resolve_source_fallback_value(
     source_node: &WorkflowNode,
     source_handle: &str,
@@ -2609,16 +3133,17 @@ async fn execute_ocr_match(
         )));
     }
 
-    let source_path = get_string(node, "sourcePath", "");
-    let match_mode = get_string(node, "matchMode", "contains");
-    let case_sensitive = get_bool(node, "caseSensitive", false);
-    let use_regex = get_bool(node, "useRegex", false);
-    let min_confidence = get_f32(node, "minConfidence", 0.5).clamp(0.0, 1.0);
+    let source_path = get_image_path(node, "sourcePath", &ctx.run_id)?;
+    let query = build_ocr_text_query(&target_text, |key| node.params.get(key));
     let timeout_ms = get_u64(node, "timeoutMs", 10_000);
     let poll_ms = get_u64(node, "pollMs", 120).max(1);
     let confirm_frames = get_u64(node, "confirmFrames", 2).max(1);
     let click_on_match = get_bool(node, "clickOnMatch", false);
     let click_times = get_u64(node, "clickTimes", 1).max(1) as usize;
+    let wait_disappear = get_string(node, "waitFor", "appear").eq_ignore_ascii_case("disappear");
+    let mut search_region = resolve_search_region(node)?;
+    let capture_target = resolve_capture_target(node)?;
+    let ocr_models = resolve_ocr_models(node)?;
     let debug_dir = prepare_ocr_match_debug_dir(node)?;
 
     on_log(
@@ -2630,42 +3155,86 @@ async fn execute_ocr_match(
         ),
     );
 
-    if !source_path.trim().is_empty() {
-        let evaluation = evaluate_ocr_path_blocking(
-            &source_path,
-            &target_text,
-            &match_mode,
-            case_sensitive,
-            use_regex,
-            min_confidence,
-        )
-        .await?;
-
-        if let Err(error) = save_ocr_static_debug_artifacts(
-            &debug_dir,
-            &source_path,
-            &evaluation,
-            &target_text,
-            &match_mode,
-            case_sensitive,
-            use_regex,
-            min_confidence,
-            node,
-        ) {
-            on_log(
-                "warn",
-                format!("OCR 匹配节点 '{}' 写入调试文件失败：{}", node.label, error),
-            );
-        }
-
-        set_node_output(
-            ctx,
-            node,
-            "confidence",
-            value_from_f64(evaluation.peak_confidence as f64),
+    if let Some(region) = search_region {
+        on_log(
+            "info",
+            format!(
+                "OCR 匹配节点 '{}' 搜索区域：({}, {}) {}x{}。",
+                node.label, region.left, region.top, region.width, region.height
+            ),
         );
+    }
 
-        if let Some(candidate) = evaluation.matched {
+    if !source_path.trim().is_empty() {
+        let evaluation = if let Some(region) = search_region {
+            let source = image::open(&source_path)
+                .map_err(|error| CommandFlowError::Automation(error.to_string()))?
+                .to_rgba8();
+            let (width, height) = source.dimensions();
+            let (rgba, width, height, origin) =
+                crop_rgba_to_region(node, source.into_raw(), width, height, region)?;
+            let debug_rgba = rgba.clone();
+            let evaluation = offset_ocr_evaluation(
+                evaluate_ocr_rgba_blocking(&ocr_models, rgba, width, height, &query).await?,
+                origin,
+            );
+            if let Err(error) = save_ocr_frame_debug_artifacts(
+                &debug_dir,
+                0,
+                (&debug_rgba, width, height, origin),
+                &evaluation,
+                &query,
+                node,
+            ) {
+                on_log(
+                    "warn",
+                    format!("OCR 匹配节点 '{}' 写入调试文件失败：{}", node.label, error),
+                );
+            }
+            evaluation
+        } else {
+            let evaluation = evaluate_ocr_path_blocking(&ocr_models, &source_path, &query).await?;
+
+            if let Err(error) =
+                save_ocr_static_debug_artifacts(&debug_dir, &source_path, &evaluation, &query, node)
+            {
+                on_log(
+                    "warn",
+                    format!("OCR 匹配节点 '{}' 写入调试文件失败：{}", node.label, error),
+                );
+            }
+            evaluation
+        };
+
+        set_node_output(
+            ctx,
+            node,
+            "confidence",
+            value_from_f64(evaluation.peak_confidence as f64),
+        );
+
+        if wait_disappear {
+            if evaluation.matched.is_none() {
+                on_log(
+                    "info",
+                    format!(
+                        "OCR 匹配节点 '{}' 静态源图中未检测到目标文本，判定已消失。",
+                        node.label
+                    ),
+                );
+                return Ok(NextDirective::Branch("true"));
+            }
+            on_log(
+                "warn",
+                format!(
+                    "OCR 匹配节点 '{}' 静态源图中目标文本仍存在（peakConfidence={:.4}，peakText='{}'），已走 false 分支。",
+                    node.label, evaluation.peak_confidence, evaluation.peak_text
+                ),
+            );
+            return Ok(NextDirective::Branch("false"));
+        }
+
+        if let Some(candidate) = evaluation.matched {
             set_node_output(ctx, node, "matchX", value_from_i32(candidate.x));
             set_node_output(ctx, node, "matchY", value_from_i32(candidate.y));
             set_node_output(
@@ -2680,6 +3249,12 @@ async fn execute_ocr_match(
                 "confidence",
                 value_from_f64(candidate.confidence as f64),
             );
+            set_node_output(
+                ctx,
+                node,
+                "similarity",
+                value_from_f64(candidate.similarity as f64),
+            );
 
             on_log(
                 "info",
@@ -2689,7 +3264,7 @@ async fn execute_ocr_match(
                 ),
             );
 
-            if click_on_match && candidate.x >= 0 && candidate.y >= 0 {
+            if click_on_match && candidate.has_position {
                 mouse::click(candidate.x, candidate.y, click_times)?;
             }
 
@@ -2699,8 +3274,8 @@ async fn execute_ocr_match(
         on_log(
             "warn",
             format!(
-                "OCR 匹配节点 '{}' 静态源图未命中（peakConfidence={:.4}，peakText='{}'），已走 false 分支。",
-                node.label, evaluation.peak_confidence, evaluation.peak_text
+                "OCR 匹配节点 '{}' 静态源图未命中（peakConfidence={:.4}，peakText='{}'，peakSimilarity={:.4}），已走 false 分支。",
+                node.label, evaluation.peak_confidence, evaluation.peak_text, evaluation.peak_similarity
             ),
         );
         return Ok(NextDirective::Branch("false"));
@@ -2713,32 +3288,46 @@ async fn execute_ocr_match(
     let mut matched_streak: u64 = 0;
     let mut best_confidence_seen = 0.0_f32;
     let mut best_text_seen = String::new();
+    let mut best_similarity_seen = 0.0_f32;
 
     loop {
         if should_cancel() {
             return Err(CommandFlowError::Canceled);
         }
 
-        let (rgba, width, height) = screenshot::capture_fullscreen_rgba()?;
+        let capture = screenshot::capture_target_rgba(&capture_target)?;
+        search_region = refresh_search_region(node, search_region);
+        let (rgba, width, height, origin) = match search_region {
+            Some(region) => {
+                let (rgba, width, height, (left, top)) = crop_rgba_to_region(
+                    node,
+                    capture.rgba,
+                    capture.width,
+                    capture.height,
+                    region.relative_to(capture.origin),
+                )?;
+                (
+                    rgba,
+                    width,
+                    height,
+                    offset_point((left, top), capture.origin),
+                )
+            }
+            None => (capture.rgba, capture.width, capture.height, capture.origin),
+        };
         attempts += 1;
         let debug_rgba = rgba.clone();
 
-        let evaluation = evaluate_ocr_rgba_blocking(
-            rgba,
-            width,
-            height,
-            &target_text,
-            &match_mode,
-            case_sensitive,
-            use_regex,
-            min_confidence,
-        )
-        .await?;
+        let evaluation = offset_ocr_evaluation(
+            evaluate_ocr_rgba_blocking(&ocr_models, rgba, width, height, &query).await?,
+            origin,
+        );
 
         if evaluation.peak_confidence > best_confidence_seen {
             best_confidence_seen = evaluation.peak_confidence;
             best_text_seen = evaluation.peak_text.clone();
         }
+        best_similarity_seen = best_similarity_seen.max(evaluation.peak_similarity);
 
         set_node_output(
             ctx,
@@ -2750,21 +3339,15 @@ async fn execute_ocr_match(
         let is_timeout_now = started.elapsed() >= deadline;
         let should_save_debug = attempts == 1
             || attempts % OCR_MATCH_DEBUG_SAVE_EVERY == 0
-            || evaluation.matched.is_some()
+            || evaluation.matched.is_some() != wait_disappear
             || is_timeout_now;
         if should_save_debug {
             if let Err(error) = save_ocr_frame_debug_artifacts(
                 &debug_dir,
                 attempts,
-                &debug_rgba,
-                width,
-                height,
+                (&debug_rgba, width, height, origin),
                 &evaluation,
-                &target_text,
-                &match_mode,
-                case_sensitive,
-                use_regex,
-                min_confidence,
+                &query,
                 node,
             ) {
                 on_log(
@@ -2774,7 +3357,30 @@ async fn execute_ocr_match(
             }
         }
 
-        if let Some(candidate) = evaluation.matched {
+        if wait_disappear {
+            if evaluation.matched.is_none() {
+                matched_streak += 1;
+                on_log(
+                    "info",
+                    format!(
+                        "OCR 匹配节点 '{}' 第 {} 帧未检测到目标文本，confirm={}/{}。",
+                        node.label, attempts, matched_streak, confirm_frames
+                    ),
+                );
+                if matched_streak >= confirm_frames {
+                    on_log(
+                        "info",
+                        format!(
+                            "OCR 匹配节点 '{}' 连续 {} 帧未检测到目标文本，已确认消失。",
+                            node.label, confirm_frames
+                        ),
+                    );
+                    return Ok(NextDirective::Branch("true"));
+                }
+            } else {
+                matched_streak = 0;
+            }
+        } else if let Some(candidate) = evaluation.matched {
             matched_streak += 1;
 
             on_log(
@@ -2805,6 +3411,12 @@ async fn execute_ocr_match(
                     "confidence",
                     value_from_f64(candidate.confidence as f64),
                 );
+                set_node_output(
+                    ctx,
+                    node,
+                    "similarity",
+                    value_from_f64(candidate.similarity as f64),
+                );
 
                 on_log(
                     "info",
@@ -2819,7 +3431,7 @@ async fn execute_ocr_match(
                     ),
                 );
 
-                if click_on_match && candidate.x >= 0 && candidate.y >= 0 {
+                if click_on_match && candidate.has_position {
                     mouse::click(candidate.x, candidate.y, click_times)?;
                 }
 
@@ -2833,8 +3445,17 @@ async fn execute_ocr_match(
             on_log(
                 "warn",
                 format!(
-                    "OCR 匹配节点 '{}' 在 {}ms 内未命中（peakConfidence={:.4}，peakText='{}'），已走 false 分支。",
-                    node.label, timeout_ms, best_confidence_seen, best_text_seen
+                    "OCR 匹配节点 '{}' 在 {}ms 内{}（peakConfidence={:.4}，peakText='{}'，peakSimilarity={:.4}），已走 false 分支。",
+                    node.label,
+                    timeout_ms,
+                    if wait_disappear {
+                        "目标文本仍未消失"
+                    } else {
+                        "未命中"
+                    },
+                    best_confidence_seen,
+                    best_text_seen,
+                    best_similarity_seen
                 ),
             );
             return Ok(NextDirective::Branch("false"));
@@ -2844,113 +3465,1422 @@ async fn execute_ocr_match(
     }
 }
 
-async fn evaluate_ocr_path_blocking(
-    source_path: &str,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
-    min_confidence: f32,
-) -> CommandResult<ocr_match::OcrMatchEvaluation> {
-    let source_path = source_path.to_string();
-    let target_text = target_text.to_string();
-    let match_mode = match_mode.to_string();
+async fn execute_pixel_color(
+    node: &WorkflowNode,
+    ctx: &mut ExecutionContext,
+    on_log: &mut impl FnMut(&str, String),
+    should_cancel: &impl Fn() -> bool,
+) -> CommandResult<NextDirective> {
+    let x = get_i32(node, "x", 0);
+    let y = get_i32(node, "y", 0);
+    let radius = get_u32(node, "sampleRadius", 0).min(PIXEL_COLOR_MAX_SAMPLE_RADIUS);
+    let tolerance = get_u32(node, "tolerance", 16).min(255) as u8;
+    let wait_until_match = get_bool(node, "waitUntilMatch", false);
+    let target_text = get_string(node, "targetColor", "");
+    let target = if target_text.trim().is_empty() {
+        None
+    } else {
+        Some(pixel_color::parse_hex_color(&target_text).ok_or_else(|| {
+            CommandFlowError::Validation(format!(
+                "node '{}' targetColor must be a hex color like #RRGGBB",
+                node.id
+            ))
+        })?)
+    };
+    if wait_until_match && target.is_none() {
+        return Err(CommandFlowError::Validation(format!(
+            "node '{}' targetColor cannot be empty when waiting for a color",
+            node.id
+        )));
+    }
 
-    tokio::task::spawn_blocking(move || {
-        ocr_match::evaluate_path(
-            &source_path,
-            &target_text,
-            &match_mode,
-            case_sensitive,
-            use_regex,
-            min_confidence,
-        )
-    })
-    .await
-    .map_err(|error| CommandFlowError::Automation(format!("OCR 任务线程执行失败：{}", error)))?
-}
+    let left = (i64::from(x) - i64::from(radius)).max(0) as u32;
+    let top = (i64::from(y) - i64::from(radius)).max(0) as u32;
+    let size = radius * 2 + 1;
+    let describe_target = || target.map(|color| color.hex()).unwrap_or_default();
 
-#[allow(clippy::too_many_arguments)]
-async fn evaluate_ocr_rgba_blocking(
-    rgba: Vec<u8>,
-    width: u32,
-    height: u32,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
-    min_confidence: f32,
-) -> CommandResult<ocr_match::OcrMatchEvaluation> {
-    let target_text = target_text.to_string();
-    let match_mode = match_mode.to_string();
+    if !wait_until_match {
+        let (rgba, width, height) = screenshot::capture_region_rgba(left, top, size, size)?;
+        let color = pixel_color::average_color(&rgba, 4, width, height, (0, 0, width, height))
+            .ok_or_else(|| CommandFlowError::Automation("empty pixel sample region".to_string()))?;
+        let matched = set_pixel_color_outputs(ctx, node, color, target, tolerance);
+        on_log(
+            "info",
+            format!(
+                "像素取色节点 '{}' 坐标=({}, {}) 颜色={}，目标={}，容差={}，结果={}。",
+                node.label,
+                x,
+                y,
+                color.hex(),
+                describe_target(),
+                tolerance,
+                matched
+            ),
+        );
+        return Ok(NextDirective::Branch(if matched {
+            "true"
+        } else {
+            "false"
+        }));
+    }
 
-    tokio::task::spawn_blocking(move || {
-        ocr_match::evaluate_rgba(
-            rgba,
-            width,
-            height,
-            &target_text,
-            &match_mode,
-            case_sensitive,
-            use_regex,
-            min_confidence,
-        )
-    })
-    .await
-    .map_err(|error| CommandFlowError::Automation(format!("OCR 任务线程执行失败：{}", error)))?
+    let timeout_ms = get_u64(node, "timeoutMs", 10_000);
+    let poll_interval = Duration::from_millis(get_u64(node, "pollMs", 50).max(1));
+    let deadline = Duration::from_millis(timeout_ms);
+    let started = tokio::time::Instant::now();
+    screenshot::ensure_primary_frame_stream()?;
+
+    loop {
+        if should_cancel() {
+            let _ = screenshot::stop_primary_frame_stream();
+            return Err(CommandFlowError::Canceled);
+        }
+
+        let frame = match tokio::task::block_in_place(|| {
+            screenshot::recv_primary_frame_rgb_timeout(poll_interval)
+        }) {
+            Ok(frame) => frame,
+            Err(error) => {
+                let _ = screenshot::stop_primary_frame_stream();
+                return Err(error);
+            }
+        };
+
+        if let Some(frame) = frame {
+            let Some(color) = pixel_color::average_color(
+                frame.as_raw(),
+                3,
+                frame.width(),
+                frame.height(),
+                (left, top, size, size),
+            ) else {
+                let _ = screenshot::stop_primary_frame_stream();
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' sample point is outside the screen",
+                    node.id
+                )));
+            };
+
+            if set_pixel_color_outputs(ctx, node, color, target, tolerance) {
+                let _ = screenshot::stop_primary_frame_stream();
+                on_log(
+                    "info",
+                    format!(
+                        "像素取色节点 '{}' 坐标=({}, {}) 颜色={} 已匹配目标 {}（容差={}），耗时 {}ms。",
+                        node.label,
+                        x,
+                        y,
+                        color.hex(),
+                        describe_target(),
+                        tolerance,
+                        started.elapsed().as_millis()
+                    ),
+                );
+                return Ok(NextDirective::Branch("true"));
+            }
+        }
+
+        if started.elapsed() >= deadline {
+            let _ = screenshot::stop_primary_frame_stream();
+            on_log(
+                "warn",
+                format!(
+                    "像素取色节点 '{}' 在 {}ms 内颜色未变为 {}，已走 false 分支。",
+                    node.label,
+                    timeout_ms,
+                    describe_target()
+                ),
+            );
+            return Ok(NextDirective::Branch("false"));
+        }
+    }
 }
 
-fn execute_file_operation(
+fn set_pixel_color_outputs(
+    ctx: &mut ExecutionContext,
+    node: &WorkflowNode,
+    color: pixel_color::RgbColor,
+    target: Option<pixel_color::RgbColor>,
+    tolerance: u8,
+) -> bool {
+    set_node_output(ctx, node, "hex", Value::String(color.hex()));
+    set_node_output(ctx, node, "r", value_from_i32(i32::from(color.r)));
+    set_node_output(ctx, node, "g", value_from_i32(i32::from(color.g)));
+    set_node_output(ctx, node, "b", value_from_i32(i32::from(color.b)));
+    // 未设置目标色时仅取色，始终走 true 分支
+    match target {
+        Some(target) => color.distance(&target) <= tolerance,
+        None => true,
+    }
+}
+
+async fn execute_screen_change(
     node: &WorkflowNode,
     ctx: &mut ExecutionContext,
-    operation_override: Option<&str>,
     on_log: &mut impl FnMut(&str, String),
+    should_cancel: &impl Fn() -> bool,
 ) -> CommandResult<NextDirective> {
-    let requested = operation_override
-        .map(ToString::to_string)
-        .unwrap_or_else(|| get_string(node, "operation", "copy"));
-    let operation = normalize_system_operation_name(&requested);
+    let wait_stable = get_string(node, "mode", "change").eq_ignore_ascii_case("stable");
+    let pixel_threshold = get_u32(node, "pixelThreshold", 24).min(255) as u8;
+    let change_threshold = get_f64(node, "changeThreshold", 1.0).clamp(0.0, 100.0);
+    let stable_duration = Duration::from_millis(get_u64(node, "stableMs", 1000));
+    let timeout_ms = get_u64(node, "timeoutMs", 10_000);
+    let poll_interval = Duration::from_millis(get_u64(node, "pollMs", 50).max(1));
+    let deadline = Duration::from_millis(timeout_ms);
+    let search_region = resolve_search_region(node)?;
 
-    set_node_output(ctx, node, "operation", Value::String(requested.clone()));
+    on_log(
+        "info",
+        format!(
+            "画面变化节点 '{}' 开始{}，阈值={:.2}%，像素阈值={}。",
+            node.label,
+            if wait_stable {
+                "等待画面稳定"
+            } else {
+                "等待画面变化"
+            },
+            change_threshold,
+            pixel_threshold
+        ),
+    );
 
-    match operation.as_str() {
-        "copy" => {
-            let source_path = get_string(node, "sourcePath", "");
-            let target_path = get_string(node, "targetPath", "");
-            let overwrite = get_bool(node, "overwrite", false);
-            let recursive = get_bool(node, "recursive", true);
+    let started = tokio::time::Instant::now();
+    let mut reference: Option<GrayImage> = None;
+    let mut stability = screen_change::StabilityTimer::new(tokio::time::Instant::now());
+    let mut peak_difference = 0.0_f64;
+    screenshot::ensure_primary_frame_stream()?;
+
+    loop {
+        if should_cancel() {
+            let _ = screenshot::stop_primary_frame_stream();
+            return Err(CommandFlowError::Canceled);
+        }
+
+        let frame = match tokio::task::block_in_place(|| {
+            screenshot::recv_primary_frame_gray_timeout(poll_interval)
+        }) {
+            Ok(frame) => frame,
+            Err(error) => {
+                let _ = screenshot::stop_primary_frame_stream();
+                return Err(error);
+            }
+        };
+
+        if let Some(frame) = frame {
+            let (left, top, width, height) = match search_region {
+                Some(region) => match region.clip_to(frame.width(), frame.height()) {
+                    Some(rect) => rect,
+                    None => {
+                        let _ = screenshot::stop_primary_frame_stream();
+                        return Err(search_region_outside_error(node));
+                    }
+                },
+                None => (0, 0, frame.width(), frame.height()),
+            };
+            let current = image::imageops::crop_imm(&frame, left, top, width, height).to_image();
+
+            match reference.as_ref() {
+                None => {
+                    reference = Some(current);
+                    stability.restart(tokio::time::Instant::now());
+                }
+                Some(previous) => {
+                    let diff = screen_change::diff_gray(
+                        previous.as_raw(),
+                        current.as_raw(),
+                        width,
+                        height,
+                        pixel_threshold,
+                    );
+                    let difference = f64::from(diff.changed_ratio) * 100.0;
+                    let changed = difference > change_threshold;
+                    peak_difference = peak_difference.max(difference);
+                    set_screen_change_outputs(
+                        ctx,
+                        node,
+                        difference,
+                        diff.bounding_box,
+                        (left, top),
+                    );
+
+                    if !wait_stable && changed {
+                        let _ = screenshot::stop_primary_frame_stream();
+                        on_log(
+                            "info",
+                            format!(
+                                "画面变化节点 '{}' 检测到变化，difference={:.2}%，耗时 {}ms。",
+                                node.label,
+                                difference,
+                                started.elapsed().as_millis()
+                            ),
+                        );
+                        return Ok(NextDirective::Branch("true"));
+                    }
+
+                    if wait_stable {
+                        if changed {
+                            stability.restart(tokio::time::Instant::now());
+                        }
+                        // 稳定模式逐帧比较，变化模式始终与首帧比较
+                        reference = Some(current);
+                    }
+                }
+            }
+        }
+
+        if wait_stable && stability.is_stable(tokio::time::Instant::now(), stable_duration) {
+            let _ = screenshot::stop_primary_frame_stream();
+            on_log(
+                "info",
+                format!(
+                    "画面变化节点 '{}' 画面已稳定 {}ms，耗时 {}ms。",
+                    node.label,
+                    stable_duration.as_millis(),
+                    started.elapsed().as_millis()
+                ),
+            );
+            return Ok(NextDirective::Branch("true"));
+        }
+
+        if started.elapsed() >= deadline {
+            let _ = screenshot::stop_primary_frame_stream();
+            on_log(
+                "warn",
+                format!(
+                    "画面变化节点 '{}' 在 {}ms 内{}（peakDifference={:.2}%），已走 false 分支。",
+                    node.label,
+                    timeout_ms,
+                    if wait_stable {
+                        "画面未稳定"
+                    } else {
+                        "画面未变化"
+                    },
+                    peak_difference
+                ),
+            );
+            return Ok(NextDirective::Branch("false"));
+        }
+    }
+}
+
+fn set_screen_change_outputs(
+    ctx: &mut ExecutionContext,
+    node: &WorkflowNode,
+    difference: f64,
+    bounding_box: Option<(u32, u32, u32, u32)>,
+    origin: (u32, u32),
+) {
+    let (x, y, width, height) = bounding_box
+        .map(|(x, y, width, height)| (x + origin.0, y + origin.1, width, height))
+        .unwrap_or_default();
+    set_node_output(ctx, node, "difference", value_from_f64(difference));
+    set_node_output(ctx, node, "changeX", value_from_u64(u64::from(x)));
+    set_node_output(ctx, node, "changeY", value_from_u64(u64::from(y)));
+    set_node_output(ctx, node, "changeWidth", value_from_u64(u64::from(width)));
+    set_node_output(ctx, node, "changeHeight", value_from_u64(u64::from(height)));
+}
+
+async fn execute_visual_assert(
+    node: &WorkflowNode,
+    ctx: &mut ExecutionContext,
+    on_log: &mut impl FnMut(&str, String),
+    should_cancel: &impl Fn() -> bool,
+) -> CommandResult<NextDirective> {
+    let baseline_path = get_string(node, "baselinePath", "");
+    if baseline_path.trim().is_empty() {
+        return Err(CommandFlowError::Validation(format!(
+            "node '{}' baselinePath cannot be empty",
+            node.id
+        )));
+    }
+    let baseline_file = PathBuf::from(baseline_path.trim());
+    let tolerance = get_u32(node, "tolerance", 16).min(255) as u8;
+    let max_diff_percent = get_f64(node, "maxDiffPercent", 0.1).clamp(0.0, 100.0);
+    let fail_with_error = get_string(node, "failAction", "branch").eq_ignore_ascii_case("error");
+    let timeout_ms = get_u64(node, "timeoutMs", 0);
+    let poll_interval = Duration::from_millis(get_u64(node, "pollMs", 200).max(1));
+    let ignore_regions = parse_visual_assert_ignore_regions(node)?;
+    let capture_target = resolve_capture_target(node)?;
+    let search_region = resolve_search_region(node)?;
+
+    let record = match get_string(node, "baselineMode", "auto").as_str() {
+        "record" => true,
+        "compare" => false,
+        // 自动模式：首次运行基准图不存在时录制，之后按基准比较
+        _ => !baseline_file.is_file(),
+    };
+
+    if record {
+        let (rgba, width, height, _) =
+            capture_visual_assert_image(node, &capture_target, search_region)?;
+        if let Some(parent) = baseline_file
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(|error| {
+                CommandFlowError::Io(format!("{}: {}", parent.display(), error))
+            })?;
+        }
+        screenshot::save_rgba_image(path_to_string(&baseline_file)?, rgba, width, height)?;
+        set_visual_assert_outputs(ctx, node, None, (0, 0), "", true);
+        on_log(
+            "info",
+            format!(
+                "视觉断言节点 '{}' 已录制基准图（{}x{}）：{}",
+                node.label,
+                width,
+                height,
+                baseline_file.display()
+            ),
+        );
+        return Ok(NextDirective::Branch("true"));
+    }
+
+    if !baseline_file.is_file() {
+        return Err(CommandFlowError::Validation(format!(
+            "node '{}' baseline image '{}' does not exist",
+            node.id,
+            baseline_file.display()
+        )));
+    }
+    let baseline = image::open(&baseline_file)
+        .map_err(|error| {
+            CommandFlowError::Automation(format!("{}: {}", baseline_file.display(), error))
+        })?
+        .to_rgba8();
+
+    let started = tokio::time::Instant::now();
+    let deadline = Duration::from_millis(timeout_ms);
+    let mut attempts: u64 = 0;
+    loop {
+        if should_cancel() {
+            return Err(CommandFlowError::Canceled);
+        }
+
+        let (rgba, width, height, origin) =
+            capture_visual_assert_image(node, &capture_target, search_region)?;
+        let actual = RgbaImage::from_raw(width, height, rgba)
+            .ok_or_else(|| CommandFlowError::Automation("invalid rgba buffer size".to_string()))?;
+        attempts += 1;
+
+        // 尺寸不一致时无法逐像素比较，直接判定失败
+        let diff = visual_assert::compare(&baseline, &actual, tolerance, &ignore_regions);
+        let diff_percent = diff
+            .as_ref()
+            .map(|diff| f64::from(diff.mismatch_ratio) * 100.0)
+            .unwrap_or(100.0);
+        let passed = diff.is_some() && diff_percent <= max_diff_percent;
+
+        if passed {
+            set_visual_assert_outputs(ctx, node, diff.as_ref(), origin, "", false);
+            on_log(
+                "info",
+                format!(
+                    "视觉断言节点 '{}' 通过，差异 {:.3}%（阈值 {:.3}%），尝试 {} 次。",
+                    node.label, diff_percent, max_diff_percent, attempts
+                ),
+            );
+            return Ok(NextDirective::Branch("true"));
+        }
+
+        if started.elapsed() >= deadline {
+            let debug_dir = prepare_node_debug_dir(node, "commandflow-visual-assert-debug")?;
+            save_visual_assert_debug_artifacts(
+                &debug_dir,
+                &baseline_file,
+                &actual,
+                diff.as_ref(),
+                node,
+                max_diff_percent,
+                tolerance,
+            )?;
+            let diff_path = debug_dir.join("diff.png");
+            let diff_path = if diff.is_some() {
+                diff_path.display().to_string()
+            } else {
+                String::new()
+            };
+            set_visual_assert_outputs(ctx, node, diff.as_ref(), origin, &diff_path, false);
+
+            let reason = if diff.is_some() {
+                format!(
+                    "差异 {:.3}% 超过阈值 {:.3}%",
+                    diff_percent, max_diff_percent
+                )
+            } else {
+                format!(
+                    "截图尺寸 {}x{} 与基准图 {}x{} 不一致",
+                    width,
+                    height,
+                    baseline.width(),
+                    baseline.height()
+                )
+            };
+            let message = format!(
+                "视觉断言节点 '{}' 未通过：{}，调试产物：{}",
+                node.label,
+                reason,
+                debug_dir.display()
+            );
+            if fail_with_error {
+                return Err(CommandFlowError::Automation(message));
+            }
+            on_log("warn", format!("{}，已走 false 分支。", message));
+            return Ok(NextDirective::Branch("false"));
+        }
+
+        interruptible_sleep(poll_interval, should_cancel).await?;
+    }
+}
+
+fn capture_visual_assert_image(
+    node: &WorkflowNode,
+    capture_target: &screenshot::CaptureTarget,
+    search_region: Option<SearchRegion>,
+) -> CommandResult<CroppedRgba> {
+    let capture = screenshot::capture_target_rgba(capture_target)?;
+    let Some(region) = search_region else {
+        return Ok((capture.rgba, capture.width, capture.height, capture.origin));
+    };
+    let (rgba, width, height, crop_origin) = crop_rgba_to_region(
+        node,
+        capture.rgba,
+        capture.width,
+        capture.height,
+        region.relative_to(capture.origin),
+    )?;
+    Ok((
+        rgba,
+        width,
+        height,
+        offset_point(crop_origin, capture.origin),
+    ))
+}
+
+fn parse_visual_assert_ignore_regions(
+    node: &WorkflowNode,
+) -> CommandResult<Vec<visual_assert::IgnoreRegion>> {
+    let raw = node
+        .params
+        .get("ignoreRegions")
+        .cloned()
+        .unwrap_or(Value::Null);
+    let parsed = match raw {
+        Value::String(text) if text.trim().is_empty() => return Ok(Vec::new()),
+        Value::String(text) => serde_json::from_str::<Value>(text.trim()).map_err(|error| {
+            CommandFlowError::Validation(format!(
+                "node '{}' ignoreRegions 解析失败：{}",
+                node.id, error
+            ))
+        })?,
+        Value::Null => return Ok(Vec::new()),
+        other => other,
+    };
+    let Some(items) = parsed.as_array() else {
+        return Err(CommandFlowError::Validation(format!(
+            "node '{}' ignoreRegions must be an array",
+            node.id
+        )));
+    };
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let number = |key: &str| {
+                item.get(key)
+                    .and_then(Value::as_f64)
+                    .unwrap_or(0.0)
+                    .round()
+                    .max(0.0) as u32
+            };
+            let region = visual_assert::IgnoreRegion {
+                left: number("x"),
+                top: number("y"),
+                width: number("width"),
+                height: number("height"),
+            };
+            if region.width == 0 || region.height == 0 {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' ignore region #{} width and height must be greater than 0",
+                    node.id,
+                    index + 1
+                )));
+            }
+            Ok(region)
+        })
+        .collect()
+}
+
+fn set_visual_assert_outputs(
+    ctx: &mut ExecutionContext,
+    node: &WorkflowNode,
+    diff: Option<&visual_assert::VisualDiff>,
+    origin: (i32, i32),
+    diff_path: &str,
+    recorded: bool,
+) {
+    let diff_percent = diff
+        .map(|diff| f64::from(diff.mismatch_ratio) * 100.0)
+        .unwrap_or(if recorded { 0.0 } else { 100.0 });
+    let (x, y, width, height) = diff
+        .and_then(|diff| diff.bounding_box)
+        .map(|(x, y, width, height)| {
+            (
+                origin.0.saturating_add(x as i32),
+                origin.1.saturating_add(y as i32),
+                width,
+                height,
+            )
+        })
+        .unwrap_or_default();
+    set_node_output(ctx, node, "diffPercent", value_from_f64(diff_percent));
+    set_node_output(
+        ctx,
+        node,
+        "mismatchedPixels",
+        value_from_u64(diff.map(|diff| diff.mismatched_pixels).unwrap_or(0)),
+    );
+    set_node_output(ctx, node, "diffX", value_from_i32(x));
+    set_node_output(ctx, node, "diffY", value_from_i32(y));
+    set_node_output(ctx, node, "diffWidth", value_from_u64(u64::from(width)));
+    set_node_output(ctx, node, "diffHeight", value_from_u64(u64::from(height)));
+    set_node_output(ctx, node, "diffPath", Value::String(diff_path.to_string()));
+    set_node_output(ctx, node, "recorded", Value::Bool(recorded));
+}
+
+fn save_visual_assert_debug_artifacts(
+    debug_dir: &Path,
+    baseline_file: &Path,
+    actual: &RgbaImage,
+    diff: Option<&visual_assert::VisualDiff>,
+    node: &WorkflowNode,
+    max_diff_percent: f64,
+    tolerance: u8,
+) -> CommandResult<()> {
+    let extension = baseline_file
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("png");
+    fs::copy(
+        baseline_file,
+        debug_dir.join(format!("baseline.{}", extension)),
+    )
+    .map_err(|error| CommandFlowError::Io(error.to_string()))?;
+    let actual_path = debug_dir.join("actual.png");
+    actual
+        .save(&actual_path)
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    if let Some(diff) = diff {
+        diff.heatmap
+            .save(debug_dir.join("diff.png"))
+            .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    }
+
+    let payload = serde_json::json!({
+        "node": {
+            "id": &node.id,
+            "label": &node.label,
+        },
+        "baselinePath": baseline_file.display().to_string(),
+        "width": actual.width(),
+        "height": actual.height(),
+        "tolerance": tolerance,
+        "maxDiffPercent": max_diff_percent,
+        "diffPercent": diff.map(|diff| f64::from(diff.mismatch_ratio) * 100.0),
+        "mismatchedPixels": diff.map(|diff| diff.mismatched_pixels),
+        "comparedPixels": diff.map(|diff| diff.compared_pixels),
+        "maxChannelDiff": diff.map(|diff| diff.max_channel_diff),
+        "boundingBox": diff.and_then(|diff| diff.bounding_box).map(|(x, y, width, height)| {
+            serde_json::json!({ "x": x, "y": y, "width": width, "height": height })
+        }),
+    });
+    let metadata = serde_json::to_string_pretty(&payload)
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    fs::write(debug_dir.join("metadata.json"), metadata)
+        .map_err(|error| CommandFlowError::Io(error.to_string()))
+}
+
+enum WaitAnyProbe {
+    Image(Box<image_match::TemplateMatcher>),
+    Ocr(usize),
+}
+
+struct WaitAnyTarget {
+    name: String,
+    probe: WaitAnyProbe,
+    streak: u64,
+}
+
+fn parse_wait_any_targets(
+    node: &WorkflowNode,
+) -> CommandResult<(Vec<WaitAnyTarget>, Vec<ocr_match::OcrTextQuery>)> {
+    let raw = node.params.get("targets").cloned().unwrap_or(Value::Null);
+    let parsed = match raw {
+        Value::String(text) if !text.trim().is_empty() => {
+            serde_json::from_str::<Value>(text.trim()).map_err(|error| {
+                CommandFlowError::Validation(format!(
+                    "node '{}' targets 解析失败：{}",
+                    node.id, error
+                ))
+            })?
+        }
+        other => other,
+    };
+    let Some(items) = parsed.as_array().filter(|items| !items.is_empty()) else {
+        return Err(CommandFlowError::Validation(format!(
+            "node '{}' targets must be a non-empty array",
+            node.id
+        )));
+    };
+
+    let mut targets = Vec::with_capacity(items.len());
+    let mut ocr_queries = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let field = |key: &str| item.get(key).and_then(Value::as_str).unwrap_or("").trim();
+        let name = field("name").to_string();
+        if name.is_empty() {
+            return Err(CommandFlowError::Validation(format!(
+                "node '{}' target #{} name cannot be empty",
+                node.id,
+                index + 1
+            )));
+        }
+        if targets
+            .iter()
+            .any(|target: &WaitAnyTarget| target.name == name)
+        {
+            return Err(CommandFlowError::Validation(format!(
+                "node '{}' target name '{}' is duplicated",
+                node.id, name
+            )));
+        }
+
+        let number =
+            |key: &str, default: f64| item.get(key).and_then(Value::as_f64).unwrap_or(default);
+        let probe = match field("type") {
+            "ocr" | "text" => {
+                let text = field("text");
+                if text.is_empty() {
+                    return Err(CommandFlowError::Validation(format!(
+                        "node '{}' target '{}' text cannot be empty",
+                        node.id, name
+                    )));
+                }
+                ocr_queries.push(build_ocr_text_query(text, |key| item.get(key)));
+                WaitAnyProbe::Ocr(ocr_queries.len() - 1)
+            }
+            _ => {
+                let template_path = field("templatePath");
+                if template_path.is_empty() {
+                    return Err(CommandFlowError::Validation(format!(
+                        "node '{}' target '{}' templatePath cannot be empty",
+                        node.id, name
+                    )));
+                }
+                let threshold = number("threshold", 0.99).clamp(0.0, 1.0) as f32;
+                WaitAnyProbe::Image(Box::new(image_match::TemplateMatcher::from_path(
+                    template_path,
+                    threshold,
+                )?))
+            }
+        };
+
+        targets.push(WaitAnyTarget {
+            name,
+            probe,
+            streak: 0,
+        });
+    }
+
+    Ok((targets, ocr_queries))
+}
+
+async fn execute_wait_any(
+    node: &WorkflowNode,
+    ctx: &mut ExecutionContext,
+    on_log: &mut impl FnMut(&str, String),
+    should_cancel: &impl Fn() -> bool,
+) -> CommandResult<NextDirective> {
+    let (mut targets, ocr_queries) = parse_wait_any_targets(node)?;
+    for target in &targets {
+        if let WaitAnyProbe::Image(matcher) = &target.probe {
+            if let Some(reason) = matcher.gpu_fallback_reason() {
+                on_log(
+                    "info",
+                    format!(
+                        "多目标等待节点 '{}' 目标 '{}' 回退到 CPU：{}。",
+                        node.label, target.name, reason
+                    ),
+                );
+            }
+        }
+    }
+    let ocr_models = if ocr_queries.is_empty() {
+        None
+    } else {
+        Some(resolve_ocr_models(node)?)
+    };
+    let timeout_ms = get_u64(node, "timeoutMs", 10_000);
+    let poll_interval = Duration::from_millis(get_u64(node, "pollMs", 50).max(1));
+    let confirm_frames = get_u64(node, "confirmFrames", 1).max(1);
+    let click_on_match = get_bool(node, "clickOnMatch", false);
+    let deadline = Duration::from_millis(timeout_ms);
+
+    on_log(
+        "info",
+        format!(
+            "多目标等待节点 '{}' 开始等待：{}。",
+            node.label,
+            targets
+                .iter()
+                .map(|target| target.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" / ")
+        ),
+    );
+
+    let started = tokio::time::Instant::now();
+    let mut attempts: u64 = 0;
+    screenshot::ensure_primary_frame_stream()?;
+
+    loop {
+        if should_cancel() {
+            let _ = screenshot::stop_primary_frame_stream();
+            return Err(CommandFlowError::Canceled);
+        }
+
+        let frame = match tokio::task::block_in_place(|| {
+            screenshot::recv_primary_frame_gray_timeout(poll_interval)
+        }) {
+            Ok(frame) => frame,
+            Err(error) => {
+                let _ = screenshot::stop_primary_frame_stream();
+                return Err(error);
+            }
+        };
+
+        if let Some(frame) = frame {
+            attempts += 1;
+            let ocr_results = if let Some(models) = ocr_models.clone() {
+                let (width, height) = frame.dimensions();
+                let rgba = frame
+                    .as_raw()
+                    .iter()
+                    .flat_map(|value| [*value, *value, *value, 255])
+                    .collect::<Vec<u8>>();
+                let queries = ocr_queries.clone();
+                match tokio::task::spawn_blocking(move || {
+                    ocr_match::evaluate_rgba_queries(&models, rgba, width, height, &queries)
+                })
+                .await
+                .map_err(|error| CommandFlowError::Automation(error.to_string()))
+                .and_then(|result| result)
+                {
+                    Ok(results) => results,
+                    Err(error) => {
+                        let _ = screenshot::stop_primary_frame_stream();
+                        return Err(error);
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+            let frame = DynamicImage::ImageLuma8(frame);
+
+            // 同一帧内多个目标同时命中时，按配置顺序取第一个
+            let mut winner: Option<(String, i32, i32)> = None;
+            for target in targets.iter_mut() {
+                let point = match &mut target.probe {
+                    WaitAnyProbe::Image(matcher) => matcher.evaluate(&frame).matched_point,
+                    WaitAnyProbe::Ocr(index) => ocr_results
+                        .get(*index)
+                        .and_then(|evaluation| evaluation.matched.as_ref())
+                        .map(|candidate| (candidate.x, candidate.y)),
+                };
+                target.streak = if point.is_some() {
+                    target.streak + 1
+                } else {
+                    0
+                };
+                if winner.is_none() && target.streak >= confirm_frames {
+                    winner = point.map(|(x, y)| (target.name.clone(), x, y));
+                }
+            }
+
+            if let Some((name, x, y)) = winner {
+                let _ = screenshot::stop_primary_frame_stream();
+                set_node_output(ctx, node, "matchedName", Value::String(name.clone()));
+                set_node_output(ctx, node, "matchX", value_from_i32(x));
+                set_node_output(ctx, node, "matchY", value_from_i32(y));
+                on_log(
+                    "info",
+                    format!(
+                        "多目标等待节点 '{}' 第 {} 帧命中 '{}'，坐标=({}, {})，耗时 {}ms。",
+                        node.label,
+                        attempts,
+                        name,
+                        x,
+                        y,
+                        started.elapsed().as_millis()
+                    ),
+                );
+                if click_on_match {
+                    mouse::click(x, y, get_u64(node, "clickTimes", 1).max(1) as usize)?;
+                }
+                return Ok(NextDirective::NamedBranch(format!(
+                    "{}{}",
+                    WAIT_ANY_HANDLE_PREFIX, name
+                )));
+            }
+        }
+
+        if started.elapsed() >= deadline {
+            let _ = screenshot::stop_primary_frame_stream();
+            set_node_output(ctx, node, "matchedName", Value::String(String::new()));
+            on_log(
+                "warn",
+                format!(
+                    "多目标等待节点 '{}' 在 {}ms 内没有任何目标出现，已走 timeout 分支。",
+                    node.label, timeout_ms
+                ),
+            );
+            return Ok(NextDirective::Branch("false"));
+        }
+    }
+}
+
+async fn execute_ocr_read(
+    node: &WorkflowNode,
+    ctx: &mut ExecutionContext,
+    on_log: &mut impl FnMut(&str, String),
+) -> CommandResult<NextDirective> {
+    let source_path = get_image_path(node, "sourcePath", &ctx.run_id)?;
+    let min_confidence = get_f32(node, "minConfidence", 0.5).clamp(0.0, 1.0);
+    let grouping = ocr_layout::OcrGrouping::parse(&get_string(node, "grouping", "lines"));
+    let search_region = resolve_search_region(node)?;
+    let models = resolve_ocr_models(node)?;
+
+    let blocks = if !source_path.trim().is_empty() && search_region.is_none() {
+        let path = source_path.clone();
+        tokio::task::spawn_blocking(move || ocr_match::read_path(&models, &path, min_confidence))
+            .await
+            .map_err(|error| CommandFlowError::Automation(error.to_string()))??
+    } else {
+        let (rgba, width, height) = if source_path.trim().is_empty() {
+            screenshot::capture_fullscreen_rgba()?
+        } else {
+            let source = image::open(&source_path)
+                .map_err(|error| CommandFlowError::Automation(error.to_string()))?
+                .to_rgba8();
+            let (width, height) = source.dimensions();
+            (source.into_raw(), width, height)
+        };
+        let (rgba, width, height, origin) = match search_region {
+            Some(region) => crop_rgba_to_region(node, rgba, width, height, region)?,
+            None => (rgba, width, height, (0, 0)),
+        };
+        let blocks = tokio::task::spawn_blocking(move || {
+            ocr_match::read_rgba(&models, rgba, width, height, min_confidence)
+        })
+        .await
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))??;
+        blocks
+            .into_iter()
+            .map(|block| block.offset(origin.0, origin.1))
+            .collect()
+    };
+
+    let blocks = ocr_layout::sort_reading_order(blocks);
+    let text = ocr_layout::compose_text(&blocks, grouping);
+    set_node_output(ctx, node, "text", Value::String(text.clone()));
+    set_node_output(
+        ctx,
+        node,
+        "blocks",
+        serde_json::to_value(&blocks).unwrap_or(Value::Array(Vec::new())),
+    );
+    set_node_output(
+        ctx,
+        node,
+        "count",
+        Value::Number(Number::from(blocks.len() as u64)),
+    );
+    on_log(
+        "info",
+        format!(
+            "OCR 读取节点 '{}' 识别到 {} 个文本块，共 {} 个字符。",
+            node.label,
+            blocks.len(),
+            text.chars().count()
+        ),
+    );
+    Ok(NextDirective::Default)
+}
+
+fn build_ocr_text_query<'a>(
+    target_text: &str,
+    lookup: impl Fn(&str) -> Option<&'a Value>,
+) -> ocr_match::OcrTextQuery {
+    let flag = |key: &str| lookup(key).and_then(Value::as_bool).unwrap_or(false);
+    let number = |key: &str, default: f64| lookup(key).and_then(Value::as_f64).unwrap_or(default);
+    let string =
+        |key: &str, default: &'static str| lookup(key).and_then(Value::as_str).unwrap_or(default);
+
+    ocr_match::OcrTextQuery {
+        target_text: target_text.to_string(),
+        match_mode: string("matchMode", "contains").to_string(),
+        case_sensitive: flag("caseSensitive"),
+        use_regex: flag("useRegex"),
+        min_confidence: number("minConfidence", 0.5).clamp(0.0, 1.0) as f32,
+        min_similarity: number("minSimilarity", 0.8).clamp(0.0, 1.0) as f32,
+        similarity_algorithm: text_similarity::SimilarityAlgorithm::parse(string(
+            "similarityAlgorithm",
+            "levenshtein",
+        )),
+        normalization: text_similarity::TextNormalization {
+            strip_whitespace: flag("normalizeWhitespace"),
+            fold_width: flag("normalizeWidth"),
+            fold_confusables: flag("normalizeConfusables"),
+        },
+    }
+}
+
+fn resolve_ocr_models(node: &WorkflowNode) -> CommandResult<ocr_models::OcrModelSet> {
+    ocr_models::resolve_model_set(
+        &get_string(node, "ocrModelDir", ""),
+        &get_string(node, "ocrLanguage", "ch"),
+    )
+}
+
+async fn evaluate_ocr_path_blocking(
+    models: &ocr_models::OcrModelSet,
+    source_path: &str,
+    query: &ocr_match::OcrTextQuery,
+) -> CommandResult<ocr_match::OcrMatchEvaluation> {
+    let models = models.clone();
+    let source_path = source_path.to_string();
+    let query = query.clone();
+
+    tokio::task::spawn_blocking(move || ocr_match::evaluate_path(&models, &source_path, &query))
+    .await
+    .map_err(|error| CommandFlowError::Automation(format!("OCR 任务线程执行失败：{}", error)))?
+}
+
+async fn evaluate_ocr_rgba_blocking(
+    models: &ocr_models::OcrModelSet,
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+    query: &ocr_match::OcrTextQuery,
+) -> CommandResult<ocr_match::OcrMatchEvaluation> {
+    let models = models.clone();
+    let query = query.clone();
+
+    tokio::task::spawn_blocking(move || {
+        ocr_match::evaluate_rgba(&models, rgba, width, height, &query)
+    })
+    .await
+    .map_err(|error| CommandFlowError::Automation(format!("OCR 任务线程执行失败：{}", error)))?
+}
+
+async fn execute_file_operation(
+    node: &WorkflowNode,
+    ctx: &mut ExecutionContext,
+    operation_override: Option<&str>,
+    on_log: &mut impl FnMut(&str, String),
+    should_cancel: &impl Fn() -> bool,
+) -> CommandResult<NextDirective> {
+    let requested = operation_override
+        .map(ToString::to_string)
+        .unwrap_or_else(|| get_string(node, "operation", "copy"));
+    let operation = normalize_system_operation_name(&requested);
+
+    set_node_output(ctx, node, "operation", Value::String(requested.clone()));
+
+    match operation.as_str() {
+        "copy" => {
+            let source_path = get_string(node, "sourcePath", "");
+            let target_path = get_string(node, "targetPath", "");
+            let overwrite = get_bool(node, "overwrite", false);
+            let recursive = get_bool(node, "recursive", true);
+
+            if source_path.trim().is_empty() || target_path.trim().is_empty() {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' sourcePath/targetPath cannot be empty",
+                    node.id
+                )));
+            }
+
+            match get_file_safety_mode(node).as_str() {
+                "preview" => {
+                    let change = file_journal::plan_change(
+                        "copy",
+                        Some(&source_path),
+                        &target_path,
+                        overwrite,
+                    );
+                    record_planned_file_change(ctx, node, change, on_log)?;
+                }
+                "journal" => {
+                    let entry = run_journal(ctx)?.copy_path(
+                        &node.id,
+                        &source_path,
+                        &target_path,
+                        overwrite,
+                        recursive,
+                    )?;
+                    set_file_journal_outputs(ctx, node, &entry);
+                }
+                _ => file_ops::copy_path(&source_path, &target_path, overwrite, recursive)?,
+            }
+            set_node_output(ctx, node, "targetPath", Value::String(target_path));
+            set_node_output(ctx, node, "action", Value::String("copy".to_string()));
+        }
+        "move" => {
+            let source_path = get_string(node, "sourcePath", "");
+            let target_path = get_string(node, "targetPath", "");
+            let overwrite = get_bool(node, "overwrite", false);
+
+            if source_path.trim().is_empty() || target_path.trim().is_empty() {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' sourcePath/targetPath cannot be empty",
+                    node.id
+                )));
+            }
+
+            match get_file_safety_mode(node).as_str() {
+                "preview" => {
+                    let change = file_journal::plan_change(
+                        "move",
+                        Some(&source_path),
+                        &target_path,
+                        overwrite,
+                    );
+                    record_planned_file_change(ctx, node, change, on_log)?;
+                }
+                "journal" => {
+                    let entry = run_journal(ctx)?.move_path(
+                        &node.id,
+                        &source_path,
+                        &target_path,
+                        overwrite,
+                    )?;
+                    set_file_journal_outputs(ctx, node, &entry);
+                }
+                _ => file_ops::move_path(&source_path, &target_path, overwrite)?,
+            }
+            set_node_output(ctx, node, "targetPath", Value::String(target_path));
+            set_node_output(ctx, node, "action", Value::String("move".to_string()));
+        }
+        "delete" => {
+            let path = get_string(node, "path", "");
+            let recursive = get_bool(node, "recursive", true);
+
+            if path.trim().is_empty() {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' path cannot be empty",
+                    node.id
+                )));
+            }
+
+            match get_file_safety_mode(node).as_str() {
+                "preview" => {
+                    let change = file_journal::plan_change("delete", None, &path, false);
+                    record_planned_file_change(ctx, node, change, on_log)?;
+                }
+                "journal" => {
+                    let entry = run_journal(ctx)?.delete_path(&node.id, &path, recursive)?;
+                    set_file_journal_outputs(ctx, node, &entry);
+                    on_log(
+                        "info",
+                        format!(
+                            "删除节点 '{}' 已将 '{}' 移入本次运行的回收区，可通过撤销恢复。",
+                            node.label, path
+                        ),
+                    );
+                }
+                _ => file_ops::delete_path(&path, recursive)?,
+            }
+            set_node_output(ctx, node, "path", Value::String(path));
+            set_node_output(ctx, node, "action", Value::String("delete".to_string()));
+        }
+        "readtext" => {
+            let path_raw = get_string(node, "path", "");
+            let path = resolve_text_template(&path_raw, &ctx.variables);
+            if path.trim().is_empty() {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' path cannot be empty",
+                    node.id
+                )));
+            }
+
+            let encoding = get_string(node, "encoding", "auto");
+            let decoded = file_ops::read_text(&path, &encoding).map_err(|error| {
+                CommandFlowError::Io(format!("读取文本文件失败 '{}': {}", path, error))
+            })?;
+            if decoded.had_errors {
+                on_log(
+                    "warn",
+                    format!(
+                        "文本读取节点 '{}' 按 {} 解码时遇到无效字节，已替换为占位字符。",
+                        node.label, decoded.encoding
+                    ),
+                );
+            }
+            let content = file_ops::normalize_line_endings(
+                &decoded.text,
+                &get_string(node, "lineEnding", "keep"),
+            );
+            set_node_output(ctx, node, "text", Value::String(content.clone()));
+            set_node_output(
+                ctx,
+                node,
+                "encoding",
+                Value::String(decoded.encoding.clone()),
+            );
+            set_node_output(ctx, node, "hadBom", Value::Bool(decoded.had_bom));
+            set_node_output(ctx, node, "action", Value::String("readText".to_string()));
+            let output_var = get_string(node, "outputVar", "fileText").trim().to_string();
+
+            if !output_var.is_empty() {
+                ctx.variables
+                    .insert(output_var.clone(), Value::String(content.clone()));
+                on_log(
+                    "info",
+                    format!(
+                        "文本读取节点 '{}' 已按 {} 读取 {} 字符到变量 '{}'。",
+                        node.label,
+                        decoded.encoding,
+                        content.chars().count(),
+                        output_var
+                    ),
+                );
+            } else {
+                on_log(
+                    "info",
+                    format!(
+                        "文本读取节点 '{}' 已按 {} 读取 {} 字符（未配置输出变量）。",
+                        node.label,
+                        decoded.encoding,
+                        content.chars().count()
+                    ),
+                );
+            }
+        }
+        "writetext" => {
+            let path_raw = get_string(node, "path", "");
+            let path = resolve_text_template(&path_raw, &ctx.variables);
+            if path.trim().is_empty() {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' path cannot be empty",
+                    node.id
+                )));
+            }
+
+            let text = file_ops::normalize_line_endings(
+                &resolve_text_input(node, &ctx.variables),
+                &get_string(node, "lineEnding", "keep"),
+            );
+            let encoding = get_string(node, "encoding", "utf-8");
+            let append = get_bool(node, "append", false);
+            let create_parent_dir = get_bool(node, "createParentDir", true);
+            set_node_output(ctx, node, "path", Value::String(path.clone()));
+            set_node_output(ctx, node, "action", Value::String("writeText".to_string()));
+
+            match get_file_safety_mode(node).as_str() {
+                "preview" => {
+                    let change = file_journal::plan_change("writeText", None, &path, !append);
+                    record_planned_file_change(ctx, node, change, on_log)?;
+                    return Ok(NextDirective::Default);
+                }
+                "journal" => {
+                    let entry = run_journal(ctx)?.write_file(&node.id, &path, || {
+                        write_text_file(&path, &text, &encoding, append, create_parent_dir)
+                    })?;
+                    set_file_journal_outputs(ctx, node, &entry);
+                }
+                _ => write_text_file(&path, &text, &encoding, append, create_parent_dir)?,
+            }
+
+            on_log(
+                "info",
+                format!(
+                    "文本写入节点 '{}' 已按 {} {} {} 字符到 '{}'。",
+                    node.label,
+                    encoding,
+                    if append { "追加" } else { "写入" },
+                    text.chars().count(),
+                    path
+                ),
+            );
+        }
+        "listfiles" => {
+            let dir_raw = get_string(node, "path", "");
+            let dir = resolve_text_template(&dir_raw, &ctx.variables);
+            if dir.trim().is_empty() {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' path cannot be empty",
+                    node.id
+                )));
+            }
+
+            let options = file_ops::ListFilesOptions {
+                patterns: get_glob_patterns(node, "patterns"),
+                recursive: get_bool(node, "recursive", false),
+                include_files: true,
+                include_dirs: get_bool(node, "includeDirs", false),
+                sort_by: get_string(node, "sortBy", "name"),
+                descending: get_bool(node, "descending", false),
+                limit: get_u64(node, "limit", 0) as usize,
+            };
+            let entries = file_ops::list_files(&dir, &options)?;
+            let files = serde_json::to_value(&entries)
+                .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+            let paths = Value::Array(
+                entries
+                    .iter()
+                    .map(|entry| Value::String(entry.path.clone()))
+                    .collect(),
+            );
+            let first = entries
+                .first()
+                .map(|entry| entry.path.clone())
+                .unwrap_or_default();
+
+            set_node_output(ctx, node, "files", files.clone());
+            set_node_output(ctx, node, "paths", paths);
+            set_node_output(ctx, node, "count", value_from_u64(entries.len() as u64));
+            set_node_output(ctx, node, "first", Value::String(first));
+            set_node_output(ctx, node, "action", Value::String("listFiles".to_string()));
+
+            let output_var = get_string(node, "listOutputVar", "fileList")
+                .trim()
+                .to_string();
+            if !output_var.is_empty() {
+                ctx.variables.insert(output_var, files);
+            }
 
-            if source_path.trim().is_empty() || target_path.trim().is_empty() {
+            on_log(
+                "info",
+                format!(
+                    "列出文件节点 '{}' 在 '{}' 中找到 {} 项（排序={}{}）。",
+                    node.label,
+                    dir,
+                    entries.len(),
+                    options.sort_by,
+                    if options.descending { "，降序" } else { "" }
+                ),
+            );
+        }
+        "archivecreate" => {
+            let source_path =
+                resolve_text_template(&get_string(node, "sourcePath", ""), &ctx.variables);
+            let archive_path =
+                resolve_text_template(&get_string(node, "targetPath", ""), &ctx.variables);
+            if source_path.trim().is_empty() || archive_path.trim().is_empty() {
                 return Err(CommandFlowError::Validation(format!(
                     "node '{}' sourcePath/targetPath cannot be empty",
                     node.id
                 )));
             }
 
-            file_ops::copy_path(&source_path, &target_path, overwrite, recursive)?;
-            set_node_output(ctx, node, "targetPath", Value::String(target_path));
-            set_node_output(ctx, node, "action", Value::String("copy".to_string()));
-        }
-        "move" => {
-            let source_path = get_string(node, "sourcePath", "");
-            let target_path = get_string(node, "targetPath", "");
-            let overwrite = get_bool(node, "overwrite", false);
+            let options = file_ops::ArchiveCreateOptions {
+                patterns: get_glob_patterns(node, "patterns"),
+                recursive: get_bool(node, "recursive", true),
+                compression_level: get_u64(node, "compressionLevel", 6) as i64,
+                overwrite: get_bool(node, "overwrite", false),
+            };
+            let (source, target) = (source_path.clone(), archive_path.clone());
+            let entries = tokio::task::spawn_blocking(move || {
+                file_ops::create_zip(&source, &target, &options)
+            })
+            .await
+            .map_err(|error| {
+                CommandFlowError::Automation(format!("压缩任务执行失败：{}", error))
+            })??;
 
-            if source_path.trim().is_empty() || target_path.trim().is_empty() {
+            set_node_output(ctx, node, "targetPath", Value::String(archive_path.clone()));
+            set_node_output(
+                ctx,
+                node,
+                "entries",
+                Value::Array(entries.iter().cloned().map(Value::String).collect()),
+            );
+            set_node_output(ctx, node, "count", value_from_u64(entries.len() as u64));
+            set_node_output(
+                ctx,
+                node,
+                "action",
+                Value::String("archiveCreate".to_string()),
+            );
+
+            on_log(
+                "info",
+                format!(
+                    "压缩节点 '{}' 已将 {} 个文件打包到 '{}'。",
+                    node.label,
+                    entries.len(),
+                    archive_path
+                ),
+            );
+        }
+        "archiveextract" => {
+            let archive_path =
+                resolve_text_template(&get_string(node, "sourcePath", ""), &ctx.variables);
+            let target_dir =
+                resolve_text_template(&get_string(node, "targetPath", ""), &ctx.variables);
+            if archive_path.trim().is_empty() || target_dir.trim().is_empty() {
                 return Err(CommandFlowError::Validation(format!(
                     "node '{}' sourcePath/targetPath cannot be empty",
                     node.id
                 )));
             }
 
-            file_ops::move_path(&source_path, &target_path, overwrite)?;
-            set_node_output(ctx, node, "targetPath", Value::String(target_path));
-            set_node_output(ctx, node, "action", Value::String("move".to_string()));
-        }
-        "delete" => {
-            let path = get_string(node, "path", "");
-            let recursive = get_bool(node, "recursive", true);
+            let password = get_string(node, "password", "");
+            let options = file_ops::ArchiveExtractOptions {
+                patterns: get_glob_patterns(node, "patterns"),
+                password: (!password.is_empty()).then_some(password),
+                overwrite: get_bool(node, "overwrite", false),
+            };
+            let (source, target) = (archive_path.clone(), target_dir.clone());
+            let extracted = tokio::task::spawn_blocking(move || {
+                file_ops::extract_zip(&source, &target, &options)
+            })
+            .await
+            .map_err(|error| {
+                CommandFlowError::Automation(format!("解压任务执行失败：{}", error))
+            })??;
+
+            set_node_output(ctx, node, "targetPath", Value::String(target_dir.clone()));
+            set_node_output(
+                ctx,
+                node,
+                "paths",
+                Value::Array(extracted.iter().cloned().map(Value::String).collect()),
+            );
+            set_node_output(ctx, node, "count", value_from_u64(extracted.len() as u64));
+            set_node_output(
+                ctx,
+                node,
+                "action",
+                Value::String("archiveExtract".to_string()),
+            );
 
+            on_log(
+                "info",
+                format!(
+                    "解压节点 '{}' 已从 '{}' 解压 {} 个文件到 '{}'。",
+                    node.label,
+                    archive_path,
+                    extracted.len(),
+                    target_dir
+                ),
+            );
+        }
+        "fileinfo" => {
+            let path_raw = get_string(node, "path", "");
+            let path = resolve_text_template(&path_raw, &ctx.variables);
             if path.trim().is_empty() {
                 return Err(CommandFlowError::Validation(format!(
                     "node '{}' path cannot be empty",
@@ -2958,11 +4888,23 @@ fn execute_file_operation(
                 )));
             }
 
-            file_ops::delete_path(&path, recursive)?;
-            set_node_output(ctx, node, "path", Value::String(path));
-            set_node_output(ctx, node, "action", Value::String("delete".to_string()));
+            let info = file_ops::file_info(&path)?;
+            set_file_info_outputs(ctx, node, &info)?;
+            set_node_output(ctx, node, "action", Value::String("fileInfo".to_string()));
+
+            on_log(
+                "info",
+                format!(
+                    "文件信息节点 '{}'：'{}' {}（类型={}，大小={} 字节）。",
+                    node.label,
+                    path,
+                    if info.exists { "存在" } else { "不存在" },
+                    info.entry.file_type,
+                    info.entry.size
+                ),
+            );
         }
-        "readtext" => {
+        "waitforfile" => {
             let path_raw = get_string(node, "path", "");
             let path = resolve_text_template(&path_raw, &ctx.variables);
             if path.trim().is_empty() {
@@ -2972,61 +4914,87 @@ fn execute_file_operation(
                 )));
             }
 
-            let content = fs::read_to_string(&path).map_err(|error| {
-                CommandFlowError::Io(format!("读取文本文件失败 '{}': {}", path, error))
-            })?;
-            set_node_output(ctx, node, "text", Value::String(content.clone()));
-            set_node_output(ctx, node, "action", Value::String("readText".to_string()));
-            let output_var = get_string(node, "outputVar", "fileText").trim().to_string();
+            let wait_mode =
+                normalize_system_operation_name(&get_string(node, "waitMode", "exists"));
+            let timeout_ms = get_u64(node, "timeoutMs", 30_000);
+            let poll_ms = get_u64(node, "pollMs", 500).max(1);
+            let stable_ms = get_u64(node, "stableMs", 2_000);
+            let fail_on_timeout = get_bool(node, "failOnTimeout", true);
 
-            if !output_var.is_empty() {
-                ctx.variables
-                    .insert(output_var.clone(), Value::String(content.clone()));
-                on_log(
-                    "info",
-                    format!(
-                        "文本读取节点 '{}' 已读取 {} 字符到变量 '{}'。",
-                        node.label,
-                        content.chars().count(),
-                        output_var
-                    ),
-                );
-            } else {
+            let started = tokio::time::Instant::now();
+            let deadline = Duration::from_millis(timeout_ms);
+            let mut last_size: Option<(String, u64)> = None;
+            let mut stable_since = started;
+
+            let found = loop {
+                if should_cancel() {
+                    return Err(CommandFlowError::Canceled);
+                }
+
+                let matches = file_ops::find_existing(&path)?;
+                if let Some(entry) = matches.first() {
+                    if wait_mode != "stable" {
+                        break Some(entry.clone());
+                    }
+
+                    let current = (entry.path.clone(), entry.size);
+                    if last_size.as_ref() != Some(&current) {
+                        last_size = Some(current);
+                        stable_since = tokio::time::Instant::now();
+                    } else if stable_since.elapsed() >= Duration::from_millis(stable_ms) {
+                        break Some(entry.clone());
+                    }
+                } else {
+                    last_size = None;
+                }
+
+                if started.elapsed() >= deadline {
+                    break None;
+                }
+
+                interruptible_sleep(Duration::from_millis(poll_ms), should_cancel).await?;
+            };
+
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            set_node_output(ctx, node, "found", Value::Bool(found.is_some()));
+            set_node_output(ctx, node, "elapsedMs", value_from_u64(elapsed_ms));
+            set_node_output(
+                ctx,
+                node,
+                "action",
+                Value::String("waitForFile".to_string()),
+            );
+
+            let Some(entry) = found else {
+                set_node_output(ctx, node, "path", Value::String(String::new()));
+                set_node_output(ctx, node, "files", Value::Array(Vec::new()));
+                if fail_on_timeout {
+                    return Err(CommandFlowError::Automation(format!(
+                        "等待文件节点 '{}' 在 {}ms 内未等到 '{}'（模式={}）",
+                        node.label, timeout_ms, path, wait_mode
+                    )));
+                }
                 on_log(
-                    "info",
+                    "warn",
                     format!(
-                        "文本读取节点 '{}' 已读取 {} 字符（未配置输出变量）。",
-                        node.label,
-                        content.chars().count()
+                        "等待文件节点 '{}' 在 {}ms 内未等到 '{}'，继续执行。",
+                        node.label, timeout_ms, path
                     ),
                 );
-            }
-        }
-        "writetext" => {
-            let path_raw = get_string(node, "path", "");
-            let path = resolve_text_template(&path_raw, &ctx.variables);
-            if path.trim().is_empty() {
-                return Err(CommandFlowError::Validation(format!(
-                    "node '{}' path cannot be empty",
-                    node.id
-                )));
-            }
+                return Ok(NextDirective::Default);
+            };
 
-            let text = resolve_text_input(node, &ctx.variables);
-            let append = get_bool(node, "append", false);
-            let create_parent_dir = get_bool(node, "createParentDir", true);
-            write_text_file(&path, &text, append, create_parent_dir)?;
-            set_node_output(ctx, node, "path", Value::String(path.clone()));
-            set_node_output(ctx, node, "action", Value::String("writeText".to_string()));
+            let files = serde_json::to_value(vec![entry.clone()])
+                .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+            set_node_output(ctx, node, "path", Value::String(entry.path.clone()));
+            set_node_output(ctx, node, "files", files);
+            set_node_output(ctx, node, "size", value_from_u64(entry.size));
 
             on_log(
                 "info",
                 format!(
-                    "文本写入节点 '{}' 已{} {} 字符到 '{}'。",
-                    node.label,
-                    if append { "追加" } else { "写入" },
-                    text.chars().count(),
-                    path
+                    "等待文件节点 '{}' 已等到 '{}'（{} 字节，耗时 {}ms）。",
+                    node.label, entry.path, entry.size, elapsed_ms
                 ),
             );
         }
@@ -3041,6 +5009,100 @@ fn execute_file_operation(
     Ok(NextDirective::Default)
 }
 
+// 未配置或无法识别时默认记录日志，保证整次运行的文件改动都可撤销；只有显式选择 direct 才跳过
+fn get_file_safety_mode(node: &WorkflowNode) -> String {
+    match get_string(node, "safetyMode", "journal")
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "direct" => "direct".to_string(),
+        "preview" | "plan" => "preview".to_string(),
+        _ => "journal".to_string(),
+    }
+}
+
+// 本次运行的日志在首次文件操作时打开，之后复用以保留内存中的序号
+fn run_journal(ctx: &mut ExecutionContext) -> CommandResult<&file_journal::RunJournal> {
+    let journal = match ctx.file_journal.take() {
+        Some(journal) => journal,
+        None => file_journal::RunJournal::open(&ctx.run_id)?,
+    };
+    Ok(ctx.file_journal.insert(journal))
+}
+
+fn record_planned_file_change(
+    ctx: &mut ExecutionContext,
+    node: &WorkflowNode,
+    change: file_journal::PlannedChange,
+    on_log: &mut impl FnMut(&str, String),
+) -> CommandResult<()> {
+    on_log(
+        "info",
+        format!(
+            "[预演] 文件节点 '{}' 将执行：{}（未修改磁盘）",
+            node.label, change.summary
+        ),
+    );
+    let plan = serde_json::to_value(&change)
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    set_node_output(ctx, node, "planned", Value::Bool(true));
+    set_node_output(ctx, node, "plan", plan);
+    Ok(())
+}
+
+fn set_file_journal_outputs(
+    ctx: &mut ExecutionContext,
+    node: &WorkflowNode,
+    entry: &file_journal::JournalEntry,
+) {
+    let run_id = ctx.run_id.clone();
+    set_node_output(ctx, node, "runId", Value::String(run_id));
+    set_node_output(
+        ctx,
+        node,
+        "backupPath",
+        Value::String(entry.backup.clone().unwrap_or_default()),
+    );
+}
+
+fn set_file_info_outputs(
+    ctx: &mut ExecutionContext,
+    node: &WorkflowNode,
+    info: &file_ops::FileInfo,
+) -> CommandResult<()> {
+    let info_value = serde_json::to_value(info)
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    let optional_ms = |value: Option<u64>| value.map(value_from_u64).unwrap_or(Value::Null);
+
+    set_node_output(ctx, node, "info", info_value);
+    set_node_output(ctx, node, "exists", Value::Bool(info.exists));
+    set_node_output(ctx, node, "path", Value::String(info.entry.path.clone()));
+    set_node_output(
+        ctx,
+        node,
+        "fileType",
+        Value::String(info.entry.file_type.clone()),
+    );
+    set_node_output(ctx, node, "size", value_from_u64(info.entry.size));
+    set_node_output(ctx, node, "modifiedMs", optional_ms(info.entry.modified_ms));
+    set_node_output(ctx, node, "createdMs", optional_ms(info.entry.created_ms));
+    set_node_output(ctx, node, "accessedMs", optional_ms(info.entry.accessed_ms));
+    Ok(())
+}
+
+fn get_glob_patterns(node: &WorkflowNode, key: &str) -> Vec<String> {
+    match node.params.get(key) {
+        Some(Value::Array(_)) => get_string_array(node, key, Vec::new()),
+        Some(Value::String(raw)) => raw
+            .split([';', ',', '\n'])
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 async fn execute_system_operation(
     node: &WorkflowNode,
     ctx: &mut ExecutionContext,
@@ -3864,15 +5926,204 @@ fn rgba_to_png_data_url(rgba: &[u8], width: u32, height: u32) -> CommandResult<S
     Ok(format!("data:image/png;base64,{}", encoded))
 }
 
+fn execute_image_operation(
+    node: &WorkflowNode,
+    ctx: &mut ExecutionContext,
+    on_log: &mut impl FnMut(&str, String),
+) -> CommandResult<NextDirective> {
+    let operation = image_ops::ImageOperation::parse(&get_string(node, "operation", "grayscale"))?;
+    let source = resolve_image_operation_input(node, &ctx.variables)?;
+    let rect = image_ops::ImageRect {
+        left: get_u32(node, "rectX", 0),
+        top: get_u32(node, "rectY", 0),
+        width: get_u32(node, "rectWidth", 0),
+        height: get_u32(node, "rectHeight", 0),
+    };
+    let color = || image_ops::parse_color(&get_string(node, "color", "#FF0000"));
+    let quality = get_u32(node, "jpegQuality", 90).min(100) as u8;
+    let mut detail = String::new();
+    let mut encoded: Option<(Vec<u8>, image_ops::EncodeFormat)> = None;
+
+    let result = match operation {
+        image_ops::ImageOperation::Load => source.clone(),
+        image_ops::ImageOperation::Crop => image_ops::crop(&source, rect)?,
+        image_ops::ImageOperation::Resize => image_ops::resize(
+            &source,
+            get_u32(node, "resizeWidth", 0),
+            get_u32(node, "resizeHeight", 0),
+            image_ops::parse_resize_filter(&get_string(node, "resizeFilter", "lanczos3")),
+        )?,
+        image_ops::ImageOperation::Rotate => {
+            image_ops::rotate(&source, &get_string(node, "rotation", "90"))?
+        }
+        image_ops::ImageOperation::Grayscale => image_ops::grayscale(&source),
+        image_ops::ImageOperation::Threshold => {
+            let level = (!get_string(node, "thresholdMode", "otsu").eq_ignore_ascii_case("otsu"))
+                .then(|| get_u32(node, "thresholdValue", 128).min(255) as u8);
+            let (binary, level) = image_ops::threshold(&source, level);
+            detail = format!("，阈值={}", level);
+            binary
+        }
+        image_ops::ImageOperation::Invert => image_ops::invert(&source),
+        image_ops::ImageOperation::DrawBox => {
+            image_ops::draw_box(&source, rect, color()?, get_u32(node, "lineWidth", 2))?
+        }
+        image_ops::ImageOperation::DrawLabel => {
+            let text = resolve_text_template(&get_string(node, "labelText", ""), &ctx.variables);
+            image_ops::draw_label(
+                &source,
+                rect.left,
+                rect.top,
+                &text,
+                get_u32(node, "fontSize", 16).max(6),
+                color()?,
+            )?
+        }
+        image_ops::ImageOperation::Blur => {
+            image_ops::blur(&source, rect, get_f32(node, "blurSigma", 4.0))?
+        }
+        image_ops::ImageOperation::Redact => {
+            let block_size =
+                if get_string(node, "redactMode", "fill").eq_ignore_ascii_case("pixelate") {
+                    get_u32(node, "pixelSize", 12).max(2)
+                } else {
+                    0
+                };
+            image_ops::redact(&source, rect, color()?, block_size)?
+        }
+        image_ops::ImageOperation::Encode => {
+            let format = image_ops::EncodeFormat::parse(&get_string(node, "encodeFormat", "png"));
+            let bytes = image_ops::encode(&source, format, quality)?;
+            detail = format!("，{} {} 字节", format.name(), bytes.len());
+            encoded = Some((bytes, format));
+            source.clone()
+        }
+    };
+
+    let save_path = resolve_text_template(&get_string(node, "savePath", ""), &ctx.variables);
+    let save_path = save_path.trim();
+    let image_value = if !save_path.is_empty() {
+        let path = PathBuf::from(save_path);
+        match encoded.as_ref() {
+            // 编码结果直接落盘，保持与 encoded 输出一致
+            Some((bytes, format)) if *format == image_ops::EncodeFormat::from_path(&path) => {
+                if let Some(parent) = path
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                {
+                    fs::create_dir_all(parent).map_err(|error| {
+                        CommandFlowError::Io(format!("{}: {}", parent.display(), error))
+                    })?;
+                }
+                fs::write(&path, bytes).map_err(|error| {
+                    CommandFlowError::Io(format!("{}: {}", path.display(), error))
+                })?;
+            }
+            _ => image_ops::save(&result, &path, quality)?,
+        }
+        image_ops::file_image_value(&path, result.width(), result.height())
+    } else if let Some((bytes, format)) = encoded.as_ref() {
+        image_ops::encoded_image_value(bytes, *format, result.width(), result.height())
+    } else {
+        image_ops::memory_image_value(&result)?
+    };
+
+    let encoded_text = encoded
+        .as_ref()
+        .map(|(bytes, format)| {
+            if get_string(node, "encodeOutput", "dataUrl").eq_ignore_ascii_case("base64") {
+                general_purpose::STANDARD.encode(bytes)
+            } else {
+                image_ops::data_url(bytes, *format)
+            }
+        })
+        .unwrap_or_default();
+
+    set_node_output(ctx, node, "image", image_value.clone());
+    set_node_output(
+        ctx,
+        node,
+        "width",
+        value_from_u64(u64::from(result.width())),
+    );
+    set_node_output(
+        ctx,
+        node,
+        "height",
+        value_from_u64(u64::from(result.height())),
+    );
+    set_node_output(ctx, node, "path", Value::String(save_path.to_string()));
+    set_node_output(ctx, node, "encoded", Value::String(encoded_text));
+
+    let output_var = get_string(node, "outputVar", "image").trim().to_string();
+    if !output_var.is_empty() {
+        ctx.variables.insert(output_var, image_value);
+    }
+
+    on_log(
+        "info",
+        format!(
+            "图像处理节点 '{}' {}完成：{}x{} -> {}x{}{}{}。",
+            node.label,
+            operation.label(),
+            source.width(),
+            source.height(),
+            result.width(),
+            result.height(),
+            detail,
+            if save_path.is_empty() {
+                String::new()
+            } else {
+                format!("，已保存到 {}", save_path)
+            }
+        ),
+    );
+    Ok(NextDirective::Default)
+}
+
+fn resolve_image_operation_input(
+    node: &WorkflowNode,
+    variables: &HashMap<String, Value>,
+) -> CommandResult<DynamicImage> {
+    if get_string(node, "inputMode", "literal").eq_ignore_ascii_case("var") {
+        let var_name = get_string(node, "inputVar", "").trim().to_string();
+        if var_name.is_empty() {
+            return Err(CommandFlowError::Validation(format!(
+                "node '{}' inputVar cannot be empty",
+                node.id
+            )));
+        }
+        let value = variables.get(&var_name).ok_or_else(|| {
+            CommandFlowError::Automation(format!("变量 '{}' 不存在，无法读取图像", var_name))
+        })?;
+        return image_ops::decode_image_value(value);
+    }
+
+    match node.params.get("source") {
+        Some(Value::String(text)) => {
+            image_ops::decode_image_value(&Value::String(resolve_text_template(text, variables)))
+        }
+        Some(Value::Null) | None => Err(CommandFlowError::Validation(format!(
+            "node '{}' source cannot be empty",
+            node.id
+        ))),
+        Some(value) => image_ops::decode_image_value(value),
+    }
+}
+
 fn resolve_clipboard_write_image(
     node: &WorkflowNode,
     variables: &HashMap<String, Value>,
+    run_id: &str,
 ) -> CommandResult<ClipboardWriteImage> {
     let image_source_raw = get_string(node, "imageSource", "literal");
     let image_source = normalize_system_operation_name(&image_source_raw);
 
     match image_source.as_str() {
         "literal" => {
+            if let Some(value @ Value::Object(_)) = node.params.get("imageData") {
+                return load_clipboard_image_from_value(value);
+            }
             let raw = resolve_text_template(&get_string(node, "imageData", ""), variables);
             load_clipboard_image_from_string(&raw, false)
         }
@@ -3894,7 +6145,8 @@ fn resolve_clipboard_write_image(
             load_clipboard_image_from_value(value)
         }
         "file" => {
-            let path = resolve_text_template(&get_string(node, "imagePath", ""), variables);
+            let path =
+                resolve_text_template(&get_image_path(node, "imagePath", run_id)?, variables);
             load_clipboard_image_from_file(&path)
         }
         _ => Err(CommandFlowError::Validation(format!(
@@ -3942,7 +6194,7 @@ fn load_clipboard_image_from_string(
         return load_clipboard_image_from_file(trimmed);
     }
 
-    let bytes = decode_base64_image_payload(trimmed)?;
+    let bytes = image_ops::decode_base64_image_payload(trimmed)?;
     load_clipboard_image_from_memory(&bytes)
 }
 
@@ -3967,7 +6219,7 @@ fn load_clipboard_image_from_memory(bytes: &[u8]) -> CommandResult<ClipboardWrit
     Ok(dynamic_image_to_clipboard_write_image(image))
 }
 
-fn dynamic_image_to_clipboard_write_image(image: image::DynamicImage) -> ClipboardWriteImage {
+fn dynamic_image_to_clipboard_write_image(image: DynamicImage) -> ClipboardWriteImage {
     let rgba = image.to_rgba8();
     let (width, height) = rgba.dimensions();
     ClipboardWriteImage {
@@ -3977,20 +6229,6 @@ fn dynamic_image_to_clipboard_write_image(image: image::DynamicImage) -> Clipboa
     }
 }
 
-fn decode_base64_image_payload(raw: &str) -> CommandResult<Vec<u8>> {
-    let payload = if raw.starts_with("data:") {
-        raw.split_once(',').map(|(_, data)| data).ok_or_else(|| {
-            CommandFlowError::Validation("图片 Data URL 缺少 base64 数据段".to_string())
-        })?
-    } else {
-        raw
-    };
-
-    general_purpose::STANDARD
-        .decode(payload.trim())
-        .map_err(|error| CommandFlowError::Automation(format!("解析图片 base64 失败：{}", error)))
-}
-
 #[cfg(target_os = "windows")]
 fn should_spawn_terminal_window(command: &str) -> bool {
     let normalized = command.trim().to_lowercase();
@@ -4082,6 +6320,14 @@ fn get_string(node: &WorkflowNode, key: &str, default: &str) -> String {
         .to_string()
 }
 
+// 图像参数可能由上游图像节点连入结构化图像值或 Data URL，统一转换为文件路径
+fn get_image_path(node: &WorkflowNode, key: &str, run_id: &str) -> CommandResult<String> {
+    node.params
+        .get(key)
+        .map(|value| image_ops::image_value_to_path(value, run_id))
+        .unwrap_or_else(|| Ok(String::new()))
+}
+
 fn get_string_array(node: &WorkflowNode, key: &str, default: Vec<String>) -> Vec<String> {
     node.params
         .get(key)
@@ -4158,10 +6404,16 @@ fn stringify_value(value: &Value) -> String {
 fn write_text_file(
     path: &str,
     text: &str,
+    encoding: &str,
     append: bool,
     create_parent_dir: bool,
 ) -> CommandResult<()> {
     let target = Path::new(path);
+    let include_bom = !append
+        || fs::metadata(target)
+            .map(|metadata| metadata.len() == 0)
+            .unwrap_or(true);
+    let bytes = file_ops::encode_text(text, encoding, include_bom)?;
 
     if create_parent_dir {
         if let Some(parent) = target.parent() {
@@ -4179,12 +6431,12 @@ fn write_text_file(
             .append(true)
             .open(target)
             .map_err(|error| CommandFlowError::Io(format!("打开文件失败 '{}': {}", path, error)))?;
-        file.write_all(text.as_bytes())
+        file.write_all(&bytes)
             .map_err(|error| CommandFlowError::Io(format!("写入文件失败 '{}': {}", path, error)))?;
         return Ok(());
     }
 
-    fs::write(target, text)
+    fs::write(target, bytes)
         .map_err(|error| CommandFlowError::Io(format!("写入文件失败 '{}': {}", path, error)))
 }
 
@@ -4293,7 +6545,7 @@ fn resolve_typed_param_value(node: &WorkflowNode, base_key: &str) -> Value {
     }
 
     match selected_type {
-        "string" => {
+        "string" | redaction::SECRET_VALUE_TYPE => {
             let key = format!("{}String", base_key);
             Value::String(get_string(node, &key, ""))
         }
@@ -4431,8 +6683,12 @@ async fn interruptible_sleep(
 }
 
 fn prepare_ocr_match_debug_dir(node: &WorkflowNode) -> CommandResult<PathBuf> {
+    prepare_node_debug_dir(node, "commandflow-ocr-match-debug")
+}
+
+fn prepare_node_debug_dir(node: &WorkflowNode, folder: &str) -> CommandResult<PathBuf> {
     let mut base = std::env::temp_dir();
-    base.push("commandflow-ocr-match-debug");
+    base.push(folder);
 
     fs::create_dir_all(&base).map_err(|error| CommandFlowError::Io(error.to_string()))?;
 
@@ -4451,16 +6707,11 @@ fn prepare_ocr_match_debug_dir(node: &WorkflowNode) -> CommandResult<PathBuf> {
     Ok(run_dir)
 }
 
-#[allow(clippy::too_many_arguments)]
 fn save_ocr_static_debug_artifacts(
     debug_dir: &Path,
     source_path: &str,
     evaluation: &ocr_match::OcrMatchEvaluation,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
-    min_confidence: f32,
+    query: &ocr_match::OcrTextQuery,
     node: &WorkflowNode,
 ) -> CommandResult<()> {
     let source = Path::new(source_path);
@@ -4479,226 +6730,776 @@ fn save_ocr_static_debug_artifacts(
     if let Ok(decoded) = image::open(source_path) {
         let rgba = decoded.to_rgba8();
         let (width, height) = rgba.dimensions();
-        let overlay = render_ocr_debug_overlay(rgba.into_raw(), width, height, evaluation)?;
+        let overlay = render_ocr_debug_overlay(rgba.into_raw(), width, height, (0, 0), evaluation)?;
         let overlay_path = debug_dir.join("static-overlay.png");
         overlay.save(&overlay_path).map_err(|error| {
             CommandFlowError::Automation(format!("保存 OCR 静态标注图失败: {}", error))
         })?;
     }
 
-    let metadata_path = debug_dir.join("static-metadata.json");
-    write_ocr_debug_metadata(
-        &metadata_path,
-        evaluation,
-        target_text,
-        match_mode,
-        case_sensitive,
-        use_regex,
-        min_confidence,
+    let metadata_path = debug_dir.join("static-metadata.json");
+    write_ocr_debug_metadata(&metadata_path, evaluation, query, node)
+}
+
+// image 为 (像素, 宽, 高, 截图左上角的屏幕坐标)；evaluation 已换算为屏幕坐标
+fn save_ocr_frame_debug_artifacts(
+    debug_dir: &Path,
+    frame: u64,
+    image: (&[u8], u32, u32, (i32, i32)),
+    evaluation: &ocr_match::OcrMatchEvaluation,
+    query: &ocr_match::OcrTextQuery,
+    node: &WorkflowNode,
+) -> CommandResult<()> {
+    let (rgba, width, height, origin) = image;
+    let input_path = debug_dir.join(format!("frame-{:04}-input.png", frame));
+    screenshot::save_rgba_image(path_to_string(&input_path)?, rgba.to_vec(), width, height)?;
+
+    let overlay = render_ocr_debug_overlay(rgba.to_vec(), width, height, origin, evaluation)?;
+    let overlay_path = debug_dir.join(format!("frame-{:04}-overlay.png", frame));
+    overlay.save(&overlay_path).map_err(|error| {
+        CommandFlowError::Automation(format!("保存 OCR 帧标注图失败: {}", error))
+    })?;
+
+    let metadata_path = debug_dir.join(format!("frame-{:04}-metadata.json", frame));
+    write_ocr_debug_metadata(&metadata_path, evaluation, query, node)
+}
+
+// evaluation 为屏幕坐标，绘制前减去 origin 换回截图内坐标
+fn render_ocr_debug_overlay(
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+    origin: (i32, i32),
+    evaluation: &ocr_match::OcrMatchEvaluation,
+) -> CommandResult<RgbaImage> {
+    let mut image =
+        ImageBuffer::<Rgba<u8>, Vec<u8>>::from_vec(width, height, rgba).ok_or_else(|| {
+            CommandFlowError::Automation("failed to build OCR debug overlay image".to_string())
+        })?;
+
+    draw_border(&mut image, Rgba([64, 180, 255, 255]));
+
+    for entry in &evaluation.debug_entries {
+        let stroke = if entry.is_text_match && entry.is_confidence_passed {
+            Rgba([64, 255, 96, 255])
+        } else if entry.is_text_match {
+            Rgba([255, 200, 0, 255])
+        } else {
+            Rgba([255, 80, 80, 220])
+        };
+
+        if let Some(quad) = entry.quad {
+            let points = quad.map(|point| {
+                (
+                    point[0].round() as i32 - origin.0,
+                    point[1].round() as i32 - origin.1,
+                )
+            });
+            for i in 0..4 {
+                let start = points[i];
+                let end = points[(i + 1) % 4];
+                draw_line(&mut image, start.0, start.1, end.0, end.1, stroke);
+            }
+
+            let (center_x, center_y) = (entry.center_x - origin.0, entry.center_y - origin.1);
+            if center_x >= 0 && center_y >= 0 {
+                draw_crosshair(&mut image, center_x, center_y, 8, stroke);
+            }
+        }
+    }
+
+    if let Some(matched) = evaluation
+        .matched
+        .as_ref()
+        .filter(|matched| matched.has_position)
+    {
+        let (x, y) = (matched.x - origin.0, matched.y - origin.1);
+        if x >= 0 && y >= 0 {
+            draw_circle_outline(&mut image, x, y, 16, Rgba([255, 255, 0, 255]));
+            draw_crosshair(&mut image, x, y, 18, Rgba([255, 255, 0, 255]));
+        }
+    }
+
+    Ok(image)
+}
+
+fn write_ocr_debug_metadata(
+    output_path: &Path,
+    evaluation: &ocr_match::OcrMatchEvaluation,
+    query: &ocr_match::OcrTextQuery,
+    node: &WorkflowNode,
+) -> CommandResult<()> {
+    let entries = evaluation
+        .debug_entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let quad = entry.quad.map(|points| {
+                points
+                    .iter()
+                    .map(|point| {
+                        serde_json::json!({
+                            "x": point[0],
+                            "y": point[1],
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            serde_json::json!({
+                "index": index,
+                "text": &entry.text,
+                "confidence": entry.confidence,
+                "center": {
+                    "x": entry.center_x,
+                    "y": entry.center_y,
+                },
+                "quad": quad,
+                "similarity": entry.similarity,
+                "isTextMatch": entry.is_text_match,
+                "isConfidencePassed": entry.is_confidence_passed,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let matched = evaluation.matched.as_ref().map(|item| {
+        serde_json::json!({
+            "x": item.x,
+            "y": item.y,
+            "text": &item.text,
+            "confidence": item.confidence,
+            "similarity": item.similarity,
+        })
+    });
+
+    let payload = serde_json::json!({
+        "node": {
+            "id": &node.id,
+            "label": &node.label,
+            "kind": "ocrMatch",
+        },
+        "query": {
+            "targetText": &query.target_text,
+            "matchMode": &query.match_mode,
+            "caseSensitive": query.case_sensitive,
+            "useRegex": query.use_regex,
+            "minConfidence": query.min_confidence,
+            "minSimilarity": query.min_similarity,
+            "similarityAlgorithm": query.similarity_algorithm.name(),
+            "normalizeWhitespace": query.normalization.strip_whitespace,
+            "normalizeWidth": query.normalization.fold_width,
+            "normalizeConfusables": query.normalization.fold_confusables,
+        },
+        "summary": {
+            "peakText": &evaluation.peak_text,
+            "peakConfidence": evaluation.peak_confidence,
+            "peakSimilarity": evaluation.peak_similarity,
+            "entryCount": entries.len(),
+            "matched": matched,
+        },
+        "entries": entries,
+    });
+
+    let text = serde_json::to_string_pretty(&payload).map_err(|error| {
+        CommandFlowError::Automation(format!("序列化 OCR debug metadata 失败: {}", error))
+    })?;
+    fs::write(output_path, text)
+        .map_err(|error| CommandFlowError::Io(format!("写入 OCR debug metadata 失败: {}", error)))
+}
+
+fn recv_image_match_frame(
+    color_mode: image_match::ColorMode,
+    timeout: Duration,
+) -> CommandResult<Option<DynamicImage>> {
+    Ok(match color_mode {
+        image_match::ColorMode::Gray => {
+            screenshot::recv_primary_frame_gray_timeout(timeout)?.map(DynamicImage::ImageLuma8)
+        }
+        image_match::ColorMode::Rgb => {
+            screenshot::recv_primary_frame_rgb_timeout(timeout)?.map(DynamicImage::ImageRgb8)
+        }
+    })
+}
+
+async fn execute_feature_image_match(
+    node: &WorkflowNode,
+    ctx: &mut ExecutionContext,
+    on_log: &mut impl FnMut(&str, String),
+    should_cancel: &impl Fn() -> bool,
+    template_path: &str,
+    detector: feature_match::FeatureDetector,
+) -> CommandResult<NextDirective> {
+    let source_path = get_image_path(node, "sourcePath", &ctx.run_id)?;
+    let min_inliers = get_u64(node, "minInliers", 12).max(4) as usize;
+    let timeout_ms = get_u64(node, "timeoutMs", 10_000);
+    let poll_interval = Duration::from_millis(get_u64(node, "pollMs", 16).max(1));
+    let confirm_frames = get_u64(node, "confirmFrames", 2).max(1);
+    let wait_disappear = get_string(node, "waitFor", "appear").eq_ignore_ascii_case("disappear");
+    let click_on_match = get_bool(node, "clickOnMatch", false);
+    let click_times = get_u64(node, "clickTimes", 1).max(1) as usize;
+    let matcher = feature_match::FeatureMatcher::from_path(template_path, detector, min_inliers)?;
+    let mut search_region = resolve_search_region(node)?;
+    let capture_target = resolve_capture_target(node)?;
+    let debug_dir = prepare_image_match_debug_dir(node)?;
+
+    on_log(
+        "info",
+        format!(
+            "图像匹配节点 '{}' 使用 {} 特征匹配，最少内点数={}。",
+            node.label,
+            matcher.detector().name(),
+            min_inliers
+        ),
+    );
+
+    if !source_path.trim().is_empty() {
+        let source = image::open(&source_path)
+            .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+        let evaluation = evaluate_feature_match_in_region(node, &matcher, &source, search_region)?;
+        set_feature_match_outputs(ctx, node, &evaluation);
+        let debug_path = debug_dir.join("static-source.png");
+        let _ = screenshot::save_image_with_box(
+            path_to_string(&debug_path)?,
+            &source,
+            evaluation
+                .candidate
+                .as_ref()
+                .map(|item| item.bounding_rect()),
+            evaluation.matched.is_some(),
+        );
+
+        let found = evaluation.matched.is_some();
+        on_log(
+            if found != wait_disappear {
+                "info"
+            } else {
+                "warn"
+            },
+            format!(
+                "图像匹配节点 '{}' 静态源图特征匹配{}，inliers={}。",
+                node.label,
+                if found { "命中" } else { "未命中" },
+                evaluation
+                    .candidate
+                    .as_ref()
+                    .map(|item| item.inliers)
+                    .unwrap_or(0)
+            ),
+        );
+        if wait_disappear {
+            return Ok(NextDirective::Branch(if found { "false" } else { "true" }));
+        }
+        if let Some(matched) = evaluation.matched {
+            if click_on_match {
+                mouse::click(matched.x, matched.y, click_times)?;
+            }
+            return Ok(NextDirective::Branch("true"));
+        }
+        return Ok(NextDirective::Branch("false"));
+    }
+
+    let started = tokio::time::Instant::now();
+    let deadline = Duration::from_millis(timeout_ms);
+    let mut attempts: u64 = 0;
+    let mut matched_streak: u64 = 0;
+    screenshot::ensure_frame_stream(&capture_target)?;
+
+    loop {
+        if should_cancel() {
+            let _ = screenshot::stop_primary_frame_stream();
+            return Err(CommandFlowError::Canceled);
+        }
+
+        let frame = match tokio::task::block_in_place(|| {
+            screenshot::recv_primary_frame_gray_timeout(poll_interval)
+        }) {
+            Ok(frame) => frame,
+            Err(error) => {
+                let _ = screenshot::stop_primary_frame_stream();
+                return Err(error);
+            }
+        };
+
+        if let Some(frame) = frame {
+            attempts += 1;
+            let frame = DynamicImage::ImageLuma8(frame);
+            let origin = screenshot::frame_stream_origin()?;
+            search_region = refresh_search_region(node, search_region);
+            let frame_region = search_region.map(|region| region.relative_to(origin));
+            let evaluation =
+                match evaluate_feature_match_in_region(node, &matcher, &frame, frame_region) {
+                    Ok(evaluation) => evaluation,
+                    Err(error) => {
+                        let _ = screenshot::stop_primary_frame_stream();
+                        return Err(error);
+                    }
+                };
+            // 调试图使用帧内坐标，输出与点击使用虚拟屏幕坐标
+            let screen_matched = evaluation
+                .matched
+                .clone()
+                .map(|item| item.offset(origin.0, origin.1));
+            set_feature_match_outputs(
+                ctx,
+                node,
+                &feature_match::FeatureEvaluation {
+                    matched: screen_matched.clone(),
+                    candidate: evaluation.candidate.clone(),
+                },
+            );
+
+            if evaluation.matched.is_some() != wait_disappear {
+                matched_streak += 1;
+            } else {
+                matched_streak = 0;
+            }
+
+            let confirmed = matched_streak >= confirm_frames;
+            if attempts % IMAGE_MATCH_DEBUG_SAVE_EVERY == 0 || confirmed {
+                let frame_path = debug_dir.join(format!(
+                    "feature-{:05}-inliers-{}.png",
+                    attempts,
+                    evaluation
+                        .candidate
+                        .as_ref()
+                        .map(|item| item.inliers)
+                        .unwrap_or(0)
+                ));
+                let _ = screenshot::save_image_with_box(
+                    path_to_string(&frame_path)?,
+                    &frame,
+                    evaluation
+                        .candidate
+                        .as_ref()
+                        .map(|item| item.bounding_rect()),
+                    evaluation.matched.is_some(),
+                );
+            }
+
+            if confirmed {
+                let _ = screenshot::stop_primary_frame_stream();
+                if wait_disappear {
+                    on_log(
+                        "info",
+                        format!(
+                            "图像匹配节点 '{}' 连续 {} 帧未检测到目标，已确认消失。",
+                            node.label, confirm_frames
+                        ),
+                    );
+                    return Ok(NextDirective::Branch("true"));
+                }
+
+                let matched = screen_matched.ok_or_else(|| {
+                    CommandFlowError::Automation("matched point missing".to_string())
+                })?;
+                on_log(
+                    "info",
+                    format!(
+                        "图像匹配节点 '{}' 连续命中 {} 帧，坐标=({}, {})，inliers={}/{}。",
+                        node.label,
+                        confirm_frames,
+                        matched.x,
+                        matched.y,
+                        matched.inliers,
+                        matched.good_matches
+                    ),
+                );
+                if click_on_match {
+                    mouse::click(matched.x, matched.y, click_times)?;
+                }
+                return Ok(NextDirective::Branch("true"));
+            }
+        }
+
+        if started.elapsed() >= deadline {
+            let _ = screenshot::stop_primary_frame_stream();
+            on_log(
+                "warn",
+                format!(
+                    "图像匹配节点 '{}' 在 {}ms 内{}，已走 false 分支。",
+                    node.label,
+                    timeout_ms,
+                    if wait_disappear {
+                        "目标仍未消失"
+                    } else {
+                        "未命中"
+                    }
+                ),
+            );
+            return Ok(NextDirective::Branch("false"));
+        }
+    }
+}
+
+fn evaluate_feature_match_in_region(
+    node: &WorkflowNode,
+    matcher: &feature_match::FeatureMatcher,
+    source: &DynamicImage,
+    region: Option<SearchRegion>,
+) -> CommandResult<feature_match::FeatureEvaluation> {
+    let Some(region) = region else {
+        return matcher.evaluate(source);
+    };
+    let (left, top, width, height) = region
+        .clip_to(source.width(), source.height())
+        .ok_or_else(|| search_region_outside_error(node))?;
+    let evaluation = matcher.evaluate(&source.crop_imm(left, top, width, height))?;
+    let (dx, dy) = (left as i32, top as i32);
+    Ok(feature_match::FeatureEvaluation {
+        matched: evaluation.matched.map(|item| item.offset(dx, dy)),
+        candidate: evaluation.candidate.map(|item| item.offset(dx, dy)),
+    })
+}
+
+fn set_feature_match_outputs(
+    ctx: &mut ExecutionContext,
+    node: &WorkflowNode,
+    evaluation: &feature_match::FeatureEvaluation,
+) {
+    let (inliers, similarity) = evaluation
+        .candidate
+        .as_ref()
+        .map(|item| {
+            (
+                item.inliers,
+                item.inliers as f64 / item.good_matches.max(1) as f64,
+            )
+        })
+        .unwrap_or((0, 0.0));
+    set_node_output(ctx, node, "similarity", value_from_f64(similarity));
+    set_node_output(ctx, node, "inliers", value_from_u64(inliers as u64));
+    if let Some(matched) = evaluation.matched.as_ref() {
+        set_node_output(ctx, node, "matchX", value_from_i32(matched.x));
+        set_node_output(ctx, node, "matchY", value_from_i32(matched.y));
+        set_node_output(
+            ctx,
+            node,
+            "matchQuad",
+            serde_json::to_value(matched.quad).unwrap_or(Value::Array(Vec::new())),
+        );
+    }
+}
+
+// origin 为帧左上角的虚拟屏幕坐标，结果坐标可能为负，因此在 JSON 上平移
+fn set_image_match_list_outputs(
+    ctx: &mut ExecutionContext,
+    node: &WorkflowNode,
+    evaluation: &image_match::MatchEvaluation,
+    origin: (i32, i32),
+) {
+    set_node_output(
+        ctx,
         node,
-    )
+        "count",
+        Value::Number(Number::from(evaluation.matches.len() as u64)),
+    );
+    let mut matches = serde_json::to_value(&evaluation.matches).unwrap_or(Value::Array(Vec::new()));
+    if origin != (0, 0) {
+        for item in matches.as_array_mut().into_iter().flatten() {
+            for (key, delta) in [
+                ("x", origin.0),
+                ("left", origin.0),
+                ("y", origin.1),
+                ("top", origin.1),
+            ] {
+                if let Some(value) = item.get_mut(key) {
+                    *value =
+                        Value::Number(Number::from(value.as_i64().unwrap_or(0) + i64::from(delta)));
+                }
+            }
+        }
+    }
+    set_node_output(ctx, node, "matches", matches);
 }
 
-#[allow(clippy::too_many_arguments)]
-fn save_ocr_frame_debug_artifacts(
-    debug_dir: &Path,
-    frame: u64,
-    rgba: &[u8],
+#[derive(Debug, Clone, Copy)]
+struct SearchRegion {
+    left: i32,
+    top: i32,
     width: u32,
     height: u32,
-    evaluation: &ocr_match::OcrMatchEvaluation,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
-    min_confidence: f32,
-    node: &WorkflowNode,
-) -> CommandResult<()> {
-    let input_path = debug_dir.join(format!("frame-{:04}-input.png", frame));
-    screenshot::save_rgba_image(path_to_string(&input_path)?, rgba.to_vec(), width, height)?;
+}
 
-    let overlay = render_ocr_debug_overlay(rgba.to_vec(), width, height, evaluation)?;
-    let overlay_path = debug_dir.join(format!("frame-{:04}-overlay.png", frame));
-    overlay.save(&overlay_path).map_err(|error| {
-        CommandFlowError::Automation(format!("保存 OCR 帧标注图失败: {}", error))
-    })?;
+impl SearchRegion {
+    // 区域坐标为虚拟屏幕坐标，换算为以 origin 为左上角的画面坐标
+    fn relative_to(&self, origin: (i32, i32)) -> Self {
+        SearchRegion {
+            left: self.left.saturating_sub(origin.0),
+            top: self.top.saturating_sub(origin.1),
+            ..*self
+        }
+    }
 
-    let metadata_path = debug_dir.join(format!("frame-{:04}-metadata.json", frame));
-    write_ocr_debug_metadata(
-        &metadata_path,
-        evaluation,
-        target_text,
-        match_mode,
-        case_sensitive,
-        use_regex,
-        min_confidence,
-        node,
-    )
+    fn clip_to(&self, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        let left = i64::from(self.left).clamp(0, i64::from(width));
+        let top = i64::from(self.top).clamp(0, i64::from(height));
+        let right = (i64::from(self.left) + i64::from(self.width)).clamp(0, i64::from(width));
+        let bottom = (i64::from(self.top) + i64::from(self.height)).clamp(0, i64::from(height));
+        if right <= left || bottom <= top {
+            return None;
+        }
+        Some((
+            left as u32,
+            top as u32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ))
+    }
 }
 
-fn render_ocr_debug_overlay(
-    rgba: Vec<u8>,
-    width: u32,
-    height: u32,
-    evaluation: &ocr_match::OcrMatchEvaluation,
-) -> CommandResult<RgbaImage> {
-    let mut image =
-        ImageBuffer::<Rgba<u8>, Vec<u8>>::from_vec(width, height, rgba).ok_or_else(|| {
-            CommandFlowError::Automation("failed to build OCR debug overlay image".to_string())
-        })?;
-
-    draw_border(&mut image, Rgba([64, 180, 255, 255]));
+fn resolve_search_region(node: &WorkflowNode) -> CommandResult<Option<SearchRegion>> {
+    let mode = get_string(node, "regionMode", "fullScreen");
+    let offset_x = get_f32(node, "regionX", 0.0).round() as i32;
+    let offset_y = get_f32(node, "regionY", 0.0).round() as i32;
+    let width = get_f32(node, "regionWidth", 0.0).round().max(0.0) as u32;
+    let height = get_f32(node, "regionHeight", 0.0).round().max(0.0) as u32;
 
-    for entry in &evaluation.debug_entries {
-        let stroke = if entry.is_text_match && entry.is_confidence_passed {
-            Rgba([64, 255, 96, 255])
-        } else if entry.is_text_match {
-            Rgba([255, 200, 0, 255])
-        } else {
-            Rgba([255, 80, 80, 220])
-        };
+    let require_size = || {
+        if width == 0 || height == 0 {
+            return Err(CommandFlowError::Validation(format!(
+                "node '{}' regionWidth and regionHeight must be greater than 0",
+                node.id
+            )));
+        }
+        Ok(())
+    };
 
-        if let Some(quad) = entry.quad {
-            let points = [
-                (quad[0][0].round() as i32, quad[0][1].round() as i32),
-                (quad[1][0].round() as i32, quad[1][1].round() as i32),
-                (quad[2][0].round() as i32, quad[2][1].round() as i32),
-                (quad[3][0].round() as i32, quad[3][1].round() as i32),
-            ];
-            for i in 0..4 {
-                let start = points[i];
-                let end = points[(i + 1) % 4];
-                draw_line(&mut image, start.0, start.1, end.0, end.1, stroke);
+    match mode.as_str() {
+        "absolute" => {
+            require_size()?;
+            Ok(Some(SearchRegion {
+                left: offset_x,
+                top: offset_y,
+                width,
+                height,
+            }))
+        }
+        "window" => {
+            let match_mode = get_string(node, "windowMatchMode", "contains");
+            let title = get_string(node, "windowTitle", "");
+            let program = get_string(node, "windowProgram", "");
+            let target = get_string(node, "windowMatchTarget", "title");
+            let by_program = target.eq_ignore_ascii_case("program");
+            let match_value = if by_program { &program } else { &title };
+            if match_value.trim().is_empty() {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' window {} cannot be empty",
+                    node.id,
+                    if by_program { "program" } else { "title" }
+                )));
             }
+            let query = build_window_match_query(node, &target, &title, &program, &match_mode);
+            let rect = window::find_window_rect(&query)?;
+            // 宽高为 0 时延伸到窗口边缘
+            let max_width = i64::from(rect.width) - i64::from(offset_x);
+            let max_height = i64::from(rect.height) - i64::from(offset_y);
+            let width = if width == 0 {
+                max_width.max(0) as u32
+            } else {
+                width
+            };
+            let height = if height == 0 {
+                max_height.max(0) as u32
+            } else {
+                height
+            };
+            Ok(Some(SearchRegion {
+                left: rect.left.saturating_add(offset_x),
+                top: rect.top.saturating_add(offset_y),
+                width,
+                height,
+            }))
         }
-
-        if entry.center_x >= 0 && entry.center_y >= 0 {
-            draw_crosshair(&mut image, entry.center_x, entry.center_y, 8, stroke);
+        "anchor" => {
+            require_size()?;
+            let anchor_x = get_f32(node, "anchorX", 0.0).round() as i32;
+            let anchor_y = get_f32(node, "anchorY", 0.0).round() as i32;
+            Ok(Some(SearchRegion {
+                left: anchor_x.saturating_add(offset_x),
+                top: anchor_y.saturating_add(offset_y),
+                width,
+                height,
+            }))
         }
+        _ => Ok(None),
     }
+}
 
-    if let Some(matched) = evaluation.matched.as_ref() {
-        if matched.x >= 0 && matched.y >= 0 {
-            draw_circle_outline(
-                &mut image,
-                matched.x,
-                matched.y,
-                16,
-                Rgba([255, 255, 0, 255]),
-            );
-            draw_crosshair(
-                &mut image,
-                matched.x,
-                matched.y,
-                18,
-                Rgba([255, 255, 0, 255]),
-            );
+fn resolve_capture_target(node: &WorkflowNode) -> CommandResult<screenshot::CaptureTarget> {
+    let mode = get_string(node, "monitorMode", "primary");
+    match mode.as_str() {
+        "index" => {
+            let index = get_u64(node, "monitorIndex", 1);
+            if index == 0 {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' monitorIndex must be greater than 0",
+                    node.id
+                )));
+            }
+            Ok(screenshot::CaptureTarget::Index(index as usize))
+        }
+        "name" => {
+            let name = get_string(node, "monitorName", "");
+            if name.trim().is_empty() {
+                return Err(CommandFlowError::Validation(format!(
+                    "node '{}' monitorName cannot be empty",
+                    node.id
+                )));
+            }
+            Ok(screenshot::CaptureTarget::Name(name))
+        }
+        "window" => {
+            let query = build_capture_window_query(node)?;
+            let rect = window::find_window_rect(&query)?;
+            // 以窗口中心所在的显示器为准
+            Ok(screenshot::CaptureTarget::Point(
+                rect.left.saturating_add((rect.width / 2) as i32),
+                rect.top.saturating_add((rect.height / 2) as i32),
+            ))
         }
+        "windowCapture" => Ok(screenshot::CaptureTarget::Window(
+            build_capture_window_query(node)?,
+        )),
+        "virtual" => Ok(screenshot::CaptureTarget::VirtualDesktop),
+        _ => Ok(screenshot::CaptureTarget::Primary),
     }
-
-    Ok(image)
 }
 
-#[allow(clippy::too_many_arguments)]
-fn write_ocr_debug_metadata(
-    output_path: &Path,
-    evaluation: &ocr_match::OcrMatchEvaluation,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
-    min_confidence: f32,
-    node: &WorkflowNode,
-) -> CommandResult<()> {
-    let entries = evaluation
-        .debug_entries
-        .iter()
-        .enumerate()
-        .map(|(index, entry)| {
-            let quad = entry.quad.map(|points| {
-                points
-                    .iter()
-                    .map(|point| {
-                        serde_json::json!({
-                            "x": point[0],
-                            "y": point[1],
-                        })
-                    })
-                    .collect::<Vec<_>>()
-            });
-
-            serde_json::json!({
-                "index": index,
-                "text": &entry.text,
-                "confidence": entry.confidence,
-                "center": {
-                    "x": entry.center_x,
-                    "y": entry.center_y,
-                },
-                "quad": quad,
-                "isTextMatch": entry.is_text_match,
-                "isConfidencePassed": entry.is_confidence_passed,
-            })
-        })
-        .collect::<Vec<_>>();
+fn build_capture_window_query(node: &WorkflowNode) -> CommandResult<window::WindowMatchQuery> {
+    let target = get_string(node, "monitorWindowTarget", "title");
+    let by_program = target.eq_ignore_ascii_case("program");
+    let key = if by_program {
+        "monitorWindowProgram"
+    } else {
+        "monitorWindowTitle"
+    };
+    let value = get_string(node, key, "");
+    if value.trim().is_empty() {
+        return Err(CommandFlowError::Validation(format!(
+            "node '{}' {} cannot be empty",
+            node.id, key
+        )));
+    }
 
-    let matched = evaluation.matched.as_ref().map(|item| {
-        serde_json::json!({
-            "x": item.x,
-            "y": item.y,
-            "text": &item.text,
-            "confidence": item.confidence,
-        })
-    });
+    let mut query =
+        window::WindowMatchQuery::new(&get_string(node, "monitorWindowMatchMode", "contains"));
+    if by_program {
+        query.program = Some(value.trim().to_string());
+    } else {
+        query.title = Some(value.trim().to_string());
+    }
+    Ok(query)
+}
 
-    let payload = serde_json::json!({
-        "node": {
-            "id": &node.id,
-            "label": &node.label,
-            "kind": "ocrMatch",
-        },
-        "query": {
-            "targetText": target_text,
-            "matchMode": match_mode,
-            "caseSensitive": case_sensitive,
-            "useRegex": use_regex,
-            "minConfidence": min_confidence,
-        },
-        "summary": {
-            "peakText": &evaluation.peak_text,
-            "peakConfidence": evaluation.peak_confidence,
-            "entryCount": entries.len(),
-            "matched": matched,
-        },
-        "entries": entries,
-    });
+fn offset_point((x, y): (i32, i32), origin: (i32, i32)) -> (i32, i32) {
+    (x.saturating_add(origin.0), y.saturating_add(origin.1))
+}
 
-    let text = serde_json::to_string_pretty(&payload).map_err(|error| {
-        CommandFlowError::Automation(format!("序列化 OCR debug metadata 失败: {}", error))
-    })?;
-    fs::write(output_path, text)
-        .map_err(|error| CommandFlowError::Io(format!("写入 OCR debug metadata 失败: {}", error)))
+// 窗口区域在等待期间会随窗口移动，每次轮询重新定位；窗口暂时找不到时沿用上一次的位置
+fn refresh_search_region(
+    node: &WorkflowNode,
+    previous: Option<SearchRegion>,
+) -> Option<SearchRegion> {
+    if get_string(node, "regionMode", "fullScreen") != "window" {
+        return previous;
+    }
+    resolve_search_region(node).ok().flatten().or(previous)
 }
 
-fn prepare_image_match_debug_dir(node: &WorkflowNode) -> CommandResult<PathBuf> {
-    let mut base = std::env::temp_dir();
-    base.push("commandflow-image-match-debug");
+fn search_region_outside_error(node: &WorkflowNode) -> CommandFlowError {
+    CommandFlowError::Validation(format!(
+        "node '{}' search region is outside the captured image",
+        node.id
+    ))
+}
 
-    fs::create_dir_all(&base).map_err(|error| CommandFlowError::Io(error.to_string()))?;
+fn evaluate_image_match_in_region(
+    node: &WorkflowNode,
+    matcher: &mut image_match::TemplateMatcher,
+    source: &DynamicImage,
+    region: Option<SearchRegion>,
+) -> CommandResult<image_match::MatchEvaluation> {
+    let Some(region) = region else {
+        return Ok(matcher.evaluate(source));
+    };
+    let (left, top, width, height) = region
+        .clip_to(source.width(), source.height())
+        .ok_or_else(|| search_region_outside_error(node))?;
+    let cropped = source.crop_imm(left, top, width, height);
+    let evaluation = matcher.evaluate(&cropped);
+    Ok(offset_match_evaluation(evaluation, left, top))
+}
+
+fn offset_match_evaluation(
+    mut evaluation: image_match::MatchEvaluation,
+    left: u32,
+    top: u32,
+) -> image_match::MatchEvaluation {
+    let dx = left as i32;
+    let dy = top as i32;
+    evaluation.matched_point = evaluation.matched_point.map(|(x, y)| (x + dx, y + dy));
+    evaluation.best_top_left = evaluation.best_top_left.map(|(x, y)| (x + left, y + top));
+    for item in &mut evaluation.matches {
+        item.x += dx;
+        item.y += dy;
+        item.left += left;
+        item.top += top;
+    }
+    evaluation
+}
+
+// (rgba, width, height, 裁剪原点)
+type CroppedRgba = (Vec<u8>, u32, u32, (i32, i32));
+
+fn crop_rgba_to_region(
+    node: &WorkflowNode,
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+    region: SearchRegion,
+) -> CommandResult<CroppedRgba> {
+    let (left, top, crop_width, crop_height) = region
+        .clip_to(width, height)
+        .ok_or_else(|| search_region_outside_error(node))?;
+    let source = RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| CommandFlowError::Automation("invalid rgba buffer size".to_string()))?;
+    let cropped = image::imageops::crop_imm(&source, left, top, crop_width, crop_height).to_image();
+    Ok((
+        cropped.into_raw(),
+        crop_width,
+        crop_height,
+        (left as i32, top as i32),
+    ))
+}
 
-    let unix_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|duration| duration.as_millis())
-        .unwrap_or_default();
-    let safe_label = node
-        .label
-        .chars()
-        .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
-        .collect::<String>();
-    let run_dir = base.join(format!("{}-{}-{}", safe_label, node.id, unix_ms));
-    fs::create_dir_all(&run_dir).map_err(|error| CommandFlowError::Io(error.to_string()))?;
+fn offset_ocr_evaluation(
+    mut evaluation: ocr_match::OcrMatchEvaluation,
+    origin: (i32, i32),
+) -> ocr_match::OcrMatchEvaluation {
+    // 匹配点、调试条目与文本框四角都换算到屏幕坐标，保持与输出坐标一致
+    if let Some(candidate) = evaluation
+        .matched
+        .as_mut()
+        .filter(|candidate| candidate.has_position)
+    {
+        candidate.x += origin.0;
+        candidate.y += origin.1;
+    }
+    for entry in &mut evaluation.debug_entries {
+        if let Some(quad) = entry.quad.as_mut() {
+            entry.center_x += origin.0;
+            entry.center_y += origin.1;
+            for point in quad.iter_mut() {
+                point[0] += origin.0 as f32;
+                point[1] += origin.1 as f32;
+            }
+        }
+    }
+    evaluation
+}
 
-    Ok(run_dir)
+fn prepare_image_match_debug_dir(node: &WorkflowNode) -> CommandResult<PathBuf> {
+    prepare_node_debug_dir(node, "commandflow-image-match-debug")
 }
 
 fn path_to_string(path: &Path) -> CommandResult<&str> {
@@ -4795,6 +7596,11 @@ async fn execute_gui_agent_action(
 
     let system_prompt = render_gui_agent_initial_prompt(&system_prompt_template, &instruction);
     let endpoint = resolve_chat_endpoint(&base_url);
+    let capture_target = if continuous_mode {
+        Some(resolve_capture_target(node)?)
+    } else {
+        None
+    };
 
     let client = reqwest::Client::new();
     let debug_dir = if continuous_mode {
@@ -4815,18 +7621,28 @@ async fn execute_gui_agent_action(
             return Err(CommandFlowError::Canceled);
         }
 
-        let (_cleaned_base64, image_width, image_height, data_url, debug_rgba) = if continuous_mode
-        {
-            let (rgba, width, height) = screenshot::capture_fullscreen_rgba()?;
-            let base64 = screenshot::encode_rgba_to_png_base64(&rgba, width, height)?;
-            let data_url = format!("data:image/png;base64,{}", base64);
-            (base64, width, height, data_url, Some(rgba))
-        } else {
-            let base64 = normalize_base64_input(&image_input);
-            let (width, height) = decode_base64_image_dimensions(&base64, &image_format)?;
-            let data_url = format!("data:image/{};base64,{}", image_format, base64);
-            (base64, width, height, data_url, None)
-        };
+        let (image_width, image_height, image_origin, data_url, debug_rgba) =
+            if let Some(target) = capture_target.as_ref() {
+                let capture = screenshot::capture_target_rgba(target)?;
+                let base64 = screenshot::encode_rgba_to_png_base64(
+                    &capture.rgba,
+                    capture.width,
+                    capture.height,
+                )?;
+                let data_url = format!("data:image/png;base64,{}", base64);
+                (
+                    capture.width,
+                    capture.height,
+                    capture.origin,
+                    data_url,
+                    Some(capture.rgba),
+                )
+            } else {
+                let base64 = normalize_base64_input(&image_input);
+                let (width, height) = decode_base64_image_dimensions(&base64, &image_format)?;
+                let data_url = format!("data:image/{};base64,{}", image_format, base64);
+                (width, height, (0, 0), data_url, None)
+            };
 
         if let (true, Some(dir), Some(rgba)) =
             (continuous_mode, debug_dir.as_ref(), debug_rgba.as_ref())
@@ -5002,6 +7818,7 @@ async fn execute_gui_agent_action(
             action.clone(),
             image_width,
             image_height,
+            image_origin,
             should_cancel,
             on_log,
         )
@@ -5205,24 +8022,7 @@ fn extract_thought_expression(content: &str) -> Option<String> {
 }
 
 fn prepare_gui_agent_debug_dir(node: &WorkflowNode) -> CommandResult<PathBuf> {
-    let mut base = std::env::temp_dir();
-    base.push("commandflow-gui-agent-debug");
-
-    fs::create_dir_all(&base).map_err(|error| CommandFlowError::Io(error.to_string()))?;
-
-    let unix_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|duration| duration.as_millis())
-        .unwrap_or_default();
-    let safe_label = node
-        .label
-        .chars()
-        .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
-        .collect::<String>();
-    let run_dir = base.join(format!("{}-{}-{}", safe_label, node.id, unix_ms));
-    fs::create_dir_all(&run_dir).map_err(|error| CommandFlowError::Io(error.to_string()))?;
-
-    Ok(run_dir)
+    prepare_node_debug_dir(node, "commandflow-gui-agent-debug")
 }
 
 fn save_gui_agent_debug_overlay(
@@ -5791,16 +8591,21 @@ fn relative_to_absolute(point: (f64, f64), image_width: u32, image_height: u32)
     (x.round() as i32, y.round() as i32)
 }
 
+// origin 为截图左上角的屏幕坐标，窗口截图时用于把图内坐标换算回屏幕坐标
 async fn apply_gui_agent_action(
     action: GuiAgentAction,
     image_width: u32,
     image_height: u32,
+    origin: (i32, i32),
     should_cancel: &impl Fn() -> bool,
     on_log: &mut impl FnMut(&str, String),
 ) -> CommandResult<Value> {
     let metadata = match action {
         GuiAgentAction::Click { point } => {
-            let abs = relative_to_absolute(point, image_width, image_height);
+            let abs = offset_point(
+                relative_to_absolute(point, image_width, image_height),
+                origin,
+            );
             mouse::click(abs.0, abs.1, 1)?;
             on_log(
                 "info",
@@ -5817,7 +8622,10 @@ async fn apply_gui_agent_action(
             })
         }
         GuiAgentAction::LeftDouble { point } => {
-            let abs = relative_to_absolute(point, image_width, image_height);
+            let abs = offset_point(
+                relative_to_absolute(point, image_width, image_height),
+                origin,
+            );
             mouse::click(abs.0, abs.1, 2)?;
             on_log(
                 "info",
@@ -5834,7 +8642,10 @@ async fn apply_gui_agent_action(
             })
         }
         GuiAgentAction::RightSingle { point } => {
-            let abs = relative_to_absolute(point, image_width, image_height);
+            let abs = offset_point(
+                relative_to_absolute(point, image_width, image_height),
+                origin,
+            );
             mouse::button_down(abs.0, abs.1, "right")?;
             mouse::button_up(abs.0, abs.1, "right")?;
             on_log(
@@ -5852,8 +8663,12 @@ async fn apply_gui_agent_action(
             })
         }
         GuiAgentAction::Drag { start, end } => {
-            let abs_start = relative_to_absolute(start, image_width, image_height);
-            let abs_end = relative_to_absolute(end, image_width, image_height);
+            let abs_start = offset_point(
+                relative_to_absolute(start, image_width, image_height),
+                origin,
+            );
+            let abs_end =
+                offset_point(relative_to_absolute(end, image_width, image_height), origin);
             mouse::drag(abs_start.0, abs_start.1, abs_end.0, abs_end.1)?;
             on_log(
                 "info",
@@ -5940,7 +8755,10 @@ async fn apply_gui_agent_action(
             })
         }
         GuiAgentAction::Scroll { point, direction } => {
-            let abs = relative_to_absolute(point, image_width, image_height);
+            let abs = offset_point(
+                relative_to_absolute(point, image_width, image_height),
+                origin,
+            );
             mouse::move_to(abs.0, abs.1)?;
 
             match direction.as_str() {
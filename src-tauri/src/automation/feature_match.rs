@@ -0,0 +1,355 @@
+use crate::error::{CommandFlowError, CommandResult};
+use image::DynamicImage;
+use serde::Serialize;
+
+#[cfg(feature = "opencv-support")]
+use opencv::{
+    calib3d,
+    core::{self, DMatch, KeyPoint, Mat, Point2f, Vector},
+    features2d, imgcodecs,
+    prelude::*,
+};
+
+// Lowe 比值检验：最近邻距离需明显小于次近邻
+#[cfg(feature = "opencv-support")]
+const RATIO_TEST: f32 = 0.75;
+#[cfg(feature = "opencv-support")]
+const RANSAC_REPROJ_THRESHOLD: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureDetector {
+    Orb,
+    Akaze,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FeaturePoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeatureMatch {
+    pub x: i32,
+    pub y: i32,
+    // 模板四角投影到源图后的四边形，顺序为左上、右上、右下、左下
+    pub quad: [FeaturePoint; 4],
+    pub inliers: usize,
+    pub good_matches: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeatureEvaluation {
+    pub matched: Option<FeatureMatch>,
+    // 最佳候选（内点不足时也会返回，便于调试）
+    pub candidate: Option<FeatureMatch>,
+}
+
+pub struct FeatureMatcher {
+    detector: FeatureDetector,
+    #[cfg(feature = "opencv-support")]
+    min_inliers: usize,
+    #[cfg(feature = "opencv-support")]
+    template_size: (i32, i32),
+    #[cfg(feature = "opencv-support")]
+    template_keypoints: Vector<KeyPoint>,
+    #[cfg(feature = "opencv-support")]
+    template_descriptors: Mat,
+}
+
+impl FeatureDetector {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "orb" => Some(FeatureDetector::Orb),
+            "akaze" => Some(FeatureDetector::Akaze),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeatureDetector::Orb => "ORB",
+            FeatureDetector::Akaze => "AKAZE",
+        }
+    }
+}
+
+impl FeatureMatch {
+    pub fn bounding_rect(&self) -> (u32, u32, u32, u32) {
+        let min_x = self
+            .quad
+            .iter()
+            .map(|point| point.x)
+            .fold(f32::MAX, f32::min);
+        let min_y = self
+            .quad
+            .iter()
+            .map(|point| point.y)
+            .fold(f32::MAX, f32::min);
+        let max_x = self
+            .quad
+            .iter()
+            .map(|point| point.x)
+            .fold(f32::MIN, f32::max);
+        let max_y = self
+            .quad
+            .iter()
+            .map(|point| point.y)
+            .fold(f32::MIN, f32::max);
+        let left = min_x.max(0.0);
+        let top = min_y.max(0.0);
+        (
+            left as u32,
+            top as u32,
+            (max_x - left).max(0.0) as u32,
+            (max_y - top).max(0.0) as u32,
+        )
+    }
+
+    pub fn offset(mut self, dx: i32, dy: i32) -> Self {
+        self.x += dx;
+        self.y += dy;
+        for point in &mut self.quad {
+            point.x += dx as f32;
+            point.y += dy as f32;
+        }
+        self
+    }
+
+    // 投影四边形必须是凸的，否则视为单应性退化的误匹配
+    #[cfg(feature = "opencv-support")]
+    fn is_convex(&self) -> bool {
+        let mut sign = 0.0_f32;
+        for index in 0..4 {
+            let a = self.quad[index];
+            let b = self.quad[(index + 1) % 4];
+            let c = self.quad[(index + 2) % 4];
+            let cross = (b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x);
+            if cross.abs() < f32::EPSILON {
+                return false;
+            }
+            if sign == 0.0 {
+                sign = cross.signum();
+            } else if cross.signum() != sign {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(not(feature = "opencv-support"))]
+impl FeatureMatcher {
+    pub fn from_path(
+        _template_path: &str,
+        detector: FeatureDetector,
+        _min_inliers: usize,
+    ) -> CommandResult<Self> {
+        Err(CommandFlowError::Automation(format!(
+            "{} feature matching requires building with the opencv-support feature",
+            detector.name()
+        )))
+    }
+
+    pub fn detector(&self) -> FeatureDetector {
+        self.detector
+    }
+
+    pub fn evaluate(&self, _source: &DynamicImage) -> CommandResult<FeatureEvaluation> {
+        Ok(FeatureEvaluation {
+            matched: None,
+            candidate: None,
+        })
+    }
+}
+
+#[cfg(feature = "opencv-support")]
+impl FeatureMatcher {
+    pub fn from_path(
+        template_path: &str,
+        detector: FeatureDetector,
+        min_inliers: usize,
+    ) -> CommandResult<Self> {
+        let template =
+            imgcodecs::imread(template_path, imgcodecs::IMREAD_GRAYSCALE).map_err(opencv_error)?;
+        if template.empty() {
+            return Err(CommandFlowError::Validation(format!(
+                "unable to read template image '{}'",
+                template_path
+            )));
+        }
+
+        let (template_keypoints, template_descriptors) = detect_features(detector, &template)?;
+        if template_keypoints.len() < 4 {
+            return Err(CommandFlowError::Validation(format!(
+                "template '{}' has too few feature points ({}), use template matching instead",
+                template_path,
+                template_keypoints.len()
+            )));
+        }
+
+        Ok(Self {
+            detector,
+            min_inliers: min_inliers.max(4),
+            template_size: (template.cols(), template.rows()),
+            template_keypoints,
+            template_descriptors,
+        })
+    }
+
+    pub fn detector(&self) -> FeatureDetector {
+        self.detector
+    }
+
+    pub fn evaluate(&self, source: &DynamicImage) -> CommandResult<FeatureEvaluation> {
+        let gray = source.to_luma8();
+        let source_mat = Mat::new_rows_cols_with_data(
+            gray.height() as i32,
+            gray.width() as i32,
+            gray.as_raw().as_slice(),
+        )
+        .map_err(opencv_error)?
+        .try_clone()
+        .map_err(opencv_error)?;
+
+        let (source_keypoints, source_descriptors) = detect_features(self.detector, &source_mat)?;
+        let empty = FeatureEvaluation {
+            matched: None,
+            candidate: None,
+        };
+        if source_keypoints.len() < 4 || source_descriptors.empty() {
+            return Ok(empty);
+        }
+
+        // ORB 与 AKAZE 默认都输出二进制描述子，使用汉明距离
+        let matcher =
+            features2d::BFMatcher::create(core::NORM_HAMMING, false).map_err(opencv_error)?;
+        let mut knn_matches = Vector::<Vector<DMatch>>::new();
+        matcher
+            .knn_match(
+                &self.template_descriptors,
+                &source_descriptors,
+                &mut knn_matches,
+                2,
+                &core::no_array(),
+                false,
+            )
+            .map_err(opencv_error)?;
+
+        let mut template_points = Vector::<Point2f>::new();
+        let mut source_points = Vector::<Point2f>::new();
+        for pair in knn_matches.iter() {
+            if pair.len() < 2 {
+                continue;
+            }
+            let best = pair.get(0).map_err(opencv_error)?;
+            let second = pair.get(1).map_err(opencv_error)?;
+            if best.distance >= RATIO_TEST * second.distance {
+                continue;
+            }
+            let template_point = self
+                .template_keypoints
+                .get(best.query_idx as usize)
+                .map_err(opencv_error)?
+                .pt();
+            let source_point = source_keypoints
+                .get(best.train_idx as usize)
+                .map_err(opencv_error)?
+                .pt();
+            template_points.push(template_point);
+            source_points.push(source_point);
+        }
+
+        let good_matches = template_points.len();
+        if good_matches < 4 {
+            return Ok(empty);
+        }
+
+        let mut inlier_mask = Mat::default();
+        let homography = calib3d::find_homography(
+            &template_points,
+            &source_points,
+            &mut inlier_mask,
+            calib3d::RANSAC,
+            RANSAC_REPROJ_THRESHOLD,
+        )
+        .map_err(opencv_error)?;
+        if homography.empty() {
+            return Ok(empty);
+        }
+        let inliers = core::count_non_zero(&inlier_mask).map_err(opencv_error)? as usize;
+
+        let (width, height) = (self.template_size.0 as f32, self.template_size.1 as f32);
+        let mut corners = Vector::<Point2f>::new();
+        corners.push(Point2f::new(0.0, 0.0));
+        corners.push(Point2f::new(width, 0.0));
+        corners.push(Point2f::new(width, height));
+        corners.push(Point2f::new(0.0, height));
+        let mut projected = Vector::<Point2f>::new();
+        core::perspective_transform(&corners, &mut projected, &homography).map_err(opencv_error)?;
+
+        let mut quad = [FeaturePoint { x: 0.0, y: 0.0 }; 4];
+        for (index, point) in projected.iter().take(4).enumerate() {
+            quad[index] = FeaturePoint {
+                x: point.x,
+                y: point.y,
+            };
+        }
+        let center_x = quad.iter().map(|point| point.x).sum::<f32>() / 4.0;
+        let center_y = quad.iter().map(|point| point.y).sum::<f32>() / 4.0;
+        let candidate = FeatureMatch {
+            x: center_x.round() as i32,
+            y: center_y.round() as i32,
+            quad,
+            inliers,
+            good_matches,
+        };
+
+        let matched =
+            (inliers >= self.min_inliers && candidate.is_convex()).then(|| candidate.clone());
+        Ok(FeatureEvaluation {
+            matched,
+            candidate: Some(candidate),
+        })
+    }
+}
+
+#[cfg(feature = "opencv-support")]
+fn detect_features(
+    detector: FeatureDetector,
+    image: &Mat,
+) -> CommandResult<(Vector<KeyPoint>, Mat)> {
+    let mut keypoints = Vector::<KeyPoint>::new();
+    let mut descriptors = Mat::default();
+    match detector {
+        FeatureDetector::Orb => {
+            let mut orb = features2d::ORB::create_def().map_err(opencv_error)?;
+            orb.detect_and_compute(
+                image,
+                &core::no_array(),
+                &mut keypoints,
+                &mut descriptors,
+                false,
+            )
+            .map_err(opencv_error)?;
+        }
+        FeatureDetector::Akaze => {
+            let mut akaze = features2d::AKAZE::create_def().map_err(opencv_error)?;
+            akaze
+                .detect_and_compute(
+                    image,
+                    &core::no_array(),
+                    &mut keypoints,
+                    &mut descriptors,
+                    false,
+                )
+                .map_err(opencv_error)?;
+        }
+    }
+    Ok((keypoints, descriptors))
+}
+
+#[cfg(feature = "opencv-support")]
+fn opencv_error(error: opencv::Error) -> CommandFlowError {
+    CommandFlowError::Automation(error.to_string())
+}
@@ -0,0 +1,825 @@
+use crate::automation::file_ops;
+use crate::error::{CommandFlowError, CommandResult};
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const JOURNAL_FILE_NAME: &str = "journal.jsonl";
+const UNDONE_MARKER_NAME: &str = "undone.json";
+// 回收区保留策略：每次运行开始时清理超过保留天数的运行，总占用超过上限时再从最旧的开始删除
+const JOURNAL_MAX_AGE_DAYS: i64 = 7;
+const JOURNAL_MAX_TOTAL_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub seq: u64,
+    pub node_id: String,
+    pub action: String,
+    pub source: Option<String>,
+    pub target: String,
+    pub backup: Option<String>,
+    pub created: bool,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedChange {
+    pub action: String,
+    pub source: Option<String>,
+    pub target: String,
+    pub target_exists: bool,
+    pub overwrite: bool,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoReport {
+    pub run_id: String,
+    pub total: usize,
+    pub restored: usize,
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    pub removed_runs: usize,
+    pub freed_bytes: u64,
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct RunJournal {
+    dir: PathBuf,
+    // 打开时从日志读取一次，之后在内存中递增，避免每次操作都重读整个日志
+    next_seq: AtomicU64,
+}
+
+pub fn new_run_id() -> String {
+    static RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
+    let counter = RUN_COUNTER.fetch_add(1, Ordering::Relaxed) % 10_000;
+    format!(
+        "{}-{:04}",
+        Local::now().format("%Y%m%d-%H%M%S-%3f"),
+        counter
+    )
+}
+
+pub fn plan_change(
+    action: &str,
+    source: Option<&str>,
+    target: &str,
+    overwrite: bool,
+) -> PlannedChange {
+    let target_exists = Path::new(target).exists();
+    let summary = match (action, source) {
+        ("delete", _) => format!("删除 '{}'", target),
+        ("writeText", _) if target_exists => format!("覆盖写入 '{}'", target),
+        ("writeText", _) => format!("新建文件 '{}'", target),
+        (_, Some(source)) if target_exists && overwrite => {
+            format!("{} '{}' -> '{}'（覆盖已有目标）", action, source, target)
+        }
+        (_, Some(source)) => format!("{} '{}' -> '{}'", action, source, target),
+        (_, None) => format!("{} '{}'", action, target),
+    };
+
+    PlannedChange {
+        action: action.to_string(),
+        source: source.map(ToString::to_string),
+        target: target.to_string(),
+        target_exists,
+        overwrite,
+        summary,
+    }
+}
+
+pub fn undo_run(run_id: &str) -> CommandResult<UndoReport> {
+    let journal = RunJournal::open(run_id)?;
+    if journal.dir.join(UNDONE_MARKER_NAME).exists() {
+        return Err(CommandFlowError::Validation(format!(
+            "run '{}' has already been undone",
+            run_id
+        )));
+    }
+
+    let entries = journal.read_entries()?;
+    if entries.is_empty() {
+        return Err(CommandFlowError::Validation(format!(
+            "run '{}' has no journaled file operations",
+            run_id
+        )));
+    }
+
+    let mut report = UndoReport {
+        run_id: run_id.to_string(),
+        total: entries.len(),
+        restored: 0,
+        failures: Vec::new(),
+    };
+
+    for entry in entries.iter().rev() {
+        match journal.undo_entry(entry) {
+            Ok(()) => report.restored += 1,
+            Err(error) => report.failures.push(format!(
+                "#{} {} '{}': {}",
+                entry.seq, entry.action, entry.target, error
+            )),
+        }
+    }
+
+    let marker = serde_json::to_string_pretty(&report)
+        .map_err(|error| CommandFlowError::Io(error.to_string()))?;
+    fs::write(journal.dir.join(UNDONE_MARKER_NAME), marker).map_err(io_error)?;
+
+    Ok(report)
+}
+
+// 运行开始时调用，按保留策略清理历史运行；当前运行不会被清理
+pub fn prune_journals(current_run_id: &str) -> CommandResult<PurgeReport> {
+    prune_journals_in(
+        &journal_root(),
+        Some(current_run_id),
+        chrono::Duration::days(JOURNAL_MAX_AGE_DAYS),
+        JOURNAL_MAX_TOTAL_BYTES,
+        Local::now().naive_local(),
+    )
+}
+
+// 手动清空全部回收区与日志，清空后历史运行将无法撤销
+pub fn purge_journals() -> CommandResult<PurgeReport> {
+    prune_journals_in(
+        &journal_root(),
+        None,
+        chrono::Duration::zero(),
+        0,
+        Local::now().naive_local(),
+    )
+}
+
+fn prune_journals_in(
+    root: &Path,
+    keep_run_id: Option<&str>,
+    max_age: chrono::Duration,
+    max_total_bytes: u64,
+    now: NaiveDateTime,
+) -> CommandResult<PurgeReport> {
+    let mut report = PurgeReport::default();
+    let Ok(read_dir) = fs::read_dir(root) else {
+        return Ok(report);
+    };
+
+    let mut runs = Vec::new();
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_dir = fs::symlink_metadata(&path)
+            .map(|metadata| metadata.is_dir())
+            .unwrap_or(false);
+        if !is_dir || keep_run_id == Some(name.as_str()) {
+            continue;
+        }
+        let started = run_started_at(&name, &path);
+        runs.push((started, directory_size(&path), path));
+    }
+    // 从最旧的运行开始清理
+    runs.sort_by_key(|(started, _, _)| *started);
+
+    let mut total: u64 = runs.iter().map(|(_, size, _)| *size).sum();
+    for (started, size, path) in runs {
+        let expired = match started {
+            Some(started) => now - started >= max_age,
+            None => true,
+        };
+        if !expired && total <= max_total_bytes {
+            continue;
+        }
+        match fs::remove_dir_all(&path) {
+            Ok(()) => {
+                total = total.saturating_sub(size);
+                report.removed_runs += 1;
+                report.freed_bytes += size;
+            }
+            Err(error) => report
+                .failures
+                .push(format!("{}: {}", path.display(), error)),
+        }
+    }
+
+    Ok(report)
+}
+
+// 运行 id 以启动时间开头；无法解析时退回目录修改时间
+fn run_started_at(run_id: &str, path: &Path) -> Option<NaiveDateTime> {
+    run_id
+        .get(..15)
+        .and_then(|prefix| NaiveDateTime::parse_from_str(prefix, "%Y%m%d-%H%M%S").ok())
+        .or_else(|| {
+            let modified = fs::metadata(path).ok()?.modified().ok()?;
+            Some(chrono::DateTime::<Local>::from(modified).naive_local())
+        })
+}
+
+fn directory_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| directory_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+impl RunJournal {
+    pub fn open(run_id: &str) -> CommandResult<Self> {
+        let run_id = run_id.trim();
+        if run_id.is_empty()
+            || !run_id
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+        {
+            return Err(CommandFlowError::Validation(format!(
+                "invalid run id: '{}'",
+                run_id
+            )));
+        }
+
+        Self::open_in(&journal_root(), run_id)
+    }
+
+    fn open_in(root: &Path, run_id: &str) -> CommandResult<Self> {
+        let journal = Self {
+            dir: root.join(run_id),
+            next_seq: AtomicU64::new(1),
+        };
+        let next = journal
+            .read_entries()?
+            .last()
+            .map(|entry| entry.seq + 1)
+            .unwrap_or(1);
+        journal.next_seq.store(next, Ordering::Relaxed);
+        Ok(journal)
+    }
+
+    pub fn copy_path(
+        &self,
+        node_id: &str,
+        source: &str,
+        target: &str,
+        overwrite: bool,
+        recursive: bool,
+    ) -> CommandResult<JournalEntry> {
+        let seq = self.next_seq()?;
+        let dst = Path::new(target);
+        let created = !dst.exists();
+        let backup = if !created && overwrite && Path::new(source).exists() {
+            Some(self.stash(seq, dst)?)
+        } else {
+            None
+        };
+
+        if let Err(error) = file_ops::copy_path(source, target, overwrite, recursive) {
+            self.restore_after_failure(backup.as_deref(), target);
+            return Err(error);
+        }
+
+        self.append(JournalEntry {
+            seq,
+            node_id: node_id.to_string(),
+            action: "copy".to_string(),
+            source: Some(source.to_string()),
+            target: target.to_string(),
+            created: backup.is_none(),
+            backup,
+            timestamp: now_text(),
+        })
+    }
+
+    pub fn move_path(
+        &self,
+        node_id: &str,
+        source: &str,
+        target: &str,
+        overwrite: bool,
+    ) -> CommandResult<JournalEntry> {
+        let seq = self.next_seq()?;
+        let dst = Path::new(target);
+        let backup = if dst.exists() && overwrite && Path::new(source).exists() {
+            Some(self.stash(seq, dst)?)
+        } else {
+            None
+        };
+
+        if let Err(error) = file_ops::move_path(source, target, overwrite) {
+            self.restore_after_failure(backup.as_deref(), target);
+            return Err(error);
+        }
+
+        self.append(JournalEntry {
+            seq,
+            node_id: node_id.to_string(),
+            action: "move".to_string(),
+            source: Some(source.to_string()),
+            target: target.to_string(),
+            created: backup.is_none(),
+            backup,
+            timestamp: now_text(),
+        })
+    }
+
+    pub fn delete_path(
+        &self,
+        node_id: &str,
+        path: &str,
+        recursive: bool,
+    ) -> CommandResult<JournalEntry> {
+        let target = Path::new(path);
+        if !target.exists() {
+            return Err(CommandFlowError::Validation(format!(
+                "path does not exist: {}",
+                path
+            )));
+        }
+
+        if target.is_dir() && !recursive {
+            let has_children = fs::read_dir(target).map_err(io_error)?.next().is_some();
+            if has_children {
+                return Err(CommandFlowError::Validation(format!(
+                    "directory is not empty; enable recursive delete: {}",
+                    path
+                )));
+            }
+        }
+
+        let seq = self.next_seq()?;
+        let backup = self.stash(seq, target)?;
+
+        self.append(JournalEntry {
+            seq,
+            node_id: node_id.to_string(),
+            action: "delete".to_string(),
+            source: None,
+            target: path.to_string(),
+            backup: Some(backup),
+            created: false,
+            timestamp: now_text(),
+        })
+    }
+
+    pub fn write_file(
+        &self,
+        node_id: &str,
+        path: &str,
+        write: impl FnOnce() -> CommandResult<()>,
+    ) -> CommandResult<JournalEntry> {
+        let seq = self.next_seq()?;
+        let target = Path::new(path);
+        let backup = if target.is_file() {
+            Some(self.snapshot(seq, target)?)
+        } else {
+            None
+        };
+
+        write()?;
+
+        self.append(JournalEntry {
+            seq,
+            node_id: node_id.to_string(),
+            action: "writeText".to_string(),
+            source: None,
+            target: path.to_string(),
+            created: backup.is_none(),
+            backup,
+            timestamp: now_text(),
+        })
+    }
+
+    fn read_entries(&self) -> CommandResult<Vec<JournalEntry>> {
+        let path = self.dir.join(JOURNAL_FILE_NAME);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path).map_err(io_error)?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str::<JournalEntry>(line).map_err(|error| {
+                    CommandFlowError::Io(format!(
+                        "failed to parse journal '{}': {}",
+                        path.display(),
+                        error
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    fn next_seq(&self) -> CommandResult<u64> {
+        Ok(self.next_seq.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn append(&self, entry: JournalEntry) -> CommandResult<JournalEntry> {
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        let line = serde_json::to_string(&entry)
+            .map_err(|error| CommandFlowError::Io(error.to_string()))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(JOURNAL_FILE_NAME))
+            .map_err(io_error)?;
+        writeln!(file, "{}", line).map_err(io_error)?;
+        Ok(entry)
+    }
+
+    fn backup_path(&self, slot: &str, original: &Path) -> CommandResult<PathBuf> {
+        let name = original
+            .file_name()
+            .map(|value| value.to_string_lossy().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| "item".to_string());
+        let slot = self.dir.join("trash").join(slot);
+        fs::create_dir_all(&slot).map_err(io_error)?;
+        Ok(slot.join(name))
+    }
+
+    fn stash(&self, seq: u64, original: &Path) -> CommandResult<String> {
+        let backup = self.backup_path(&format!("{:04}", seq), original)?;
+        let backup_text = backup.to_string_lossy().to_string();
+        file_ops::move_path(&original.to_string_lossy(), &backup_text, false)?;
+        Ok(backup_text)
+    }
+
+    fn snapshot(&self, seq: u64, original: &Path) -> CommandResult<String> {
+        let backup = self.backup_path(&format!("{:04}", seq), original)?;
+        let backup_text = backup.to_string_lossy().to_string();
+        file_ops::copy_path(&original.to_string_lossy(), &backup_text, false, true)?;
+        Ok(backup_text)
+    }
+
+    fn restore_after_failure(&self, backup: Option<&str>, target: &str) {
+        if let Some(backup) = backup {
+            if !Path::new(target).exists() {
+                let _ = file_ops::move_path(backup, target, false);
+            }
+        }
+    }
+
+    fn undo_entry(&self, entry: &JournalEntry) -> CommandResult<()> {
+        let target_exists = Path::new(&entry.target).exists();
+        match entry.action.as_str() {
+            "copy" | "writeText" => {
+                // 撤销时不直接删除新产生的内容，而是移入回收区，避免误撤销后无法找回
+                if target_exists {
+                    let discarded = self
+                        .backup_path(&format!("{:04}-undo", entry.seq), Path::new(&entry.target))?;
+                    file_ops::move_path(&entry.target, &discarded.to_string_lossy(), false)?;
+                }
+            }
+            "move" => {
+                let Some(source) = entry.source.as_deref() else {
+                    return Err(CommandFlowError::Validation(
+                        "journal entry is missing the move source".to_string(),
+                    ));
+                };
+                if !target_exists {
+                    return Err(CommandFlowError::Validation(format!(
+                        "moved item no longer exists: {}",
+                        entry.target
+                    )));
+                }
+                file_ops::move_path(&entry.target, source, false)?;
+            }
+            "delete" => {
+                if target_exists {
+                    return Err(CommandFlowError::Validation(format!(
+                        "path was recreated after delete, refusing to overwrite: {}",
+                        entry.target
+                    )));
+                }
+            }
+            other => {
+                return Err(CommandFlowError::Validation(format!(
+                    "unsupported journal action: {}",
+                    other
+                )));
+            }
+        }
+
+        if let Some(backup) = entry.backup.as_deref() {
+            file_ops::move_path(backup, &entry.target, false)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn journal_root() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(local_app_data) = std::env::var_os("LOCALAPPDATA") {
+            return PathBuf::from(local_app_data)
+                .join("CommandFlow")
+                .join("file-journal");
+        }
+    }
+
+    std::env::temp_dir()
+        .join("CommandFlow")
+        .join("file-journal")
+}
+
+fn now_text() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+fn io_error(error: std::io::Error) -> CommandFlowError {
+    CommandFlowError::Io(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{new_run_id, prune_journals_in, undo_run, RunJournal};
+    use chrono::NaiveDateTime;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_workspace(name: &str) -> PathBuf {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system clock")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("commandflow-journal-{}-{}", name, stamp));
+        fs::create_dir_all(&dir).expect("create workspace");
+        dir
+    }
+
+    fn text(path: &std::path::Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn undo_copy_moves_created_target_to_trash() {
+        let dir = temp_workspace("copy");
+        let source = dir.join("a.txt");
+        let target = dir.join("b.txt");
+        fs::write(&source, "source").expect("write source");
+
+        let run_id = new_run_id();
+        let journal = RunJournal::open(&run_id).expect("open journal");
+        let entry = journal
+            .copy_path("n1", &text(&source), &text(&target), false, true)
+            .expect("copy");
+        assert!(entry.created);
+        assert!(target.exists());
+
+        let report = undo_run(&run_id).expect("undo");
+        assert_eq!(report.restored, 1);
+        assert!(report.failures.is_empty());
+        assert!(!target.exists());
+        assert!(source.exists());
+        let discarded = journal.dir.join("trash").join("0001-undo").join("b.txt");
+        assert_eq!(
+            fs::read_to_string(discarded).expect("discarded copy"),
+            "source"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&journal.dir);
+    }
+
+    #[test]
+    fn undo_overwriting_copy_restores_previous_target() {
+        let dir = temp_workspace("overwrite");
+        let source = dir.join("a.txt");
+        let target = dir.join("b.txt");
+        fs::write(&source, "new").expect("write source");
+        fs::write(&target, "old").expect("write target");
+
+        let run_id = new_run_id();
+        let journal = RunJournal::open(&run_id).expect("open journal");
+        journal
+            .copy_path("n1", &text(&source), &text(&target), true, true)
+            .expect("copy");
+        assert_eq!(fs::read_to_string(&target).expect("read target"), "new");
+
+        undo_run(&run_id).expect("undo");
+        assert_eq!(fs::read_to_string(&target).expect("read target"), "old");
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&journal.dir);
+    }
+
+    #[test]
+    fn undo_restores_moves_deletes_and_writes_in_reverse_order() {
+        let dir = temp_workspace("mixed");
+        let original = dir.join("report.txt");
+        let moved = dir.join("archive").join("report.txt");
+        let notes = dir.join("notes.txt");
+        let doomed = dir.join("doomed");
+        fs::write(&original, "report").expect("write report");
+        fs::write(&notes, "v1").expect("write notes");
+        fs::create_dir_all(&doomed).expect("create dir");
+        fs::write(doomed.join("inner.txt"), "inner").expect("write inner");
+        fs::create_dir_all(moved.parent().expect("parent")).expect("create archive");
+
+        let run_id = new_run_id();
+        let journal = RunJournal::open(&run_id).expect("open journal");
+        journal
+            .move_path("n1", &text(&original), &text(&moved), false)
+            .expect("move");
+        journal
+            .write_file("n2", &text(&notes), || {
+                fs::write(&notes, "v2").map_err(super::io_error)
+            })
+            .expect("write");
+        journal
+            .delete_path("n3", &text(&doomed), true)
+            .expect("delete");
+        assert!(!original.exists());
+        assert!(!doomed.exists());
+
+        let report = undo_run(&run_id).expect("undo");
+        assert_eq!(report.total, 3);
+        assert_eq!(report.restored, 3);
+        assert_eq!(fs::read_to_string(&original).expect("report"), "report");
+        assert!(!moved.exists());
+        assert_eq!(fs::read_to_string(&notes).expect("notes"), "v1");
+        assert_eq!(
+            fs::read_to_string(doomed.join("inner.txt")).expect("inner"),
+            "inner"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&journal.dir);
+    }
+
+    #[test]
+    fn undo_run_only_once() {
+        let dir = temp_workspace("once");
+        let path = dir.join("new.txt");
+
+        let run_id = new_run_id();
+        let journal = RunJournal::open(&run_id).expect("open journal");
+        journal
+            .write_file("n1", &text(&path), || {
+                fs::write(&path, "created").map_err(super::io_error)
+            })
+            .expect("write");
+
+        undo_run(&run_id).expect("first undo");
+        assert!(!path.exists());
+        assert!(undo_run(&run_id).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&journal.dir);
+    }
+
+    #[test]
+    fn open_rejects_path_like_run_ids() {
+        for run_id in ["", "../escape", "a/b", "a\\b"] {
+            assert!(RunJournal::open(run_id).is_err(), "{run_id:?}");
+        }
+    }
+
+    fn write_run(root: &std::path::Path, run_id: &str, bytes: usize) {
+        let trash = root.join(run_id).join("trash").join("0001");
+        fs::create_dir_all(&trash).expect("create trash");
+        fs::write(trash.join("data.bin"), vec![0_u8; bytes]).expect("write data");
+    }
+
+    fn remaining_runs(root: &std::path::Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(root)
+            .expect("read root")
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").expect("timestamp")
+    }
+
+    #[test]
+    fn prune_removes_expired_runs_but_keeps_current() {
+        let root = temp_workspace("prune-age");
+        write_run(&root, "20260101-080000-000-0000", 10);
+        write_run(&root, "20260109-080000-000-0001", 10);
+        write_run(&root, "20260110-080000-000-0002", 10);
+
+        let report = prune_journals_in(
+            &root,
+            Some("20260101-080000-000-0000"),
+            chrono::Duration::days(7),
+            u64::MAX,
+            at("2026-01-10 09:00:00"),
+        )
+        .expect("prune");
+
+        assert_eq!(report.removed_runs, 0);
+        assert_eq!(remaining_runs(&root).len(), 3);
+
+        let report = prune_journals_in(
+            &root,
+            Some("20260110-080000-000-0002"),
+            chrono::Duration::days(7),
+            u64::MAX,
+            at("2026-01-10 09:00:00"),
+        )
+        .expect("prune");
+        assert_eq!(report.removed_runs, 1);
+        assert_eq!(report.freed_bytes, 10);
+        assert_eq!(
+            remaining_runs(&root),
+            vec!["20260109-080000-000-0001", "20260110-080000-000-0002"]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn prune_drops_oldest_runs_until_under_size_cap() {
+        let root = temp_workspace("prune-size");
+        write_run(&root, "20260108-080000-000-0000", 100);
+        write_run(&root, "20260109-080000-000-0001", 100);
+        write_run(&root, "20260110-080000-000-0002", 100);
+
+        let report = prune_journals_in(
+            &root,
+            None,
+            chrono::Duration::days(7),
+            150,
+            at("2026-01-10 09:00:00"),
+        )
+        .expect("prune");
+
+        assert_eq!(report.removed_runs, 2);
+        assert_eq!(remaining_runs(&root), vec!["20260110-080000-000-0002"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn purge_with_zero_retention_removes_everything() {
+        let root = temp_workspace("purge");
+        write_run(&root, "20260110-080000-000-0000", 1);
+        write_run(&root, "not-a-run-id", 1);
+
+        let report = prune_journals_in(
+            &root,
+            None,
+            chrono::Duration::zero(),
+            0,
+            at("2026-01-10 09:00:00"),
+        )
+        .expect("purge");
+
+        assert_eq!(report.removed_runs, 2);
+        assert!(remaining_runs(&root).is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn sequence_continues_from_existing_journal() {
+        let dir = temp_workspace("seq");
+        let run_id = new_run_id();
+        let first = RunJournal::open(&run_id).expect("open journal");
+        for index in 0..3 {
+            let path = dir.join(format!("{}.txt", index));
+            let entry = first
+                .write_file("n1", &text(&path), || {
+                    fs::write(&path, "x").map_err(super::io_error)
+                })
+                .expect("write");
+            assert_eq!(entry.seq, index + 1);
+        }
+
+        let reopened = RunJournal::open(&run_id).expect("reopen journal");
+        let path = dir.join("next.txt");
+        let entry = reopened
+            .write_file("n2", &text(&path), || {
+                fs::write(&path, "y").map_err(super::io_error)
+            })
+            .expect("write");
+        assert_eq!(entry.seq, 4);
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&first.dir);
+    }
+}
@@ -1,6 +1,72 @@
 use crate::error::{CommandFlowError, CommandResult};
+use chrono::{DateTime, Local};
+use encoding_rs::{Encoding, GB18030, GBK, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use glob::{MatchOptions, Pattern};
+use serde::Serialize;
+use std::cmp::Ordering;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEntry {
+    pub path: String,
+    pub name: String,
+    pub extension: String,
+    pub file_type: String,
+    pub size: u64,
+    pub modified_ms: Option<u64>,
+    pub created_ms: Option<u64>,
+    pub accessed_ms: Option<u64>,
+    pub modified: String,
+    pub readonly: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileInfo {
+    pub exists: bool,
+    #[serde(flatten)]
+    pub entry: FileEntry,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: String,
+    pub had_bom: bool,
+    pub had_errors: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveCreateOptions {
+    pub patterns: Vec<String>,
+    pub recursive: bool,
+    pub compression_level: i64,
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveExtractOptions {
+    pub patterns: Vec<String>,
+    pub password: Option<String>,
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListFilesOptions {
+    pub patterns: Vec<String>,
+    pub recursive: bool,
+    pub include_files: bool,
+    pub include_dirs: bool,
+    pub sort_by: String,
+    pub descending: bool,
+    pub limit: usize,
+}
 
 pub fn copy_path(
     source: &str,
@@ -150,6 +216,811 @@ fn delete_existing(path: &Path) -> CommandResult<()> {
     Ok(())
 }
 
+pub fn list_files(dir: &str, options: &ListFilesOptions) -> CommandResult<Vec<FileEntry>> {
+    let root = Path::new(dir);
+    if !root.is_dir() {
+        return Err(CommandFlowError::Validation(format!(
+            "directory does not exist: {}",
+            dir
+        )));
+    }
+
+    let patterns = compile_patterns(&options.patterns)?;
+    let mut entries = Vec::new();
+    collect_entries(root, root, options, &patterns, &mut entries)?;
+
+    let sort_by = options.sort_by.trim().to_lowercase();
+    entries.sort_by(|left, right| {
+        let ordering = match sort_by.as_str() {
+            "mtime" | "modified" => left.modified_ms.cmp(&right.modified_ms),
+            "size" => left.size.cmp(&right.size),
+            _ => Ordering::Equal,
+        };
+        ordering.then_with(|| left.path.to_lowercase().cmp(&right.path.to_lowercase()))
+    });
+    if options.descending {
+        entries.reverse();
+    }
+    if options.limit > 0 {
+        entries.truncate(options.limit);
+    }
+
+    Ok(entries)
+}
+
+pub fn file_info(path: &str) -> CommandResult<FileInfo> {
+    let target = Path::new(path);
+    match fs::metadata(target) {
+        Ok(metadata) => Ok(FileInfo {
+            exists: true,
+            entry: build_entry(target, &metadata),
+        }),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(FileInfo {
+            exists: false,
+            entry: FileEntry {
+                path: path.to_string(),
+                name: file_name_of(target),
+                extension: extension_of(target),
+                file_type: "missing".to_string(),
+                size: 0,
+                modified_ms: None,
+                created_ms: None,
+                accessed_ms: None,
+                modified: String::new(),
+                readonly: false,
+            },
+        }),
+        Err(error) => Err(io_error(error)),
+    }
+}
+
+pub fn find_existing(path: &str) -> CommandResult<Vec<FileEntry>> {
+    let target = Path::new(path);
+    let file_name = file_name_of(target);
+    if !is_glob_pattern(&file_name) {
+        let info = file_info(path)?;
+        return Ok(if info.exists {
+            vec![info.entry]
+        } else {
+            Vec::new()
+        });
+    }
+
+    let Some(parent) = target.parent().filter(|parent| parent.is_dir()) else {
+        return Ok(Vec::new());
+    };
+    list_files(
+        &parent.to_string_lossy(),
+        &ListFilesOptions {
+            patterns: vec![file_name],
+            recursive: false,
+            include_files: true,
+            include_dirs: false,
+            sort_by: "modified".to_string(),
+            descending: true,
+            limit: 0,
+        },
+    )
+}
+
+pub fn read_text(path: &str, encoding: &str) -> CommandResult<DecodedText> {
+    let bytes = fs::read(path).map_err(io_error)?;
+    decode_text(&bytes, encoding)
+}
+
+pub fn decode_text(bytes: &[u8], encoding: &str) -> CommandResult<DecodedText> {
+    let name = canonical_encoding(encoding)?;
+    if name == "auto" {
+        return Ok(decode_auto(bytes));
+    }
+
+    let (text, had_bom, had_errors) = match name {
+        "utf-8" | "utf-8-bom" => decode_with_bom(UTF_8, bytes),
+        "utf-16le" => decode_with_bom(UTF_16LE, bytes),
+        "utf-16be" => decode_with_bom(UTF_16BE, bytes),
+        _ => {
+            let (text, had_errors) = encoding_for(name).decode_without_bom_handling(bytes);
+            (text.into_owned(), false, had_errors)
+        }
+    };
+
+    Ok(DecodedText {
+        text,
+        encoding: name.to_string(),
+        had_bom,
+        had_errors,
+    })
+}
+
+pub fn encode_text(text: &str, encoding: &str, include_bom: bool) -> CommandResult<Vec<u8>> {
+    let name = match canonical_encoding(encoding)? {
+        "auto" => "utf-8",
+        other => other,
+    };
+
+    let mut bytes = Vec::with_capacity(text.len() + 3);
+    match name {
+        "utf-8" | "utf-8-bom" => {
+            if include_bom && name == "utf-8-bom" {
+                bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+            }
+            bytes.extend_from_slice(text.as_bytes());
+        }
+        "utf-16le" | "utf-16be" => {
+            let little_endian = name == "utf-16le";
+            if include_bom {
+                bytes.extend_from_slice(if little_endian {
+                    &[0xFF, 0xFE]
+                } else {
+                    &[0xFE, 0xFF]
+                });
+            }
+            for unit in text.encode_utf16() {
+                let pair = if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                };
+                bytes.extend_from_slice(&pair);
+            }
+        }
+        _ => {
+            let (encoded, _, had_unmappable) = encoding_for(name).encode(text);
+            if had_unmappable {
+                return Err(CommandFlowError::Validation(format!(
+                    "text contains characters that cannot be encoded as {}",
+                    name
+                )));
+            }
+            bytes.extend_from_slice(&encoded);
+        }
+    }
+
+    Ok(bytes)
+}
+
+pub fn normalize_line_endings(text: &str, mode: &str) -> String {
+    let separator = match mode.trim().to_lowercase().as_str() {
+        "lf" | "unix" => "\n",
+        "crlf" | "windows" => "\r\n",
+        "cr" => "\r",
+        _ => return text.to_string(),
+    };
+
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', separator)
+}
+
+fn canonical_encoding(raw: &str) -> CommandResult<&'static str> {
+    let normalized = raw.trim().to_lowercase().replace(['_', ' '], "-");
+    let name = match normalized.as_str() {
+        "" | "auto" => "auto",
+        "utf-8" | "utf8" => "utf-8",
+        "utf-8-bom" | "utf8-bom" | "utf-8-sig" => "utf-8-bom",
+        "utf-16le" | "utf-16-le" | "utf16le" | "utf-16" | "unicode" => "utf-16le",
+        "utf-16be" | "utf-16-be" | "utf16be" => "utf-16be",
+        "gbk" | "gb2312" | "cp936" => "gbk",
+        "gb18030" => "gb18030",
+        "shift-jis" | "shiftjis" | "sjis" | "cp932" => "shift_jis",
+        "windows-1252" | "cp1252" | "latin1" | "iso-8859-1" => "windows-1252",
+        _ => {
+            return Err(CommandFlowError::Validation(format!(
+                "unsupported text encoding: {}",
+                raw
+            )))
+        }
+    };
+    Ok(name)
+}
+
+fn encoding_for(name: &str) -> &'static Encoding {
+    match name {
+        "utf-16le" => UTF_16LE,
+        "utf-16be" => UTF_16BE,
+        "gbk" => GBK,
+        "gb18030" => GB18030,
+        "shift_jis" => SHIFT_JIS,
+        "windows-1252" => WINDOWS_1252,
+        _ => UTF_8,
+    }
+}
+
+fn decode_with_bom(encoding: &'static Encoding, bytes: &[u8]) -> (String, bool, bool) {
+    let had_bom = Encoding::for_bom(bytes)
+        .map(|(found, _)| found == encoding)
+        .unwrap_or(false);
+    let (text, had_errors) = encoding.decode_with_bom_removal(bytes);
+    (text.into_owned(), had_bom, had_errors)
+}
+
+fn decode_auto(bytes: &[u8]) -> DecodedText {
+    let (name, had_bom) = match Encoding::for_bom(bytes) {
+        Some((found, _)) if found == UTF_8 => ("utf-8-bom", true),
+        Some((found, _)) if found == UTF_16LE => ("utf-16le", true),
+        Some((found, _)) if found == UTF_16BE => ("utf-16be", true),
+        _ => (sniff_encoding(bytes), false),
+    };
+
+    let encoding = encoding_for(name);
+    let (text, had_errors) = if had_bom {
+        encoding.decode_with_bom_removal(bytes)
+    } else {
+        encoding.decode_without_bom_handling(bytes)
+    };
+
+    DecodedText {
+        text: text.into_owned(),
+        encoding: name.to_string(),
+        had_bom,
+        had_errors,
+    }
+}
+
+fn sniff_encoding(bytes: &[u8]) -> &'static str {
+    let sample = &bytes[..bytes.len().min(4096)];
+    if sample.len() >= 4 && sample.len().is_multiple_of(2) {
+        let pairs = sample.len() / 2;
+        let even_zeros = sample.iter().step_by(2).filter(|byte| **byte == 0).count();
+        let odd_zeros = sample
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|byte| **byte == 0)
+            .count();
+        if odd_zeros * 10 >= pairs * 3 && even_zeros * 10 < pairs {
+            return "utf-16le";
+        }
+        if even_zeros * 10 >= pairs * 3 && odd_zeros * 10 < pairs {
+            return "utf-16be";
+        }
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        return "utf-8";
+    }
+
+    let (shift_jis_text, shift_jis_errors) = SHIFT_JIS.decode_without_bom_handling(bytes);
+    let (_, gbk_errors) = GBK.decode_without_bom_handling(bytes);
+    if !shift_jis_errors && (gbk_errors || looks_like_japanese(&shift_jis_text)) {
+        return "shift_jis";
+    }
+    if !gbk_errors {
+        return "gbk";
+    }
+
+    "windows-1252"
+}
+
+fn looks_like_japanese(text: &str) -> bool {
+    let mut non_ascii = 0usize;
+    let mut kana = 0usize;
+    for ch in text.chars().filter(|ch| !ch.is_ascii()) {
+        non_ascii += 1;
+        if ('\u{3040}'..='\u{30FF}').contains(&ch) {
+            kana += 1;
+        }
+    }
+    non_ascii > 0 && kana * 5 >= non_ascii
+}
+
+pub fn create_zip(
+    source: &str,
+    archive_path: &str,
+    options: &ArchiveCreateOptions,
+) -> CommandResult<Vec<String>> {
+    let src = Path::new(source);
+    if !src.exists() {
+        return Err(CommandFlowError::Validation(format!(
+            "source path does not exist: {}",
+            source
+        )));
+    }
+
+    let archive = Path::new(archive_path);
+    if archive.exists() && !options.overwrite {
+        return Err(CommandFlowError::Validation(format!(
+            "archive already exists: {}",
+            archive_path
+        )));
+    }
+
+    let files: Vec<(PathBuf, String)> = if src.is_file() {
+        vec![(src.to_path_buf(), file_name_of(src))]
+    } else {
+        let archive_abs = fs::canonicalize(archive).ok();
+        let patterns = compile_patterns(&options.patterns)?;
+        let list_options = ListFilesOptions {
+            patterns: options.patterns.clone(),
+            recursive: options.recursive,
+            include_files: true,
+            include_dirs: false,
+            sort_by: "name".to_string(),
+            descending: false,
+            limit: 0,
+        };
+        let mut entries = Vec::new();
+        collect_entries(src, src, &list_options, &patterns, &mut entries)?;
+        entries.sort_by(|left, right| left.path.cmp(&right.path));
+        entries
+            .into_iter()
+            .map(|entry| PathBuf::from(entry.path))
+            .filter(|path| archive_abs.is_none() || fs::canonicalize(path).ok() != archive_abs)
+            .map(|path| {
+                let name = path
+                    .strip_prefix(src)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                (path, name)
+            })
+            .collect()
+    };
+
+    if files.is_empty() {
+        return Err(CommandFlowError::Validation(format!(
+            "no files matched for archive: {}",
+            source
+        )));
+    }
+
+    if let Some(parent) = archive.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+    }
+
+    let level = options.compression_level.clamp(0, 9);
+    let base_options = if level == 0 {
+        SimpleFileOptions::default().compression_method(CompressionMethod::Stored)
+    } else {
+        SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .compression_level(Some(level))
+    };
+
+    let file = fs::File::create(archive).map_err(io_error)?;
+    let mut writer = ZipWriter::new(file);
+    let mut entry_names = Vec::with_capacity(files.len());
+    for (path, name) in files {
+        let metadata = fs::metadata(&path).map_err(io_error)?;
+        let mut file_options = base_options.large_file(metadata.len() >= u32::MAX as u64);
+        if let Some(modified) = metadata
+            .modified()
+            .ok()
+            .map(|time| DateTime::<Local>::from(time).naive_local())
+            .and_then(|time| zip::DateTime::try_from(time).ok())
+        {
+            file_options = file_options.last_modified_time(modified);
+        }
+
+        writer
+            .start_file(name.as_str(), file_options)
+            .map_err(zip_error)?;
+        let mut input = fs::File::open(&path).map_err(io_error)?;
+        io::copy(&mut input, &mut writer).map_err(io_error)?;
+        entry_names.push(name);
+    }
+    writer.finish().map_err(zip_error)?;
+
+    Ok(entry_names)
+}
+
+pub fn extract_zip(
+    archive_path: &str,
+    target_dir: &str,
+    options: &ArchiveExtractOptions,
+) -> CommandResult<Vec<String>> {
+    let file = fs::File::open(archive_path).map_err(io_error)?;
+    let mut archive = ZipArchive::new(file).map_err(zip_error)?;
+    let target_root = Path::new(target_dir);
+    let patterns = compile_patterns(&options.patterns)?;
+    let pattern_options = MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+
+    let mut planned = Vec::new();
+    for index in 0..archive.len() {
+        let entry = archive.by_index_raw(index).map_err(zip_error)?;
+        let Some(relative) = entry.enclosed_name() else {
+            return Err(CommandFlowError::Validation(format!(
+                "archive entry escapes the target directory: {}",
+                entry.name()
+            )));
+        };
+        let relative_text = relative.to_string_lossy().replace('\\', "/");
+        let wanted = patterns.is_empty()
+            || patterns
+                .iter()
+                .any(|pattern| pattern.matches_with(&relative_text, pattern_options));
+        if wanted {
+            planned.push((index, target_root.join(relative), entry.is_dir()));
+        }
+    }
+
+    if !options.overwrite {
+        if let Some((_, path, _)) = planned
+            .iter()
+            .find(|(_, path, is_dir)| !is_dir && path.exists())
+        {
+            return Err(CommandFlowError::Validation(format!(
+                "target file already exists: {}",
+                path.display()
+            )));
+        }
+    }
+
+    fs::create_dir_all(target_root).map_err(io_error)?;
+    let mut extracted = Vec::new();
+    for (index, output_path, is_dir) in planned {
+        if is_dir {
+            fs::create_dir_all(&output_path).map_err(io_error)?;
+            continue;
+        }
+
+        let mut entry = match options
+            .password
+            .as_deref()
+            .filter(|value| !value.is_empty())
+        {
+            Some(password) => archive.by_index_decrypt(index, password.as_bytes()),
+            None => archive.by_index(index),
+        }
+        .map_err(zip_error)?;
+
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let mut output = fs::File::create(&output_path).map_err(io_error)?;
+        io::copy(&mut entry, &mut output).map_err(io_error)?;
+        extracted.push(output_path.to_string_lossy().to_string());
+    }
+
+    Ok(extracted)
+}
+
+fn collect_entries(
+    root: &Path,
+    dir: &Path,
+    options: &ListFilesOptions,
+    patterns: &[Pattern],
+    output: &mut Vec<FileEntry>,
+) -> CommandResult<()> {
+    // 只有根目录不可读才报错；无权限的子目录（如 System Volume Information）直接跳过
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(_) if dir != root => return Ok(()),
+        Err(error) => return Err(io_error(error)),
+    };
+    for entry in read_dir {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        // 符号链接与目录联接可能指回上级目录形成环，只列出不深入
+        let is_link = fs::symlink_metadata(&path)
+            .map(|link| link.file_type().is_symlink())
+            .unwrap_or(true);
+
+        let is_dir = metadata.is_dir();
+        let wanted = if is_dir {
+            options.include_dirs
+        } else {
+            options.include_files
+        };
+        if wanted && matches_patterns(root, &path, patterns) {
+            output.push(build_entry(&path, &metadata));
+        }
+
+        if is_dir && options.recursive && !is_link {
+            collect_entries(root, &path, options, patterns, output)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn compile_patterns(raw: &[String]) -> CommandResult<Vec<Pattern>> {
+    raw.iter()
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            Pattern::new(&item.replace('\\', "/")).map_err(|error| {
+                CommandFlowError::Validation(format!("invalid glob pattern '{}': {}", item, error))
+            })
+        })
+        .collect()
+}
+
+fn matches_patterns(root: &Path, path: &Path, patterns: &[Pattern]) -> bool {
+    if patterns.is_empty() {
+        return true;
+    }
+
+    let options = MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+    let name = file_name_of(path);
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    patterns.iter().any(|pattern| {
+        if pattern.as_str().contains('/') {
+            pattern.matches_with(&relative, options)
+        } else {
+            pattern.matches_with(&name, options)
+        }
+    })
+}
+
+fn is_glob_pattern(value: &str) -> bool {
+    value.contains(['*', '?', '['])
+}
+
+fn build_entry(path: &Path, metadata: &fs::Metadata) -> FileEntry {
+    let file_type = if metadata.is_dir() {
+        "dir"
+    } else if metadata.is_file() {
+        "file"
+    } else {
+        "other"
+    };
+    let modified = metadata.modified().ok();
+
+    FileEntry {
+        path: path.to_string_lossy().to_string(),
+        name: file_name_of(path),
+        extension: extension_of(path),
+        file_type: file_type.to_string(),
+        size: if metadata.is_file() {
+            metadata.len()
+        } else {
+            0
+        },
+        modified_ms: modified.and_then(system_time_to_ms),
+        created_ms: metadata.created().ok().and_then(system_time_to_ms),
+        accessed_ms: metadata.accessed().ok().and_then(system_time_to_ms),
+        modified: modified
+            .map(|time| {
+                DateTime::<Local>::from(time)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default(),
+        readonly: metadata.permissions().readonly(),
+    }
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn system_time_to_ms(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_millis() as u64)
+}
+
 fn io_error(error: std::io::Error) -> CommandFlowError {
     CommandFlowError::Io(error.to_string())
 }
+
+fn zip_error(error: zip::result::ZipError) -> CommandFlowError {
+    match error {
+        zip::result::ZipError::Io(error) => io_error(error),
+        zip::result::ZipError::InvalidPassword => {
+            CommandFlowError::Validation("invalid archive password".to_string())
+        }
+        other => CommandFlowError::Automation(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_zip, list_files, ArchiveExtractOptions, ListFilesOptions};
+    use crate::error::CommandFlowError;
+    use std::fs;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use zip::write::SimpleFileOptions;
+    use zip::{AesMode, ZipWriter};
+
+    fn temp_workspace(name: &str) -> PathBuf {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system clock")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("commandflow-file-ops-{}-{}", name, stamp));
+        fs::create_dir_all(&dir).expect("create workspace");
+        dir
+    }
+
+    fn recursive_listing() -> ListFilesOptions {
+        ListFilesOptions {
+            patterns: vec!["*".to_string()],
+            recursive: true,
+            include_files: true,
+            include_dirs: true,
+            sort_by: "name".to_string(),
+            descending: false,
+            limit: 0,
+        }
+    }
+
+    fn write_archive(path: &Path, entries: &[(&str, &str)], password: Option<&str>) {
+        let mut writer = ZipWriter::new(fs::File::create(path).expect("create archive"));
+        for (name, content) in entries {
+            let options = match password {
+                Some(password) => {
+                    SimpleFileOptions::default().with_aes_encryption(AesMode::Aes256, password)
+                }
+                None => SimpleFileOptions::default(),
+            };
+            writer.start_file(*name, options).expect("start entry");
+            writer.write_all(content.as_bytes()).expect("write entry");
+        }
+        writer.finish().expect("finish archive");
+    }
+
+    fn extract_options(password: Option<&str>) -> ArchiveExtractOptions {
+        ArchiveExtractOptions {
+            patterns: Vec::new(),
+            password: password.map(ToString::to_string),
+            overwrite: false,
+        }
+    }
+
+    #[cfg(unix)]
+    fn link_dir(target: &Path, link: &Path) -> bool {
+        std::os::unix::fs::symlink(target, link).is_ok()
+    }
+
+    // 创建目录符号链接在 Windows 上需要开发者模式或管理员权限，无权限时跳过
+    #[cfg(windows)]
+    fn link_dir(target: &Path, link: &Path) -> bool {
+        std::os::windows::fs::symlink_dir(target, link).is_ok()
+    }
+
+    #[test]
+    fn list_files_does_not_follow_directory_link_cycles() {
+        let dir = temp_workspace("cycle");
+        let nested = dir.join("nested");
+        fs::create_dir_all(&nested).expect("create nested");
+        fs::write(nested.join("a.txt"), "a").expect("write file");
+        if !link_dir(&dir, &nested.join("loop")) {
+            let _ = fs::remove_dir_all(&dir);
+            return;
+        }
+
+        let entries = list_files(&dir.to_string_lossy(), &recursive_listing()).expect("list");
+        let mut names: Vec<String> = entries.iter().map(|entry| entry.name.clone()).collect();
+        names.sort();
+        assert_eq!(names, vec!["a.txt", "loop", "nested"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn extract_zip_refuses_entries_escaping_target_dir() {
+        let dir = temp_workspace("zip-slip");
+        let archive = dir.join("evil.zip");
+        let target = dir.join("out");
+        write_archive(
+            &archive,
+            &[("safe.txt", "ok"), ("../escaped.txt", "pwned")],
+            None,
+        );
+
+        let result = extract_zip(
+            &archive.to_string_lossy(),
+            &target.to_string_lossy(),
+            &extract_options(None),
+        );
+        assert!(matches!(result, Err(CommandFlowError::Validation(_))));
+        assert!(!dir.join("escaped.txt").exists());
+        assert!(!target.join("safe.txt").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn extract_zip_with_password_round_trip() {
+        let dir = temp_workspace("zip-password");
+        let archive = dir.join("secret.zip");
+        let target = dir.join("out");
+        write_archive(&archive, &[("docs/a.txt", "hello")], Some("right-password"));
+
+        let extracted = extract_zip(
+            &archive.to_string_lossy(),
+            &target.to_string_lossy(),
+            &extract_options(Some("right-password")),
+        )
+        .expect("extract");
+        assert_eq!(extracted.len(), 1);
+        assert_eq!(
+            fs::read_to_string(target.join("docs").join("a.txt")).expect("read"),
+            "hello"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn extract_zip_with_wrong_password_is_validation_error() {
+        let dir = temp_workspace("zip-wrong-password");
+        let archive = dir.join("secret.zip");
+        let target = dir.join("out");
+        write_archive(&archive, &[("a.txt", "hello")], Some("right-password"));
+
+        let result = extract_zip(
+            &archive.to_string_lossy(),
+            &target.to_string_lossy(),
+            &extract_options(Some("wrong-password")),
+        );
+        match result {
+            Err(CommandFlowError::Validation(message)) => {
+                assert_eq!(message, "invalid archive password")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn list_files_skips_unreadable_subdirectories() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_workspace("unreadable");
+        let locked = dir.join("locked");
+        fs::create_dir_all(&locked).expect("create locked");
+        fs::write(locked.join("hidden.txt"), "x").expect("write hidden");
+        fs::write(dir.join("visible.txt"), "y").expect("write visible");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).expect("lock dir");
+        // 以 root 运行时权限不生效，无法构造不可读目录
+        if fs::read_dir(&locked).is_ok() {
+            let _ = fs::set_permissions(&locked, fs::Permissions::from_mode(0o755));
+            let _ = fs::remove_dir_all(&dir);
+            return;
+        }
+
+        let result = list_files(&dir.to_string_lossy(), &recursive_listing());
+        let _ = fs::set_permissions(&locked, fs::Permissions::from_mode(0o755));
+        let mut names: Vec<String> = result
+            .expect("list")
+            .iter()
+            .map(|entry| entry.name.clone())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["locked", "visible.txt"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn list_files_reports_missing_root() {
+        let dir = temp_workspace("missing-root");
+        let missing = dir.join("missing");
+        assert!(list_files(&missing.to_string_lossy(), &recursive_listing()).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,8 +1,20 @@
 use crate::error::{CommandFlowError, CommandResult};
-use image::GrayImage;
+use image::{DynamicImage, RgbaImage};
+use serde::Serialize;
 use template_matching::{MatchTemplateMethod, TemplateMatcher as GpuTemplateMatcher};
 
 const COARSE_SCALE: u32 = 4;
+const MIN_TEMPLATE_SCALE: f32 = 0.1;
+const MAX_TEMPLATE_SCALE: f32 = 4.0;
+const MIN_SCALE_STEP: f32 = 0.01;
+const MAX_SCALE_COUNT: usize = 64;
+const NMS_IOU_THRESHOLD: f32 = 0.3;
+const COARSE_CANDIDATE_MARGIN: f32 = 0.2;
+const MAX_REFINE_CANDIDATES: usize = 256;
+// 非极大值抑制为 O(n²)，只对得分最高的若干候选执行，避免低阈值全屏搜索卡死
+const MAX_NMS_CANDIDATES: usize = 2048;
+const MASK_ALPHA_CUTOFF: u8 = 128;
+const CORRELATION_EPSILON: f32 = 1e-6;
 
 #[derive(Debug, Clone)]
 pub struct MatchEvaluation {
@@ -10,19 +22,375 @@ pub struct MatchEvaluation {
     pub best_similarity: f32,
     pub best_top_left: Option<(u32, u32)>,
     pub template_size: (u32, u32),
+    pub best_scale: f32,
+    pub matches: Vec<TemplateMatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateMatch {
+    pub x: i32,
+    pub y: i32,
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+    pub similarity: f32,
+    pub scale: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchSortOrder {
+    Score,
+    ReadingOrder,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FindAllOptions {
+    pub sort: MatchSortOrder,
+    pub max_results: usize,
+    // 从 0 开始，决定 matched_point 取第几个结果
+    pub select_index: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScaleRange {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Gray,
+    Rgb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMethod {
+    Ssd,
+    Ncc,
+    Zncc,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MatchOptions {
+    pub color_mode: ColorMode,
+    pub method: MatchMethod,
+    pub use_alpha_mask: bool,
+}
+
+// 按通道分平面存储的 0~1 浮点图，灰度 1 个平面，RGB 3 个平面
+struct MatchImage {
+    width: u32,
+    height: u32,
+    planes: Vec<Vec<f32>>,
+}
+
+struct PreparedTemplate {
+    image: MatchImage,
+    mask: Option<Vec<f32>>,
+    weight: f32,
+    channel_sums: Vec<f32>,
+    energy: f32,
+    variance: f32,
+}
+
+struct ScaledTemplate {
+    scale: f32,
+    full: PreparedTemplate,
+    coarse: Option<PreparedTemplate>,
+}
+
+struct SimilarityMap {
+    width: u32,
+    height: u32,
+    data: Vec<f32>,
 }
 
 pub struct TemplateMatcher {
-    template: GrayImage,
+    template: RgbaImage,
+    options: MatchOptions,
+    // 各缩放比例的模板只生成一次，实时帧流的每一帧复用
+    scaled_templates: Vec<ScaledTemplate>,
     threshold: f32,
+    find_all: Option<FindAllOptions>,
     gpu_matcher: Option<GpuTemplateMatcher>,
 }
 
+impl MatchSortOrder {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "readingorder" | "reading_order" | "position" => MatchSortOrder::ReadingOrder,
+            _ => MatchSortOrder::Score,
+        }
+    }
+}
+
+impl ColorMode {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "rgb" | "color" => ColorMode::Rgb,
+            _ => ColorMode::Gray,
+        }
+    }
+}
+
+impl MatchMethod {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ncc" => MatchMethod::Ncc,
+            "zncc" => MatchMethod::Zncc,
+            _ => MatchMethod::Ssd,
+        }
+    }
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self {
+            color_mode: ColorMode::Gray,
+            method: MatchMethod::Ssd,
+            use_alpha_mask: true,
+        }
+    }
+}
+
+impl MatchEvaluation {
+    // 屏幕上是否仍有目标：多目标模式下只要还有任一匹配即视为存在，与 matchIndex 选中与否无关
+    pub fn target_present(&self) -> bool {
+        self.matched_point.is_some() || !self.matches.is_empty()
+    }
+}
+
+impl TemplateMatch {
+    fn from_top_left(left: u32, top: u32, size: (u32, u32), similarity: f32, scale: f32) -> Self {
+        Self {
+            x: (left + size.0 / 2) as i32,
+            y: (top + size.1 / 2) as i32,
+            left,
+            top,
+            width: size.0,
+            height: size.1,
+            similarity,
+            scale,
+        }
+    }
+
+    fn iou(&self, other: &TemplateMatch) -> f32 {
+        let x1 = self.left.max(other.left);
+        let y1 = self.top.max(other.top);
+        let x2 = (self.left + self.width).min(other.left + other.width);
+        let y2 = (self.top + self.height).min(other.top + other.height);
+        if x2 <= x1 || y2 <= y1 {
+            return 0.0;
+        }
+
+        let intersection = ((x2 - x1) * (y2 - y1)) as f32;
+        let union = (self.width * self.height + other.width * other.height) as f32 - intersection;
+        if union <= 0.0 {
+            0.0
+        } else {
+            intersection / union
+        }
+    }
+}
+
+impl MatchImage {
+    fn from_dynamic(source: &DynamicImage, color_mode: ColorMode) -> Self {
+        match color_mode {
+            ColorMode::Gray => {
+                let plane_of = |gray: &image::GrayImage| MatchImage {
+                    width: gray.width(),
+                    height: gray.height(),
+                    planes: vec![gray
+                        .as_raw()
+                        .iter()
+                        .map(|value| *value as f32 / 255.0)
+                        .collect()],
+                };
+                match source {
+                    DynamicImage::ImageLuma8(gray) => plane_of(gray),
+                    other => plane_of(&other.to_luma8()),
+                }
+            }
+            ColorMode::Rgb => {
+                let rgb = source.to_rgb8();
+                let pixel_count = (rgb.width() * rgb.height()) as usize;
+                let mut planes: Vec<Vec<f32>> =
+                    (0..3).map(|_| Vec::with_capacity(pixel_count)).collect();
+                for pixel in rgb.as_raw().chunks_exact(3) {
+                    for (plane, value) in planes.iter_mut().zip(pixel) {
+                        plane.push(*value as f32 / 255.0);
+                    }
+                }
+                MatchImage {
+                    width: rgb.width(),
+                    height: rgb.height(),
+                    planes,
+                }
+            }
+        }
+    }
+
+    fn channels(&self) -> usize {
+        self.planes.len()
+    }
+
+    fn downsample(&self, factor: u32) -> Self {
+        let width = (self.width / factor).max(1);
+        let height = (self.height / factor).max(1);
+        let planes = self
+            .planes
+            .iter()
+            .map(|plane| downsample_plane(plane, self.width, factor, width, height))
+            .collect();
+        Self {
+            width,
+            height,
+            planes,
+        }
+    }
+}
+
+impl PreparedTemplate {
+    fn new(image: MatchImage, mask: Option<Vec<f32>>) -> Self {
+        let channels = image.channels();
+        let pixel_count = (image.width * image.height) as usize;
+        let weight = mask
+            .as_ref()
+            .map(|mask| mask.iter().sum())
+            .unwrap_or(pixel_count as f32);
+
+        let mut channel_sums = vec![0.0_f32; channels];
+        let mut energy = 0.0_f32;
+        for (channel, plane) in image.planes.iter().enumerate() {
+            for (index, value) in plane.iter().enumerate() {
+                let m = mask.as_ref().map_or(1.0, |mask| mask[index]);
+                channel_sums[channel] += m * value;
+                energy += m * value * value;
+            }
+        }
+        let variance = if weight > 0.0 {
+            energy
+                - channel_sums
+                    .iter()
+                    .map(|sum| sum * sum / weight)
+                    .sum::<f32>()
+        } else {
+            0.0
+        };
+
+        Self {
+            image,
+            mask,
+            weight,
+            channel_sums,
+            energy,
+            variance: variance.max(0.0),
+        }
+    }
+
+    fn from_rgba(template: &RgbaImage, options: MatchOptions) -> Self {
+        let image = MatchImage::from_dynamic(
+            &DynamicImage::ImageRgba8(template.clone()),
+            options.color_mode,
+        );
+        // 仅在模板确实带透明像素时启用掩码，不透明模板仍可走 GPU 路径
+        let has_transparency = template.pixels().any(|pixel| pixel.0[3] < u8::MAX);
+        let mask = (options.use_alpha_mask && has_transparency).then(|| {
+            template
+                .pixels()
+                .map(|pixel| {
+                    if pixel.0[3] >= MASK_ALPHA_CUTOFF {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        });
+        Self::new(image, mask)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.image.width, self.image.height)
+    }
+
+    fn downsample(&self, factor: u32) -> Self {
+        let width = self.image.width;
+        let image = self.image.downsample(factor);
+        let mask = self
+            .mask
+            .as_ref()
+            .map(|mask| downsample_plane(mask, width, factor, image.width, image.height));
+        Self::new(image, mask)
+    }
+}
+
+impl ScaledTemplate {
+    fn new(scale: f32, template: &RgbaImage, options: MatchOptions) -> Self {
+        let full = PreparedTemplate::from_rgba(template, options);
+        let (tw, th) = full.size();
+        let coarse = (tw >= COARSE_SCALE * 2 && th >= COARSE_SCALE * 2)
+            .then(|| full.downsample(COARSE_SCALE));
+        Self {
+            scale,
+            full,
+            coarse,
+        }
+    }
+}
+
+impl SimilarityMap {
+    fn best(&self) -> Option<(u32, u32, f32)> {
+        self.data
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(index, similarity)| {
+                let index = index as u32;
+                (index % self.width, index / self.width, *similarity)
+            })
+    }
+
+    fn local_maxima(&self, threshold: f32) -> Vec<(u32, u32, f32)> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let data = &self.data;
+        let mut candidates = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let similarity = data[y * width + x];
+                if similarity < threshold {
+                    continue;
+                }
+                // 只保留局部极大值，避免命中区域周围的整片像素都成为候选
+                let is_local_max = (x == 0 || data[y * width + x - 1] <= similarity)
+                    && (x + 1 >= width || data[y * width + x + 1] < similarity)
+                    && (y == 0 || data[(y - 1) * width + x] <= similarity)
+                    && (y + 1 >= height || data[(y + 1) * width + x] < similarity);
+                if is_local_max {
+                    candidates.push((x as u32, y as u32, similarity));
+                }
+            }
+        }
+        candidates
+    }
+}
+
 impl TemplateMatcher {
     pub fn from_path(template_path: &str, threshold: f32) -> CommandResult<Self> {
+        Self::from_path_with_options(template_path, threshold, MatchOptions::default())
+    }
+
+    pub fn from_path_with_options(
+        template_path: &str,
+        threshold: f32,
+        options: MatchOptions,
+    ) -> CommandResult<Self> {
         let template = image::open(template_path)
             .map_err(|error| CommandFlowError::Automation(error.to_string()))?
-            .to_luma8();
+            .to_rgba8();
         let (tw, th) = template.dimensions();
         if tw == 0 || th == 0 {
             return Err(CommandFlowError::Validation(
@@ -30,57 +398,190 @@ impl TemplateMatcher {
             ));
         }
 
+        let native = ScaledTemplate::new(1.0, &template, options);
+        if native.full.weight <= 0.0 {
+            return Err(CommandFlowError::Validation(
+                "template image is fully transparent".to_string(),
+            ));
+        }
+
         Ok(Self {
+            scaled_templates: vec![native],
             template,
+            options,
             threshold: threshold.clamp(0.0, 1.0),
+            find_all: None,
             gpu_matcher: std::panic::catch_unwind(GpuTemplateMatcher::new).ok(),
         })
     }
 
-    pub fn evaluate(&mut self, source: &GrayImage) -> MatchEvaluation {
-        if let Some(gpu) = &mut self.gpu_matcher {
-            let template_f32 = to_luma32f(&self.template);
-            let input_f32 = to_luma32f(source);
-            let input_image =
-                template_matching::Image::new(&input_f32, source.width(), source.height());
-            let template_image = template_matching::Image::new(
-                &template_f32,
-                self.template.width(),
-                self.template.height(),
-            );
-
-            gpu.match_template(
-                input_image,
-                template_image,
-                MatchTemplateMethod::SumOfSquaredDifferences,
-            );
-
-            if let Some(result) = gpu.wait_for_result() {
-                let extremes = template_matching::find_extremes(&result);
-
-                let score = extremes.min_value.max(0.0);
-                let pixel_count = (self.template.width() * self.template.height()).max(1) as f32;
-                let normalized_score = (score / pixel_count).clamp(0.0, 1.0);
-                let similarity = (1.0 - normalized_score).clamp(0.0, 1.0);
-
-                let matched_point = if similarity >= self.threshold {
-                    let center_x = extremes.min_value_location.0 + self.template.width() / 2;
-                    let center_y = extremes.min_value_location.1 + self.template.height() / 2;
-                    Some((center_x as i32, center_y as i32))
-                } else {
-                    None
-                };
+    // GPU 可用但当前配置只能走 CPU 时给出原因，供节点日志提示
+    pub fn gpu_fallback_reason(&self) -> Option<&'static str> {
+        self.gpu_matcher.as_ref()?;
+        if self.options.method != MatchMethod::Ssd {
+            return Some("GPU 仅实现 SSD，NCC/ZNCC 使用 CPU 计算");
+        }
+        if self
+            .scaled_templates
+            .iter()
+            .any(|scaled| scaled.full.mask.is_some())
+        {
+            return Some("GPU 不支持透明掩码，带透明区域的模板使用 CPU 计算");
+        }
+        None
+    }
+
+    pub fn with_scale_range(mut self, range: ScaleRange) -> CommandResult<Self> {
+        let (tw, th) = self.template.dimensions();
+        let mut scaled_templates: Vec<ScaledTemplate> = Vec::new();
+
+        for scale in build_scales(range)? {
+            let width = ((tw as f32) * scale).round().max(1.0) as u32;
+            let height = ((th as f32) * scale).round().max(1.0) as u32;
+            if scaled_templates
+                .iter()
+                .any(|item| item.full.size() == (width, height))
+            {
+                continue;
+            }
+
+            let scaled = if (width, height) == (tw, th) {
+                ScaledTemplate::new(scale, &self.template, self.options)
+            } else {
+                let image = image::imageops::resize(
+                    &self.template,
+                    width,
+                    height,
+                    image::imageops::FilterType::Triangle,
+                );
+                ScaledTemplate::new(scale, &image, self.options)
+            };
+            if scaled.full.weight > 0.0 {
+                scaled_templates.push(scaled);
+            }
+        }
+
+        if !scaled_templates.is_empty() {
+            self.scaled_templates = scaled_templates;
+        }
+        Ok(self)
+    }
+
+    pub fn with_find_all(mut self, options: FindAllOptions) -> Self {
+        self.find_all = Some(FindAllOptions {
+            max_results: options.max_results.max(1),
+            ..options
+        });
+        self
+    }
+
+    pub fn evaluate(&mut self, source: &DynamicImage) -> MatchEvaluation {
+        let source = MatchImage::from_dynamic(source, self.options.color_mode);
+        let coarse_source = (source.width >= COARSE_SCALE * 2 && source.height >= COARSE_SCALE * 2)
+            .then(|| source.downsample(COARSE_SCALE));
+
+        let best = self.evaluate_best(&source, coarse_source.as_ref());
+        let Some(options) = self.find_all else {
+            return best;
+        };
+        // 最佳分数都未达阈值时无需再逐个收集
+        if best.best_similarity < self.threshold {
+            return best;
+        }
+
+        let matches = self.find_all_matches(&source, coarse_source.as_ref(), options);
+        let selected = matches.get(options.select_index).cloned();
+        MatchEvaluation {
+            matched_point: selected.as_ref().map(|item| (item.x, item.y)),
+            best_similarity: best.best_similarity,
+            best_top_left: selected
+                .as_ref()
+                .map(|item| (item.left, item.top))
+                .or(best.best_top_left),
+            template_size: selected
+                .as_ref()
+                .map(|item| (item.width, item.height))
+                .unwrap_or(best.template_size),
+            best_scale: selected
+                .as_ref()
+                .map(|item| item.scale)
+                .unwrap_or(best.best_scale),
+            matches,
+        }
+    }
+
+    fn find_all_matches(
+        &mut self,
+        source: &MatchImage,
+        coarse_source: Option<&MatchImage>,
+        options: FindAllOptions,
+    ) -> Vec<TemplateMatch> {
+        let mut candidates = Vec::new();
+        let method = self.options.method;
+
+        for scaled in &self.scaled_templates {
+            let (tw, th) = scaled.full.size();
+            if tw > source.width || th > source.height {
+                continue;
+            }
+
+            let positions = gpu_similarity_map(self.gpu_matcher.as_mut(), method, source, scaled)
+                .map(|map| map.local_maxima(self.threshold))
+                .unwrap_or_else(|| {
+                    collect_candidates_cpu(source, coarse_source, scaled, method, self.threshold)
+                });
+
+            candidates.extend(positions.into_iter().map(|(left, top, similarity)| {
+                TemplateMatch::from_top_left(left, top, (tw, th), similarity, scaled.scale)
+            }));
+        }
+
+        let mut matches = suppress_overlaps(candidates);
+        matches.truncate(options.max_results);
+        if options.sort == MatchSortOrder::ReadingOrder {
+            sort_reading_order(&mut matches);
+        }
+        matches
+    }
 
-                return MatchEvaluation {
-                    matched_point,
-                    best_similarity: similarity,
-                    best_top_left: Some(extremes.min_value_location),
-                    template_size: (self.template.width(), self.template.height()),
+    fn evaluate_best(
+        &mut self,
+        source: &MatchImage,
+        coarse_source: Option<&MatchImage>,
+    ) -> MatchEvaluation {
+        let mut best: Option<MatchEvaluation> = None;
+        let method = self.options.method;
+
+        for scaled in &self.scaled_templates {
+            let (tw, th) = scaled.full.size();
+            if tw > source.width || th > source.height {
+                continue;
+            }
+
+            let position =
+                match gpu_similarity_map(self.gpu_matcher.as_mut(), method, source, scaled) {
+                    Some(map) => map.best(),
+                    None => find_best_position_cpu(source, coarse_source, scaled, method),
                 };
+            let evaluation = build_evaluation(position, (tw, th), scaled.scale, self.threshold);
+
+            let is_better = match &best {
+                Some(current) => evaluation.best_similarity > current.best_similarity,
+                None => true,
+            };
+            if is_better {
+                best = Some(evaluation);
             }
         }
 
-        evaluate_template_cpu(source, &self.template, self.threshold)
+        best.unwrap_or_else(|| {
+            let native = self
+                .scaled_templates
+                .first()
+                .map(|item| (item.full.size(), item.scale))
+                .unwrap_or((self.template.dimensions(), 1.0));
+            build_evaluation(None, native.0, native.1, self.threshold)
+        })
     }
 }
 
@@ -90,123 +591,414 @@ pub fn find_template(
     threshold: f32,
 ) -> CommandResult<Option<(i32, i32)>> {
     let source = image::open(source_path)
-        .map_err(|error| CommandFlowError::Automation(error.to_string()))?
-        .to_luma8();
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
     let mut matcher = TemplateMatcher::from_path(template_path, threshold)?;
     let evaluation = matcher.evaluate(&source);
     Ok(evaluation.matched_point)
 }
 
-fn to_luma32f(source: &GrayImage) -> Vec<f32> {
-    let (w, h) = source.dimensions();
-    let mut data = Vec::with_capacity((w * h) as usize);
-    for value in source.as_raw() {
-        data.push(*value as f32 / 255.0);
-    }
-    data
-}
-
-fn evaluate_template_cpu(
-    source: &GrayImage,
-    template: &GrayImage,
+fn build_evaluation(
+    position: Option<(u32, u32, f32)>,
+    template_size: (u32, u32),
+    scale: f32,
     threshold: f32,
 ) -> MatchEvaluation {
-    let (sw, sh) = source.dimensions();
-    let (tw, th) = template.dimensions();
-
-    if tw > sw || th > sh {
+    let Some((x, y, similarity)) = position else {
         return MatchEvaluation {
             matched_point: None,
             best_similarity: 0.0,
             best_top_left: None,
-            template_size: (tw, th),
+            template_size,
+            best_scale: scale,
+            matches: Vec::new(),
         };
+    };
+
+    let similarity = similarity.clamp(0.0, 1.0);
+    let matched_point = if similarity >= threshold {
+        let center_x = x + template_size.0 / 2;
+        let center_y = y + template_size.1 / 2;
+        Some((center_x as i32, center_y as i32))
+    } else {
+        None
+    };
+
+    MatchEvaluation {
+        matched_point,
+        best_similarity: similarity,
+        best_top_left: Some((x, y)),
+        template_size,
+        best_scale: scale,
+        matches: Vec::new(),
     }
+}
 
-    let (coarse_source, coarse_template, scale) = build_coarse_images(source, template);
-    let coarse_best = find_best_position(&coarse_source, &coarse_template, 0, 0, None);
-    let Some((coarse_x, coarse_y, _)) = coarse_best else {
-        return MatchEvaluation {
-            matched_point: None,
-            best_similarity: 0.0,
-            best_top_left: None,
-            template_size: (tw, th),
-        };
+fn build_scales(range: ScaleRange) -> CommandResult<Vec<f32>> {
+    if !range.min.is_finite() || !range.max.is_finite() || !range.step.is_finite() {
+        return Err(CommandFlowError::Validation(
+            "template scale range must be finite numbers".to_string(),
+        ));
+    }
+    if range.min <= 0.0 || range.max < range.min {
+        return Err(CommandFlowError::Validation(format!(
+            "invalid template scale range {}~{}",
+            range.min, range.max
+        )));
+    }
+
+    let min = range.min.clamp(MIN_TEMPLATE_SCALE, MAX_TEMPLATE_SCALE);
+    let max = range.max.clamp(min, MAX_TEMPLATE_SCALE);
+    let step = range.step.max(MIN_SCALE_STEP);
+
+    let mut scales = Vec::new();
+    let mut index = 0_u32;
+    loop {
+        let scale = min + step * index as f32;
+        if scale > max + 1e-4 || scales.len() >= MAX_SCALE_COUNT {
+            break;
+        }
+        scales.push(scale);
+        index += 1;
+    }
+
+    // 原始尺寸附近的比例优先，分数相同时保留更接近 1.0 的结果
+    scales.sort_by(|a, b| (a - 1.0).abs().total_cmp(&(b - 1.0).abs()));
+    Ok(scales)
+}
+
+// GPU 只实现了 SSD 且不支持掩码（template_matching 未提供归一化相关与加权版本）；
+// RGB 模式逐通道计算后求和，其余情况回退到 CPU，原因见 gpu_fallback_reason
+fn gpu_similarity_map(
+    gpu: Option<&mut GpuTemplateMatcher>,
+    method: MatchMethod,
+    source: &MatchImage,
+    scaled: &ScaledTemplate,
+) -> Option<SimilarityMap> {
+    let gpu = gpu?;
+    if method != MatchMethod::Ssd || scaled.full.mask.is_some() {
+        return None;
+    }
+
+    let template = &scaled.full.image;
+    let mut total: Option<(u32, u32, Vec<f32>)> = None;
+    for (source_plane, template_plane) in source.planes.iter().zip(&template.planes) {
+        gpu.match_template(
+            template_matching::Image::new(source_plane.as_slice(), source.width, source.height),
+            template_matching::Image::new(
+                template_plane.as_slice(),
+                template.width,
+                template.height,
+            ),
+            MatchTemplateMethod::SumOfSquaredDifferences,
+        );
+        let result = gpu.wait_for_result()?;
+        match &mut total {
+            Some((_, _, data)) => data
+                .iter_mut()
+                .zip(result.data.iter())
+                .for_each(|(sum, value)| *sum += value.max(0.0)),
+            None => {
+                total = Some((
+                    result.width,
+                    result.height,
+                    result.data.iter().map(|value| value.max(0.0)).collect(),
+                ))
+            }
+        }
+    }
+
+    let (width, height, data) = total?;
+    let normalizer = (template.width * template.height * template.channels() as u32).max(1) as f32;
+    Some(SimilarityMap {
+        width: width.max(1),
+        height,
+        data: data
+            .into_iter()
+            .map(|score| (1.0 - score / normalizer).clamp(0.0, 1.0))
+            .collect(),
+    })
+}
+
+fn find_best_position_cpu(
+    source: &MatchImage,
+    coarse_source: Option<&MatchImage>,
+    scaled: &ScaledTemplate,
+    method: MatchMethod,
+) -> Option<(u32, u32, f32)> {
+    let template = &scaled.full;
+    let (sw, sh) = (source.width, source.height);
+    let (tw, th) = template.size();
+
+    let (coarse_source, coarse_template) = match (coarse_source, &scaled.coarse) {
+        (Some(coarse_source), Some(coarse_template)) => (coarse_source, coarse_template),
+        _ => return find_best_position(source, template, method, 0, 0, None),
     };
 
-    let coarse_ref_x = coarse_x.saturating_mul(scale);
-    let coarse_ref_y = coarse_y.saturating_mul(scale);
-    let radius = scale.max(1) * 2;
+    let (coarse_x, coarse_y, _) =
+        find_best_position(coarse_source, coarse_template, method, 0, 0, None)?;
+
+    let coarse_ref_x = coarse_x.saturating_mul(COARSE_SCALE);
+    let coarse_ref_y = coarse_y.saturating_mul(COARSE_SCALE);
+    let radius = COARSE_SCALE * 2;
 
     let min_x = coarse_ref_x.saturating_sub(radius);
     let min_y = coarse_ref_y.saturating_sub(radius);
     let max_x = (coarse_ref_x + radius).min(sw.saturating_sub(tw));
     let max_y = (coarse_ref_y + radius).min(sh.saturating_sub(th));
 
-    let refine_best = find_best_position(source, template, min_x, min_y, Some((max_x, max_y)));
-    let Some((x, y, best_score)) = refine_best else {
-        return MatchEvaluation {
-            matched_point: None,
-            best_similarity: 0.0,
-            best_top_left: None,
-            template_size: (tw, th),
-        };
+    find_best_position(source, template, method, min_x, min_y, Some((max_x, max_y)))
+}
+
+fn collect_candidates_cpu(
+    source: &MatchImage,
+    coarse_source: Option<&MatchImage>,
+    scaled: &ScaledTemplate,
+    method: MatchMethod,
+    threshold: f32,
+) -> Vec<(u32, u32, f32)> {
+    let template = &scaled.full;
+    let (sw, sh) = (source.width, source.height);
+    let (tw, th) = template.size();
+    if tw > sw || th > sh {
+        return Vec::new();
+    }
+
+    // 先在缩小图上粗筛候选，再回到原图邻域精修
+    let (search_source, search_template, factor) = match (coarse_source, &scaled.coarse) {
+        (Some(coarse_source), Some(coarse_template)) => {
+            (coarse_source, coarse_template, COARSE_SCALE)
+        }
+        _ => (source, template, 1),
     };
+    let (ctw, cth) = search_template.size();
+    if ctw > search_source.width || cth > search_source.height {
+        return Vec::new();
+    }
 
-    let similarity = (1.0 - best_score).clamp(0.0, 1.0);
-    let matched_point = if similarity >= threshold {
-        let center_x = x + tw / 2;
-        let center_y = y + th / 2;
-        Some((center_x as i32, center_y as i32))
+    let coarse_threshold = if factor > 1 {
+        (threshold - COARSE_CANDIDATE_MARGIN).max(0.0)
     } else {
-        None
+        threshold
     };
+    let map_width = search_source.width - ctw + 1;
+    let map_height = search_source.height - cth + 1;
+    let mut data = Vec::with_capacity((map_width * map_height) as usize);
+    for y in 0..map_height {
+        for x in 0..map_width {
+            data.push(similarity_at(
+                search_source,
+                search_template,
+                method,
+                x,
+                y,
+                0.0,
+            ));
+        }
+    }
+    let map = SimilarityMap {
+        width: map_width,
+        height: map_height,
+        data,
+    };
+    // 只取局部极大值作为候选，命中区域周围的整片高分像素不再逐个进入 NMS
+    let coarse_hits = map
+        .local_maxima(coarse_threshold)
+        .into_iter()
+        .map(|(x, y, similarity)| TemplateMatch::from_top_left(x, y, (ctw, cth), similarity, 1.0))
+        .collect();
 
-    MatchEvaluation {
-        matched_point,
-        best_similarity: similarity,
-        best_top_left: Some((x, y)),
-        template_size: (tw, th),
+    let mut coarse_hits = suppress_overlaps(coarse_hits);
+    if factor == 1 {
+        return coarse_hits
+            .into_iter()
+            .map(|hit| (hit.left, hit.top, hit.similarity))
+            .collect();
     }
+    coarse_hits.truncate(MAX_REFINE_CANDIDATES);
+
+    let radius = factor * 2;
+    coarse_hits
+        .into_iter()
+        .filter_map(|hit| {
+            let ref_x = hit.left.saturating_mul(factor);
+            let ref_y = hit.top.saturating_mul(factor);
+            let max_x = (ref_x + radius).min(sw - tw);
+            let max_y = (ref_y + radius).min(sh - th);
+            let (x, y, similarity) = find_best_position(
+                source,
+                template,
+                method,
+                ref_x.saturating_sub(radius),
+                ref_y.saturating_sub(radius),
+                Some((max_x, max_y)),
+            )?;
+            (similarity >= threshold).then_some((x, y, similarity))
+        })
+        .collect()
+}
+
+fn suppress_overlaps(mut candidates: Vec<TemplateMatch>) -> Vec<TemplateMatch> {
+    candidates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    candidates.truncate(MAX_NMS_CANDIDATES);
+    let mut kept: Vec<TemplateMatch> = Vec::new();
+    for candidate in candidates {
+        if kept
+            .iter()
+            .all(|item| item.iou(&candidate) < NMS_IOU_THRESHOLD)
+        {
+            kept.push(candidate);
+        }
+    }
+    kept
 }
 
-fn build_coarse_images(source: &GrayImage, template: &GrayImage) -> (GrayImage, GrayImage, u32) {
-    let (sw, sh) = source.dimensions();
-    let (tw, th) = template.dimensions();
+fn sort_reading_order(matches: &mut [TemplateMatch]) {
+    matches.sort_by_key(|item| (item.y, item.x));
+    // 中心点纵向偏差小于半个模板高度的视为同一行
+    let mut row = 0_u32;
+    let mut row_y = matches.first().map(|item| item.y).unwrap_or(0);
+    let mut rows = Vec::with_capacity(matches.len());
+    for item in matches.iter() {
+        if item.y - row_y > (item.height / 2).max(1) as i32 {
+            row += 1;
+            row_y = item.y;
+        }
+        rows.push(row);
+    }
 
-    if tw < COARSE_SCALE * 2
-        || th < COARSE_SCALE * 2
-        || sw < COARSE_SCALE * 2
-        || sh < COARSE_SCALE * 2
-    {
-        return (source.clone(), template.clone(), 1);
+    let mut keyed: Vec<(u32, TemplateMatch)> =
+        rows.into_iter().zip(matches.iter().cloned()).collect();
+    keyed.sort_by_key(|(row, item)| (*row, item.x));
+    for (slot, (_, item)) in matches.iter_mut().zip(keyed) {
+        *slot = item;
     }
+}
+
+fn downsample_plane(plane: &[f32], width: u32, factor: u32, out_w: u32, out_h: u32) -> Vec<f32> {
+    let block = (factor * factor) as f32;
+    let mut output = Vec::with_capacity((out_w * out_h) as usize);
+    for oy in 0..out_h {
+        for ox in 0..out_w {
+            let mut sum = 0.0_f32;
+            for dy in 0..factor {
+                let row = ((oy * factor + dy) * width) as usize;
+                for dx in 0..factor {
+                    sum += plane[row + (ox * factor + dx) as usize];
+                }
+            }
+            output.push(sum / block);
+        }
+    }
+    output
+}
+
+// floor 仅用于 SSD 提前终止：误差已超出 floor 对应的上限时直接返回 0
+fn similarity_at(
+    source: &MatchImage,
+    template: &PreparedTemplate,
+    method: MatchMethod,
+    x: u32,
+    y: u32,
+    floor: f32,
+) -> f32 {
+    let (tw, th) = template.size();
+    let sw = source.width as usize;
+    let channels = template.image.channels().min(source.channels());
+    if template.weight <= 0.0 {
+        return 0.0;
+    }
+
+    match method {
+        MatchMethod::Ssd => {
+            let normalizer = template.weight * channels as f32;
+            let budget = (1.0 - floor).max(0.0) * normalizer;
+            let mut error = 0.0_f32;
+            for py in 0..th {
+                let source_row = (y + py) as usize * sw + x as usize;
+                let template_row = (py * tw) as usize;
+                for px in 0..tw as usize {
+                    let m = template
+                        .mask
+                        .as_ref()
+                        .map_or(1.0, |mask| mask[template_row + px]);
+                    if m <= 0.0 {
+                        continue;
+                    }
+                    for channel in 0..channels {
+                        let diff = source.planes[channel][source_row + px]
+                            - template.image.planes[channel][template_row + px];
+                        error += m * diff * diff;
+                    }
+                }
+                if error > budget {
+                    return 0.0;
+                }
+            }
+            (1.0 - error / normalizer).clamp(0.0, 1.0)
+        }
+        MatchMethod::Ncc | MatchMethod::Zncc => {
+            let mut source_sums = [0.0_f32; 3];
+            let mut cross_sums = [0.0_f32; 3];
+            let mut source_energy = 0.0_f32;
+            for py in 0..th {
+                let source_row = (y + py) as usize * sw + x as usize;
+                let template_row = (py * tw) as usize;
+                for px in 0..tw as usize {
+                    let m = template
+                        .mask
+                        .as_ref()
+                        .map_or(1.0, |mask| mask[template_row + px]);
+                    if m <= 0.0 {
+                        continue;
+                    }
+                    for channel in 0..channels {
+                        let s = source.planes[channel][source_row + px];
+                        let t = template.image.planes[channel][template_row + px];
+                        source_sums[channel] += m * s;
+                        cross_sums[channel] += m * s * t;
+                        source_energy += m * s * s;
+                    }
+                }
+            }
 
-    let coarse_source = image::imageops::resize(
-        source,
-        (sw / COARSE_SCALE).max(1),
-        (sh / COARSE_SCALE).max(1),
-        image::imageops::FilterType::Triangle,
-    );
-    let coarse_template = image::imageops::resize(
-        template,
-        (tw / COARSE_SCALE).max(1),
-        (th / COARSE_SCALE).max(1),
-        image::imageops::FilterType::Triangle,
-    );
+            let (numerator, source_term, template_term) = if method == MatchMethod::Ncc {
+                (
+                    cross_sums.iter().sum::<f32>(),
+                    source_energy,
+                    template.energy,
+                )
+            } else {
+                let mut numerator = 0.0_f32;
+                let mut source_variance = source_energy;
+                for channel in 0..channels {
+                    numerator -=
+                        source_sums[channel] * template.channel_sums[channel] / template.weight;
+                    numerator += cross_sums[channel];
+                    source_variance -=
+                        source_sums[channel] * source_sums[channel] / template.weight;
+                }
+                (numerator, source_variance.max(0.0), template.variance)
+            };
 
-    (coarse_source, coarse_template, COARSE_SCALE)
+            let denominator = (source_term * template_term).sqrt();
+            if denominator <= CORRELATION_EPSILON {
+                return 0.0;
+            }
+            (numerator / denominator).clamp(0.0, 1.0)
+        }
+    }
 }
 
 fn find_best_position(
-    source: &GrayImage,
-    template: &GrayImage,
+    source: &MatchImage,
+    template: &PreparedTemplate,
+    method: MatchMethod,
     min_x: u32,
     min_y: u32,
     max_xy: Option<(u32, u32)>,
 ) -> Option<(u32, u32, f32)> {
-    let (sw, sh) = source.dimensions();
-    let (tw, th) = template.dimensions();
+    let (sw, sh) = (source.width, source.height);
+    let (tw, th) = template.size();
     if tw > sw || th > sh {
         return None;
     }
@@ -223,46 +1015,97 @@ fn find_best_position(
         return None;
     }
 
-    let total_pixels = (tw * th) as f32;
-    let mut best = f32::MAX;
-    let mut best_x = min_x;
-    let mut best_y = min_y;
-
+    let mut best: Option<(u32, u32, f32)> = None;
     for y in min_y..=max_y {
         for x in min_x..=max_x {
-            let mut sum_abs_diff: f32 = 0.0;
-            let mut aborted = false;
-            let early_exit_threshold = best * total_pixels * 255.0;
+            let floor = best.map_or(0.0, |(_, _, similarity)| similarity);
+            let similarity = similarity_at(source, template, method, x, y, floor);
+            if best.is_none() || similarity > floor {
+                best = Some((x, y, similarity));
+            }
+        }
+    }
+    best
+}
 
-            for py in 0..th {
-                for px in 0..tw {
-                    let s = source.get_pixel(x + px, y + py).0[0] as f32;
-                    let t = template.get_pixel(px, py).0[0] as f32;
-                    sum_abs_diff += (s - t).abs();
-                }
+#[cfg(test)]
+mod tests {
+    use super::{
+        sort_reading_order, suppress_overlaps, SimilarityMap, TemplateMatch, MAX_NMS_CANDIDATES,
+    };
 
-                if sum_abs_diff > early_exit_threshold {
-                    aborted = true;
-                    break;
-                }
-            }
+    fn hit(left: u32, top: u32, similarity: f32) -> TemplateMatch {
+        TemplateMatch::from_top_left(left, top, (20, 10), similarity, 1.0)
+    }
 
-            if aborted {
-                continue;
-            }
+    fn positions(matches: &[TemplateMatch]) -> Vec<(u32, u32)> {
+        matches.iter().map(|item| (item.left, item.top)).collect()
+    }
 
-            let normalized = sum_abs_diff / (total_pixels * 255.0);
-            if normalized < best {
-                best = normalized;
-                best_x = x;
-                best_y = y;
-            }
-        }
+    #[test]
+    fn suppress_overlaps_keeps_highest_score_per_cluster() {
+        let kept = suppress_overlaps(vec![
+            hit(0, 0, 0.90),
+            hit(2, 1, 0.95),
+            hit(100, 0, 0.80),
+            hit(101, 0, 0.70),
+        ]);
+        assert_eq!(positions(&kept), vec![(2, 1), (100, 0)]);
     }
 
-    if best.is_finite() {
-        Some((best_x, best_y, best.clamp(0.0, 1.0)))
-    } else {
-        None
+    #[test]
+    fn suppress_overlaps_keeps_touching_but_separate_matches() {
+        let kept = suppress_overlaps(vec![hit(0, 0, 0.9), hit(20, 0, 0.9), hit(0, 10, 0.9)]);
+        assert_eq!(kept.len(), 3);
+    }
+
+    #[test]
+    fn suppress_overlaps_caps_candidates_by_score() {
+        let candidates: Vec<TemplateMatch> = (0..(MAX_NMS_CANDIDATES as u32 + 500))
+            .map(|index| hit(index * 40, 0, index as f32 / 10_000.0))
+            .collect();
+        let kept = suppress_overlaps(candidates);
+        assert_eq!(kept.len(), MAX_NMS_CANDIDATES);
+        assert!(kept
+            .windows(2)
+            .all(|pair| pair[0].similarity >= pair[1].similarity));
+        assert_eq!(kept[0].left, (MAX_NMS_CANDIDATES as u32 + 499) * 40);
+    }
+
+    #[test]
+    fn reading_order_groups_rows_by_half_template_height() {
+        let mut matches = vec![
+            hit(200, 4, 0.9),
+            hit(0, 0, 0.8),
+            hit(100, 2, 0.7),
+            hit(50, 40, 0.95),
+            hit(0, 42, 0.6),
+        ];
+        sort_reading_order(&mut matches);
+        assert_eq!(
+            positions(&matches),
+            vec![(0, 0), (100, 2), (200, 4), (0, 42), (50, 40)]
+        );
+    }
+
+    #[test]
+    fn reading_order_handles_empty_and_single() {
+        let mut empty: Vec<TemplateMatch> = Vec::new();
+        sort_reading_order(&mut empty);
+        assert!(empty.is_empty());
+
+        let mut single = vec![hit(5, 5, 0.9)];
+        sort_reading_order(&mut single);
+        assert_eq!(positions(&single), vec![(5, 5)]);
+    }
+
+    #[test]
+    fn local_maxima_skips_plateau_neighbours() {
+        let map = SimilarityMap {
+            width: 4,
+            height: 2,
+            data: vec![0.9, 0.9, 0.2, 0.1, 0.3, 0.2, 0.1, 0.95],
+        };
+        assert_eq!(map.local_maxima(0.5), vec![(1, 0, 0.9), (3, 1, 0.95)]);
     }
 }
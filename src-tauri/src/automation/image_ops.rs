@@ -0,0 +1,807 @@
+use crate::automation::{pixel_color, text_render};
+use crate::error::{CommandFlowError, CommandResult};
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GrayImage, ImageFormat, Luma, Rgba, RgbaImage};
+use serde_json::Value;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+const IMAGE_VALUE_TYPE: &str = "image";
+const LABEL_PADDING: (u32, u32) = (4, 2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageOperation {
+    Load,
+    Crop,
+    Resize,
+    Rotate,
+    Grayscale,
+    Threshold,
+    Invert,
+    DrawBox,
+    DrawLabel,
+    Blur,
+    Redact,
+    Encode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeFormat {
+    Png,
+    Jpeg,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImageRect {
+    pub left: u32,
+    pub top: u32,
+    // 宽高为 0 时延伸到图像边缘
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageOperation {
+    pub fn parse(value: &str) -> CommandResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "load" => Ok(ImageOperation::Load),
+            "crop" => Ok(ImageOperation::Crop),
+            "resize" => Ok(ImageOperation::Resize),
+            "rotate" => Ok(ImageOperation::Rotate),
+            "grayscale" => Ok(ImageOperation::Grayscale),
+            "threshold" | "binarize" => Ok(ImageOperation::Threshold),
+            "invert" => Ok(ImageOperation::Invert),
+            "drawbox" => Ok(ImageOperation::DrawBox),
+            "drawlabel" => Ok(ImageOperation::DrawLabel),
+            "blur" => Ok(ImageOperation::Blur),
+            "redact" => Ok(ImageOperation::Redact),
+            "encode" => Ok(ImageOperation::Encode),
+            other => Err(CommandFlowError::Validation(format!(
+                "unsupported image operation '{}'",
+                other
+            ))),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImageOperation::Load => "加载",
+            ImageOperation::Crop => "裁剪",
+            ImageOperation::Resize => "缩放",
+            ImageOperation::Rotate => "旋转",
+            ImageOperation::Grayscale => "灰度化",
+            ImageOperation::Threshold => "二值化",
+            ImageOperation::Invert => "反色",
+            ImageOperation::DrawBox => "绘制矩形框",
+            ImageOperation::DrawLabel => "绘制文字标签",
+            ImageOperation::Blur => "模糊",
+            ImageOperation::Redact => "遮盖",
+            ImageOperation::Encode => "编码",
+        }
+    }
+}
+
+impl EncodeFormat {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "jpeg" | "jpg" => EncodeFormat::Jpeg,
+            _ => EncodeFormat::Png,
+        }
+    }
+
+    // 按保存路径扩展名推断，无法识别时沿用 PNG
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(EncodeFormat::parse)
+            .unwrap_or(EncodeFormat::Png)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EncodeFormat::Png => "png",
+            EncodeFormat::Jpeg => "jpeg",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            EncodeFormat::Png => "image/png",
+            EncodeFormat::Jpeg => "image/jpeg",
+        }
+    }
+}
+
+impl ImageRect {
+    pub fn clip(&self, width: u32, height: u32) -> Option<ImageRect> {
+        if self.left >= width || self.top >= height {
+            return None;
+        }
+        let max_width = width - self.left;
+        let max_height = height - self.top;
+        let clip_dimension = |value: u32, max: u32| {
+            if value == 0 {
+                max
+            } else {
+                value.min(max)
+            }
+        };
+        Some(ImageRect {
+            left: self.left,
+            top: self.top,
+            width: clip_dimension(self.width, max_width),
+            height: clip_dimension(self.height, max_height),
+        })
+    }
+}
+
+pub fn decode_base64_image_payload(raw: &str) -> CommandResult<Vec<u8>> {
+    let payload = if raw.starts_with("data:") {
+        raw.split_once(',').map(|(_, data)| data).ok_or_else(|| {
+            CommandFlowError::Validation("图片 Data URL 缺少 base64 数据段".to_string())
+        })?
+    } else {
+        raw
+    };
+
+    general_purpose::STANDARD
+        .decode(payload.trim())
+        .map_err(|error| CommandFlowError::Automation(format!("解析图片 base64 失败：{}", error)))
+}
+
+// 图像值可以是文件路径、Data URL、裸 base64，或带 image/path 字段的结构化对象
+pub fn decode_image_value(value: &Value) -> CommandResult<DynamicImage> {
+    match value {
+        Value::String(text) => decode_image_string(text),
+        Value::Object(object) => {
+            if let Some(image) = object
+                .get("image")
+                .and_then(Value::as_str)
+                .filter(|image| !image.trim().is_empty())
+            {
+                return decode_image_string(image);
+            }
+            if let Some(path) = object
+                .get("path")
+                .and_then(Value::as_str)
+                .filter(|path| !path.trim().is_empty())
+            {
+                return decode_image_string(path);
+            }
+            Err(CommandFlowError::Automation(
+                "图像对象不包含可识别的图片数据(image/path)".to_string(),
+            ))
+        }
+        _ => Err(CommandFlowError::Automation(
+            "图像值不是字符串或结构化图片对象".to_string(),
+        )),
+    }
+}
+
+fn decode_image_string(raw: &str) -> CommandResult<DynamicImage> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err(CommandFlowError::Validation(
+            "image value cannot be empty".to_string(),
+        ));
+    }
+
+    if !trimmed.starts_with("data:") && Path::new(trimmed).is_file() {
+        return image::open(trimmed).map_err(|error| {
+            CommandFlowError::Automation(format!("读取图片文件失败 '{}': {}", trimmed, error))
+        });
+    }
+
+    let bytes = decode_base64_image_payload(trimmed).map_err(|error| {
+        if trimmed.starts_with("data:") {
+            error
+        } else {
+            CommandFlowError::Validation(format!(
+                "image file '{}' does not exist and is not valid base64",
+                trimmed
+            ))
+        }
+    })?;
+    image::load_from_memory(&bytes)
+        .map_err(|error| CommandFlowError::Automation(format!("解析图片数据失败：{}", error)))
+}
+
+// 供只接受文件路径的节点参数使用：路径原样返回，内存中的图像写入本次运行的临时目录后返回路径
+pub fn image_value_to_path(value: &Value, run_id: &str) -> CommandResult<String> {
+    match value {
+        Value::String(text) if text.trim().starts_with("data:") => {
+            materialize_data_url(text, run_id)
+        }
+        Value::String(text) => Ok(text.clone()),
+        Value::Object(object) => {
+            if let Some(path) = object
+                .get("path")
+                .and_then(Value::as_str)
+                .filter(|path| !path.trim().is_empty())
+            {
+                return Ok(path.to_string());
+            }
+            match object.get("image").and_then(Value::as_str) {
+                Some(image) if !image.trim().is_empty() => materialize_data_url(image, run_id),
+                _ => Err(CommandFlowError::Automation(
+                    "图像对象不包含可识别的图片数据(image/path)".to_string(),
+                )),
+            }
+        }
+        Value::Null => Ok(String::new()),
+        _ => Err(CommandFlowError::Automation(
+            "图像值不是字符串或结构化图片对象".to_string(),
+        )),
+    }
+}
+
+fn materialize_data_url(raw: &str, run_id: &str) -> CommandResult<String> {
+    let bytes = decode_base64_image_payload(raw.trim())?;
+    let extension = match image::guess_format(&bytes) {
+        Ok(ImageFormat::Jpeg) => "jpg",
+        Ok(ImageFormat::Bmp) => "bmp",
+        Ok(ImageFormat::WebP) => "webp",
+        _ => "png",
+    };
+
+    let dir = temp_image_dir(run_id);
+    std::fs::create_dir_all(&dir)
+        .map_err(|error| CommandFlowError::Io(format!("{}: {}", dir.display(), error)))?;
+    // 相同内容复用同一个临时文件，避免循环中反复写盘；散列冲突时比对内容后另起文件名
+    let stem = format!("{:016x}", content_hash(&bytes));
+    let mut index = 0_u32;
+    loop {
+        let name = if index == 0 {
+            format!("{}.{}", stem, extension)
+        } else {
+            format!("{}-{}.{}", stem, index, extension)
+        };
+        let path = dir.join(name);
+        match std::fs::read(&path) {
+            Ok(existing) if existing == bytes => return Ok(path.display().to_string()),
+            Ok(_) => index += 1,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                std::fs::write(&path, &bytes).map_err(|error| {
+                    CommandFlowError::Io(format!("{}: {}", path.display(), error))
+                })?;
+                return Ok(path.display().to_string());
+            }
+            Err(error) => {
+                return Err(CommandFlowError::Io(format!(
+                    "{}: {}",
+                    path.display(),
+                    error
+                )))
+            }
+        }
+    }
+}
+
+// FNV-1a，只用于同一次运行内的文件命名
+fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn temp_image_dir(run_id: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join("commandflow-image-values")
+        .join(run_id)
+}
+
+// 运行结束时删除本次运行写出的临时图像
+pub fn remove_temp_images(run_id: &str) -> CommandResult<()> {
+    let dir = temp_image_dir(run_id);
+    match std::fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(CommandFlowError::Io(format!(
+            "{}: {}",
+            dir.display(),
+            error
+        ))),
+    }
+}
+
+pub fn memory_image_value(image: &DynamicImage) -> CommandResult<Value> {
+    let bytes = encode(image, EncodeFormat::Png, 100)?;
+    Ok(encoded_image_value(
+        &bytes,
+        EncodeFormat::Png,
+        image.width(),
+        image.height(),
+    ))
+}
+
+pub fn encoded_image_value(bytes: &[u8], format: EncodeFormat, width: u32, height: u32) -> Value {
+    data_url_image_value(data_url(bytes, format), format, width, height)
+}
+
+pub fn data_url_image_value(
+    data_url: String,
+    format: EncodeFormat,
+    width: u32,
+    height: u32,
+) -> Value {
+    serde_json::json!({
+        "type": IMAGE_VALUE_TYPE,
+        "image": data_url,
+        "width": width,
+        "height": height,
+        "format": format.name(),
+    })
+}
+
+pub fn file_image_value(path: &Path, width: u32, height: u32) -> Value {
+    serde_json::json!({
+        "type": IMAGE_VALUE_TYPE,
+        "path": path.display().to_string(),
+        "width": width,
+        "height": height,
+        "format": EncodeFormat::from_path(path).name(),
+    })
+}
+
+pub fn save(image: &DynamicImage, path: &Path, quality: u8) -> CommandResult<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| CommandFlowError::Io(format!("{}: {}", parent.display(), error)))?;
+    }
+    let bytes = encode(image, EncodeFormat::from_path(path), quality)?;
+    std::fs::write(path, bytes)
+        .map_err(|error| CommandFlowError::Io(format!("{}: {}", path.display(), error)))
+}
+
+pub fn encode(image: &DynamicImage, format: EncodeFormat, quality: u8) -> CommandResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    match format {
+        EncodeFormat::Png => image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .map_err(|error| CommandFlowError::Automation(error.to_string()))?,
+        EncodeFormat::Jpeg => {
+            // JPEG 不支持透明通道，先转为 RGB
+            let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+            rgb.write_with_encoder(JpegEncoder::new_with_quality(
+                &mut bytes,
+                quality.clamp(1, 100),
+            ))
+            .map_err(|error| CommandFlowError::Automation(error.to_string()))?
+        }
+    }
+    Ok(bytes)
+}
+
+pub fn data_url(bytes: &[u8], format: EncodeFormat) -> String {
+    format!(
+        "data:{};base64,{}",
+        format.mime(),
+        general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+pub fn parse_color(value: &str) -> CommandResult<Rgba<u8>> {
+    pixel_color::parse_hex_color(value)
+        .map(|color| Rgba([color.r, color.g, color.b, 255]))
+        .ok_or_else(|| {
+            CommandFlowError::Validation(format!("invalid color '{}', expected #RRGGBB", value))
+        })
+}
+
+fn rect_outside_error(rect: ImageRect, image: &DynamicImage) -> CommandFlowError {
+    CommandFlowError::Validation(format!(
+        "rect ({}, {}) is outside the {}x{} image",
+        rect.left,
+        rect.top,
+        image.width(),
+        image.height()
+    ))
+}
+
+pub fn crop(image: &DynamicImage, rect: ImageRect) -> CommandResult<DynamicImage> {
+    let clipped = rect
+        .clip(image.width(), image.height())
+        .ok_or_else(|| rect_outside_error(rect, image))?;
+    Ok(image.crop_imm(clipped.left, clipped.top, clipped.width, clipped.height))
+}
+
+// 宽高只填一个时按原图比例计算另一个
+pub fn resize(
+    image: &DynamicImage,
+    width: u32,
+    height: u32,
+    filter: FilterType,
+) -> CommandResult<DynamicImage> {
+    let (source_width, source_height) = (image.width().max(1), image.height().max(1));
+    let (width, height) = match (width, height) {
+        (0, 0) => {
+            return Err(CommandFlowError::Validation(
+                "resize width and height cannot both be 0".to_string(),
+            ))
+        }
+        (0, height) => (
+            ((u64::from(source_width) * u64::from(height)) / u64::from(source_height)).max(1)
+                as u32,
+            height,
+        ),
+        (width, 0) => (
+            width,
+            ((u64::from(source_height) * u64::from(width)) / u64::from(source_width)).max(1) as u32,
+        ),
+        size => size,
+    };
+    Ok(image.resize_exact(width, height, filter))
+}
+
+pub fn parse_resize_filter(value: &str) -> FilterType {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "nearest" => FilterType::Nearest,
+        "triangle" | "bilinear" => FilterType::Triangle,
+        "catmullrom" | "cubic" => FilterType::CatmullRom,
+        _ => FilterType::Lanczos3,
+    }
+}
+
+pub fn rotate(image: &DynamicImage, rotation: &str) -> CommandResult<DynamicImage> {
+    match rotation.trim().to_ascii_lowercase().as_str() {
+        "90" | "cw90" => Ok(image.rotate90()),
+        "180" => Ok(image.rotate180()),
+        "270" | "ccw90" | "-90" => Ok(image.rotate270()),
+        "fliph" | "horizontal" => Ok(image.fliph()),
+        "flipv" | "vertical" => Ok(image.flipv()),
+        other => Err(CommandFlowError::Validation(format!(
+            "unsupported rotation '{}'",
+            other
+        ))),
+    }
+}
+
+pub fn grayscale(image: &DynamicImage) -> DynamicImage {
+    DynamicImage::ImageLuma8(image.to_luma8())
+}
+
+// level 为 None 时使用 Otsu 自动阈值；返回二值图与实际使用的阈值
+pub fn threshold(image: &DynamicImage, level: Option<u8>) -> (DynamicImage, u8) {
+    let gray = image.to_luma8();
+    let level = level.unwrap_or_else(|| otsu_level(&gray));
+    let binary = GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        if gray.get_pixel(x, y)[0] > level {
+            Luma([255])
+        } else {
+            Luma([0])
+        }
+    });
+    (DynamicImage::ImageLuma8(binary), level)
+}
+
+fn otsu_level(gray: &GrayImage) -> u8 {
+    let mut histogram = [0_u64; 256];
+    for pixel in gray.pixels() {
+        histogram[usize::from(pixel[0])] += 1;
+    }
+    let total = gray.width() as f64 * gray.height() as f64;
+    if total == 0.0 {
+        return 128;
+    }
+    let weighted_total = histogram
+        .iter()
+        .enumerate()
+        .map(|(value, count)| value as f64 * *count as f64)
+        .sum::<f64>();
+
+    let mut background_count = 0.0;
+    let mut background_sum = 0.0;
+    let mut best_level = 0_u8;
+    let mut best_variance = 0.0;
+    for (value, count) in histogram.iter().enumerate() {
+        background_count += *count as f64;
+        if background_count == 0.0 {
+            continue;
+        }
+        let foreground_count = total - background_count;
+        if foreground_count == 0.0 {
+            break;
+        }
+        background_sum += value as f64 * *count as f64;
+        let background_mean = background_sum / background_count;
+        let foreground_mean = (weighted_total - background_sum) / foreground_count;
+        let variance = background_count
+            * foreground_count
+            * (background_mean - foreground_mean)
+            * (background_mean - foreground_mean);
+        if variance > best_variance {
+            best_variance = variance;
+            best_level = value as u8;
+        }
+    }
+    best_level
+}
+
+pub fn invert(image: &DynamicImage) -> DynamicImage {
+    let mut inverted = image.clone();
+    inverted.invert();
+    inverted
+}
+
+pub fn draw_box(
+    image: &DynamicImage,
+    rect: ImageRect,
+    color: Rgba<u8>,
+    line_width: u32,
+) -> CommandResult<DynamicImage> {
+    let mut canvas = image.to_rgba8();
+    let clipped = rect
+        .clip(canvas.width(), canvas.height())
+        .ok_or_else(|| rect_outside_error(rect, image))?;
+    let line_width = line_width
+        .max(1)
+        .min(clipped.width.min(clipped.height).max(1));
+    let right = clipped.left + clipped.width;
+    let bottom = clipped.top + clipped.height;
+    for y in clipped.top..bottom {
+        for x in clipped.left..right {
+            let on_edge = x < clipped.left + line_width
+                || x >= right.saturating_sub(line_width)
+                || y < clipped.top + line_width
+                || y >= bottom.saturating_sub(line_width);
+            if on_edge {
+                canvas.put_pixel(x, y, color);
+            }
+        }
+    }
+    Ok(DynamicImage::ImageRgba8(canvas))
+}
+
+// 标签以指定颜色为底色，文字颜色按底色亮度自动选择黑或白
+pub fn draw_label(
+    image: &DynamicImage,
+    left: u32,
+    top: u32,
+    text: &str,
+    font_height: u32,
+    color: Rgba<u8>,
+) -> CommandResult<DynamicImage> {
+    if text.trim().is_empty() {
+        return Err(CommandFlowError::Validation(
+            "label text cannot be empty".to_string(),
+        ));
+    }
+    let luma = u32::from(color[0]) * 299 + u32::from(color[1]) * 587 + u32::from(color[2]) * 114;
+    let text_color = if luma > 128_000 {
+        Rgba([0, 0, 0, 255])
+    } else {
+        Rgba([255, 255, 255, 255])
+    };
+    let label = text_render::render_text(text, font_height, text_color, color, LABEL_PADDING)
+        .ok_or_else(|| CommandFlowError::Automation("绘制文字标签失败".to_string()))?;
+
+    let mut canvas = image.to_rgba8();
+    imageops::replace(&mut canvas, &label, i64::from(left), i64::from(top));
+    Ok(DynamicImage::ImageRgba8(canvas))
+}
+
+pub fn blur(image: &DynamicImage, rect: ImageRect, sigma: f32) -> CommandResult<DynamicImage> {
+    let mut canvas = image.to_rgba8();
+    let clipped = rect
+        .clip(canvas.width(), canvas.height())
+        .ok_or_else(|| rect_outside_error(rect, image))?;
+    let region = imageops::crop_imm(
+        &canvas,
+        clipped.left,
+        clipped.top,
+        clipped.width,
+        clipped.height,
+    )
+    .to_image();
+    let blurred = imageops::blur(&region, sigma.max(0.1));
+    imageops::replace(
+        &mut canvas,
+        &blurred,
+        i64::from(clipped.left),
+        i64::from(clipped.top),
+    );
+    Ok(DynamicImage::ImageRgba8(canvas))
+}
+
+// block_size 大于 0 时按块取平均做马赛克，否则用纯色填充
+pub fn redact(
+    image: &DynamicImage,
+    rect: ImageRect,
+    color: Rgba<u8>,
+    block_size: u32,
+) -> CommandResult<DynamicImage> {
+    let mut canvas = image.to_rgba8();
+    let clipped = rect
+        .clip(canvas.width(), canvas.height())
+        .ok_or_else(|| rect_outside_error(rect, image))?;
+    if block_size == 0 {
+        fill_rect(&mut canvas, clipped, color);
+        return Ok(DynamicImage::ImageRgba8(canvas));
+    }
+
+    let right = clipped.left + clipped.width;
+    let bottom = clipped.top + clipped.height;
+    for block_top in (clipped.top..bottom).step_by(block_size as usize) {
+        for block_left in (clipped.left..right).step_by(block_size as usize) {
+            let block = ImageRect {
+                left: block_left,
+                top: block_top,
+                width: block_size.min(right - block_left),
+                height: block_size.min(bottom - block_top),
+            };
+            let average = average_pixel(&canvas, block);
+            fill_rect(&mut canvas, block, average);
+        }
+    }
+    Ok(DynamicImage::ImageRgba8(canvas))
+}
+
+fn fill_rect(canvas: &mut RgbaImage, rect: ImageRect, color: Rgba<u8>) {
+    for y in rect.top..rect.top + rect.height {
+        for x in rect.left..rect.left + rect.width {
+            canvas.put_pixel(x, y, color);
+        }
+    }
+}
+
+fn average_pixel(canvas: &RgbaImage, rect: ImageRect) -> Rgba<u8> {
+    let mut sums = [0_u64; 4];
+    for y in rect.top..rect.top + rect.height {
+        for x in rect.left..rect.left + rect.width {
+            let pixel = canvas.get_pixel(x, y);
+            for (sum, channel) in sums.iter_mut().zip(pixel.0.iter()) {
+                *sum += u64::from(*channel);
+            }
+        }
+    }
+    let count = (u64::from(rect.width) * u64::from(rect.height)).max(1);
+    Rgba(sums.map(|sum| (sum / count) as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        data_url, decode_image_value, encode, image_value_to_path, otsu_level, remove_temp_images,
+        temp_image_dir, threshold, EncodeFormat, ImageRect,
+    };
+    use image::{DynamicImage, GrayImage, Luma, Rgba, RgbaImage};
+    use std::path::Path;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    type Bounds = (u32, u32, u32, u32);
+
+    fn unique_name(name: &str) -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or(0);
+        format!("commandflow-image-ops-{}-{}", name, nanos)
+    }
+
+    fn sample_image() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(3, 2, Rgba([10, 20, 30, 255])))
+    }
+
+    fn sample_data_url() -> String {
+        let bytes = encode(&sample_image(), EncodeFormat::Png, 100).expect("encode png");
+        data_url(&bytes, EncodeFormat::Png)
+    }
+
+    fn rect(left: u32, top: u32, width: u32, height: u32) -> ImageRect {
+        ImageRect {
+            left,
+            top,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn clip_limits_rect_to_image_bounds() {
+        let cases: &[(ImageRect, Option<Bounds>)] = &[
+            (rect(2, 3, 4, 5), Some((2, 3, 4, 5))),
+            (rect(8, 6, 10, 10), Some((8, 6, 2, 4))),
+            (rect(4, 2, 0, 0), Some((4, 2, 6, 8))),
+            (rect(0, 0, 0, 3), Some((0, 0, 10, 3))),
+            (rect(9, 9, 1, 1), Some((9, 9, 1, 1))),
+            (rect(10, 0, 1, 1), None),
+            (rect(0, 10, 1, 1), None),
+        ];
+        for (input, expected) in cases {
+            let clipped = input
+                .clip(10, 10)
+                .map(|rect| (rect.left, rect.top, rect.width, rect.height));
+            assert_eq!(clipped, *expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn otsu_level_separates_bimodal_histogram() {
+        let gray = GrayImage::from_fn(10, 10, |x, _| if x < 4 { Luma([40]) } else { Luma([200]) });
+        let level = otsu_level(&gray);
+        assert!((40..200).contains(&level), "level {}", level);
+
+        let (binary, applied) = threshold(&DynamicImage::ImageLuma8(gray), None);
+        assert_eq!(applied, level);
+        let binary = binary.to_luma8();
+        assert_eq!(binary.get_pixel(0, 0)[0], 0);
+        assert_eq!(binary.get_pixel(9, 9)[0], 255);
+    }
+
+    #[test]
+    fn otsu_level_handles_degenerate_images() {
+        assert_eq!(otsu_level(&GrayImage::new(0, 0)), 128);
+        assert_eq!(otsu_level(&GrayImage::from_pixel(4, 4, Luma([90]))), 0);
+    }
+
+    #[test]
+    fn decode_image_value_accepts_path_data_url_and_object() {
+        let dir = std::env::temp_dir().join(unique_name("decode"));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("sample.png");
+        sample_image().save(&path).expect("save sample");
+        let path_text = path.display().to_string();
+        let url = sample_data_url();
+
+        let values = [
+            serde_json::json!(path_text),
+            serde_json::json!(url),
+            serde_json::json!({ "type": "image", "image": url, "path": "" }),
+            serde_json::json!({ "type": "image", "path": path_text }),
+        ];
+        for value in &values {
+            let image = decode_image_value(value).expect("decode image value");
+            assert_eq!((image.width(), image.height()), (3, 2));
+            assert_eq!(image.to_rgba8().get_pixel(1, 1), &Rgba([10, 20, 30, 255]));
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn decode_image_value_rejects_unusable_values() {
+        let values = [
+            serde_json::json!(""),
+            serde_json::json!("missing-file-and-not-base64.png"),
+            serde_json::json!("data:image/png;base64"),
+            serde_json::json!({ "type": "image", "width": 3 }),
+            serde_json::json!(42),
+        ];
+        for value in &values {
+            assert!(decode_image_value(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn data_url_values_are_written_per_run_and_removed() {
+        let run_id = unique_name("materialize");
+        let url = sample_data_url();
+
+        let first = image_value_to_path(&serde_json::json!(url), &run_id).expect("materialize");
+        let second = image_value_to_path(&serde_json::json!({ "image": url }), &run_id)
+            .expect("materialize object");
+        assert_eq!(first, second);
+        assert!(Path::new(&first).starts_with(temp_image_dir(&run_id)));
+        assert!(first.ends_with(".png"));
+        let materialized = image::open(&first).expect("open materialized");
+        assert_eq!((materialized.width(), materialized.height()), (3, 2));
+
+        assert_eq!(
+            image_value_to_path(&serde_json::json!("C:/shots/a.png"), &run_id).expect("path"),
+            "C:/shots/a.png"
+        );
+        assert_eq!(
+            image_value_to_path(&serde_json::Value::Null, &run_id).expect("null"),
+            ""
+        );
+
+        remove_temp_images(&run_id).expect("remove temp images");
+        assert!(!temp_image_dir(&run_id).exists());
+        remove_temp_images(&run_id).expect("removing twice is fine");
+    }
+}
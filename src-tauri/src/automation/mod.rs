@@ -1,13 +1,25 @@
 pub mod executor;
+pub mod feature_match;
+pub mod file_journal;
 pub mod file_ops;
 pub mod image_match;
+pub mod image_ops;
 pub mod keyboard;
 pub mod mouse;
+pub mod ocr_layout;
 pub mod ocr_match;
+pub mod ocr_models;
+pub mod pixel_color;
 pub mod power;
 pub mod process;
+pub mod redaction;
+pub mod run_recorder;
+pub mod screen_change;
 pub mod screenshot;
 pub mod start_menu;
 pub mod system_settings;
+pub mod text_render;
+pub mod text_similarity;
 pub mod uia;
+pub mod visual_assert;
 pub mod window;
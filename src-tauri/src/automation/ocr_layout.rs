@@ -0,0 +1,362 @@
+use serde::Serialize;
+
+// 同一行的判定：两个文本块纵向重叠至少占较矮者高度的一半
+const LINE_OVERLAP_RATIO: f32 = 0.5;
+// 段落的判定：行间距小于平均行高的该倍数时视为同一段
+const PARAGRAPH_GAP_RATIO: f32 = 0.8;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrTextBlock {
+    pub text: String,
+    pub confidence: f32,
+    pub x: i32,
+    pub y: i32,
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+    pub quad: Option<[[f32; 2]; 4]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcrGrouping {
+    Blocks,
+    Lines,
+    Paragraphs,
+}
+
+#[derive(Debug, Clone)]
+struct TextLine {
+    text: String,
+    top: f32,
+    bottom: f32,
+}
+
+impl OcrGrouping {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "blocks" | "block" => OcrGrouping::Blocks,
+            "paragraphs" | "paragraph" => OcrGrouping::Paragraphs,
+            _ => OcrGrouping::Lines,
+        }
+    }
+}
+
+impl OcrTextBlock {
+    pub fn from_quad(text: String, confidence: f32, quad: [[f32; 2]; 4]) -> Self {
+        let xs = quad.map(|point| point[0]);
+        let ys = quad.map(|point| point[1]);
+        let left = xs.iter().copied().fold(f32::MAX, f32::min);
+        let right = xs.iter().copied().fold(f32::MIN, f32::max);
+        let top = ys.iter().copied().fold(f32::MAX, f32::min);
+        let bottom = ys.iter().copied().fold(f32::MIN, f32::max);
+        Self {
+            text,
+            confidence,
+            x: ((left + right) / 2.0).round() as i32,
+            y: ((top + bottom) / 2.0).round() as i32,
+            left,
+            top,
+            width: right - left,
+            height: bottom - top,
+            quad: Some(quad),
+        }
+    }
+
+    // 仅识别（无检测框）时没有坐标，按识别顺序保留
+    pub fn without_position(text: String, confidence: f32) -> Self {
+        Self {
+            text,
+            confidence,
+            x: -1,
+            y: -1,
+            left: 0.0,
+            top: 0.0,
+            width: 0.0,
+            height: 0.0,
+            quad: None,
+        }
+    }
+
+    pub fn offset(mut self, dx: i32, dy: i32) -> Self {
+        if self.quad.is_none() {
+            return self;
+        }
+        self.x += dx;
+        self.y += dy;
+        self.left += dx as f32;
+        self.top += dy as f32;
+        if let Some(quad) = self.quad.as_mut() {
+            for point in quad.iter_mut() {
+                point[0] += dx as f32;
+                point[1] += dy as f32;
+            }
+        }
+        self
+    }
+
+    fn bottom(&self) -> f32 {
+        self.top + self.height
+    }
+}
+
+// 按阅读顺序（从上到下、从左到右）排列文本块
+pub fn sort_reading_order(blocks: Vec<OcrTextBlock>) -> Vec<OcrTextBlock> {
+    group_block_lines(blocks).into_iter().flatten().collect()
+}
+
+pub fn compose_text(blocks: &[OcrTextBlock], grouping: OcrGrouping) -> String {
+    match grouping {
+        OcrGrouping::Blocks => blocks
+            .iter()
+            .map(|block| block.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OcrGrouping::Lines => text_lines(blocks)
+            .into_iter()
+            .map(|line| line.text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OcrGrouping::Paragraphs => compose_paragraphs(&text_lines(blocks)),
+    }
+}
+
+fn group_block_lines(mut blocks: Vec<OcrTextBlock>) -> Vec<Vec<OcrTextBlock>> {
+    if blocks.iter().any(|block| block.quad.is_none()) {
+        return blocks.into_iter().map(|block| vec![block]).collect();
+    }
+
+    blocks.sort_by(|a, b| a.top.total_cmp(&b.top));
+    let mut lines: Vec<Vec<OcrTextBlock>> = Vec::new();
+    for block in blocks {
+        let target = lines.iter_mut().find(|line| {
+            let top = line.iter().map(|item| item.top).fold(f32::MAX, f32::min);
+            let bottom = line
+                .iter()
+                .map(|item| item.bottom())
+                .fold(f32::MIN, f32::max);
+            let overlap = bottom.min(block.bottom()) - top.max(block.top);
+            let shorter = (bottom - top).min(block.height).max(1.0);
+            overlap >= shorter * LINE_OVERLAP_RATIO
+        });
+        match target {
+            Some(line) => line.push(block),
+            None => lines.push(vec![block]),
+        }
+    }
+
+    for line in &mut lines {
+        line.sort_by(|a, b| a.left.total_cmp(&b.left));
+    }
+    lines.sort_by(|a, b| {
+        let top_a = a.iter().map(|item| item.top).fold(f32::MAX, f32::min);
+        let top_b = b.iter().map(|item| item.top).fold(f32::MAX, f32::min);
+        top_a.total_cmp(&top_b)
+    });
+    lines
+}
+
+fn text_lines(blocks: &[OcrTextBlock]) -> Vec<TextLine> {
+    group_block_lines(blocks.to_vec())
+        .into_iter()
+        .map(|line| TextLine {
+            text: join_fragments(line.iter().map(|block| block.text.as_str())),
+            top: line.iter().map(|item| item.top).fold(f32::MAX, f32::min),
+            bottom: line
+                .iter()
+                .map(|item| item.bottom())
+                .fold(f32::MIN, f32::max),
+        })
+        .collect()
+}
+
+fn compose_paragraphs(lines: &[TextLine]) -> String {
+    if lines.is_empty() {
+        return String::new();
+    }
+    let average_height =
+        lines.iter().map(|line| line.bottom - line.top).sum::<f32>() / lines.len() as f32;
+
+    let mut paragraphs: Vec<Vec<&str>> = vec![Vec::new()];
+    let mut previous_bottom: Option<f32> = None;
+    for line in lines {
+        if let Some(bottom) = previous_bottom {
+            if line.top - bottom > average_height * PARAGRAPH_GAP_RATIO {
+                paragraphs.push(Vec::new());
+            }
+        }
+        if let Some(current) = paragraphs.last_mut() {
+            current.push(line.text.as_str());
+        }
+        previous_bottom = Some(line.bottom);
+    }
+
+    paragraphs
+        .into_iter()
+        .map(|paragraph| join_fragments(paragraph.into_iter()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// 中文等不使用空格分词的文字直接拼接，仅在英文/数字之间补空格
+fn join_fragments<'a>(fragments: impl Iterator<Item = &'a str>) -> String {
+    let mut output = String::new();
+    for fragment in fragments {
+        let fragment = fragment.trim();
+        if fragment.is_empty() {
+            continue;
+        }
+        let needs_space = match (output.chars().last(), fragment.chars().next()) {
+            (Some(before), Some(after)) => {
+                (before.is_ascii_alphanumeric() || before.is_ascii_punctuation())
+                    && after.is_ascii_alphanumeric()
+            }
+            _ => false,
+        };
+        if needs_space {
+            output.push(' ');
+        }
+        output.push_str(fragment);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compose_text, join_fragments, sort_reading_order, OcrGrouping, OcrTextBlock};
+
+    fn block(text: &str, left: f32, top: f32, width: f32, height: f32) -> OcrTextBlock {
+        OcrTextBlock::from_quad(
+            text.to_string(),
+            0.9,
+            [
+                [left, top],
+                [left + width, top],
+                [left + width, top + height],
+                [left, top + height],
+            ],
+        )
+    }
+
+    fn texts(blocks: &[OcrTextBlock]) -> Vec<&str> {
+        blocks.iter().map(|block| block.text.as_str()).collect()
+    }
+
+    #[test]
+    fn grouping_parse_falls_back_to_lines() {
+        assert_eq!(OcrGrouping::parse("Blocks"), OcrGrouping::Blocks);
+        assert_eq!(OcrGrouping::parse(" paragraph "), OcrGrouping::Paragraphs);
+        assert_eq!(OcrGrouping::parse("lines"), OcrGrouping::Lines);
+        assert_eq!(OcrGrouping::parse("unknown"), OcrGrouping::Lines);
+        assert_eq!(OcrGrouping::parse(""), OcrGrouping::Lines);
+    }
+
+    #[test]
+    fn from_quad_computes_bounds_and_center() {
+        let item = block("a", 10.0, 20.0, 30.0, 10.0);
+        assert_eq!((item.x, item.y), (25, 25));
+        assert_eq!(
+            (item.left, item.top, item.width, item.height),
+            (10.0, 20.0, 30.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn blocks_overlapping_half_height_share_a_line() {
+        // "right" 纵向偏移 4px，与 "left" 重叠 6/10，归为同一行；
+        // 该行范围扩展为 0..14，"below" 只重叠 2/10，另起一行
+        let sorted = sort_reading_order(vec![
+            block("below", 0.0, 12.0, 40.0, 10.0),
+            block("right", 100.0, 4.0, 40.0, 10.0),
+            block("left", 0.0, 0.0, 40.0, 10.0),
+        ]);
+        assert_eq!(texts(&sorted), vec!["left", "right", "below"]);
+        assert_eq!(
+            compose_text(&sorted, OcrGrouping::Lines),
+            "left right\nbelow"
+        );
+    }
+
+    #[test]
+    fn paragraphs_split_on_gap_larger_than_line_height_ratio() {
+        let blocks = vec![
+            block("First line", 0.0, 0.0, 100.0, 10.0),
+            block("continues", 0.0, 14.0, 100.0, 10.0),
+            // 与上一行间距 7px，小于 0.8 倍行高，仍属同一段
+            block("still same", 0.0, 31.0, 100.0, 10.0),
+            // 间距 9px，超过 0.8 倍行高，另起一段
+            block("New paragraph", 0.0, 50.0, 100.0, 10.0),
+        ];
+        assert_eq!(
+            compose_text(&blocks, OcrGrouping::Paragraphs),
+            "First line continues still same\n\nNew paragraph"
+        );
+    }
+
+    #[test]
+    fn blocks_grouping_keeps_original_order() {
+        let blocks = vec![
+            block("b", 50.0, 0.0, 10.0, 10.0),
+            block("a", 0.0, 0.0, 10.0, 10.0),
+        ];
+        assert_eq!(compose_text(&blocks, OcrGrouping::Blocks), "b\na");
+    }
+
+    #[test]
+    fn join_fragments_spaces_only_between_latin_words() {
+        let cases: &[(&[&str], &str)] = &[
+            (&["你好", "世界"], "你好世界"),
+            (&["Hello", "world"], "Hello world"),
+            (&["版本", "2.0"], "版本2.0"),
+            (&["v2.0", "发布"], "v2.0发布"),
+            (&["Total:", "42"], "Total: 42"),
+            (&["  padded ", "", "text"], "padded text"),
+            (&["CJK", "中文"], "CJK中文"),
+        ];
+        for (fragments, expected) in cases {
+            assert_eq!(
+                join_fragments(fragments.iter().copied()),
+                *expected,
+                "{fragments:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn mixed_cjk_and_latin_line_joins_naturally() {
+        let blocks = vec![
+            block("请点击", 0.0, 0.0, 60.0, 20.0),
+            block("Submit", 70.0, 1.0, 60.0, 20.0),
+            block("Now", 140.0, 0.0, 40.0, 20.0),
+            block("按钮", 190.0, 2.0, 40.0, 20.0),
+        ];
+        assert_eq!(
+            compose_text(&blocks, OcrGrouping::Lines),
+            "请点击Submit Now按钮"
+        );
+    }
+
+    #[test]
+    fn unpositioned_blocks_keep_recognition_order() {
+        let blocks = vec![
+            OcrTextBlock::without_position("second".to_string(), 0.9),
+            OcrTextBlock::without_position("first".to_string(), 0.9),
+        ];
+        let sorted = sort_reading_order(blocks);
+        assert_eq!(texts(&sorted), vec!["second", "first"]);
+        assert_eq!(compose_text(&sorted, OcrGrouping::Lines), "second\nfirst");
+    }
+
+    #[test]
+    fn offset_moves_positioned_blocks_only() {
+        let moved = block("a", 10.0, 20.0, 30.0, 10.0).offset(-100, 5);
+        assert_eq!((moved.x, moved.y), (-75, 30));
+        assert_eq!((moved.left, moved.top), (-90.0, 25.0));
+        assert_eq!(moved.quad.expect("quad")[2], [-60.0, 35.0]);
+
+        let unpositioned = OcrTextBlock::without_position("b".to_string(), 0.5).offset(-100, 5);
+        assert_eq!((unpositioned.x, unpositioned.y), (-1, -1));
+        assert_eq!((unpositioned.left, unpositioned.top), (0.0, 0.0));
+    }
+}
@@ -1,14 +1,21 @@
+use crate::automation::ocr_layout::OcrTextBlock;
+use crate::automation::ocr_models::OcrModelSet;
+use crate::automation::text_similarity::{self, SimilarityAlgorithm, TextNormalization};
 use crate::error::{CommandFlowError, CommandResult};
 use paddle_ocr_rs::{EngineConfig, OcrInput, OcrResult, RapidOcrEngine, RunOptions};
 use regex::Regex;
-use std::sync::{Mutex, OnceLock};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
 
 #[derive(Debug, Clone)]
 pub struct OcrMatchCandidate {
     pub x: i32,
     pub y: i32,
+    // 仅识别文本、没有文本框时坐标无意义；多显示器下负坐标是合法位置，不能以符号判断
+    pub has_position: bool,
     pub text: String,
     pub confidence: f32,
+    pub similarity: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -16,9 +23,34 @@ pub struct OcrMatchEvaluation {
     pub matched: Option<OcrMatchCandidate>,
     pub peak_text: String,
     pub peak_confidence: f32,
+    // 模糊匹配时所有文本块中的最高相似度，便于超时排查
+    pub peak_similarity: f32,
     pub debug_entries: Vec<OcrDebugEntry>,
 }
 
+#[derive(Debug, Clone)]
+pub struct OcrTextQuery {
+    pub target_text: String,
+    pub match_mode: String,
+    pub case_sensitive: bool,
+    pub use_regex: bool,
+    pub min_confidence: f32,
+    pub min_similarity: f32,
+    pub similarity_algorithm: SimilarityAlgorithm,
+    pub normalization: TextNormalization,
+}
+
+enum TextMatcher {
+    Regex(Regex),
+    Exact(String),
+    Contains(String),
+    Fuzzy {
+        target: String,
+        algorithm: SimilarityAlgorithm,
+        min_similarity: f32,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct OcrDebugEntry {
     pub text: String,
@@ -26,90 +58,157 @@ pub struct OcrDebugEntry {
     pub center_x: i32,
     pub center_y: i32,
     pub quad: Option<[[f32; 2]; 4]>,
+    pub similarity: f32,
     pub is_text_match: bool,
     pub is_confidence_passed: bool,
 }
 
-static OCR_ENGINE: OnceLock<Mutex<RapidOcrEngine>> = OnceLock::new();
+type SharedEngine = Arc<Mutex<RapidOcrEngine>>;
 
-fn get_engine() -> CommandResult<&'static Mutex<RapidOcrEngine>> {
-    if let Some(engine) = OCR_ENGINE.get() {
-        return Ok(engine);
-    }
+static OCR_ENGINES: OnceLock<Mutex<HashMap<OcrModelSet, SharedEngine>>> = OnceLock::new();
 
+fn engine_config(models: &OcrModelSet) -> EngineConfig {
     let mut config = EngineConfig::default();
-    config.det.allow_download = true;
-    config.cls.allow_download = true;
-    config.rec.model.allow_download = true;
-
-    let engine = RapidOcrEngine::new(config).map_err(|error| {
-        CommandFlowError::Automation(format!(
-            "初始化 OCR 引擎失败（自动模型下载/加载阶段）：{}",
-            error
-        ))
+    match models {
+        OcrModelSet::Builtin => {
+            config.det.allow_download = true;
+            config.cls.allow_download = true;
+            config.rec.model.allow_download = true;
+        }
+        OcrModelSet::Local {
+            det,
+            cls,
+            rec,
+            dict,
+            ..
+        } => {
+            config.det.allow_download = false;
+            config.det.model_path = Some(det.clone());
+            config.cls.allow_download = false;
+            config.cls.model_path = Some(cls.clone());
+            config.rec.model.allow_download = false;
+            config.rec.model.model_path = Some(rec.clone());
+            config.rec.rec_keys_path = Some(dict.clone());
+        }
+    }
+    config
+}
+
+fn get_engine(models: &OcrModelSet) -> CommandResult<SharedEngine> {
+    let engines = OCR_ENGINES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut engines = engines
+        .lock()
+        .map_err(|_| CommandFlowError::Automation("OCR 引擎缓存锁已中毒".to_string()))?;
+    if let Some(engine) = engines.get(models) {
+        return Ok(Arc::clone(engine));
+    }
+
+    let engine = RapidOcrEngine::new(engine_config(models)).map_err(|error| {
+        let stage = match models {
+            OcrModelSet::Builtin => "自动模型下载/加载阶段",
+            OcrModelSet::Local { .. } => "本地模型加载阶段",
+        };
+        CommandFlowError::Automation(format!("初始化 OCR 引擎失败（{}）：{}", stage, error))
     })?;
 
-    let _ = OCR_ENGINE.set(Mutex::new(engine));
-    OCR_ENGINE
-        .get()
-        .ok_or_else(|| CommandFlowError::Automation("OCR 引擎初始化后无法获取实例".to_string()))
+    let engine = Arc::new(Mutex::new(engine));
+    engines.insert(models.clone(), Arc::clone(&engine));
+    Ok(engine)
 }
 
 pub fn evaluate_path(
+    models: &OcrModelSet,
     image_path: &str,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
-    min_confidence: f32,
+    query: &OcrTextQuery,
 ) -> CommandResult<OcrMatchEvaluation> {
     let input = OcrInput::Path(std::path::PathBuf::from(image_path));
-    evaluate_input(
-        input,
-        target_text,
-        match_mode,
-        case_sensitive,
-        use_regex,
-        min_confidence,
-    )
+    find_match(&run_ocr(models, input)?, query)
 }
 
-#[allow(clippy::too_many_arguments)]
 pub fn evaluate_rgba(
+    models: &OcrModelSet,
     rgba: Vec<u8>,
     width: u32,
     height: u32,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
-    min_confidence: f32,
+    query: &OcrTextQuery,
 ) -> CommandResult<OcrMatchEvaluation> {
     let input = OcrInput::RgbaU8 {
         width: width as usize,
         height: height as usize,
         data: rgba,
     };
+    find_match(&run_ocr(models, input)?, query)
+}
 
-    evaluate_input(
-        input,
-        target_text,
-        match_mode,
-        case_sensitive,
-        use_regex,
-        min_confidence,
-    )
+pub fn read_path(
+    models: &OcrModelSet,
+    image_path: &str,
+    min_confidence: f32,
+) -> CommandResult<Vec<OcrTextBlock>> {
+    let input = OcrInput::Path(std::path::PathBuf::from(image_path));
+    Ok(collect_blocks(&run_ocr(models, input)?, min_confidence))
 }
 
-fn evaluate_input(
-    input: OcrInput,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
+pub fn read_rgba(
+    models: &OcrModelSet,
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
     min_confidence: f32,
-) -> CommandResult<OcrMatchEvaluation> {
-    let engine = get_engine()?;
+) -> CommandResult<Vec<OcrTextBlock>> {
+    let input = OcrInput::RgbaU8 {
+        width: width as usize,
+        height: height as usize,
+        data: rgba,
+    };
+    Ok(collect_blocks(&run_ocr(models, input)?, min_confidence))
+}
+
+fn collect_blocks(result: &OcrResult, min_confidence: f32) -> Vec<OcrTextBlock> {
+    let min_confidence = min_confidence.clamp(0.0, 1.0);
+    match result {
+        OcrResult::Full(full) => full
+            .txts
+            .iter()
+            .zip(full.scores.iter().copied())
+            .zip(full.boxes.iter())
+            .filter(|((_, score), _)| *score >= min_confidence)
+            .map(|((text, score), quad)| OcrTextBlock::from_quad(text.clone(), score, *quad))
+            .collect(),
+        OcrResult::Rec(rec) => rec
+            .txts
+            .iter()
+            .zip(rec.scores.iter().copied())
+            .filter(|(_, score)| *score >= min_confidence)
+            .map(|(text, score)| OcrTextBlock::without_position(text.clone(), score))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// 只做一次识别，再分别匹配多个目标文本
+pub fn evaluate_rgba_queries(
+    models: &OcrModelSet,
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+    queries: &[OcrTextQuery],
+) -> CommandResult<Vec<OcrMatchEvaluation>> {
+    let input = OcrInput::RgbaU8 {
+        width: width as usize,
+        height: height as usize,
+        data: rgba,
+    };
+    let result = run_ocr(models, input)?;
+
+    queries
+        .iter()
+        .map(|query| find_match(&result, query))
+        .collect()
+}
+
+fn run_ocr(models: &OcrModelSet, input: OcrInput) -> CommandResult<OcrResult> {
+    let engine = get_engine(models)?;
     let mut guard = engine
         .lock()
         .map_err(|_| CommandFlowError::Automation("OCR 引擎锁已中毒，无法继续识别".to_string()))?;
@@ -121,185 +220,144 @@ fn evaluate_input(
         ..RunOptions::default()
     };
 
-    let result = guard
+    guard
         .run(input, run_options)
-        .map_err(|error| CommandFlowError::Automation(format!("OCR 识别失败：{}", error)))?;
-
-    find_match(
-        &result,
-        target_text,
-        match_mode,
-        case_sensitive,
-        use_regex,
-        min_confidence.clamp(0.0, 1.0),
-    )
+        .map_err(|error| CommandFlowError::Automation(format!("OCR 识别失败：{}", error)))
 }
 
-fn find_match(
-    result: &OcrResult,
-    target_text: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    use_regex: bool,
-    min_confidence: f32,
-) -> CommandResult<OcrMatchEvaluation> {
+impl TextMatcher {
+    fn new(query: &OcrTextQuery) -> CommandResult<Self> {
+        if query.use_regex {
+            let pattern = if query.case_sensitive {
+                query.target_text.clone()
+            } else {
+                format!("(?i){}", query.target_text)
+            };
+            return Regex::new(&pattern)
+                .map(TextMatcher::Regex)
+                .map_err(|error| {
+                    CommandFlowError::Validation(format!("OCR 正则表达式无效：{}", error))
+                });
+        }
+
+        let target = query
+            .normalization
+            .apply(&query.target_text, query.case_sensitive);
+        Ok(match query.match_mode.trim().to_lowercase().as_str() {
+            "exact" => TextMatcher::Exact(target),
+            "fuzzy" => TextMatcher::Fuzzy {
+                target,
+                algorithm: query.similarity_algorithm,
+                min_similarity: query.min_similarity.clamp(0.0, 1.0),
+            },
+            _ => TextMatcher::Contains(target),
+        })
+    }
+
+    // 返回 (相似度, 是否命中)；非模糊模式下相似度只有 0 或 1
+    fn score(&self, candidate: &str, query: &OcrTextQuery) -> (f32, bool) {
+        let normalized = || query.normalization.apply(candidate, query.case_sensitive);
+        let binary = |hit: bool| (if hit { 1.0 } else { 0.0 }, hit);
+        match self {
+            TextMatcher::Regex(regex) => binary(regex.is_match(candidate)),
+            TextMatcher::Exact(target) => binary(normalized() == *target),
+            TextMatcher::Contains(target) => binary(normalized().contains(target.as_str())),
+            TextMatcher::Fuzzy {
+                target,
+                algorithm,
+                min_similarity,
+            } => {
+                let similarity =
+                    text_similarity::partial_similarity(&normalized(), target, *algorithm);
+                (similarity, similarity >= *min_similarity)
+            }
+        }
+    }
+}
+
+fn find_match(result: &OcrResult, query: &OcrTextQuery) -> CommandResult<OcrMatchEvaluation> {
+    let matcher = TextMatcher::new(query)?;
+    let min_confidence = query.min_confidence.clamp(0.0, 1.0);
     let mut matched: Option<OcrMatchCandidate> = None;
     let mut peak_confidence = 0.0_f32;
+    let mut peak_similarity = 0.0_f32;
     let mut peak_text = String::new();
     let mut debug_entries = Vec::<OcrDebugEntry>::new();
 
-    let regex = if use_regex {
-        let pattern = if case_sensitive {
-            target_text.to_string()
-        } else {
-            format!("(?i){}", target_text)
-        };
-        Some(Regex::new(&pattern).map_err(|error| {
-            CommandFlowError::Validation(format!("OCR 正则表达式无效：{}", error))
-        })?)
-    } else {
-        None
+    let entries: Vec<_> = match result {
+        OcrResult::Full(full) => full
+            .txts
+            .iter()
+            .zip(full.scores.iter().copied())
+            .zip(full.boxes.iter())
+            .map(|((text, score), quad)| (text, score, Some(quad)))
+            .collect(),
+        OcrResult::Rec(rec) => rec
+            .txts
+            .iter()
+            .zip(rec.scores.iter().copied())
+            .map(|(text, score)| (text, score, None))
+            .collect(),
+        _ => Vec::new(),
     };
 
-    match result {
-        OcrResult::Full(full) => {
-            for ((text, score), quad) in full
-                .txts
-                .iter()
-                .zip(full.scores.iter().copied())
-                .zip(full.boxes.iter())
-            {
-                if score > peak_confidence {
-                    peak_confidence = score;
-                    peak_text = text.clone();
-                }
-
-                let is_text_match = text_matches(
-                    text,
-                    target_text,
-                    match_mode,
-                    case_sensitive,
-                    regex.as_ref(),
-                );
-                let is_confidence_passed = score >= min_confidence;
-                let (x, y) = quad_center(quad);
-
-                debug_entries.push(OcrDebugEntry {
-                    text: text.to_string(),
-                    confidence: score,
-                    center_x: x,
-                    center_y: y,
-                    quad: Some(*quad),
-                    is_text_match,
-                    is_confidence_passed,
-                });
+    for (text, score, quad) in entries {
+        if score > peak_confidence {
+            peak_confidence = score;
+            peak_text = text.clone();
+        }
 
-                if !is_confidence_passed {
-                    continue;
-                }
-
-                if !is_text_match {
-                    continue;
-                }
-
-                let candidate = OcrMatchCandidate {
-                    x,
-                    y,
-                    text: text.to_string(),
-                    confidence: score,
-                };
-
-                let replace = matched
-                    .as_ref()
-                    .map(|current| candidate.confidence > current.confidence)
-                    .unwrap_or(true);
-                if replace {
-                    matched = Some(candidate);
-                }
-            }
+        let (similarity, is_text_match) = matcher.score(text, query);
+        peak_similarity = peak_similarity.max(similarity);
+        let is_confidence_passed = score >= min_confidence;
+        let (x, y) = quad.map(quad_center).unwrap_or((-1, -1));
+
+        debug_entries.push(OcrDebugEntry {
+            text: text.to_string(),
+            confidence: score,
+            center_x: x,
+            center_y: y,
+            quad: quad.copied(),
+            similarity,
+            is_text_match,
+            is_confidence_passed,
+        });
+
+        if !is_confidence_passed || !is_text_match {
+            continue;
         }
-        OcrResult::Rec(rec) => {
-            for (text, score) in rec.txts.iter().zip(rec.scores.iter().copied()) {
-                if score > peak_confidence {
-                    peak_confidence = score;
-                    peak_text = text.clone();
-                }
-
-                let is_text_match = text_matches(
-                    text,
-                    target_text,
-                    match_mode,
-                    case_sensitive,
-                    regex.as_ref(),
-                );
-                let is_confidence_passed = score >= min_confidence;
-                debug_entries.push(OcrDebugEntry {
-                    text: text.to_string(),
-                    confidence: score,
-                    center_x: -1,
-                    center_y: -1,
-                    quad: None,
-                    is_text_match,
-                    is_confidence_passed,
-                });
 
-                if !is_confidence_passed {
-                    continue;
-                }
-
-                if !is_text_match {
-                    continue;
-                }
-
-                let candidate = OcrMatchCandidate {
-                    x: -1,
-                    y: -1,
-                    text: text.to_string(),
-                    confidence: score,
-                };
-                let replace = matched
-                    .as_ref()
-                    .map(|current| candidate.confidence > current.confidence)
-                    .unwrap_or(true);
-                if replace {
-                    matched = Some(candidate);
-                }
-            }
+        let candidate = OcrMatchCandidate {
+            x,
+            y,
+            has_position: quad.is_some(),
+            text: text.to_string(),
+            confidence: score,
+            similarity,
+        };
+
+        // 先比相似度，再比置信度
+        let replace = matched
+            .as_ref()
+            .map(|current| {
+                (candidate.similarity, candidate.confidence)
+                    > (current.similarity, current.confidence)
+            })
+            .unwrap_or(true);
+        if replace {
+            matched = Some(candidate);
         }
-        _ => {}
     }
 
     Ok(OcrMatchEvaluation {
         matched,
         peak_text,
         peak_confidence,
+        peak_similarity,
         debug_entries,
     })
 }
 
-fn text_matches(
-    candidate: &str,
-    target: &str,
-    match_mode: &str,
-    case_sensitive: bool,
-    regex: Option<&Regex>,
-) -> bool {
-    if let Some(regex) = regex {
-        return regex.is_match(candidate);
-    }
-
-    let (left, right) = if case_sensitive {
-        (candidate.to_string(), target.to_string())
-    } else {
-        (candidate.to_lowercase(), target.to_lowercase())
-    };
-
-    match match_mode.trim().to_lowercase().as_str() {
-        "exact" => left == right,
-        _ => left.contains(&right),
-    }
-}
-
 fn quad_center(quad: &[[f32; 2]; 4]) -> (i32, i32) {
     let mut sum_x = 0.0_f32;
     let mut sum_y = 0.0_f32;
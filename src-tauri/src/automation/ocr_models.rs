@@ -0,0 +1,294 @@
+use crate::error::{CommandFlowError, CommandResult};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+// 检测与方向分类模型与语言无关，放在 common 子目录；识别模型和字典按语言分目录
+const COMMON_DIR: &str = "common";
+const DET_FILE: &str = "det.onnx";
+const CLS_FILE: &str = "cls.onnx";
+const REC_FILE: &str = "rec.onnx";
+const DICT_FILE: &str = "dict.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OcrLanguage {
+    Chinese,
+    English,
+    Japanese,
+    Korean,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcrModelComponent {
+    Det,
+    Cls,
+    Rec,
+    Dict,
+}
+
+// 引擎缓存的键：同一组模型文件只初始化一次
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OcrModelSet {
+    // 未导入本地模型时沿用引擎内置的中文模型（需联网下载）
+    Builtin,
+    Local {
+        language: OcrLanguage,
+        det: PathBuf,
+        cls: PathBuf,
+        rec: PathBuf,
+        dict: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrModelEntry {
+    pub language: String,
+    pub label: String,
+    pub directory: String,
+    pub det: Option<String>,
+    pub cls: Option<String>,
+    pub rec: Option<String>,
+    pub dict: Option<String>,
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrModelImportResult {
+    pub language: String,
+    pub component: String,
+    pub path: String,
+}
+
+impl OcrLanguage {
+    pub const ALL: [OcrLanguage; 4] = [
+        OcrLanguage::Chinese,
+        OcrLanguage::English,
+        OcrLanguage::Japanese,
+        OcrLanguage::Korean,
+    ];
+
+    pub fn parse(value: &str) -> CommandResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "ch" | "zh" | "chinese" => Ok(OcrLanguage::Chinese),
+            "en" | "english" => Ok(OcrLanguage::English),
+            "ja" | "japan" | "japanese" => Ok(OcrLanguage::Japanese),
+            "ko" | "korean" => Ok(OcrLanguage::Korean),
+            other => Err(CommandFlowError::Validation(format!(
+                "unsupported OCR language '{}'",
+                other
+            ))),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            OcrLanguage::Chinese => "ch",
+            OcrLanguage::English => "en",
+            OcrLanguage::Japanese => "japan",
+            OcrLanguage::Korean => "korean",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OcrLanguage::Chinese => "中文",
+            OcrLanguage::English => "英文",
+            OcrLanguage::Japanese => "日文",
+            OcrLanguage::Korean => "韩文",
+        }
+    }
+}
+
+impl OcrModelComponent {
+    pub fn parse(value: &str) -> CommandResult<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "det" => Ok(OcrModelComponent::Det),
+            "cls" => Ok(OcrModelComponent::Cls),
+            "rec" => Ok(OcrModelComponent::Rec),
+            "dict" | "keys" => Ok(OcrModelComponent::Dict),
+            other => Err(CommandFlowError::Validation(format!(
+                "unsupported OCR model component '{}'",
+                other
+            ))),
+        }
+    }
+
+    // 按文件名推断组件类型，如 ch_PP-OCRv4_det_infer.onnx、japan_dict.txt
+    fn infer(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        if name.ends_with(".txt") {
+            return Some(OcrModelComponent::Dict);
+        }
+        if !name.ends_with(".onnx") {
+            return None;
+        }
+        if name.contains("det") {
+            Some(OcrModelComponent::Det)
+        } else if name.contains("cls") {
+            Some(OcrModelComponent::Cls)
+        } else if name.contains("rec") {
+            Some(OcrModelComponent::Rec)
+        } else {
+            None
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            OcrModelComponent::Det => "det",
+            OcrModelComponent::Cls => "cls",
+            OcrModelComponent::Rec => "rec",
+            OcrModelComponent::Dict => "dict",
+        }
+    }
+
+    fn target_path(&self, root: &Path, language: OcrLanguage) -> PathBuf {
+        match self {
+            OcrModelComponent::Det => root.join(COMMON_DIR).join(DET_FILE),
+            OcrModelComponent::Cls => root.join(COMMON_DIR).join(CLS_FILE),
+            OcrModelComponent::Rec => root.join(language.code()).join(REC_FILE),
+            OcrModelComponent::Dict => root.join(language.code()).join(DICT_FILE),
+        }
+    }
+}
+
+pub fn default_model_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(program_data) = std::env::var("PROGRAMDATA") {
+            return PathBuf::from(program_data)
+                .join("CommandFlow")
+                .join("ocr_models");
+        }
+
+        PathBuf::from(r"C:\ProgramData")
+            .join("CommandFlow")
+            .join("ocr_models")
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::env::temp_dir().join("commandflow_ocr_models")
+    }
+}
+
+pub fn model_dir_or_default(model_dir: &str) -> PathBuf {
+    if model_dir.trim().is_empty() {
+        default_model_dir()
+    } else {
+        PathBuf::from(model_dir.trim())
+    }
+}
+
+fn existing(path: PathBuf) -> Option<PathBuf> {
+    path.is_file().then_some(path)
+}
+
+// 依次为 det、cls、rec、dict
+fn local_paths(root: &Path, language: OcrLanguage) -> [Option<PathBuf>; 4] {
+    [
+        OcrModelComponent::Det,
+        OcrModelComponent::Cls,
+        OcrModelComponent::Rec,
+        OcrModelComponent::Dict,
+    ]
+    .map(|component| existing(component.target_path(root, language)))
+}
+
+pub fn resolve_model_set(model_dir: &str, language: &str) -> CommandResult<OcrModelSet> {
+    let language = OcrLanguage::parse(language)?;
+    let root = model_dir_or_default(model_dir);
+    let [det, cls, rec, dict] = local_paths(&root, language);
+
+    match (det, cls, rec, dict) {
+        (Some(det), Some(cls), Some(rec), Some(dict)) => Ok(OcrModelSet::Local {
+            language,
+            det,
+            cls,
+            rec,
+            dict,
+        }),
+        // 中文在本地模型缺失时保持原有的在线下载行为
+        _ if language == OcrLanguage::Chinese && model_dir.trim().is_empty() => {
+            Ok(OcrModelSet::Builtin)
+        }
+        (det, cls, rec, dict) => {
+            let missing = [("det", det), ("cls", cls), ("rec", rec), ("dict", dict)]
+                .into_iter()
+                .filter(|(_, path)| path.is_none())
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(CommandFlowError::Automation(format!(
+                "未找到{} OCR 模型文件（缺少 {}），请先导入到 {}",
+                language.label(),
+                missing,
+                root.display()
+            )))
+        }
+    }
+}
+
+pub fn list_models(model_dir: &str) -> Vec<OcrModelEntry> {
+    let root = model_dir_or_default(model_dir);
+    let display = |path: &Option<PathBuf>| path.as_ref().map(|path| path.display().to_string());
+
+    OcrLanguage::ALL
+        .iter()
+        .map(|language| {
+            let [det, cls, rec, dict] = local_paths(&root, *language);
+            OcrModelEntry {
+                language: language.code().to_string(),
+                label: language.label().to_string(),
+                directory: root.join(language.code()).display().to_string(),
+                ready: det.is_some() && cls.is_some() && rec.is_some() && dict.is_some(),
+                det: display(&det),
+                cls: display(&cls),
+                rec: display(&rec),
+                dict: display(&dict),
+            }
+        })
+        .collect()
+}
+
+pub fn import_model(
+    source_path: &str,
+    language: &str,
+    component: &str,
+    model_dir: &str,
+) -> CommandResult<OcrModelImportResult> {
+    let source = PathBuf::from(source_path.trim());
+    if !source.is_file() {
+        return Err(CommandFlowError::Validation(format!(
+            "OCR model file '{}' does not exist",
+            source.display()
+        )));
+    }
+
+    let language = OcrLanguage::parse(language)?;
+    let component = if component.trim().is_empty() {
+        OcrModelComponent::infer(&source).ok_or_else(|| {
+            CommandFlowError::Validation(format!(
+                "unable to infer OCR model component from '{}', specify det, cls, rec or dict",
+                source.display()
+            ))
+        })?
+    } else {
+        OcrModelComponent::parse(component)?
+    };
+
+    let target = component.target_path(&model_dir_or_default(model_dir), language);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| CommandFlowError::Io(format!("{}: {}", parent.display(), error)))?;
+    }
+    std::fs::copy(&source, &target)
+        .map_err(|error| CommandFlowError::Io(format!("{}: {}", target.display(), error)))?;
+
+    Ok(OcrModelImportResult {
+        language: language.code().to_string(),
+        component: component.name().to_string(),
+        path: target.display().to_string(),
+    })
+}
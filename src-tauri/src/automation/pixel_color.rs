@@ -0,0 +1,164 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RgbColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl RgbColor {
+    pub fn hex(&self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    // 各通道差值的最大值，0 表示完全一致
+    pub fn distance(&self, other: &RgbColor) -> u8 {
+        self.r
+            .abs_diff(other.r)
+            .max(self.g.abs_diff(other.g))
+            .max(self.b.abs_diff(other.b))
+    }
+}
+
+pub fn parse_hex_color(value: &str) -> Option<RgbColor> {
+    let trimmed = value.trim();
+    let hex = trimmed.strip_prefix('#').unwrap_or(trimmed);
+    // from_str_radix 接受前导 '+'，且按字节切片遇到多字节字符会越界，先逐字符校验
+    if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    let expanded = match hex.len() {
+        3 => hex.chars().flat_map(|ch| [ch, ch]).collect::<String>(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    let channel = |index: usize| u8::from_str_radix(&expanded[index..index + 2], 16).ok();
+    Some(RgbColor {
+        r: channel(0)?,
+        g: channel(2)?,
+        b: channel(4)?,
+    })
+}
+
+// 对交错存储的像素缓冲区（RGB 或 RGBA）在给定区域内求平均色
+pub fn average_color(
+    data: &[u8],
+    channels: usize,
+    width: u32,
+    height: u32,
+    rect: (u32, u32, u32, u32),
+) -> Option<RgbColor> {
+    if channels < 3 {
+        return None;
+    }
+    let (left, top, rect_width, rect_height) = rect;
+    let right = left.saturating_add(rect_width).min(width);
+    let bottom = top.saturating_add(rect_height).min(height);
+    if left >= right || top >= bottom {
+        return None;
+    }
+
+    let mut sums = [0_u64; 3];
+    let mut count = 0_u64;
+    for y in top..bottom {
+        for x in left..right {
+            let offset = (y as usize * width as usize + x as usize) * channels;
+            let pixel = data.get(offset..offset + 3)?;
+            sums[0] += u64::from(pixel[0]);
+            sums[1] += u64::from(pixel[1]);
+            sums[2] += u64::from(pixel[2]);
+            count += 1;
+        }
+    }
+
+    let average = |sum: u64| ((sum + count / 2) / count) as u8;
+    Some(RgbColor {
+        r: average(sums[0]),
+        g: average(sums[1]),
+        b: average(sums[2]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{average_color, parse_hex_color, RgbColor};
+
+    fn rgb(r: u8, g: u8, b: u8) -> RgbColor {
+        RgbColor { r, g, b }
+    }
+
+    #[test]
+    fn parses_short_and_long_hex() {
+        let cases: &[(&str, Option<RgbColor>)] = &[
+            ("#FF8000", Some(rgb(255, 128, 0))),
+            ("ff8000", Some(rgb(255, 128, 0))),
+            ("  #0a0B0c ", Some(rgb(10, 11, 12))),
+            ("#F80", Some(rgb(255, 136, 0))),
+            ("abc", Some(rgb(0xAA, 0xBB, 0xCC))),
+            ("#GG0000", None),
+            ("#12345", None),
+            ("#1234567", None),
+            ("", None),
+            ("#", None),
+            ("+1+2+3", None),
+            ("aéaaa", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_hex_color(input), *expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        assert_eq!(rgb(255, 128, 0).hex(), "#FF8000");
+        assert_eq!(parse_hex_color(&rgb(1, 2, 3).hex()), Some(rgb(1, 2, 3)));
+    }
+
+    #[test]
+    fn distance_is_max_channel_difference() {
+        assert_eq!(rgb(10, 20, 30).distance(&rgb(10, 20, 30)), 0);
+        assert_eq!(rgb(10, 20, 30).distance(&rgb(15, 5, 31)), 15);
+        assert_eq!(rgb(0, 0, 255).distance(&rgb(255, 0, 0)), 255);
+        assert_eq!(rgb(200, 0, 0).distance(&rgb(0, 0, 0)), 200);
+    }
+
+    #[test]
+    fn averages_rgba_region_with_rounding() {
+        // 2x2 RGBA：左列 (0,0,0)，右列 (255,101,3)
+        let data = [
+            0, 0, 0, 255, 255, 101, 3, 255, //
+            0, 0, 0, 255, 255, 101, 3, 255,
+        ];
+        assert_eq!(
+            average_color(&data, 4, 2, 2, (0, 0, 2, 2)),
+            Some(rgb(128, 51, 2))
+        );
+        assert_eq!(
+            average_color(&data, 4, 2, 2, (1, 0, 1, 2)),
+            Some(rgb(255, 101, 3))
+        );
+    }
+
+    #[test]
+    fn clips_region_to_buffer_and_supports_rgb() {
+        let data = [10, 20, 30, 50, 60, 70, 90, 100, 110];
+        assert_eq!(
+            average_color(&data, 3, 3, 1, (1, 0, 100, 100)),
+            Some(rgb(70, 80, 90))
+        );
+        assert_eq!(
+            average_color(&data, 3, 3, 1, (2, 0, u32::MAX, u32::MAX)),
+            Some(rgb(90, 100, 110))
+        );
+    }
+
+    #[test]
+    fn rejects_unusable_inputs() {
+        let data = [10_u8; 16];
+        assert_eq!(average_color(&data, 2, 2, 2, (0, 0, 2, 2)), None);
+        assert_eq!(average_color(&data, 4, 2, 2, (2, 0, 1, 1)), None);
+        assert_eq!(average_color(&data, 4, 2, 2, (0, 2, 1, 1)), None);
+        assert_eq!(average_color(&data, 4, 2, 2, (0, 0, 0, 1)), None);
+        // 缓冲区比声明的尺寸短
+        assert_eq!(average_color(&data[..8], 4, 2, 2, (0, 0, 2, 2)), None);
+    }
+}
@@ -0,0 +1,422 @@
+use crate::error::CommandFlowError;
+use crate::workflow::node::{NodeKind, WorkflowNode};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+pub const REDACTED_TEXT: &str = "******";
+pub const SECRET_VALUE_TYPE: &str = "secret";
+
+// 过短的密钥值若参与全文替换会误伤普通日志，仅按变量名/参数名遮蔽
+const MIN_SECRET_TEXT_LEN: usize = 4;
+
+const SENSITIVE_KEY_MARKERS: &[&str] = &[
+    "apikey",
+    "password",
+    "passwd",
+    "passphrase",
+    "secret",
+    "accesstoken",
+    "authtoken",
+];
+
+// 敏感参数规则只在后端维护，前端通过 get_sensitive_param_rules 读取后做提示
+const SENSITIVE_NODE_PARAMS: &[(NodeKind, &[&str])] = &[
+    (NodeKind::KeyboardOperation, &["text"]),
+    (NodeKind::KeyboardInput, &["text"]),
+    (NodeKind::ClipboardWrite, &["inputText", "imageData"]),
+];
+
+const SECRET_VALUE_PARAMS: &[&str] = &["value", "valueString"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensitiveNodeParams {
+    pub kind: NodeKind,
+    pub params: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensitiveParamRules {
+    pub key_markers: Vec<String>,
+    pub node_params: Vec<SensitiveNodeParams>,
+    pub secret_value_type: String,
+    pub secret_value_params: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct RedactionState {
+    secret_values: Vec<String>,
+    secret_variables: HashSet<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    state: Arc<Mutex<RedactionState>>,
+}
+
+pub fn is_sensitive_param(node: &WorkflowNode, key: &str) -> bool {
+    let lowered = key.to_ascii_lowercase();
+    if SENSITIVE_KEY_MARKERS
+        .iter()
+        .any(|marker| lowered.contains(marker))
+    {
+        return true;
+    }
+
+    if SENSITIVE_NODE_PARAMS
+        .iter()
+        .any(|(kind, keys)| *kind == node.kind && keys.contains(&key))
+    {
+        return true;
+    }
+
+    matches!(
+        node.kind,
+        NodeKind::VarDefine | NodeKind::VarSet | NodeKind::ConstValue
+    ) && is_secret_typed_node(node)
+        && SECRET_VALUE_PARAMS.contains(&key)
+}
+
+pub fn sensitive_param_rules() -> SensitiveParamRules {
+    let to_strings =
+        |items: &[&str]| -> Vec<String> { items.iter().map(|item| item.to_string()).collect() };
+    SensitiveParamRules {
+        key_markers: to_strings(SENSITIVE_KEY_MARKERS),
+        node_params: SENSITIVE_NODE_PARAMS
+            .iter()
+            .map(|(kind, keys)| SensitiveNodeParams {
+                kind: kind.clone(),
+                params: to_strings(keys),
+            })
+            .collect(),
+        secret_value_type: SECRET_VALUE_TYPE.to_string(),
+        secret_value_params: to_strings(SECRET_VALUE_PARAMS),
+    }
+}
+
+pub fn is_sensitive_output(node: &WorkflowNode, handle: &str) -> bool {
+    match node.kind {
+        NodeKind::ClipboardRead => matches!(handle, "text" | "image" | "content"),
+        NodeKind::VarDefine | NodeKind::VarSet | NodeKind::ConstValue => {
+            is_secret_typed_node(node) && handle == "value"
+        }
+        _ => false,
+    }
+}
+
+pub fn is_secret_typed_node(node: &WorkflowNode) -> bool {
+    node.params
+        .get("valueType")
+        .and_then(Value::as_str)
+        .is_some_and(|value_type| value_type.eq_ignore_ascii_case(SECRET_VALUE_TYPE))
+}
+
+impl Redactor {
+    pub fn register_value(&self, value: &str) {
+        if value.chars().count() < MIN_SECRET_TEXT_LEN {
+            return;
+        }
+
+        let mut state = self.lock();
+        if state.secret_values.iter().any(|item| item == value) {
+            return;
+        }
+        state.secret_values.push(value.to_string());
+        // 长值优先替换，避免短值先命中后残留长值片段
+        state
+            .secret_values
+            .sort_by_key(|item| std::cmp::Reverse(item.len()));
+    }
+
+    pub fn register_variable(&self, name: &str, value: &Value) {
+        self.register_variable_name(name);
+        self.register_json_value(value);
+    }
+
+    pub fn register_variable_name(&self, name: &str) {
+        if !name.trim().is_empty() {
+            self.lock().secret_variables.insert(name.to_string());
+        }
+    }
+
+    pub fn register_variable_value(&self, value: &Value) {
+        self.register_json_value(value);
+    }
+
+    pub fn is_secret_variable(&self, name: &str) -> bool {
+        self.lock().secret_variables.contains(name)
+    }
+
+    pub fn redact_text(&self, text: &str) -> String {
+        let state = self.lock();
+        redact_text_with(&state.secret_values, text)
+    }
+
+    pub fn redact_node(&self, node: &WorkflowNode) -> WorkflowNode {
+        let state = self.lock();
+        let mut redacted = node.clone();
+        for (key, value) in redacted.params.iter_mut() {
+            *value = if is_sensitive_param(node, key) {
+                mask_value(value)
+            } else {
+                redact_value_with(&state.secret_values, value)
+            };
+        }
+        redacted
+    }
+
+    pub fn redact_outputs(
+        &self,
+        node: &WorkflowNode,
+        outputs: &HashMap<String, Value>,
+    ) -> HashMap<String, Value> {
+        let reads_secret_variable = matches!(node.kind, NodeKind::VarGet)
+            && node
+                .params
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|name| self.is_secret_variable(name));
+
+        let state = self.lock();
+        outputs
+            .iter()
+            .map(|(handle, value)| {
+                let masked = if is_sensitive_output(node, handle)
+                    || (reads_secret_variable && handle == "value")
+                {
+                    mask_value(value)
+                } else {
+                    redact_value_with(&state.secret_values, value)
+                };
+                (handle.clone(), masked)
+            })
+            .collect()
+    }
+
+    pub fn redact_variables(&self, variables: &HashMap<String, Value>) -> HashMap<String, Value> {
+        let state = self.lock();
+        variables
+            .iter()
+            .map(|(name, value)| {
+                let masked = if state.secret_variables.contains(name) {
+                    mask_value(value)
+                } else {
+                    redact_value_with(&state.secret_values, value)
+                };
+                (name.clone(), masked)
+            })
+            .collect()
+    }
+
+    pub fn redact_error(&self, error: CommandFlowError) -> CommandFlowError {
+        match error {
+            CommandFlowError::Io(message) => CommandFlowError::Io(self.redact_text(&message)),
+            CommandFlowError::Validation(message) => {
+                CommandFlowError::Validation(self.redact_text(&message))
+            }
+            CommandFlowError::Automation(message) => {
+                CommandFlowError::Automation(self.redact_text(&message))
+            }
+            CommandFlowError::Canceled => CommandFlowError::Canceled,
+        }
+    }
+
+    fn register_json_value(&self, value: &Value) {
+        match value {
+            Value::String(text) => self.register_value(text),
+            Value::Array(items) => items.iter().for_each(|item| self.register_json_value(item)),
+            Value::Object(map) => map.values().for_each(|item| self.register_json_value(item)),
+            Value::Number(number) => self.register_value(&number.to_string()),
+            _ => {}
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RedactionState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn mask_value(value: &Value) -> Value {
+    match value {
+        Value::Null => Value::Null,
+        Value::String(text) if text.is_empty() => Value::String(String::new()),
+        _ => Value::String(REDACTED_TEXT.to_string()),
+    }
+}
+
+fn redact_text_with(secret_values: &[String], text: &str) -> String {
+    let mut output = text.to_string();
+    for secret in secret_values {
+        if output.contains(secret.as_str()) {
+            output = output.replace(secret.as_str(), REDACTED_TEXT);
+        }
+    }
+    output
+}
+
+fn redact_value_with(secret_values: &[String], value: &Value) -> Value {
+    if secret_values.is_empty() {
+        return value.clone();
+    }
+
+    match value {
+        Value::String(text) => Value::String(redact_text_with(secret_values, text)),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| redact_value_with(secret_values, item))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), redact_value_with(secret_values, item)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_sensitive_param, sensitive_param_rules, Redactor, REDACTED_TEXT};
+    use crate::error::CommandFlowError;
+    use crate::workflow::node::{NodeKind, WorkflowNode};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    fn node(kind: NodeKind, params: Value) -> WorkflowNode {
+        WorkflowNode {
+            id: "n1".to_string(),
+            label: "node".to_string(),
+            kind,
+            position_x: 0.0,
+            position_y: 0.0,
+            params: serde_json::from_value(params).expect("params object"),
+        }
+    }
+
+    #[test]
+    fn redacts_registered_values_in_text_longest_first() {
+        let redactor = Redactor::default();
+        redactor.register_value("hunter2");
+        redactor.register_value("hunter2-extended");
+        assert_eq!(
+            redactor.redact_text("a=hunter2-extended b=hunter2"),
+            format!("a={} b={}", REDACTED_TEXT, REDACTED_TEXT)
+        );
+    }
+
+    #[test]
+    fn ignores_values_shorter_than_minimum_length() {
+        let redactor = Redactor::default();
+        redactor.register_value("abc");
+        assert_eq!(redactor.redact_text("abc abcd"), "abc abcd");
+    }
+
+    #[test]
+    fn masks_secret_variables_by_name_and_value() {
+        let redactor = Redactor::default();
+        redactor.register_variable("token", &json!("s3cr3t-value"));
+        redactor.register_variable_name("clipboardImage");
+
+        let mut variables = HashMap::new();
+        variables.insert("token".to_string(), json!("s3cr3t-value"));
+        variables.insert(
+            "clipboardImage".to_string(),
+            json!("data:image/png;base64,AAAA"),
+        );
+        variables.insert("copy".to_string(), json!("prefix s3cr3t-value"));
+        variables.insert("empty".to_string(), Value::Null);
+
+        let redacted = redactor.redact_variables(&variables);
+        assert_eq!(redacted["token"], json!(REDACTED_TEXT));
+        assert_eq!(redacted["clipboardImage"], json!(REDACTED_TEXT));
+        assert_eq!(redacted["copy"], json!(format!("prefix {}", REDACTED_TEXT)));
+        assert_eq!(redacted["empty"], Value::Null);
+        assert!(redactor.is_secret_variable("clipboardImage"));
+    }
+
+    #[test]
+    fn masks_sensitive_params_and_nested_secret_values() {
+        let redactor = Redactor::default();
+        redactor.register_value("pa55word");
+        let input = node(
+            NodeKind::KeyboardOperation,
+            json!({
+                "text": "typed text",
+                "key": "Enter",
+                "extra": { "items": ["x pa55word y"] },
+            }),
+        );
+
+        let redacted = redactor.redact_node(&input);
+        assert_eq!(redacted.params["text"], json!(REDACTED_TEXT));
+        assert_eq!(redacted.params["key"], json!("Enter"));
+        assert_eq!(
+            redacted.params["extra"],
+            json!({ "items": [format!("x {} y", REDACTED_TEXT)] })
+        );
+    }
+
+    #[test]
+    fn masks_clipboard_outputs_and_secret_var_get() {
+        let redactor = Redactor::default();
+        let mut outputs = HashMap::new();
+        outputs.insert("text".to_string(), json!("copied password"));
+        outputs.insert("imageWidth".to_string(), json!(0));
+        let clipboard = node(NodeKind::ClipboardRead, json!({}));
+        let redacted = redactor.redact_outputs(&clipboard, &outputs);
+        assert_eq!(redacted["text"], json!(REDACTED_TEXT));
+        assert_eq!(redacted["imageWidth"], json!(0));
+
+        redactor.register_variable_name("apiToken");
+        let mut outputs = HashMap::new();
+        outputs.insert("value".to_string(), json!("tok"));
+        let var_get = node(NodeKind::VarGet, json!({ "name": "apiToken" }));
+        assert_eq!(
+            redactor.redact_outputs(&var_get, &outputs)["value"],
+            json!(REDACTED_TEXT)
+        );
+    }
+
+    #[test]
+    fn redacts_error_messages() {
+        let redactor = Redactor::default();
+        redactor.register_value("very-secret");
+        let error = redactor.redact_error(CommandFlowError::Automation(
+            "request failed with very-secret".to_string(),
+        ));
+        assert_eq!(
+            error.to_string(),
+            CommandFlowError::Automation(format!("request failed with {}", REDACTED_TEXT))
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn exported_rules_match_backend_sensitivity() {
+        let rules = serde_json::to_value(sensitive_param_rules()).expect("serialize rules");
+        let clipboard_write = rules["nodeParams"]
+            .as_array()
+            .expect("node params")
+            .iter()
+            .find(|entry| entry["kind"] == json!("clipboardWrite"))
+            .expect("clipboardWrite entry");
+        assert_eq!(clipboard_write["params"], json!(["inputText", "imageData"]));
+
+        let write = node(NodeKind::ClipboardWrite, json!({}));
+        assert!(is_sensitive_param(&write, "inputText"));
+        assert!(is_sensitive_param(&write, "llmApiKey"));
+        assert!(!is_sensitive_param(&write, "contentType"));
+
+        let secret = node(NodeKind::VarSet, json!({ "valueType": "secret" }));
+        let plain = node(NodeKind::VarSet, json!({ "valueType": "string" }));
+        assert!(is_sensitive_param(&secret, "valueString"));
+        assert!(!is_sensitive_param(&plain, "valueString"));
+    }
+}
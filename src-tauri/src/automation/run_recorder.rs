@@ -0,0 +1,515 @@
+use crate::automation::{mouse, screenshot};
+use crate::error::{CommandFlowError, CommandResult};
+use chrono::Local;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::codecs::png::{CompressionType, FilterType as PngFilter, PngEncoder};
+use image::imageops::{self, FilterType};
+use image::{Delay, ExtendedColorType, Frame, ImageEncoder, ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "windows")]
+use std::ptr;
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::Graphics::Gdi::{
+    CreateCompatibleDC, CreateDIBSection, CreateFontW, DeleteDC, DeleteObject, GdiFlush,
+    SelectObject, SetBkMode, SetTextColor, TextOutW, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
+    DIB_RGB_COLORS, RGBQUAD,
+};
+
+const CAPTION_HEIGHT: u32 = 24;
+const CAPTION_BACKGROUND: u8 = 0x20;
+const CURSOR_RADIUS: i32 = 9;
+const CURSOR_COLOR: Rgba<u8> = Rgba([255, 48, 48, 255]);
+const MAX_FPS: f32 = 10.0;
+const MAX_BUFFER_SECONDS: u32 = 600;
+const GIF_ENCODE_SPEED: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingFormat {
+    #[default]
+    Gif,
+    PngSequence,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingSaveMode {
+    #[default]
+    OnError,
+    Always,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRecordingOptions {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_fps")]
+    pub fps: f32,
+    #[serde(default = "default_buffer_seconds")]
+    pub buffer_seconds: u32,
+    #[serde(default)]
+    pub format: RecordingFormat,
+    #[serde(default)]
+    pub save_mode: RecordingSaveMode,
+    #[serde(default)]
+    pub output_dir: String,
+    #[serde(default = "default_max_width")]
+    pub max_width: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingOutput {
+    pub path: String,
+    pub frame_count: usize,
+}
+
+// 帧以 PNG 压缩后驻留内存，避免长缓冲占用过多内存
+struct RecordedFrame {
+    png: Vec<u8>,
+    captured_at: Instant,
+}
+
+pub struct RunRecorder {
+    run_id: String,
+    options: RunRecordingOptions,
+    stop: Arc<AtomicBool>,
+    current_label: Arc<Mutex<String>>,
+    frames: Arc<Mutex<VecDeque<RecordedFrame>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+fn default_fps() -> f32 {
+    2.0
+}
+
+fn default_buffer_seconds() -> u32 {
+    30
+}
+
+fn default_max_width() -> u32 {
+    960
+}
+
+pub fn default_output_dir() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(program_data) = std::env::var("PROGRAMDATA") {
+            return PathBuf::from(program_data)
+                .join("CommandFlow")
+                .join("recordings");
+        }
+
+        PathBuf::from(r"C:\ProgramData")
+            .join("CommandFlow")
+            .join("recordings")
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::env::temp_dir().join("commandflow_recordings")
+    }
+}
+
+impl RunRecorder {
+    pub fn start(run_id: &str, options: RunRecordingOptions) -> Self {
+        let fps = if options.fps.is_finite() {
+            options.fps.clamp(0.1, MAX_FPS)
+        } else {
+            default_fps()
+        };
+        let interval = Duration::from_secs_f32(1.0 / fps);
+        let capacity = ((fps * options.buffer_seconds.clamp(1, MAX_BUFFER_SECONDS) as f32).ceil()
+            as usize)
+            .max(1);
+        let max_width = options.max_width.max(160);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let current_label = Arc::new(Mutex::new(String::new()));
+        let frames = Arc::new(Mutex::new(VecDeque::with_capacity(capacity)));
+
+        let worker = {
+            let stop = Arc::clone(&stop);
+            let current_label = Arc::clone(&current_label);
+            let frames = Arc::clone(&frames);
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let tick = Instant::now();
+                    let label = current_label
+                        .lock()
+                        .map(|label| label.clone())
+                        .unwrap_or_default();
+                    // 单帧截图失败（如锁屏、UAC 桌面）时跳过该帧，录像不影响工作流执行
+                    if let Ok(png) = capture_annotated_frame(&label, max_width) {
+                        if let Ok(mut frames) = frames.lock() {
+                            while frames.len() >= capacity {
+                                frames.pop_front();
+                            }
+                            frames.push_back(RecordedFrame {
+                                png,
+                                captured_at: tick,
+                            });
+                        }
+                    }
+
+                    let elapsed = tick.elapsed();
+                    if elapsed < interval {
+                        thread::sleep(interval - elapsed);
+                    }
+                }
+            })
+        };
+
+        Self {
+            run_id: run_id.to_string(),
+            options,
+            stop,
+            current_label,
+            frames,
+            worker: Some(worker),
+        }
+    }
+
+    pub fn set_node_label(&self, label: &str) {
+        if let Ok(mut current) = self.current_label.lock() {
+            *current = label.to_string();
+        }
+    }
+
+    // 停止采样；按保存策略决定是否落盘，未保存时返回 None
+    pub fn finish(mut self, failed: bool) -> CommandResult<Option<RecordingOutput>> {
+        self.stop_worker();
+
+        if !failed && self.options.save_mode == RecordingSaveMode::OnError {
+            return Ok(None);
+        }
+
+        let frames = self
+            .frames
+            .lock()
+            .map(|mut frames| frames.drain(..).collect::<Vec<_>>())
+            .map_err(|_| CommandFlowError::Automation("recording buffer poisoned".to_string()))?;
+        if frames.is_empty() {
+            return Ok(None);
+        }
+
+        let output_dir = if self.options.output_dir.trim().is_empty() {
+            default_output_dir()
+        } else {
+            PathBuf::from(self.options.output_dir.trim())
+        };
+        fs::create_dir_all(&output_dir).map_err(|error| {
+            CommandFlowError::Io(format!("{}: {}", output_dir.display(), error))
+        })?;
+
+        let file_stem = format!(
+            "{}-{}{}",
+            Local::now().format("%Y%m%d-%H%M%S"),
+            self.run_id,
+            if failed { "-failed" } else { "" }
+        );
+        let path = match self.options.format {
+            RecordingFormat::Gif => {
+                let path = output_dir.join(format!("{}.gif", file_stem));
+                write_gif(&path, &frames)?;
+                path
+            }
+            RecordingFormat::PngSequence => {
+                let dir = output_dir.join(file_stem);
+                write_png_sequence(&dir, &frames)?;
+                dir
+            }
+        };
+
+        Ok(Some(RecordingOutput {
+            path: path.display().to_string(),
+            frame_count: frames.len(),
+        }))
+    }
+
+    fn stop_worker(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for RunRecorder {
+    fn drop(&mut self) {
+        self.stop_worker();
+    }
+}
+
+fn write_gif(path: &Path, frames: &[RecordedFrame]) -> CommandResult<()> {
+    let file = fs::File::create(path)
+        .map_err(|error| CommandFlowError::Io(format!("{}: {}", path.display(), error)))?;
+    let mut encoder = GifEncoder::new_with_speed(file, GIF_ENCODE_SPEED);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+
+    for (index, frame) in frames.iter().enumerate() {
+        let image = decode_frame(frame)?;
+        // 帧间隔取真实采样间隔，最后一帧沿用前一帧的间隔
+        let delay = frames
+            .get(index + 1)
+            .map(|next| next.captured_at.duration_since(frame.captured_at))
+            .or_else(|| {
+                index.checked_sub(1).map(|previous| {
+                    frame
+                        .captured_at
+                        .duration_since(frames[previous].captured_at)
+                })
+            })
+            .unwrap_or(Duration::from_millis(500));
+        encoder
+            .encode_frame(Frame::from_parts(
+                image,
+                0,
+                0,
+                Delay::from_saturating_duration(delay),
+            ))
+            .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    }
+    Ok(())
+}
+
+fn write_png_sequence(dir: &Path, frames: &[RecordedFrame]) -> CommandResult<()> {
+    fs::create_dir_all(dir)
+        .map_err(|error| CommandFlowError::Io(format!("{}: {}", dir.display(), error)))?;
+    for (index, frame) in frames.iter().enumerate() {
+        let path = dir.join(format!("frame-{:04}.png", index + 1));
+        fs::write(&path, &frame.png)
+            .map_err(|error| CommandFlowError::Io(format!("{}: {}", path.display(), error)))?;
+    }
+    Ok(())
+}
+
+fn decode_frame(frame: &RecordedFrame) -> CommandResult<RgbaImage> {
+    image::load_from_memory_with_format(&frame.png, ImageFormat::Png)
+        .map(|image| image.to_rgba8())
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))
+}
+
+fn capture_annotated_frame(label: &str, max_width: u32) -> CommandResult<Vec<u8>> {
+    let capture = screenshot::capture_target_rgba(&screenshot::CaptureTarget::Primary)?;
+    let source = RgbaImage::from_raw(capture.width, capture.height, capture.rgba)
+        .ok_or_else(|| CommandFlowError::Automation("invalid rgba buffer size".to_string()))?;
+
+    let scale = if source.width() > max_width {
+        max_width as f32 / source.width() as f32
+    } else {
+        1.0
+    };
+    let frame = if scale < 1.0 {
+        let height = ((source.height() as f32 * scale).round() as u32).max(1);
+        imageops::resize(&source, max_width, height, FilterType::Triangle)
+    } else {
+        source
+    };
+
+    let cursor = mouse::cursor_position().ok();
+    let mut canvas = RgbaImage::from_pixel(
+        frame.width(),
+        frame.height() + CAPTION_HEIGHT,
+        Rgba([
+            CAPTION_BACKGROUND,
+            CAPTION_BACKGROUND,
+            CAPTION_BACKGROUND,
+            255,
+        ]),
+    );
+    imageops::replace(&mut canvas, &frame, 0, i64::from(CAPTION_HEIGHT));
+
+    if let Some((x, y)) = cursor {
+        let local_x = ((x - capture.origin.0) as f32 * scale).round() as i32;
+        let local_y =
+            ((y - capture.origin.1) as f32 * scale).round() as i32 + CAPTION_HEIGHT as i32;
+        draw_cursor_marker(&mut canvas, local_x, local_y);
+    }
+
+    let caption = format!(
+        "{}  {}  光标 {}",
+        Local::now().format("%H:%M:%S%.3f"),
+        if label.is_empty() { "-" } else { label },
+        cursor
+            .map(|(x, y)| format!("({}, {})", x, y))
+            .unwrap_or_else(|| "-".to_string())
+    );
+    if let Some(text) = render_caption_text(&caption, canvas.width(), CAPTION_HEIGHT) {
+        imageops::replace(&mut canvas, &text, 0, 0);
+    }
+
+    let mut png = Vec::new();
+    PngEncoder::new_with_quality(&mut png, CompressionType::Fast, PngFilter::Adaptive)
+        .write_image(
+            canvas.as_raw(),
+            canvas.width(),
+            canvas.height(),
+            ExtendedColorType::Rgba8,
+        )
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    Ok(png)
+}
+
+// 圆环加十字标出光标位置，超出画面的部分直接裁掉
+fn draw_cursor_marker(image: &mut RgbaImage, center_x: i32, center_y: i32) {
+    let (width, height) = (image.width() as i32, image.height() as i32);
+    let mut put = |x: i32, y: i32| {
+        if x >= 0 && y >= CAPTION_HEIGHT as i32 && x < width && y < height {
+            image.put_pixel(x as u32, y as u32, CURSOR_COLOR);
+        }
+    };
+
+    let outer = CURSOR_RADIUS * CURSOR_RADIUS;
+    let inner = (CURSOR_RADIUS - 2) * (CURSOR_RADIUS - 2);
+    for dy in -CURSOR_RADIUS..=CURSOR_RADIUS {
+        for dx in -CURSOR_RADIUS..=CURSOR_RADIUS {
+            let distance = dx * dx + dy * dy;
+            if distance <= outer && distance >= inner {
+                put(center_x + dx, center_y + dy);
+            }
+        }
+    }
+    for offset in -(CURSOR_RADIUS / 2)..=(CURSOR_RADIUS / 2) {
+        put(center_x + offset, center_y);
+        put(center_x, center_y + offset);
+    }
+}
+
+// 使用 GDI 绘制标题文字，节点名称通常包含中文，需要系统字体支持
+#[cfg(target_os = "windows")]
+fn render_caption_text(text: &str, width: u32, height: u32) -> Option<RgbaImage> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let dc = unsafe { CreateCompatibleDC(ptr::null_mut()) };
+    if dc.is_null() {
+        return None;
+    }
+
+    let mut bits = ptr::null_mut();
+    let bitmap_info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width as i32,
+            biHeight: -(height as i32),
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB,
+            biSizeImage: 0,
+            biXPelsPerMeter: 0,
+            biYPelsPerMeter: 0,
+            biClrUsed: 0,
+            biClrImportant: 0,
+        },
+        bmiColors: [RGBQUAD {
+            rgbBlue: 0,
+            rgbGreen: 0,
+            rgbRed: 0,
+            rgbReserved: 0,
+        }],
+    };
+
+    let bitmap = unsafe {
+        CreateDIBSection(
+            dc,
+            &bitmap_info,
+            DIB_RGB_COLORS,
+            &mut bits,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if bitmap.is_null() || bits.is_null() {
+        unsafe {
+            DeleteDC(dc);
+        }
+        return None;
+    }
+
+    let byte_len = (width as usize) * (height as usize) * 4;
+    unsafe {
+        std::slice::from_raw_parts_mut(bits as *mut u8, byte_len).fill(CAPTION_BACKGROUND);
+    }
+
+    let face: Vec<u16> = "Microsoft YaHei UI"
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    // 字符集/精度参数均取默认值(0/1)，4 = ANTIALIASED_QUALITY
+    let font = unsafe {
+        CreateFontW(
+            -((height as i32) - 8),
+            0,
+            0,
+            0,
+            400,
+            0,
+            0,
+            0,
+            1,
+            0,
+            0,
+            4,
+            0,
+            face.as_ptr(),
+        )
+    };
+    let previous_bitmap = unsafe { SelectObject(dc, bitmap as _) };
+    let previous_font = if font.is_null() {
+        ptr::null_mut()
+    } else {
+        unsafe { SelectObject(dc, font as _) }
+    };
+
+    let wide: Vec<u16> = text.encode_utf16().collect();
+    let drawn = unsafe {
+        // 1 = TRANSPARENT，保留预先填充的背景色
+        SetBkMode(dc, 1);
+        SetTextColor(dc, 0x00FFFFFF);
+        let drawn = TextOutW(dc, 6, 3, wide.as_ptr(), wide.len() as i32);
+        GdiFlush();
+        drawn
+    };
+
+    let bgra = unsafe { std::slice::from_raw_parts(bits as *const u8, byte_len).to_vec() };
+
+    unsafe {
+        if !previous_font.is_null() {
+            SelectObject(dc, previous_font);
+        }
+        SelectObject(dc, previous_bitmap);
+        if !font.is_null() {
+            DeleteObject(font as _);
+        }
+        DeleteObject(bitmap as _);
+        DeleteDC(dc);
+    }
+
+    if drawn == 0 {
+        return None;
+    }
+
+    let rgba = bgra
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], 255])
+        .collect::<Vec<u8>>();
+    RgbaImage::from_raw(width, height, rgba)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn render_caption_text(_text: &str, _width: u32, _height: u32) -> Option<RgbaImage> {
+    None
+}
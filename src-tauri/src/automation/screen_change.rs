@@ -0,0 +1,141 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Copy)]
+pub struct FrameDiff {
+    // 变化像素占比，0~1
+    pub changed_ratio: f32,
+    // 变化区域外接矩形 (left, top, width, height)，相对比较区域左上角
+    pub bounding_box: Option<(u32, u32, u32, u32)>,
+}
+
+// 稳定模式计时：画面变化时重新计时。帧流只在画面变化时推送新帧，
+// 静止画面收不到帧，因此调用方需在每次轮询时判断是否已稳定，而不是只在收到新帧时判断
+#[derive(Debug, Clone, Copy)]
+pub struct StabilityTimer {
+    since: Instant,
+}
+
+impl StabilityTimer {
+    pub fn new(now: Instant) -> Self {
+        Self { since: now }
+    }
+
+    pub fn restart(&mut self, now: Instant) {
+        self.since = now;
+    }
+
+    pub fn is_stable(&self, now: Instant, duration: Duration) -> bool {
+        now.saturating_duration_since(self.since) >= duration
+    }
+}
+
+pub fn diff_gray(
+    previous: &[u8],
+    current: &[u8],
+    width: u32,
+    height: u32,
+    pixel_threshold: u8,
+) -> FrameDiff {
+    let total = width as usize * height as usize;
+    if total == 0 || previous.len() != total || current.len() != total {
+        return FrameDiff {
+            changed_ratio: 1.0,
+            bounding_box: Some((0, 0, width, height)),
+        };
+    }
+
+    let mut changed = 0_usize;
+    let mut min_x = u32::MAX;
+    let mut min_y = u32::MAX;
+    let mut max_x = 0_u32;
+    let mut max_y = 0_u32;
+    for (index, (before, after)) in previous.iter().zip(current.iter()).enumerate() {
+        if before.abs_diff(*after) <= pixel_threshold {
+            continue;
+        }
+        let x = (index % width as usize) as u32;
+        let y = (index / width as usize) as u32;
+        changed += 1;
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+
+    FrameDiff {
+        changed_ratio: changed as f32 / total as f32,
+        bounding_box: (changed > 0).then(|| (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_gray, StabilityTimer};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[test]
+    fn pixel_threshold_is_inclusive() {
+        let previous = [100_u8, 100, 100, 100];
+        let current = [110_u8, 111, 100, 89];
+        let diff = diff_gray(&previous, &current, 2, 2, 10);
+        assert_eq!(diff.changed_ratio, 0.5);
+        assert_eq!(diff.bounding_box, Some((1, 0, 1, 2)));
+
+        let diff = diff_gray(&previous, &current, 2, 2, 11);
+        assert_eq!(diff.changed_ratio, 0.0);
+        assert!(diff.bounding_box.is_none());
+    }
+
+    #[test]
+    fn bounding_box_covers_changed_pixels_only() {
+        let previous = vec![0_u8; 6 * 4];
+        let mut current = previous.clone();
+        current[4 + 6] = 255;
+        current[1 + 6 * 3] = 255;
+        current[2 + 6 * 2] = 255;
+
+        let diff = diff_gray(&previous, &current, 6, 4, 0);
+        assert_eq!(diff.bounding_box, Some((1, 1, 4, 3)));
+        assert_eq!(diff.changed_ratio, 3.0 / 24.0);
+    }
+
+    #[test]
+    fn mismatched_buffers_count_as_fully_changed() {
+        let cases: &[(&[u8], &[u8], u32, u32)] = &[
+            (&[0, 0, 0], &[0, 0, 0, 0], 2, 2),
+            (&[0, 0, 0, 0], &[0, 0], 2, 2),
+            (&[], &[], 0, 3),
+        ];
+        for (previous, current, width, height) in cases {
+            let diff = diff_gray(previous, current, *width, *height, 0);
+            assert_eq!(diff.changed_ratio, 1.0);
+            assert_eq!(diff.bounding_box, Some((0, 0, *width, *height)));
+        }
+    }
+
+    #[test]
+    fn stability_is_reached_without_new_frames() {
+        let started = Instant::now();
+        let timer = StabilityTimer::new(started);
+        let duration = Duration::from_millis(500);
+
+        // 静止画面期间没有新帧，也没有 restart 调用，只随时间推移判定为稳定
+        assert!(!timer.is_stable(started + Duration::from_millis(499), duration));
+        assert!(timer.is_stable(started + duration, duration));
+    }
+
+    #[test]
+    fn change_restarts_stability_window() {
+        let started = Instant::now();
+        let mut timer = StabilityTimer::new(started);
+        let duration = Duration::from_millis(500);
+
+        timer.restart(started + Duration::from_millis(400));
+        assert!(!timer.is_stable(started + Duration::from_millis(600), duration));
+        assert!(timer.is_stable(started + Duration::from_millis(900), duration));
+        // 时钟早于计时起点时视为尚未稳定
+        assert!(!timer.is_stable(started, duration));
+    }
+}
@@ -1,6 +1,8 @@
+use crate::automation::window::{self, WindowMatchQuery};
 use crate::error::{CommandFlowError, CommandResult};
 use base64::{engine::general_purpose, Engine as _};
-use image::{GrayImage, ImageBuffer, Luma, Rgba};
+use image::{DynamicImage, GrayImage, ImageBuffer, Luma, RgbImage, Rgba, RgbaImage};
+use serde::Serialize;
 use std::fs;
 use std::io::Cursor;
 use std::path::Path;
@@ -8,7 +10,7 @@ use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::sync::{Mutex, OnceLock};
 use std::thread;
 use std::time::Duration;
-use xcap::{Frame, Monitor, VideoRecorder};
+use xcap::{Frame, Monitor, VideoRecorder, Window};
 
 const STREAM_START_RETRY: usize = 5;
 const STREAM_START_RETRY_DELAY: Duration = Duration::from_millis(250);
@@ -19,6 +21,52 @@ pub struct PrimaryFrameStream {
     receiver: Receiver<Frame>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureTarget {
+    Primary,
+    // 从 1 开始，与系统显示设置中的编号习惯一致
+    Index(usize),
+    Name(String),
+    // 包含该虚拟屏幕坐标的显示器，用于按窗口定位
+    Point(i32, i32),
+    VirtualDesktop,
+    // 直接截取窗口自身画面，被遮挡部分也能截到
+    Window(WindowMatchQuery),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub index: usize,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+    pub is_primary: bool,
+}
+
+// origin 为图像左上角在虚拟屏幕中的坐标
+pub struct ScreenCapture {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub origin: (i32, i32),
+}
+
+enum FrameSource {
+    Recorder(PrimaryFrameStream),
+    // 虚拟桌面与窗口无法用单个显示器录屏实例，每次接收时重新截图；窗口可能移动，原点随之更新
+    Polling,
+}
+
+struct ActiveFrameStream {
+    target: CaptureTarget,
+    origin: (i32, i32),
+    source: FrameSource,
+}
+
 impl PrimaryFrameStream {
     pub fn recv_gray_timeout(&mut self, timeout: Duration) -> CommandResult<Option<GrayImage>> {
         match self.receiver.recv_timeout(timeout) {
@@ -29,6 +77,16 @@ impl PrimaryFrameStream {
             )),
         }
     }
+
+    pub fn recv_rgb_timeout(&mut self, timeout: Duration) -> CommandResult<Option<RgbImage>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(frame) => Ok(Some(frame_to_rgb_image(&frame)?)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(CommandFlowError::Automation(
+                "xcap recv frame failed: stream disconnected".to_string(),
+            )),
+        }
+    }
 }
 
 impl Drop for PrimaryFrameStream {
@@ -38,8 +96,8 @@ impl Drop for PrimaryFrameStream {
     }
 }
 
-fn primary_stream_store() -> &'static Mutex<Option<PrimaryFrameStream>> {
-    static PRIMARY_STREAM: OnceLock<Mutex<Option<PrimaryFrameStream>>> = OnceLock::new();
+fn primary_stream_store() -> &'static Mutex<Option<ActiveFrameStream>> {
+    static PRIMARY_STREAM: OnceLock<Mutex<Option<ActiveFrameStream>>> = OnceLock::new();
     PRIMARY_STREAM.get_or_init(|| Mutex::new(None))
 }
 
@@ -69,6 +127,185 @@ pub fn capture_fullscreen_rgba() -> CommandResult<(Vec<u8>, u32, u32)> {
     capture_primary_rgba()
 }
 
+pub fn list_monitors() -> CommandResult<Vec<MonitorInfo>> {
+    let monitors =
+        Monitor::all().map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| MonitorInfo {
+            index: index + 1,
+            name: monitor.name().unwrap_or_default(),
+            x: monitor.x().unwrap_or_default(),
+            y: monitor.y().unwrap_or_default(),
+            width: monitor.width().unwrap_or_default(),
+            height: monitor.height().unwrap_or_default(),
+            scale_factor: monitor.scale_factor().unwrap_or(1.0),
+            is_primary: monitor.is_primary().unwrap_or(false),
+        })
+        .collect())
+}
+
+fn resolve_monitor(target: &CaptureTarget) -> CommandResult<Monitor> {
+    let all = || Monitor::all().map_err(|error| CommandFlowError::Automation(error.to_string()));
+    match target {
+        CaptureTarget::Primary | CaptureTarget::VirtualDesktop => primary_monitor(),
+        CaptureTarget::Window(_) => Err(CommandFlowError::Validation(
+            "window capture target does not map to a single monitor".to_string(),
+        )),
+        CaptureTarget::Index(index) => {
+            let monitors = all()?;
+            let count = monitors.len();
+            index
+                .checked_sub(1)
+                .and_then(|position| monitors.into_iter().nth(position))
+                .ok_or_else(|| {
+                    CommandFlowError::Validation(format!(
+                        "monitor index {} is out of range (found {} monitors)",
+                        index, count
+                    ))
+                })
+        }
+        CaptureTarget::Name(name) => all()?
+            .into_iter()
+            .find(|monitor| {
+                monitor
+                    .name()
+                    .map(|value| value.eq_ignore_ascii_case(name.trim()))
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| {
+                CommandFlowError::Validation(format!("monitor '{}' was not found", name))
+            }),
+        CaptureTarget::Point(x, y) => Monitor::from_point(*x, *y).map_err(|error| {
+            CommandFlowError::Automation(format!(
+                "no monitor contains point ({}, {}): {}",
+                x, y, error
+            ))
+        }),
+    }
+}
+
+fn monitor_origin(monitor: &Monitor) -> (i32, i32) {
+    (
+        monitor.x().unwrap_or_default(),
+        monitor.y().unwrap_or_default(),
+    )
+}
+
+fn virtual_desktop_origin() -> CommandResult<(i32, i32)> {
+    let monitors =
+        Monitor::all().map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    let origin = monitors
+        .iter()
+        .map(monitor_origin)
+        .fold(None, |acc, (x, y)| {
+            Some(match acc {
+                Some((min_x, min_y)) => (x.min(min_x), y.min(min_y)),
+                None => (x, y),
+            })
+        });
+    origin.ok_or_else(|| CommandFlowError::Automation("no monitor found".to_string()))
+}
+
+// 将所有显示器按虚拟屏幕坐标拼接为一张图
+fn capture_virtual_desktop() -> CommandResult<ScreenCapture> {
+    let monitors =
+        Monitor::all().map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    let mut parts = Vec::with_capacity(monitors.len());
+    for monitor in &monitors {
+        let image = monitor
+            .capture_image()
+            .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+        parts.push((monitor_origin(monitor), image));
+    }
+
+    let left = parts.iter().map(|((x, _), _)| *x).min().unwrap_or(0);
+    let top = parts.iter().map(|((_, y), _)| *y).min().unwrap_or(0);
+    let right = parts
+        .iter()
+        .map(|((x, _), image)| i64::from(*x) + i64::from(image.width()))
+        .max()
+        .unwrap_or(0);
+    let bottom = parts
+        .iter()
+        .map(|((_, y), image)| i64::from(*y) + i64::from(image.height()))
+        .max()
+        .unwrap_or(0);
+    let width = (right - i64::from(left)).max(0) as u32;
+    let height = (bottom - i64::from(top)).max(0) as u32;
+    if width == 0 || height == 0 {
+        return Err(CommandFlowError::Automation("no monitor found".to_string()));
+    }
+
+    let mut canvas = RgbaImage::new(width, height);
+    for ((x, y), image) in &parts {
+        image::imageops::replace(
+            &mut canvas,
+            image,
+            i64::from(*x - left),
+            i64::from(*y - top),
+        );
+    }
+
+    Ok(ScreenCapture {
+        rgba: canvas.into_raw(),
+        width,
+        height,
+        origin: (left, top),
+    })
+}
+
+fn capture_window(query: &WindowMatchQuery) -> CommandResult<ScreenCapture> {
+    let id = window::find_window_id(query)?;
+    let windows = Window::all().map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    let target = windows
+        .into_iter()
+        .find(|item| item.id().map(|value| value == id).unwrap_or(false))
+        .ok_or_else(|| {
+            CommandFlowError::Automation(format!("xcap cannot find window id {}", id))
+        })?;
+    let image = target.capture_image().map_err(|error| {
+        CommandFlowError::Automation(format!("xcap window capture failed: {}", error))
+    })?;
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Err(CommandFlowError::Automation(
+            "captured window image is empty".to_string(),
+        ));
+    }
+
+    Ok(ScreenCapture {
+        rgba: image.into_raw(),
+        width,
+        height,
+        origin: (
+            target.x().unwrap_or_default(),
+            target.y().unwrap_or_default(),
+        ),
+    })
+}
+
+pub fn capture_target_rgba(target: &CaptureTarget) -> CommandResult<ScreenCapture> {
+    match target {
+        CaptureTarget::VirtualDesktop => return capture_virtual_desktop(),
+        CaptureTarget::Window(query) => return capture_window(query),
+        _ => {}
+    }
+
+    let monitor = resolve_monitor(target)?;
+    let image = monitor
+        .capture_image()
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    let (width, height) = image.dimensions();
+    Ok(ScreenCapture {
+        rgba: image.into_raw(),
+        width,
+        height,
+        origin: monitor_origin(&monitor),
+    })
+}
+
 pub fn capture_region_rgba(
     start_x: u32,
     start_y: u32,
@@ -136,8 +373,11 @@ pub fn capture_fullscreen_gray() -> CommandResult<GrayImage> {
 }
 
 pub fn start_primary_frame_stream() -> CommandResult<PrimaryFrameStream> {
+    start_frame_stream(&monitor_candidates()?)
+}
+
+fn start_frame_stream(monitors: &[Monitor]) -> CommandResult<PrimaryFrameStream> {
     let mut last_error: Option<String> = None;
-    let monitors = monitor_candidates()?;
 
     if monitors.is_empty() {
         return Err(CommandFlowError::Automation(
@@ -146,7 +386,7 @@ pub fn start_primary_frame_stream() -> CommandResult<PrimaryFrameStream> {
     }
 
     for attempt in 1..=STREAM_START_RETRY {
-        for monitor in &monitors {
+        for monitor in monitors {
             let monitor_label = describe_monitor(monitor);
             let stream = monitor.video_recorder().map_err(|error| {
                 CommandFlowError::Automation(format!(
@@ -189,38 +429,119 @@ pub fn start_primary_frame_stream() -> CommandResult<PrimaryFrameStream> {
 }
 
 pub fn ensure_primary_frame_stream() -> CommandResult<()> {
+    ensure_frame_stream(&CaptureTarget::Primary).map(|_| ())
+}
+
+// 帧流同一时间只保留一个实例，截图目标变化时重建；返回帧左上角的虚拟屏幕坐标
+pub fn ensure_frame_stream(target: &CaptureTarget) -> CommandResult<(i32, i32)> {
     let store = primary_stream_store();
     let mut guard = store
         .lock()
         .map_err(|_| CommandFlowError::Automation("primary stream mutex poisoned".to_string()))?;
 
+    if guard
+        .as_ref()
+        .map(|active| active.target != *target)
+        .unwrap_or(false)
+    {
+        // Drop 中会停止录屏并等待释放
+        guard.take();
+    }
+
     if guard.is_none() {
-        let stream = start_primary_frame_stream()?;
-        *guard = Some(stream);
+        let (origin, source) = match target {
+            CaptureTarget::VirtualDesktop => (virtual_desktop_origin()?, FrameSource::Polling),
+            CaptureTarget::Window(query) => (capture_window(query)?.origin, FrameSource::Polling),
+            CaptureTarget::Primary => {
+                ((0, 0), FrameSource::Recorder(start_primary_frame_stream()?))
+            }
+            other => {
+                let monitor = resolve_monitor(other)?;
+                let origin = monitor_origin(&monitor);
+                (
+                    origin,
+                    FrameSource::Recorder(start_frame_stream(&[monitor])?),
+                )
+            }
+        };
+        *guard = Some(ActiveFrameStream {
+            target: target.clone(),
+            origin,
+            source,
+        });
     }
 
-    let stream = guard.as_ref().ok_or_else(|| {
+    let active = guard.as_ref().ok_or_else(|| {
         CommandFlowError::Automation("primary frame stream unavailable".to_string())
     })?;
 
-    stream.recorder.start().map_err(|error| {
-        CommandFlowError::Automation(format!("xcap recorder.start failed: {}", error))
-    })?;
+    if let FrameSource::Recorder(stream) = &active.source {
+        stream.recorder.start().map_err(|error| {
+            CommandFlowError::Automation(format!("xcap recorder.start failed: {}", error))
+        })?;
+    }
 
-    Ok(())
+    Ok(active.origin)
 }
 
-pub fn recv_primary_frame_gray_timeout(timeout: Duration) -> CommandResult<Option<GrayImage>> {
+fn with_active_stream<T>(
+    recv: impl FnOnce(&mut PrimaryFrameStream) -> CommandResult<Option<T>>,
+    convert: impl FnOnce(ScreenCapture) -> CommandResult<T>,
+) -> CommandResult<Option<T>> {
     let store = primary_stream_store();
     let mut guard = store
         .lock()
         .map_err(|_| CommandFlowError::Automation("primary stream mutex poisoned".to_string()))?;
 
-    let stream = guard.as_mut().ok_or_else(|| {
+    let active = guard.as_mut().ok_or_else(|| {
         CommandFlowError::Automation("primary frame stream has not been initialized".to_string())
     })?;
 
-    stream.recv_gray_timeout(timeout)
+    match &mut active.source {
+        FrameSource::Recorder(stream) => recv(stream),
+        FrameSource::Polling => {
+            let capture = capture_target_rgba(&active.target)?;
+            active.origin = capture.origin;
+            convert(capture).map(Some)
+        }
+    }
+}
+
+// 最近一帧左上角的虚拟屏幕坐标；窗口目标在移动后会变化
+pub fn frame_stream_origin() -> CommandResult<(i32, i32)> {
+    let store = primary_stream_store();
+    let guard = store
+        .lock()
+        .map_err(|_| CommandFlowError::Automation("primary stream mutex poisoned".to_string()))?;
+    guard.as_ref().map(|active| active.origin).ok_or_else(|| {
+        CommandFlowError::Automation("primary frame stream has not been initialized".to_string())
+    })
+}
+
+pub fn recv_primary_frame_gray_timeout(timeout: Duration) -> CommandResult<Option<GrayImage>> {
+    with_active_stream(
+        |stream| stream.recv_gray_timeout(timeout),
+        |capture| {
+            let rgba = RgbaImage::from_raw(capture.width, capture.height, capture.rgba)
+                .ok_or_else(|| {
+                    CommandFlowError::Automation("invalid rgba buffer size".to_string())
+                })?;
+            Ok(DynamicImage::ImageRgba8(rgba).to_luma8())
+        },
+    )
+}
+
+pub fn recv_primary_frame_rgb_timeout(timeout: Duration) -> CommandResult<Option<RgbImage>> {
+    with_active_stream(
+        |stream| stream.recv_rgb_timeout(timeout),
+        |capture| {
+            let rgba = RgbaImage::from_raw(capture.width, capture.height, capture.rgba)
+                .ok_or_else(|| {
+                    CommandFlowError::Automation("invalid rgba buffer size".to_string())
+                })?;
+            Ok(DynamicImage::ImageRgba8(rgba).to_rgb8())
+        },
+    )
 }
 
 pub fn stop_primary_frame_stream() -> CommandResult<()> {
@@ -229,7 +550,7 @@ pub fn stop_primary_frame_stream() -> CommandResult<()> {
         .lock()
         .map_err(|_| CommandFlowError::Automation("primary stream mutex poisoned".to_string()))?;
 
-    if let Some(stream) = guard.as_ref() {
+    if let Some(FrameSource::Recorder(stream)) = guard.as_ref().map(|active| &active.source) {
         stream.recorder.stop().map_err(|error| {
             CommandFlowError::Automation(format!("xcap recorder.stop failed: {}", error))
         })?;
@@ -244,10 +565,8 @@ pub fn reset_primary_frame_stream(reason: &str) -> CommandResult<()> {
         .lock()
         .map_err(|_| CommandFlowError::Automation("primary stream mutex poisoned".to_string()))?;
 
-    if let Some(stream) = guard.take() {
-        let _ = stream.recorder.stop();
-        thread::sleep(STREAM_STOP_SETTLE_DELAY);
-    }
+    // Drop 中会停止录屏并等待释放
+    guard.take();
 
     if reason.trim().is_empty() {
         return Ok(());
@@ -301,6 +620,33 @@ pub fn save_gray_with_box(
     Ok(path.to_string())
 }
 
+pub fn save_image_with_box(
+    path: &str,
+    image: &DynamicImage,
+    rect: Option<(u32, u32, u32, u32)>,
+    matched: bool,
+) -> CommandResult<String> {
+    if let DynamicImage::ImageLuma8(gray) = image {
+        return save_gray_with_box(path, gray, rect, matched);
+    }
+
+    ensure_output_parent(path)?;
+    let mut rgb = image.to_rgb8();
+    if let Some((x, y, w, h)) = rect {
+        let color = if matched {
+            image::Rgb([0, 255, 0])
+        } else {
+            image::Rgb([255, 64, 64])
+        };
+
+        draw_rect_outline(&mut rgb, x, y, w, h, color);
+    }
+
+    rgb.save(path)
+        .map_err(|error| CommandFlowError::Automation(error.to_string()))?;
+    Ok(path.to_string())
+}
+
 fn draw_rect_outline(
     image: &mut image::RgbImage,
     x: u32,
@@ -331,6 +677,34 @@ fn draw_rect_outline(
     }
 }
 
+fn frame_to_rgb_image(frame: &Frame) -> CommandResult<RgbImage> {
+    if frame.width == 0 || frame.height == 0 {
+        return Err(CommandFlowError::Automation(
+            "xcap frame has invalid dimensions".to_string(),
+        ));
+    }
+
+    let expected = (frame.width as usize)
+        .checked_mul(frame.height as usize)
+        .and_then(|v| v.checked_mul(4))
+        .ok_or_else(|| CommandFlowError::Automation("xcap frame size overflow".to_string()))?;
+
+    if frame.raw.len() < expected {
+        return Err(CommandFlowError::Automation(
+            "xcap frame raw buffer is smaller than expected".to_string(),
+        ));
+    }
+
+    let mut rgb = Vec::with_capacity((frame.width * frame.height * 3) as usize);
+    for pixel in frame.raw[..expected].chunks_exact(4) {
+        rgb.extend_from_slice(&pixel[..3]);
+    }
+
+    RgbImage::from_vec(frame.width, frame.height, rgb).ok_or_else(|| {
+        CommandFlowError::Automation("failed to build rgb image from xcap frame".to_string())
+    })
+}
+
 fn frame_to_gray_image(frame: &Frame) -> CommandResult<GrayImage> {
     if frame.width == 0 || frame.height == 0 {
         return Err(CommandFlowError::Automation(
@@ -0,0 +1,177 @@
+use image::{Rgba, RgbaImage};
+
+#[cfg(target_os = "windows")]
+use std::ptr;
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::Foundation::SIZE;
+#[cfg(target_os = "windows")]
+use windows_sys::Win32::Graphics::Gdi::{
+    CreateCompatibleDC, CreateDIBSection, CreateFontW, DeleteDC, DeleteObject, GdiFlush,
+    GetTextExtentPoint32W, SelectObject, SetBkMode, SetTextColor, TextOutW, BITMAPINFO,
+    BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, RGBQUAD,
+};
+
+// 使用 GDI 绘制单行文字，文字内容通常包含中文，需要系统字体支持；
+// 返回的图像按文字实际宽度加上内边距裁剪，背景不透明
+#[cfg(target_os = "windows")]
+pub fn render_text(
+    text: &str,
+    font_height: u32,
+    color: Rgba<u8>,
+    background: Rgba<u8>,
+    padding: (u32, u32),
+) -> Option<RgbaImage> {
+    let wide: Vec<u16> = text.encode_utf16().collect();
+    if wide.is_empty() || font_height == 0 {
+        return None;
+    }
+
+    let dc = unsafe { CreateCompatibleDC(ptr::null_mut()) };
+    if dc.is_null() {
+        return None;
+    }
+
+    let face: Vec<u16> = "Microsoft YaHei UI"
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    // 字符集/精度参数均取默认值(0/1)，4 = ANTIALIASED_QUALITY
+    let font = unsafe {
+        CreateFontW(
+            -(font_height as i32),
+            0,
+            0,
+            0,
+            400,
+            0,
+            0,
+            0,
+            1,
+            0,
+            0,
+            4,
+            0,
+            face.as_ptr(),
+        )
+    };
+    let previous_font = if font.is_null() {
+        ptr::null_mut()
+    } else {
+        unsafe { SelectObject(dc, font as _) }
+    };
+
+    let release = |bitmap: *mut std::ffi::c_void, previous_bitmap: *mut std::ffi::c_void| unsafe {
+        if !previous_bitmap.is_null() {
+            SelectObject(dc, previous_bitmap);
+        }
+        if !previous_font.is_null() {
+            SelectObject(dc, previous_font);
+        }
+        if !font.is_null() {
+            DeleteObject(font as _);
+        }
+        if !bitmap.is_null() {
+            DeleteObject(bitmap as _);
+        }
+        DeleteDC(dc);
+    };
+
+    let mut extent = SIZE { cx: 0, cy: 0 };
+    let measured =
+        unsafe { GetTextExtentPoint32W(dc, wide.as_ptr(), wide.len() as i32, &mut extent) };
+    if measured == 0 || extent.cx <= 0 || extent.cy <= 0 {
+        release(ptr::null_mut(), ptr::null_mut());
+        return None;
+    }
+    let width = extent.cx as u32 + padding.0 * 2;
+    let height = extent.cy as u32 + padding.1 * 2;
+
+    let mut bits = ptr::null_mut();
+    let bitmap_info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width as i32,
+            biHeight: -(height as i32),
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB,
+            biSizeImage: 0,
+            biXPelsPerMeter: 0,
+            biYPelsPerMeter: 0,
+            biClrUsed: 0,
+            biClrImportant: 0,
+        },
+        bmiColors: [RGBQUAD {
+            rgbBlue: 0,
+            rgbGreen: 0,
+            rgbRed: 0,
+            rgbReserved: 0,
+        }],
+    };
+
+    let bitmap = unsafe {
+        CreateDIBSection(
+            dc,
+            &bitmap_info,
+            DIB_RGB_COLORS,
+            &mut bits,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if bitmap.is_null() || bits.is_null() {
+        release(bitmap as _, ptr::null_mut());
+        return None;
+    }
+
+    let byte_len = (width as usize) * (height as usize) * 4;
+    unsafe {
+        let pixels = std::slice::from_raw_parts_mut(bits as *mut u8, byte_len);
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[background[2], background[1], background[0], 0]);
+        }
+    }
+
+    let previous_bitmap = unsafe { SelectObject(dc, bitmap as _) };
+    let drawn = unsafe {
+        // 1 = TRANSPARENT，保留预先填充的背景色
+        SetBkMode(dc, 1);
+        SetTextColor(
+            dc,
+            u32::from(color[0]) | (u32::from(color[1]) << 8) | (u32::from(color[2]) << 16),
+        );
+        let drawn = TextOutW(
+            dc,
+            padding.0 as i32,
+            padding.1 as i32,
+            wide.as_ptr(),
+            wide.len() as i32,
+        );
+        GdiFlush();
+        drawn
+    };
+
+    let bgra = unsafe { std::slice::from_raw_parts(bits as *const u8, byte_len).to_vec() };
+    release(bitmap as _, previous_bitmap as _);
+
+    if drawn == 0 {
+        return None;
+    }
+
+    let rgba = bgra
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], 255])
+        .collect::<Vec<u8>>();
+    RgbaImage::from_raw(width, height, rgba)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn render_text(
+    _text: &str,
+    _font_height: u32,
+    _color: Rgba<u8>,
+    _background: Rgba<u8>,
+    _padding: (u32, u32),
+) -> Option<RgbaImage> {
+    None
+}
@@ -0,0 +1,312 @@
+// Jaro-Winkler 前缀加权系数与最大前缀长度
+const JARO_WINKLER_SCALE: f32 = 0.1;
+const JARO_WINKLER_MAX_PREFIX: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimilarityAlgorithm {
+    Levenshtein,
+    JaroWinkler,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextNormalization {
+    pub strip_whitespace: bool,
+    pub fold_width: bool,
+    pub fold_confusables: bool,
+}
+
+impl SimilarityAlgorithm {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "jarowinkler" | "jaro_winkler" | "jaro-winkler" => SimilarityAlgorithm::JaroWinkler,
+            _ => SimilarityAlgorithm::Levenshtein,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SimilarityAlgorithm::Levenshtein => "levenshtein",
+            SimilarityAlgorithm::JaroWinkler => "jaroWinkler",
+        }
+    }
+}
+
+impl TextNormalization {
+    pub fn apply(&self, text: &str, case_sensitive: bool) -> String {
+        // 混淆字符折叠须先于小写化，否则 'I'、'B' 等大写形态会先变成小写而错过映射
+        let folded: String = text
+            .chars()
+            .map(|ch| {
+                if self.fold_width {
+                    fold_full_width(ch)
+                } else {
+                    ch
+                }
+            })
+            .filter(|ch| !(self.strip_whitespace && ch.is_whitespace()))
+            .map(|ch| {
+                if self.fold_confusables {
+                    fold_confusable(ch)
+                } else {
+                    ch
+                }
+            })
+            .collect();
+        if case_sensitive {
+            folded
+        } else {
+            folded.to_lowercase()
+        }
+    }
+}
+
+// 全角 ASCII（U+FF01~U+FF5E）与全角空格折叠为半角
+fn fold_full_width(ch: char) -> char {
+    match ch {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(ch as u32 - 0xFEE0).unwrap_or(ch),
+        _ => ch,
+    }
+}
+
+// OCR 常见混淆字符统一映射到同一代表字符，匹配双方都做映射
+fn fold_confusable(ch: char) -> char {
+    match ch {
+        'O' | 'o' | 'Q' | '〇' | 'Ο' | 'ο' => '0',
+        'I' | 'l' | '|' | 'Ι' | '丨' => '1',
+        'Z' | 'z' => '2',
+        'S' | 's' => '5',
+        'B' => '8',
+        '—' | '–' => '-',
+        _ => ch,
+    }
+}
+
+// 目标文本在候选文本中的近似出现程度，0~1；1 表示候选中存在与目标完全一致的片段
+pub fn partial_similarity(candidate: &str, target: &str, algorithm: SimilarityAlgorithm) -> f32 {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+    if target.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+
+    match algorithm {
+        SimilarityAlgorithm::Levenshtein => {
+            let distance = substring_edit_distance(&candidate, &target);
+            (1.0 - distance as f32 / target.len() as f32).max(0.0)
+        }
+        SimilarityAlgorithm::JaroWinkler => {
+            if candidate.len() <= target.len() {
+                return jaro_winkler(&candidate, &target);
+            }
+            candidate
+                .windows(target.len())
+                .map(|window| jaro_winkler(window, &target))
+                .fold(0.0, f32::max)
+        }
+    }
+}
+
+// 目标与候选任意连续片段之间的最小编辑距离（片段两端不计代价）
+fn substring_edit_distance(candidate: &[char], target: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=target.len()).collect();
+    let mut best = previous[target.len()];
+    let mut current = vec![0_usize; target.len() + 1];
+    for ch in candidate {
+        current[0] = 0;
+        for (index, target_ch) in target.iter().enumerate() {
+            let substitution = previous[index] + usize::from(ch != target_ch);
+            current[index + 1] = substitution
+                .min(previous[index + 1] + 1)
+                .min(current[index] + 1);
+        }
+        best = best.min(current[target.len()]);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    best
+}
+
+fn jaro_winkler(left: &[char], right: &[char]) -> f32 {
+    let jaro = jaro(left, right);
+    let prefix = left
+        .iter()
+        .zip(right.iter())
+        .take(JARO_WINKLER_MAX_PREFIX)
+        .take_while(|(a, b)| a == b)
+        .count();
+    jaro + prefix as f32 * JARO_WINKLER_SCALE * (1.0 - jaro)
+}
+
+fn jaro(left: &[char], right: &[char]) -> f32 {
+    if left.is_empty() && right.is_empty() {
+        return 1.0;
+    }
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+
+    let window = (left.len().max(right.len()) / 2).saturating_sub(1);
+    let mut left_matched = vec![false; left.len()];
+    let mut right_matched = vec![false; right.len()];
+    let mut matches = 0_usize;
+    for (i, ch) in left.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(right.len());
+        for j in start..end {
+            if !right_matched[j] && right[j] == *ch {
+                left_matched[i] = true;
+                right_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let left_sequence = left
+        .iter()
+        .zip(left_matched.iter())
+        .filter(|(_, matched)| **matched)
+        .map(|(ch, _)| *ch);
+    let right_sequence = right
+        .iter()
+        .zip(right_matched.iter())
+        .filter(|(_, matched)| **matched)
+        .map(|(ch, _)| *ch);
+    let transpositions = left_sequence
+        .zip(right_sequence)
+        .filter(|(a, b)| a != b)
+        .count()
+        / 2;
+
+    let matches = matches as f32;
+    (matches / left.len() as f32
+        + matches / right.len() as f32
+        + (matches - transpositions as f32) / matches)
+        / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{partial_similarity, SimilarityAlgorithm, TextNormalization};
+
+    const ALL_FOLDS: TextNormalization = TextNormalization {
+        strip_whitespace: true,
+        fold_width: true,
+        fold_confusables: true,
+    };
+
+    #[test]
+    fn normalization_table() {
+        let cases: &[(TextNormalization, bool, &str, &str)] = &[
+            (
+                TextNormalization::default(),
+                false,
+                "Hello World",
+                "hello world",
+            ),
+            (
+                TextNormalization::default(),
+                true,
+                "Hello World",
+                "Hello World",
+            ),
+            (ALL_FOLDS, false, "ＡＢＣ　１２３", "a8c123"),
+            (ALL_FOLDS, false, "Invoice No. IB0", "1nv0icen0.180"),
+            (ALL_FOLDS, true, "IB-lO", "18-10"),
+            (ALL_FOLDS, false, "价格—100", "价格-100"),
+            (ALL_FOLDS, false, "一二三", "一二三"),
+            (
+                TextNormalization {
+                    strip_whitespace: true,
+                    ..TextNormalization::default()
+                },
+                false,
+                " a b\tc ",
+                "abc",
+            ),
+        ];
+        for (normalization, case_sensitive, input, expected) in cases {
+            assert_eq!(
+                normalization.apply(input, *case_sensitive),
+                *expected,
+                "input {input:?} case_sensitive={case_sensitive}"
+            );
+        }
+    }
+
+    #[test]
+    fn confusable_capitals_match_digits_in_case_insensitive_mode() {
+        let recognized = ALL_FOLDS.apply("ID 1B", false);
+        let target = ALL_FOLDS.apply("1D I8", false);
+        assert_eq!(recognized, target);
+    }
+
+    #[test]
+    fn partial_similarity_table() {
+        let cases: &[(&str, &str, SimilarityAlgorithm, f32)] = &[
+            ("登录账号", "登录", SimilarityAlgorithm::Levenshtein, 1.0),
+            (
+                "please click submit now",
+                "submit",
+                SimilarityAlgorithm::Levenshtein,
+                1.0,
+            ),
+            (
+                "please click submlt now",
+                "submit",
+                SimilarityAlgorithm::Levenshtein,
+                5.0 / 6.0,
+            ),
+            ("abc", "xyz", SimilarityAlgorithm::Levenshtein, 0.0),
+            ("", "submit", SimilarityAlgorithm::Levenshtein, 0.0),
+            ("submit", "", SimilarityAlgorithm::Levenshtein, 0.0),
+            (
+                "please click submit now",
+                "submit",
+                SimilarityAlgorithm::JaroWinkler,
+                1.0,
+            ),
+            ("abc", "xyz", SimilarityAlgorithm::JaroWinkler, 0.0),
+            ("", "submit", SimilarityAlgorithm::JaroWinkler, 0.0),
+        ];
+        for (candidate, target, algorithm, expected) in cases {
+            let score = partial_similarity(candidate, target, *algorithm);
+            assert!(
+                (score - expected).abs() < 1e-4,
+                "{candidate:?} ~ {target:?} with {}: expected {expected}, got {score}",
+                algorithm.name()
+            );
+        }
+    }
+
+    #[test]
+    fn jaro_winkler_rewards_shared_prefix() {
+        let prefixed = partial_similarity("submib", "submit", SimilarityAlgorithm::JaroWinkler);
+        let suffixed = partial_similarity("xubmit", "submit", SimilarityAlgorithm::JaroWinkler);
+        assert!(prefixed > suffixed, "{prefixed} <= {suffixed}");
+        assert!(prefixed < 1.0);
+    }
+
+    #[test]
+    fn levenshtein_partial_score_never_negative() {
+        let score = partial_similarity("a", "abcdefgh", SimilarityAlgorithm::Levenshtein);
+        assert!((0.0..=1.0).contains(&score));
+    }
+
+    #[test]
+    fn algorithm_parse_accepts_aliases() {
+        for value in ["jaroWinkler", "jaro_winkler", "Jaro-Winkler"] {
+            assert_eq!(
+                SimilarityAlgorithm::parse(value),
+                SimilarityAlgorithm::JaroWinkler
+            );
+        }
+        assert_eq!(
+            SimilarityAlgorithm::parse("unknown"),
+            SimilarityAlgorithm::Levenshtein
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use image::{Rgba, RgbaImage};
+
+#[derive(Debug, Clone, Copy)]
+pub struct IgnoreRegion {
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct VisualDiff {
+    // 超出容差的像素占参与比较像素的比例，0~1
+    pub mismatch_ratio: f32,
+    pub mismatched_pixels: u64,
+    pub compared_pixels: u64,
+    pub max_channel_diff: u8,
+    // 差异区域外接矩形 (left, top, width, height)，相对比较图像左上角
+    pub bounding_box: Option<(u32, u32, u32, u32)>,
+    pub heatmap: RgbaImage,
+}
+
+impl IgnoreRegion {
+    fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.left
+            && y >= self.top
+            && u64::from(x) < u64::from(self.left) + u64::from(self.width)
+            && u64::from(y) < u64::from(self.top) + u64::from(self.height)
+    }
+}
+
+// 基准图中完全透明的像素与忽略区域内的像素不参与比较；尺寸不一致时无法逐像素比较，返回 None
+pub fn compare(
+    baseline: &RgbaImage,
+    actual: &RgbaImage,
+    tolerance: u8,
+    ignore_regions: &[IgnoreRegion],
+) -> Option<VisualDiff> {
+    if baseline.dimensions() != actual.dimensions() {
+        return None;
+    }
+    let (width, height) = actual.dimensions();
+    let mut heatmap = RgbaImage::new(width, height);
+    let mut mismatched = 0_u64;
+    let mut compared = 0_u64;
+    let mut max_channel_diff = 0_u8;
+    let mut min_x = u32::MAX;
+    let mut min_y = u32::MAX;
+    let mut max_x = 0_u32;
+    let mut max_y = 0_u32;
+
+    for (x, y, actual_pixel) in actual.enumerate_pixels() {
+        let background = dimmed(actual_pixel);
+        let baseline_pixel = baseline.get_pixel(x, y);
+        if baseline_pixel[3] == 0 || ignore_regions.iter().any(|region| region.contains(x, y)) {
+            heatmap.put_pixel(x, y, ignored(background));
+            continue;
+        }
+
+        compared += 1;
+        let diff = (0..3)
+            .map(|channel| baseline_pixel[channel].abs_diff(actual_pixel[channel]))
+            .max()
+            .unwrap_or(0);
+        if diff <= tolerance {
+            heatmap.put_pixel(x, y, background);
+            continue;
+        }
+
+        mismatched += 1;
+        max_channel_diff = max_channel_diff.max(diff);
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+        heatmap.put_pixel(x, y, heat(diff));
+    }
+
+    Some(VisualDiff {
+        mismatch_ratio: if compared == 0 {
+            0.0
+        } else {
+            mismatched as f32 / compared as f32
+        },
+        mismatched_pixels: mismatched,
+        compared_pixels: compared,
+        max_channel_diff,
+        bounding_box: (mismatched > 0)
+            .then(|| (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)),
+        heatmap,
+    })
+}
+
+// 底图转为压暗的灰度，突出差异着色
+fn dimmed(pixel: &Rgba<u8>) -> Rgba<u8> {
+    let luma =
+        (u32::from(pixel[0]) * 299 + u32::from(pixel[1]) * 587 + u32::from(pixel[2]) * 114) / 1000;
+    let value = (luma / 3) as u8;
+    Rgba([value, value, value, 255])
+}
+
+fn ignored(background: Rgba<u8>) -> Rgba<u8> {
+    Rgba([
+        background[0],
+        background[1],
+        background[2].saturating_add(70),
+        255,
+    ])
+}
+
+// 差异越大越接近亮黄色，轻微差异为红色
+fn heat(diff: u8) -> Rgba<u8> {
+    let green = (u32::from(diff) * 230 / 255) as u8;
+    Rgba([255, green, 0, 255])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, IgnoreRegion};
+    use image::{Rgba, RgbaImage};
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn identical_images_have_no_mismatch() {
+        let image = solid(4, 3, 120);
+        let diff = compare(&image, &image, 0, &[]).expect("same size");
+        assert_eq!(diff.compared_pixels, 12);
+        assert_eq!(diff.mismatched_pixels, 0);
+        assert_eq!(diff.mismatch_ratio, 0.0);
+        assert_eq!(diff.max_channel_diff, 0);
+        assert!(diff.bounding_box.is_none());
+        assert_eq!(diff.heatmap.dimensions(), (4, 3));
+    }
+
+    #[test]
+    fn size_mismatch_returns_none() {
+        assert!(compare(&solid(4, 3, 0), &solid(3, 4, 0), 0, &[]).is_none());
+    }
+
+    #[test]
+    fn tolerance_boundary_is_inclusive() {
+        let baseline = solid(2, 1, 100);
+        let mut actual = baseline.clone();
+        actual.put_pixel(0, 0, Rgba([110, 100, 100, 255]));
+        actual.put_pixel(1, 0, Rgba([100, 100, 89, 255]));
+
+        let diff = compare(&baseline, &actual, 10, &[]).expect("same size");
+        assert_eq!(diff.mismatched_pixels, 1);
+        assert_eq!(diff.max_channel_diff, 11);
+        assert_eq!(diff.bounding_box, Some((1, 0, 1, 1)));
+        assert_eq!(diff.mismatch_ratio, 0.5);
+
+        let diff = compare(&baseline, &actual, 11, &[]).expect("same size");
+        assert_eq!(diff.mismatched_pixels, 0);
+    }
+
+    #[test]
+    fn alpha_channel_is_not_compared() {
+        let baseline = solid(1, 1, 50);
+        let actual = RgbaImage::from_pixel(1, 1, Rgba([50, 50, 50, 10]));
+        let diff = compare(&baseline, &actual, 0, &[]).expect("same size");
+        assert_eq!(diff.compared_pixels, 1);
+        assert_eq!(diff.mismatched_pixels, 0);
+    }
+
+    #[test]
+    fn transparent_baseline_pixels_are_excluded() {
+        let mut baseline = solid(3, 1, 0);
+        baseline.put_pixel(0, 0, Rgba([0, 0, 0, 0]));
+        let actual = solid(3, 1, 255);
+
+        let diff = compare(&baseline, &actual, 0, &[]).expect("same size");
+        assert_eq!(diff.compared_pixels, 2);
+        assert_eq!(diff.mismatched_pixels, 2);
+        assert_eq!(diff.bounding_box, Some((1, 0, 2, 1)));
+    }
+
+    #[test]
+    fn ignore_regions_are_excluded() {
+        let baseline = solid(4, 4, 0);
+        let actual = solid(4, 4, 200);
+        let regions = [
+            IgnoreRegion {
+                left: 0,
+                top: 0,
+                width: 2,
+                height: 4,
+            },
+            IgnoreRegion {
+                left: 2,
+                top: 3,
+                width: 10,
+                height: 10,
+            },
+        ];
+
+        let diff = compare(&baseline, &actual, 0, &regions).expect("same size");
+        assert_eq!(diff.compared_pixels, 6);
+        assert_eq!(diff.mismatched_pixels, 6);
+        assert_eq!(diff.mismatch_ratio, 1.0);
+        assert_eq!(diff.bounding_box, Some((2, 0, 2, 3)));
+    }
+
+    #[test]
+    fn bounding_box_spans_all_mismatches() {
+        let baseline = solid(10, 8, 0);
+        let mut actual = baseline.clone();
+        actual.put_pixel(2, 5, Rgba([40, 0, 0, 255]));
+        actual.put_pixel(7, 1, Rgba([0, 0, 90, 255]));
+
+        let diff = compare(&baseline, &actual, 5, &[]).expect("same size");
+        assert_eq!(diff.mismatched_pixels, 2);
+        assert_eq!(diff.max_channel_diff, 90);
+        assert_eq!(diff.bounding_box, Some((2, 1, 6, 5)));
+        assert_eq!(diff.heatmap.get_pixel(7, 1)[0], 255);
+    }
+
+    #[test]
+    fn nothing_compared_reports_zero_ratio() {
+        let baseline = RgbaImage::from_pixel(3, 3, Rgba([0, 0, 0, 0]));
+        let actual = solid(3, 3, 255);
+
+        let diff = compare(&baseline, &actual, 0, &[]).expect("same size");
+        assert_eq!(diff.compared_pixels, 0);
+        assert_eq!(diff.mismatched_pixels, 0);
+        assert_eq!(diff.mismatch_ratio, 0.0);
+        assert!(diff.bounding_box.is_none());
+    }
+}
@@ -4,16 +4,16 @@ use serde::Serialize;
 use std::path::Path;
 
 #[cfg(target_os = "windows")]
-use windows_sys::Win32::Foundation::{CloseHandle, BOOL, HWND, LPARAM};
+use windows_sys::Win32::Foundation::{CloseHandle, BOOL, HWND, LPARAM, RECT};
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::System::Threading::{
     OpenProcess, QueryFullProcessImageNameW, PROCESS_QUERY_LIMITED_INFORMATION,
 };
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::UI::WindowsAndMessaging::{
-    EnumWindows, GetClassNameW, GetForegroundWindow, GetWindowTextLengthW, GetWindowTextW,
-    GetWindowThreadProcessId, IsIconic, IsWindowVisible, SetForegroundWindow, ShowWindow,
-    SW_RESTORE,
+    EnumWindows, GetClassNameW, GetForegroundWindow, GetWindowRect, GetWindowTextLengthW,
+    GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindowVisible, SetForegroundWindow,
+    ShowWindow, SW_RESTORE,
 };
 
 #[derive(Debug, Clone, Serialize)]
@@ -26,7 +26,16 @@ pub struct OpenWindowEntry {
     pub process_id: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowRect {
+    pub left: i32,
+    pub top: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WindowMatchQuery {
     pub title: Option<String>,
     pub program: Option<String>,
@@ -512,6 +521,87 @@ pub fn activate_window_by_program(
     }
 }
 
+pub fn find_window_rect(query: &WindowMatchQuery) -> CommandResult<WindowRect> {
+    #[cfg(target_os = "windows")]
+    {
+        let window = enumerate_windows()
+            .into_iter()
+            .find(|entry| matches_query(entry, query))
+            .ok_or_else(|| {
+                CommandFlowError::Automation(
+                    "cannot find open window matching current filters".to_string(),
+                )
+            })?;
+
+        if unsafe { IsIconic(window.hwnd) } != 0 {
+            return Err(CommandFlowError::Automation(format!(
+                "target window is minimized: {}",
+                window.title
+            )));
+        }
+
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        if unsafe { GetWindowRect(window.hwnd, &mut rect) } == 0 {
+            return Err(CommandFlowError::Automation(format!(
+                "failed to read window bounds: {}",
+                window.title
+            )));
+        }
+
+        Ok(WindowRect {
+            left: rect.left,
+            top: rect.top,
+            width: (rect.right - rect.left).max(0) as u32,
+            height: (rect.bottom - rect.top).max(0) as u32,
+        })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = query;
+        Err(CommandFlowError::Automation(
+            "window bounds lookup is only supported on Windows currently".to_string(),
+        ))
+    }
+}
+
+// 返回窗口句柄数值，供 xcap 按窗口 id 截图
+pub fn find_window_id(query: &WindowMatchQuery) -> CommandResult<u32> {
+    #[cfg(target_os = "windows")]
+    {
+        let window = enumerate_windows()
+            .into_iter()
+            .find(|entry| matches_query(entry, query))
+            .ok_or_else(|| {
+                CommandFlowError::Automation(
+                    "cannot find open window matching current filters".to_string(),
+                )
+            })?;
+
+        if unsafe { IsIconic(window.hwnd) } != 0 {
+            return Err(CommandFlowError::Automation(format!(
+                "target window is minimized: {}",
+                window.title
+            )));
+        }
+
+        Ok(window.hwnd as usize as u32)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = query;
+        Err(CommandFlowError::Automation(
+            "window lookup is only supported on Windows currently".to_string(),
+        ))
+    }
+}
+
 pub fn activate_window(query: &WindowMatchQuery) -> CommandResult<OpenWindowEntry> {
     #[cfg(target_os = "windows")]
     {
@@ -1,10 +1,15 @@
 use crate::automation::executor::WorkflowExecutor;
+use crate::automation::file_journal;
+use crate::automation::ocr_models;
 use crate::automation::screenshot;
 use crate::automation::start_menu;
 use crate::automation::uia;
 use crate::automation::window;
 use crate::automation::process;
+use crate::automation::redaction;
+use crate::automation::run_recorder;
 use crate::input_recorder;
+use crate::workflow::capability::{self, Capability};
 use crate::workflow::graph::WorkflowGraph;
 use encoding_rs::GBK;
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
@@ -218,6 +223,11 @@ fn get_virtual_screen_bounds() -> (i32, i32, u32, u32) {
     (x, y, width.max(1) as u32, height.max(1) as u32)
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowRunStartedPayload {
+    pub run_id: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct NodeProgressPayload {
     pub node_id: String,
@@ -273,7 +283,12 @@ pub async fn health_check() -> Result<String, String> {
 }
 
 #[tauri::command]
-pub async fn run_workflow(app: AppHandle, graph: WorkflowGraph) -> Result<String, String> {
+pub async fn run_workflow(
+    app: AppHandle,
+    graph: WorkflowGraph,
+    safe_mode: Option<bool>,
+    recording: Option<run_recorder::RunRecordingOptions>,
+) -> Result<String, String> {
     let control = execution_control();
     if control
         .running
@@ -285,8 +300,20 @@ pub async fn run_workflow(app: AppHandle, graph: WorkflowGraph) -> Result<String
 
     control.cancel_requested.store(false, Ordering::SeqCst);
 
-    let executor = WorkflowExecutor;
+    let executor = WorkflowExecutor::default().with_safe_mode(safe_mode.unwrap_or(false));
+    let _ = app.emit(
+        "workflow-run-started",
+        WorkflowRunStartedPayload {
+            run_id: executor.run_id().to_string(),
+        },
+    );
+    let recorder = recording
+        .filter(|options| options.enabled)
+        .map(|options| run_recorder::RunRecorder::start(executor.run_id(), options));
     let mut emit_progress = |node: &crate::workflow::node::WorkflowNode| {
+        if let Some(recorder) = recorder.as_ref() {
+            recorder.set_node_label(&node.label);
+        }
         let _ = app.emit(
             "workflow-node-started",
             NodeProgressPayload {
@@ -347,6 +374,33 @@ pub async fn run_workflow(app: AppHandle, graph: WorkflowGraph) -> Result<String
     control.cancel_requested.store(false, Ordering::SeqCst);
     control.running.store(false, Ordering::SeqCst);
 
+    if let Some(recorder) = recorder {
+        let failed = run_result.is_err();
+        // GIF 编码较慢，放到阻塞线程中完成
+        let saved = tokio::task::spawn_blocking(move || recorder.finish(failed))
+            .await
+            .map_err(|error| error.to_string())
+            .and_then(|result| result.map_err(|error| error.to_string()));
+        let (level, message) = match saved {
+            Ok(Some(output)) => (
+                "info",
+                format!(
+                    "运行录像已保存（{} 帧）：{}",
+                    output.frame_count, output.path
+                ),
+            ),
+            Ok(None) => ("info", "运行成功，未保存运行录像。".to_string()),
+            Err(error) => ("warn", format!("保存运行录像失败：{}", error)),
+        };
+        let _ = app.emit(
+            "workflow-log",
+            ExecutionLogPayload {
+                level: level.to_string(),
+                message,
+            },
+        );
+    }
+
     run_result.map_err(|e| e.to_string())?;
     Ok("workflow finished".to_string())
 }
@@ -377,6 +431,30 @@ pub async fn stop_workflow() -> Result<String, String> {
     }
 }
 
+#[tauri::command]
+pub async fn undo_run_file_operations(run_id: String) -> Result<file_journal::UndoReport, String> {
+    if execution_control().running.load(Ordering::SeqCst) {
+        return Err("工作流仍在执行中，请先停止后再撤销文件操作。".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || file_journal::undo_run(&run_id))
+        .await
+        .map_err(|error| format!("撤销文件操作任务失败：{}", error))?
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub async fn purge_file_journals() -> Result<file_journal::PurgeReport, String> {
+    if execution_control().running.load(Ordering::SeqCst) {
+        return Err("工作流仍在执行中，请先停止后再清理文件回收区。".to_string());
+    }
+
+    tokio::task::spawn_blocking(file_journal::purge_journals)
+        .await
+        .map_err(|error| format!("清理文件回收区任务失败：{}", error))?
+        .map_err(|error| error.to_string())
+}
+
 #[tauri::command]
 pub async fn save_workflow(path: String, graph: WorkflowGraph) -> Result<String, String> {
     let payload = serde_json::to_string_pretty(&graph).map_err(|error| error.to_string())?;
@@ -509,6 +587,7 @@ pub async fn start_package_workflow_as_exe(
     if requested_target.is_empty() {
         return Err("输出路径不能为空。".to_string());
     }
+    ensure_packaged_capabilities_declared(&graph)?;
 
     let normalized_build_options = normalize_build_options(build_options);
 
@@ -1166,12 +1245,15 @@ edition = "2021"
 rust-version = "1.75"
 
 [dependencies]
+aes-gcm = "0.10"
 anyhow = "1"
 arboard = "3"
 base64 = "0.22"
 chrono = {{ version = "0.4", features = ["serde"] }}
 ddc-hi = "0.4.1"
+encoding_rs = "0.8"
 enigo = "0.2"
+glob = "0.3"
 image = "0.25"
 lnk_parser = "0.4.3"
 ort = {{ version = "2.0.0-rc.10", default-features = false, features = ["ndarray", "std", "download-binaries", "copy-dylibs"] }}
@@ -1186,6 +1268,7 @@ template-matching = {{ version = "0.2", features = ["image"] }}
 thiserror = "2"
 tokio = {{ version = "1", features = ["rt-multi-thread", "macros", "time", "process", "sync"] }}
 xcap = {{ version = "0.8", features = ["image"] }}
+zip = {{ version = "2.2", default-features = false, features = ["deflate", "aes-crypto", "chrono"] }}
 
 [target.'cfg(windows)'.dependencies]
 windows-sys = {{ version = "0.59", features = ["Win32_Foundation", "Win32_Graphics_Gdi", "Win32_Storage_FileSystem", "Win32_UI_Shell", "Win32_UI_WindowsAndMessaging", "Win32_UI_Input_KeyboardAndMouse", "Win32_System_Console", "Win32_System_Diagnostics_Debug", "Win32_Security_Cryptography", "Win32_System_Memory", "Win32_System_Threading"] }}
@@ -1291,6 +1374,52 @@ fn format_option_string(value: &Option<String>) -> String {
     }
 }
 
+// 打包产物没有确认界面，未声明权限时运行必然失败，因此要求先在编辑器中声明
+fn ensure_packaged_capabilities_declared(graph: &WorkflowGraph) -> Result<(), String> {
+    if graph.capabilities.is_some() {
+        return Ok(());
+    }
+
+    let mut required: Vec<Capability> = Vec::new();
+    for node in &graph.nodes {
+        for item in capability::required_capabilities(node) {
+            if !required.contains(&item) {
+                required.push(item);
+            }
+        }
+    }
+    if required.is_empty() {
+        return Ok(());
+    }
+
+    let labels = required
+        .iter()
+        .map(|item| format!("{}（{}）", item.label(), item.key()))
+        .collect::<Vec<_>>()
+        .join("、");
+    Err(format!(
+        "工作流尚未声明权限，节点需要：{}。请先在“设置 > 工作流权限”中声明后再打包。",
+        labels
+    ))
+}
+
+fn format_capabilities(capabilities: Option<&[Capability]>) -> String {
+    match capabilities {
+        Some(items) => format!(
+            "Some(vec![{}])",
+            items
+                .iter()
+                .map(|capability| format!(
+                    "crate::workflow::capability::Capability::{:?}",
+                    capability
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => "None".to_string(),
+    }
+}
+
 fn generate_workflow_bin_source(graph: &WorkflowGraph) -> Result<String, String> {
     let mut source = String::new();
     source.push_str("#[cfg(not(target_os = \"windows\"))]\n");
@@ -1343,7 +1472,13 @@ fn generate_workflow_bin_source(graph: &WorkflowGraph) -> Result<String, String>
             format_option_string(&edge.target_handle)
         );
     }
-    source.push_str("        ],\n    }\n}\n\n");
+    source.push_str("        ],\n");
+    let _ = writeln!(
+        &mut source,
+        "        capabilities: {},",
+        format_capabilities(graph.capabilities.as_deref())
+    );
+    source.push_str("    }\n}\n\n");
 
     source.push_str("#[tokio::main]\n");
     source.push_str("async fn main() {\n");
@@ -1718,6 +1853,45 @@ pub async fn list_start_menu_apps() -> Result<Vec<start_menu::StartMenuAppEntry>
     start_menu::scan_start_menu_apps().map_err(|error| error.to_string())
 }
 
+#[tauri::command]
+pub async fn list_ocr_models(
+    model_dir: Option<String>,
+) -> Result<Vec<ocr_models::OcrModelEntry>, String> {
+    Ok(ocr_models::list_models(model_dir.as_deref().unwrap_or("")))
+}
+
+#[tauri::command]
+pub async fn import_ocr_model(
+    source_path: String,
+    language: String,
+    component: Option<String>,
+    model_dir: Option<String>,
+) -> Result<ocr_models::OcrModelImportResult, String> {
+    tokio::task::spawn_blocking(move || {
+        ocr_models::import_model(
+            &source_path,
+            &language,
+            component.as_deref().unwrap_or(""),
+            model_dir.as_deref().unwrap_or(""),
+        )
+        .map_err(|error| error.to_string())
+    })
+    .await
+    .map_err(|error| format!("导入 OCR 模型任务失败：{}", error))?
+}
+
+#[tauri::command]
+pub fn get_sensitive_param_rules() -> redaction::SensitiveParamRules {
+    redaction::sensitive_param_rules()
+}
+
+#[tauri::command]
+pub async fn list_monitors() -> Result<Vec<screenshot::MonitorInfo>, String> {
+    tokio::task::spawn_blocking(|| screenshot::list_monitors().map_err(|error| error.to_string()))
+        .await
+        .map_err(|error| format!("枚举显示器任务失败：{}", error))?
+}
+
 #[tauri::command]
 pub async fn resolve_start_menu_app_icon(
     icon_path: String,
@@ -1819,6 +1993,51 @@ pub async fn save_llm_presets(
     crate::secure_settings::save_llm_presets(presets)
 }
 
+#[tauri::command]
+pub async fn list_secrets() -> Result<Vec<crate::secure_settings::SecretSummary>, String> {
+    crate::secure_settings::list_secrets()
+}
+
+#[tauri::command]
+pub async fn set_secret(
+    name: String,
+    value: String,
+) -> Result<crate::secure_settings::SecretSummary, String> {
+    crate::secure_settings::set_secret(&name, &value)
+}
+
+#[tauri::command]
+pub async fn delete_secret(name: String) -> Result<bool, String> {
+    crate::secure_settings::delete_secret(&name)
+}
+
+#[tauri::command]
+pub async fn export_presets(
+    path: String,
+    passphrase: String,
+    kinds: Vec<String>,
+) -> Result<crate::preset_bundle::PresetExportResult, String> {
+    tokio::task::spawn_blocking(move || {
+        crate::preset_bundle::export_presets(&path, &passphrase, &kinds)
+    })
+    .await
+    .map_err(|error| format!("导出预设任务失败：{}", error))?
+}
+
+#[tauri::command]
+pub async fn import_presets(
+    path: String,
+    passphrase: String,
+    merge_strategy: String,
+) -> Result<crate::preset_bundle::PresetImportReport, String> {
+    let strategy = crate::preset_bundle::MergeStrategy::parse(&merge_strategy)?;
+    tokio::task::spawn_blocking(move || {
+        crate::preset_bundle::import_presets(&path, &passphrase, strategy)
+    })
+    .await
+    .map_err(|error| format!("导入预设任务失败：{}", error))?
+}
+
 #[tauri::command]
 pub async fn load_input_recording_presets(
 ) -> Result<Vec<crate::secure_settings::InputRecordingPreset>, String> {
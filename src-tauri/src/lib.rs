@@ -8,6 +8,7 @@ pub mod commands;
 pub mod config;
 pub mod error;
 pub mod input_recorder;
+pub mod preset_bundle;
 pub mod secure_settings;
 pub mod workflow;
 
@@ -68,6 +69,8 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::run_workflow,
             commands::stop_workflow,
+            commands::undo_run_file_operations,
+            commands::purge_file_journals,
             commands::check_packaging_environment,
             commands::start_package_workflow_as_exe,
             commands::save_workflow,
@@ -85,12 +88,21 @@ pub fn run() {
             commands::list_running_processes,
             commands::list_start_menu_apps,
             commands::resolve_start_menu_app_icon,
+            commands::list_ocr_models,
+            commands::import_ocr_model,
+            commands::list_monitors,
+            commands::get_sensitive_param_rules,
             commands::fetch_llm_models,
             commands::load_llm_presets,
             commands::load_input_recording_presets,
             commands::play_completion_beep,
             commands::save_llm_presets,
             commands::save_input_recording_presets,
+            commands::list_secrets,
+            commands::set_secret,
+            commands::delete_secret,
+            commands::export_presets,
+            commands::import_presets,
             commands::health_check,
             commands::set_background_mode,
             commands::start_input_recording,
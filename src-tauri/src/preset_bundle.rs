@@ -0,0 +1,644 @@
+use crate::secure_settings::{
+    load_input_recording_presets, load_llm_presets, save_input_recording_presets, save_llm_presets,
+    InputRecordingPreset, LlmPreset,
+};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const BUNDLE_FORMAT: &str = "commandflow-presets";
+const BUNDLE_VERSION: u32 = 1;
+const KIND_LLM: &str = "llm";
+const KIND_INPUT_RECORDING: &str = "inputRecording";
+const MIN_PASSPHRASE_CHARS: usize = 8;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+// Argon2id 参数取 OWASP 推荐下限；导入时限制上限，防止恶意文件耗尽内存
+const KDF_MEMORY_KIB: u32 = 19 * 1024;
+const KDF_ITERATIONS: u32 = 2;
+const KDF_PARALLELISM: u32 = 1;
+const KDF_MAX_MEMORY_KIB: u32 = 256 * 1024;
+const KDF_MAX_ITERATIONS: u32 = 16;
+const KDF_MAX_PARALLELISM: u32 = 8;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleKdf {
+    algorithm: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleCipher {
+    algorithm: String,
+    nonce: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PresetBundleFile {
+    format: String,
+    version: u32,
+    created_at: String,
+    kdf: BundleKdf,
+    cipher: BundleCipher,
+    payload: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PresetBundlePayload {
+    #[serde(default)]
+    llm_presets: Option<Vec<LlmPreset>>,
+    #[serde(default)]
+    input_recording_presets: Option<Vec<InputRecordingPreset>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetExportResult {
+    pub path: String,
+    pub llm_count: usize,
+    pub input_recording_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetConflict {
+    pub kind: String,
+    pub imported_id: String,
+    pub imported_name: String,
+    pub existing_id: String,
+    pub existing_name: String,
+    pub matched_by: String,
+    pub resolution: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetMergeSummary {
+    pub added: usize,
+    pub overwritten: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetImportReport {
+    pub llm: Option<PresetMergeSummary>,
+    pub input_recording: Option<PresetMergeSummary>,
+    pub conflicts: Vec<PresetConflict>,
+}
+
+trait BundlePreset: Clone {
+    fn preset_id(&self) -> &str;
+    fn preset_name(&self) -> &str;
+    fn rename(&mut self, id: String, name: String);
+}
+
+impl BundlePreset for LlmPreset {
+    fn preset_id(&self) -> &str {
+        &self.id
+    }
+
+    fn preset_name(&self) -> &str {
+        &self.name
+    }
+
+    fn rename(&mut self, id: String, name: String) {
+        self.id = id;
+        self.name = name;
+    }
+}
+
+impl BundlePreset for InputRecordingPreset {
+    fn preset_id(&self) -> &str {
+        &self.id
+    }
+
+    fn preset_name(&self) -> &str {
+        &self.name
+    }
+
+    fn rename(&mut self, id: String, name: String) {
+        self.id = id;
+        self.name = name;
+    }
+}
+
+impl MergeStrategy {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "skip" => Ok(Self::Skip),
+            "overwrite" | "replace" => Ok(Self::Overwrite),
+            "keepboth" | "keep_both" | "rename" => Ok(Self::KeepBoth),
+            other => Err(format!(
+                "不支持的合并策略：{}（可选 skip / overwrite / keepBoth）",
+                other
+            )),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Skip => "skipped",
+            Self::Overwrite => "overwritten",
+            Self::KeepBoth => "renamed",
+        }
+    }
+}
+
+pub fn export_presets(
+    path: &str,
+    passphrase: &str,
+    kinds: &[String],
+) -> Result<PresetExportResult, String> {
+    validate_passphrase(passphrase)?;
+    let (include_llm, include_input) = parse_kinds(kinds)?;
+
+    let payload = PresetBundlePayload {
+        llm_presets: if include_llm {
+            Some(load_llm_presets()?)
+        } else {
+            None
+        },
+        input_recording_presets: if include_input {
+            Some(load_input_recording_presets()?)
+        } else {
+            None
+        },
+    };
+    let result = PresetExportResult {
+        path: path.to_string(),
+        llm_count: payload.llm_presets.as_ref().map_or(0, Vec::len),
+        input_recording_count: payload.input_recording_presets.as_ref().map_or(0, Vec::len),
+    };
+
+    let plain =
+        serde_json::to_vec(&payload).map_err(|error| format!("序列化预设失败：{}", error))?;
+    let bundle = seal_bundle(&plain, passphrase)?;
+    let serialized = serde_json::to_string_pretty(&bundle)
+        .map_err(|error| format!("序列化预设包失败：{}", error))?;
+
+    if let Some(parent) = Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)
+                .map_err(|error| format!("创建导出目录失败（{}）：{}", parent.display(), error))?;
+        }
+    }
+    std::fs::write(path, serialized)
+        .map_err(|error| format!("写入预设包失败（{}）：{}", path, error))?;
+
+    Ok(result)
+}
+
+pub fn import_presets(
+    path: &str,
+    passphrase: &str,
+    strategy: MergeStrategy,
+) -> Result<PresetImportReport, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|error| format!("读取预设包失败（{}）：{}", path, error))?;
+    let bundle = serde_json::from_str::<PresetBundleFile>(&raw)
+        .map_err(|error| format!("预设包格式无效：{}", error))?;
+    let plain = open_bundle(&bundle, passphrase)?;
+    let payload = serde_json::from_slice::<PresetBundlePayload>(&plain)
+        .map_err(|error| format!("解析预设包内容失败：{}", error))?;
+
+    let mut report = PresetImportReport::default();
+
+    if let Some(imported) = payload.llm_presets {
+        let (merged, summary) = merge_presets(
+            load_llm_presets()?,
+            imported,
+            strategy,
+            KIND_LLM,
+            &mut report.conflicts,
+        );
+        save_llm_presets(merged)?;
+        report.llm = Some(summary);
+    }
+
+    if let Some(imported) = payload.input_recording_presets {
+        let (merged, summary) = merge_presets(
+            load_input_recording_presets()?,
+            imported,
+            strategy,
+            KIND_INPUT_RECORDING,
+            &mut report.conflicts,
+        );
+        save_input_recording_presets(merged)?;
+        report.input_recording = Some(summary);
+    }
+
+    Ok(report)
+}
+
+fn parse_kinds(kinds: &[String]) -> Result<(bool, bool), String> {
+    if kinds.is_empty() {
+        return Ok((true, true));
+    }
+
+    let mut include_llm = false;
+    let mut include_input = false;
+    for kind in kinds {
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "llm" => include_llm = true,
+            "inputrecording" | "input_recording" => include_input = true,
+            other => return Err(format!("不支持的预设类型：{}", other)),
+        }
+    }
+    Ok((include_llm, include_input))
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(format!(
+            "导出口令至少需要 {} 个字符。",
+            MIN_PASSPHRASE_CHARS
+        ));
+    }
+    Ok(())
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+) -> Result<[u8; 32], String> {
+    let params = Params::new(memory_kib, iterations, parallelism, Some(32))
+        .map_err(|error| format!("Argon2 参数无效：{}", error))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|error| format!("派生加密密钥失败：{}", error))?;
+    Ok(key)
+}
+
+fn seal_bundle(plain: &[u8], passphrase: &str) -> Result<PresetBundleFile, String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(
+        passphrase,
+        &salt,
+        KDF_MEMORY_KIB,
+        KDF_ITERATIONS,
+        KDF_PARALLELISM,
+    )?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|error| error.to_string())?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let encrypted = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|_| "加密预设包失败。".to_string())?;
+
+    Ok(PresetBundleFile {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        created_at: Local::now().to_rfc3339(),
+        kdf: BundleKdf {
+            algorithm: "argon2id".to_string(),
+            memory_kib: KDF_MEMORY_KIB,
+            iterations: KDF_ITERATIONS,
+            parallelism: KDF_PARALLELISM,
+            salt: general_purpose::STANDARD.encode(salt),
+        },
+        cipher: BundleCipher {
+            algorithm: "aes-256-gcm".to_string(),
+            nonce: general_purpose::STANDARD.encode(nonce),
+        },
+        payload: general_purpose::STANDARD.encode(encrypted),
+    })
+}
+
+fn open_bundle(bundle: &PresetBundleFile, passphrase: &str) -> Result<Vec<u8>, String> {
+    if bundle.format != BUNDLE_FORMAT {
+        return Err("文件不是 CommandFlow 预设包。".to_string());
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "预设包版本 {} 高于当前支持的版本 {}，请升级应用。",
+            bundle.version, BUNDLE_VERSION
+        ));
+    }
+    if !bundle.kdf.algorithm.eq_ignore_ascii_case("argon2id")
+        || !bundle.cipher.algorithm.eq_ignore_ascii_case("aes-256-gcm")
+    {
+        return Err("预设包使用了不支持的加密算法。".to_string());
+    }
+    if bundle.kdf.memory_kib > KDF_MAX_MEMORY_KIB
+        || bundle.kdf.iterations > KDF_MAX_ITERATIONS
+        || bundle.kdf.parallelism > KDF_MAX_PARALLELISM
+    {
+        return Err("预设包的密钥派生参数超出允许范围。".to_string());
+    }
+
+    let decode = |field: &str, value: &str| {
+        general_purpose::STANDARD
+            .decode(value)
+            .map_err(|error| format!("预设包字段 {} 无效：{}", field, error))
+    };
+    let salt = decode("kdf.salt", &bundle.kdf.salt)?;
+    let nonce = decode("cipher.nonce", &bundle.cipher.nonce)?;
+    let payload = decode("payload", &bundle.payload)?;
+    if nonce.len() != NONCE_LEN {
+        return Err("预设包字段 cipher.nonce 长度无效。".to_string());
+    }
+
+    let key = derive_key(
+        passphrase,
+        &salt,
+        bundle.kdf.memory_kib,
+        bundle.kdf.iterations,
+        bundle.kdf.parallelism,
+    )?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|error| error.to_string())?;
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), payload.as_ref())
+        .map_err(|_| "解密失败：口令错误或文件已损坏。".to_string())
+}
+
+fn normalized_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn merge_presets<T: BundlePreset>(
+    existing: Vec<T>,
+    imported: Vec<T>,
+    strategy: MergeStrategy,
+    kind: &str,
+    conflicts: &mut Vec<PresetConflict>,
+) -> (Vec<T>, PresetMergeSummary) {
+    let mut merged = existing;
+    let mut summary = PresetMergeSummary::default();
+
+    for item in imported {
+        let matched = merged
+            .iter()
+            .position(|current| current.preset_id() == item.preset_id())
+            .map(|index| (index, "id"))
+            .or_else(|| {
+                let name = normalized_name(item.preset_name());
+                merged
+                    .iter()
+                    .position(|current| normalized_name(current.preset_name()) == name)
+                    .map(|index| (index, "name"))
+            });
+
+        let Some((index, matched_by)) = matched else {
+            merged.push(item);
+            summary.added += 1;
+            continue;
+        };
+
+        conflicts.push(PresetConflict {
+            kind: kind.to_string(),
+            imported_id: item.preset_id().to_string(),
+            imported_name: item.preset_name().to_string(),
+            existing_id: merged[index].preset_id().to_string(),
+            existing_name: merged[index].preset_name().to_string(),
+            matched_by: matched_by.to_string(),
+            resolution: strategy.label().to_string(),
+        });
+
+        match strategy {
+            MergeStrategy::Skip => summary.skipped += 1,
+            MergeStrategy::Overwrite => {
+                // 按名称命中时保留本地 id，避免工作流中引用的预设 id 失效
+                let mut replacement = item;
+                let keep_id = merged[index].preset_id().to_string();
+                let name = replacement.preset_name().to_string();
+                replacement.rename(keep_id, name);
+                merged[index] = replacement;
+                summary.overwritten += 1;
+            }
+            MergeStrategy::KeepBoth => {
+                let mut copy = item;
+                let id = unique_id(&merged, copy.preset_id());
+                let name = unique_name(&merged, copy.preset_name());
+                copy.rename(id, name);
+                merged.push(copy);
+                summary.renamed += 1;
+            }
+        }
+    }
+
+    (merged, summary)
+}
+
+fn unique_id<T: BundlePreset>(items: &[T], base: &str) -> String {
+    let base = if base.trim().is_empty() {
+        "preset"
+    } else {
+        base.trim()
+    };
+    (1..)
+        .map(|index| format!("{}-imported-{}", base, index))
+        .find(|candidate| items.iter().all(|item| item.preset_id() != candidate))
+        .unwrap_or_else(|| base.to_string())
+}
+
+fn unique_name<T: BundlePreset>(items: &[T], base: &str) -> String {
+    (1..)
+        .map(|index| {
+            if index == 1 {
+                format!("{}（导入）", base.trim())
+            } else {
+                format!("{}（导入 {}）", base.trim(), index)
+            }
+        })
+        .find(|candidate| {
+            let candidate = normalized_name(candidate);
+            items
+                .iter()
+                .all(|item| normalized_name(item.preset_name()) != candidate)
+        })
+        .unwrap_or_else(|| base.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_presets, open_bundle, seal_bundle, MergeStrategy, BUNDLE_FORMAT};
+    use crate::secure_settings::LlmPreset;
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    fn preset(id: &str, name: &str, model: &str) -> LlmPreset {
+        LlmPreset {
+            id: id.to_string(),
+            name: name.to_string(),
+            base_url: "https://api.example.com/v1".to_string(),
+            api_key: "sk-test".to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    fn summary_of(items: &[LlmPreset]) -> Vec<(String, String, String)> {
+        items
+            .iter()
+            .map(|item| (item.id.clone(), item.name.clone(), item.model.clone()))
+            .collect()
+    }
+
+    fn triple(id: &str, name: &str, model: &str) -> (String, String, String) {
+        (id.to_string(), name.to_string(), model.to_string())
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let plain = br#"{"llmPresets":[{"id":"a"}]}"#;
+        let bundle = seal_bundle(plain, PASSPHRASE).expect("seal");
+        assert_eq!(bundle.format, BUNDLE_FORMAT);
+        assert_ne!(bundle.payload.as_bytes(), plain.as_slice());
+
+        let opened = open_bundle(&bundle, PASSPHRASE).expect("open");
+        assert_eq!(opened, plain.to_vec());
+    }
+
+    #[test]
+    fn open_with_wrong_passphrase_fails() {
+        let bundle = seal_bundle(b"secret presets", PASSPHRASE).expect("seal");
+        let error = open_bundle(&bundle, "wrong passphrase").expect_err("must fail");
+        assert!(error.contains("口令错误"), "{error}");
+    }
+
+    #[test]
+    fn open_rejects_foreign_format_and_excessive_kdf_params() {
+        let mut bundle = seal_bundle(b"presets", PASSPHRASE).expect("seal");
+        bundle.format = "something-else".to_string();
+        assert!(open_bundle(&bundle, PASSPHRASE).is_err());
+
+        let mut bundle = seal_bundle(b"presets", PASSPHRASE).expect("seal");
+        bundle.kdf.memory_kib = u32::MAX;
+        let error = open_bundle(&bundle, PASSPHRASE).expect_err("must fail");
+        assert!(error.contains("超出允许范围"), "{error}");
+    }
+
+    #[test]
+    fn each_seal_uses_fresh_salt_and_nonce() {
+        let first = seal_bundle(b"presets", PASSPHRASE).expect("seal");
+        let second = seal_bundle(b"presets", PASSPHRASE).expect("seal");
+        assert_ne!(first.kdf.salt, second.kdf.salt);
+        assert_ne!(first.cipher.nonce, second.cipher.nonce);
+    }
+
+    #[test]
+    fn merge_skip_keeps_existing_and_reports_conflicts() {
+        let existing = vec![preset("a", "Work", "m1"), preset("b", "Home", "m1")];
+        let imported = vec![
+            preset("a", "Renamed", "m2"),
+            preset("x", " home ", "m2"),
+            preset("c", "New", "m2"),
+        ];
+        let mut conflicts = Vec::new();
+        let (merged, summary) = merge_presets(
+            existing,
+            imported,
+            MergeStrategy::Skip,
+            "llm",
+            &mut conflicts,
+        );
+
+        assert_eq!(
+            summary_of(&merged),
+            vec![
+                triple("a", "Work", "m1"),
+                triple("b", "Home", "m1"),
+                triple("c", "New", "m2"),
+            ]
+        );
+        assert_eq!((summary.added, summary.skipped), (1, 2));
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].matched_by, "id");
+        assert_eq!(conflicts[0].existing_name, "Work");
+        assert_eq!(conflicts[1].matched_by, "name");
+        assert_eq!(conflicts[1].existing_id, "b");
+        assert!(conflicts
+            .iter()
+            .all(|conflict| conflict.resolution == "skipped" && conflict.kind == "llm"));
+    }
+
+    #[test]
+    fn merge_overwrite_keeps_local_id_on_name_match() {
+        let existing = vec![preset("a", "Work", "m1"), preset("b", "Home", "m1")];
+        let imported = vec![preset("a", "Work 2", "m2"), preset("x", "HOME", "m2")];
+        let mut conflicts = Vec::new();
+        let (merged, summary) = merge_presets(
+            existing,
+            imported,
+            MergeStrategy::Overwrite,
+            "llm",
+            &mut conflicts,
+        );
+
+        assert_eq!(
+            summary_of(&merged),
+            vec![triple("a", "Work 2", "m2"), triple("b", "HOME", "m2")]
+        );
+        assert_eq!(summary.overwritten, 2);
+        assert!(conflicts
+            .iter()
+            .all(|conflict| conflict.resolution == "overwritten"));
+    }
+
+    #[test]
+    fn merge_keep_both_generates_unique_id_and_name() {
+        let existing = vec![
+            preset("a", "Work", "m1"),
+            preset("a-imported-1", "Work（导入）", "m1"),
+        ];
+        let imported = vec![preset("a", "Work", "m2")];
+        let mut conflicts = Vec::new();
+        let (merged, summary) = merge_presets(
+            existing,
+            imported,
+            MergeStrategy::KeepBoth,
+            "llm",
+            &mut conflicts,
+        );
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[2].id, "a-imported-2");
+        assert_eq!(merged[2].name, "Work（导入 2）");
+        assert_eq!(merged[2].model, "m2");
+        assert_eq!(summary.renamed, 1);
+        assert_eq!(conflicts[0].resolution, "renamed");
+    }
+
+    #[test]
+    fn merge_strategy_parse_accepts_aliases() {
+        assert_eq!(
+            MergeStrategy::parse("").expect("empty"),
+            MergeStrategy::Skip
+        );
+        assert_eq!(
+            MergeStrategy::parse("Replace").expect("replace"),
+            MergeStrategy::Overwrite
+        );
+        assert_eq!(
+            MergeStrategy::parse("keepBoth").expect("keepBoth"),
+            MergeStrategy::KeepBoth
+        );
+        assert!(MergeStrategy::parse("merge").is_err());
+    }
+}
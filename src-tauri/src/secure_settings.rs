@@ -16,6 +16,13 @@ pub struct LlmPreset {
     pub model: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretSummary {
+    pub name: String,
+    pub updated_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InputRecordingOptions {
@@ -232,6 +239,11 @@ fn open_connection() -> Result<Connection, String> {
             encrypted_value BLOB NOT NULL,
             updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
         );
+        CREATE TABLE IF NOT EXISTS secure_secrets (
+            secret_name TEXT PRIMARY KEY,
+            encrypted_value BLOB NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        );
         ",
     )
     .map_err(|error| format!("初始化共享设置数据库失败：{}", error))?;
@@ -346,6 +358,178 @@ fn decrypt_for_windows(_encrypted: &[u8]) -> Result<Vec<u8>, String> {
     Err("当前平台暂不支持 Windows DPAPI 解密。".to_string())
 }
 
+#[cfg(target_os = "windows")]
+fn encrypt_secret_value(plain: &[u8]) -> Result<Vec<u8>, String> {
+    encrypt_for_windows(plain)
+}
+
+#[cfg(target_os = "windows")]
+fn decrypt_secret_value(encrypted: &[u8]) -> Result<Vec<u8>, String> {
+    decrypt_for_windows(encrypted)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn secret_key_path() -> PathBuf {
+    shared_db_path().with_file_name("commandflow_secrets.key")
+}
+
+#[cfg(not(target_os = "windows"))]
+fn load_or_create_secret_key() -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::{KeyInit, OsRng};
+    use aes_gcm::Aes256Gcm;
+
+    let path = secret_key_path();
+    if let Ok(existing) = std::fs::read(&path) {
+        if existing.len() == 32 {
+            return Ok(existing);
+        }
+        return Err(format!("密钥文件已损坏（{}）。", path.display()));
+    }
+
+    ensure_parent_dir(&path)?;
+    let key = Aes256Gcm::generate_key(OsRng).to_vec();
+    std::fs::write(&path, &key)
+        .map_err(|error| format!("写入密钥文件失败（{}）：{}", path.display(), error))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(key)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn encrypt_secret_value(plain: &[u8]) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use aes_gcm::Aes256Gcm;
+
+    let key = load_or_create_secret_key()?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|error| error.to_string())?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let encrypted = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|_| "加密密钥值失败。".to_string())?;
+
+    let mut output = nonce.to_vec();
+    output.extend_from_slice(&encrypted);
+    Ok(output)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn decrypt_secret_value(encrypted: &[u8]) -> Result<Vec<u8>, String> {
+    use aes_gcm::aead::{Aead, KeyInit};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    if encrypted.len() < 12 {
+        return Err("密钥值数据已损坏。".to_string());
+    }
+    let key = load_or_create_secret_key()?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|error| error.to_string())?;
+    let (nonce, payload) = encrypted.split_at(12);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| "解密密钥值失败，密钥文件可能已更换。".to_string())
+}
+
+fn normalize_secret_name(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("密钥名称不能为空。".to_string());
+    }
+    if !trimmed
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        return Err(format!(
+            "密钥名称 '{}' 只能包含字母、数字、下划线、短横线和点。",
+            trimmed
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+pub fn list_secrets() -> Result<Vec<SecretSummary>, String> {
+    let conn = open_connection()?;
+    let mut statement = conn
+        .prepare("SELECT secret_name, updated_at FROM secure_secrets ORDER BY secret_name")
+        .map_err(|error| format!("读取密钥列表失败：{}", error))?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok(SecretSummary {
+                name: row.get(0)?,
+                updated_at: row.get(1)?,
+            })
+        })
+        .map_err(|error| format!("读取密钥列表失败：{}", error))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|error| format!("读取密钥列表失败：{}", error))
+}
+
+pub fn set_secret(name: &str, value: &str) -> Result<SecretSummary, String> {
+    let name = normalize_secret_name(name)?;
+    if value.is_empty() {
+        return Err("密钥值不能为空。".to_string());
+    }
+    let encrypted = encrypt_secret_value(value.as_bytes())?;
+
+    let conn = open_connection()?;
+    conn.execute(
+        "
+        INSERT INTO secure_secrets (secret_name, encrypted_value, updated_at)
+        VALUES (?1, ?2, CURRENT_TIMESTAMP)
+        ON CONFLICT(secret_name)
+        DO UPDATE SET encrypted_value = excluded.encrypted_value, updated_at = CURRENT_TIMESTAMP
+        ",
+        params![name, encrypted],
+    )
+    .map_err(|error| format!("保存密钥失败：{}", error))?;
+
+    let updated_at = conn
+        .query_row(
+            "SELECT updated_at FROM secure_secrets WHERE secret_name = ?1",
+            params![name],
+            |row| row.get::<_, String>(0),
+        )
+        .map_err(|error| format!("读取密钥失败：{}", error))?;
+
+    Ok(SecretSummary { name, updated_at })
+}
+
+pub fn delete_secret(name: &str) -> Result<bool, String> {
+    let name = normalize_secret_name(name)?;
+    let conn = open_connection()?;
+    let removed = conn
+        .execute(
+            "DELETE FROM secure_secrets WHERE secret_name = ?1",
+            params![name],
+        )
+        .map_err(|error| format!("删除密钥失败：{}", error))?;
+    Ok(removed > 0)
+}
+
+pub fn load_secret(name: &str) -> Result<Option<String>, String> {
+    let name = normalize_secret_name(name)?;
+    let conn = open_connection()?;
+    let encrypted = conn
+        .query_row(
+            "SELECT encrypted_value FROM secure_secrets WHERE secret_name = ?1",
+            params![name],
+            |row| row.get::<_, Vec<u8>>(0),
+        )
+        .optional()
+        .map_err(|error| format!("读取密钥失败：{}", error))?;
+
+    let Some(encrypted) = encrypted else {
+        return Ok(None);
+    };
+
+    let plain = decrypt_secret_value(&encrypted)?;
+    String::from_utf8(plain)
+        .map(Some)
+        .map_err(|error| format!("密钥 '{}' 不是有效文本：{}", name, error))
+}
+
 pub fn save_llm_presets(presets: Vec<LlmPreset>) -> Result<(), String> {
     let normalized = normalize_presets(presets);
     let serialized =
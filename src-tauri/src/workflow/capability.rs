@@ -0,0 +1,216 @@
+use crate::workflow::node::{NodeKind, WorkflowNode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Capability {
+    Input,
+    FileSystemWrite,
+    ProcessKill,
+    Power,
+    Network,
+    Shell,
+    Llm,
+}
+
+impl Capability {
+    pub fn key(self) -> &'static str {
+        match self {
+            Capability::Input => "input",
+            Capability::FileSystemWrite => "fileSystemWrite",
+            Capability::ProcessKill => "processKill",
+            Capability::Power => "power",
+            Capability::Network => "network",
+            Capability::Shell => "shell",
+            Capability::Llm => "llm",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Capability::Input => "键鼠输入",
+            Capability::FileSystemWrite => "文件写入",
+            Capability::ProcessKill => "结束进程",
+            Capability::Power => "电源操作",
+            Capability::Network => "网络开关",
+            Capability::Shell => "命令/程序执行",
+            Capability::Llm => "LLM 调用",
+        }
+    }
+}
+
+fn param_text(node: &WorkflowNode, key: &str, default: &str) -> String {
+    node.params
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or(default)
+        .chars()
+        .filter(|ch| *ch != '-' && *ch != '_' && !ch.is_whitespace())
+        .flat_map(|ch| ch.to_lowercase())
+        .collect()
+}
+
+fn system_operation_capability(operation: &str) -> Option<Capability> {
+    match operation {
+        "shutdown" | "restart" | "sleep" | "hibernate" | "lock" | "signout" => {
+            Some(Capability::Power)
+        }
+        "wifiswitch" | "bluetoothswitch" | "networkadapterswitch" => Some(Capability::Network),
+        "runcommand" => Some(Capability::Shell),
+        _ => None,
+    }
+}
+
+fn file_operation_writes(node: &WorkflowNode, operation: &str) -> bool {
+    // 预览模式只生成变更计划，不落盘
+    if matches!(
+        param_text(node, "safetyMode", "journal").as_str(),
+        "preview" | "plan"
+    ) {
+        return false;
+    }
+    !matches!(
+        operation,
+        "readtext" | "listfiles" | "fileinfo" | "waitforfile"
+    )
+}
+
+fn param_bool(node: &WorkflowNode, key: &str, default: bool) -> bool {
+    node.params
+        .get(key)
+        .and_then(Value::as_bool)
+        .unwrap_or(default)
+}
+
+fn when(condition: bool, capability: Capability) -> Vec<Capability> {
+    if condition {
+        vec![capability]
+    } else {
+        Vec::new()
+    }
+}
+
+// 每种节点都显式归类，不使用通配分支：新增节点若未在此处登记将无法编译
+pub fn required_capabilities(node: &WorkflowNode) -> Vec<Capability> {
+    match node.kind {
+        NodeKind::MouseOperation
+        | NodeKind::MouseClick
+        | NodeKind::MouseMove
+        | NodeKind::MouseDrag
+        | NodeKind::MouseWheel
+        | NodeKind::MouseDown
+        | NodeKind::MouseUp
+        | NodeKind::KeyboardOperation
+        | NodeKind::KeyboardKey
+        | NodeKind::KeyboardInput
+        | NodeKind::KeyboardDown
+        | NodeKind::KeyboardUp
+        | NodeKind::Shortcut
+        | NodeKind::InputPresetReplay => vec![Capability::Input],
+        // 找图/OCR/等待任一在命中后可直接点击
+        NodeKind::ImageMatch | NodeKind::OcrMatch | NodeKind::WaitAny => {
+            when(param_bool(node, "clickOnMatch", false), Capability::Input)
+        }
+        // 快捷键切换窗口会模拟按键
+        NodeKind::WindowActivate => when(
+            param_text(node, "switchMode", "title") == "shortcut",
+            Capability::Input,
+        ),
+        NodeKind::GuiAgent => vec![Capability::Llm, Capability::Input],
+        NodeKind::TerminateProcess => vec![Capability::ProcessKill],
+        NodeKind::LaunchApplication | NodeKind::RunCommand | NodeKind::PythonCode => {
+            vec![Capability::Shell]
+        }
+        NodeKind::PowerShutdown
+        | NodeKind::PowerRestart
+        | NodeKind::PowerSleep
+        | NodeKind::PowerHibernate
+        | NodeKind::PowerLock
+        | NodeKind::PowerSignOut => vec![Capability::Power],
+        NodeKind::SystemWifiSwitch
+        | NodeKind::SystemBluetoothSwitch
+        | NodeKind::SystemNetworkAdapterSwitch => vec![Capability::Network],
+        NodeKind::SystemOperation => {
+            system_operation_capability(&param_text(node, "operation", "shutdown"))
+                .into_iter()
+                .collect()
+        }
+        NodeKind::FileOperation => {
+            let operation = param_text(node, "operation", "copy");
+            when(
+                file_operation_writes(node, &operation),
+                Capability::FileSystemWrite,
+            )
+        }
+        NodeKind::FileCopy
+        | NodeKind::FileMove
+        | NodeKind::FileDelete
+        | NodeKind::FileWriteText
+        | NodeKind::ArchiveCreate
+        | NodeKind::ArchiveExtract => {
+            when(file_operation_writes(node, ""), Capability::FileSystemWrite)
+        }
+        NodeKind::Screenshot => when(
+            param_bool(node, "shouldSave", true),
+            Capability::FileSystemWrite,
+        ),
+        NodeKind::ImageOperation => when(
+            !param_text(node, "savePath", "").is_empty(),
+            Capability::FileSystemWrite,
+        ),
+        // 会录制基准图，失败时还会写出差异热力图
+        NodeKind::VisualAssert => vec![Capability::FileSystemWrite],
+        // UiaElement 只定位元素并输出坐标，点击由下游鼠标节点完成
+        NodeKind::Trigger
+        | NodeKind::HotkeyTrigger
+        | NodeKind::TimerTrigger
+        | NodeKind::ManualTrigger
+        | NodeKind::WindowTrigger
+        | NodeKind::UiaElement
+        | NodeKind::GetMousePosition
+        | NodeKind::ListFiles
+        | NodeKind::FileInfo
+        | NodeKind::WaitForFile
+        | NodeKind::FileReadText
+        | NodeKind::ClipboardRead
+        | NodeKind::ClipboardWrite
+        | NodeKind::ShowMessage
+        | NodeKind::Delay
+        | NodeKind::SystemVolumeMute
+        | NodeKind::SystemVolumeSet
+        | NodeKind::SystemVolumeAdjust
+        | NodeKind::SystemBrightnessSet
+        | NodeKind::SystemTheme
+        | NodeKind::SystemPowerPlan
+        | NodeKind::SystemOpenSettings
+        | NodeKind::GuiAgentActionParser
+        | NodeKind::Condition
+        | NodeKind::Loop
+        | NodeKind::WhileLoop
+        | NodeKind::TryCatch
+        | NodeKind::OcrRead
+        | NodeKind::PixelColor
+        | NodeKind::ScreenChange
+        | NodeKind::VarDefine
+        | NodeKind::VarSet
+        | NodeKind::VarMath
+        | NodeKind::VarGet
+        | NodeKind::ConstValue
+        | NodeKind::CurrentTime
+        | NodeKind::JsonExtract => Vec::new(),
+    }
+}
+
+pub fn requires_confirmation(node: &WorkflowNode) -> bool {
+    required_capabilities(node)
+        .iter()
+        .any(|capability| matches!(capability, Capability::Power | Capability::ProcessKill))
+}
+
+pub fn missing_capabilities(node: &WorkflowNode, declared: &[Capability]) -> Vec<Capability> {
+    required_capabilities(node)
+        .into_iter()
+        .filter(|capability| !declared.contains(capability))
+        .collect()
+}
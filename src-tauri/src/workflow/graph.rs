@@ -1,3 +1,4 @@
+use crate::workflow::capability::Capability;
 use crate::workflow::edge::WorkflowEdge;
 use crate::workflow::node::WorkflowNode;
 use serde::{Deserialize, Serialize};
@@ -8,4 +9,6 @@ pub struct WorkflowGraph {
     pub name: String,
     pub nodes: Vec<WorkflowNode>,
     pub edges: Vec<WorkflowEdge>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<Capability>>,
 }
@@ -1,3 +1,4 @@
+pub mod capability;
 pub mod edge;
 pub mod graph;
 pub mod node;
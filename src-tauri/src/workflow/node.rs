@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum NodeKind {
     Trigger,
@@ -34,10 +34,16 @@ pub enum NodeKind {
     FileCopy,
     FileMove,
     FileDelete,
+    ListFiles,
+    FileInfo,
+    WaitForFile,
+    ArchiveCreate,
+    ArchiveExtract,
     RunCommand,
     PythonCode,
     ClipboardRead,
     ClipboardWrite,
+    ImageOperation,
     FileReadText,
     FileWriteText,
     ShowMessage,
@@ -67,6 +73,11 @@ pub enum NodeKind {
     TryCatch,
     ImageMatch,
     OcrMatch,
+    OcrRead,
+    PixelColor,
+    ScreenChange,
+    WaitAny,
+    VisualAssert,
     VarDefine,
     VarSet,
     VarMath,
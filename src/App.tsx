@@ -21,6 +21,11 @@ import CoordinatePicker from "./components/CoordinatePicker";
 import InputRecorderCompactPanel from "./components/InputRecorderCompactPanel";
 import InputRecordingSettingsModal from "./components/InputRecordingSettingsModal";
 import LlmSettingsModal from "./components/LlmSettingsModal";
+import SecretsModal from "./components/SecretsModal";
+import WorkflowCapabilitiesModal from "./components/WorkflowCapabilitiesModal";
+import PresetTransferModal, {
+  type PresetTransferMode,
+} from "./components/PresetTransferModal";
 import { useWorkflowStore } from "./stores/workflowStore";
 import {
   useExecutionStore,
@@ -62,6 +67,7 @@ import {
   type WorkflowFailedEventDetail,
 } from "./utils/workflowCompletion";
 import { toBackendGraph } from "./utils/workflowBridge";
+import { CAPABILITY_OPTIONS, detectRequiredCapabilities } from "./utils/capabilities";
 import type { WorkflowFile, WorkflowNode } from "./types/workflow";
 
 const RIGHT_PANE_RATIO_STORAGE_KEY =
@@ -72,7 +78,7 @@ const menuGroups = {
   编辑: ["撤销", "重做", "复制", "粘贴"],
   视图: ["放大", "缩小", "重置缩放", "后台模式"],
   运行: ["运行", "停止", "单步", "拾取坐标", "提取元素"],
-  设置: ["LLM 预设", "键鼠预设"],
+  设置: ["LLM 预设", "键鼠预设", "密钥管理", "工作流权限", "导出预设", "导入预设"],
   帮助: ["文档", "快捷键"],
 };
 
@@ -155,6 +161,7 @@ const workflowNodeKinds = [
   "pythonCode",
   "clipboardRead",
   "clipboardWrite",
+  "imageOperation",
   "showMessage",
   "delay",
   "systemOperation",
@@ -166,6 +173,11 @@ const workflowNodeKinds = [
   "tryCatch",
   "imageMatch",
   "ocrMatch",
+  "ocrRead",
+  "pixelColor",
+  "screenChange",
+  "waitAny",
+  "visualAssert",
   "varDefine",
   "varSet",
   "varMath",
@@ -342,8 +354,14 @@ function App() {
     clearRunningNodes,
     setCursor,
   } = useWorkflowStore();
-  const { running, setRunning, addLog, setVariables, clearVariables } =
-    useExecutionStore();
+  const {
+    running,
+    setRunning,
+    addLog,
+    setVariables,
+    clearVariables,
+    setLastRunId,
+  } = useExecutionStore();
   const loadSecureLlmPresets = useSettingsStore(
     (state) => state.loadLlmPresets,
   );
@@ -365,6 +383,10 @@ function App() {
   const [helpModalOpen, setHelpModalOpen] = useState(false);
   const [helpType, setHelpType] = useState<"docs" | "shortcuts">("docs");
   const [llmSettingsOpen, setLlmSettingsOpen] = useState(false);
+  const [secretsOpen, setSecretsOpen] = useState(false);
+  const [capabilitiesOpen, setCapabilitiesOpen] = useState(false);
+  const [presetTransferMode, setPresetTransferMode] =
+    useState<PresetTransferMode | null>(null);
   const [inputRecordingSettingsOpen, setInputRecordingSettingsOpen] =
     useState(false);
   const [backgroundMode, setBackgroundModeState] = useState(false);
@@ -870,6 +892,19 @@ function App() {
     let unlistenNodeCompleted: (() => void) | null = null;
     let unlistenVariables: (() => void) | null = null;
     let unlistenLog: (() => void) | null = null;
+    let unlistenRunStarted: (() => void) | null = null;
+    void listen<{ run_id: string }>("workflow-run-started", (event) => {
+      const runId = event.payload?.run_id;
+      if (!runId) return;
+      setLastRunId(runId);
+    })
+      .then((cleanup) => {
+        unlistenRunStarted = cleanup;
+      })
+      .catch((error) => {
+        addLog("warn", `监听运行开始事件失败：${String(error)}`);
+      });
+
     void listen<WorkflowNodeEventPayload>("workflow-node-started", (event) => {
       const nodeId = event.payload?.node_id;
       if (!nodeId) return;
@@ -977,10 +1012,18 @@ function App() {
       unlistenNodeCompleted?.();
       unlistenVariables?.();
       unlistenLog?.();
+      unlistenRunStarted?.();
       clearRunningNodes();
       loopRoundRef.current.clear();
     };
-  }, [addLog, clearRunningNodes, setRunningNode, setSelectedNode, setVariables]);
+  }, [
+    addLog,
+    clearRunningNodes,
+    setLastRunId,
+    setRunningNode,
+    setSelectedNode,
+    setVariables,
+  ]);
 
   const handleFlowEditorPaneClick = useCallback(() => {
     setActiveMenu(null);
@@ -1142,12 +1185,44 @@ function App() {
     }
   };
 
+  // 未声明权限的工作流（如他人分享的文件）执行前先展示节点所需权限，确认后写入声明
+  const confirmUndeclaredCapabilities = useCallback(() => {
+    const { capabilities, nodes: workflowNodes, setCapabilities } = useWorkflowStore.getState();
+    if (capabilities) {
+      return true;
+    }
+
+    const required = detectRequiredCapabilities(workflowNodes);
+    if (required.length === 0) {
+      return true;
+    }
+
+    const labels = CAPABILITY_OPTIONS.filter((option) => required.includes(option.value))
+      .map((option) => option.label)
+      .join("、");
+    const confirmed = window.confirm(
+      `该工作流尚未声明权限，节点需要：${labels}。\n\n请确认工作流来源可信；确认后将按此声明权限并继续，取消则不执行。`,
+    );
+    if (!confirmed) {
+      addLog("warn", `已取消执行：工作流未声明所需权限（${labels}）。`);
+      return false;
+    }
+
+    setCapabilities(required);
+    addLog("info", `已按节点声明工作流权限：${labels}。`);
+    return true;
+  }, [addLog]);
+
   const runSingleStep = useCallback(async () => {
     if (running) {
       addLog("warn", "当前正在执行，请先停止后再进行单步。");
       return;
     }
 
+    if (!confirmUndeclaredCapabilities()) {
+      return;
+    }
+
     const resetStepSession = () => {
       stepNextNodeIdRef.current = null;
       clearRunningNodes();
@@ -1256,7 +1331,7 @@ function App() {
     try {
       addLog("info", `单步执行节点：${currentNode.data.label}`);
       setRunningNode(currentNode.id);
-      const message = await runWorkflow(toBackendGraph(stepFile));
+      const message = await runWorkflow(toBackendGraph(stepFile), useSettingsStore.getState().safeMode, useSettingsStore.getState().runRecording);
       updateStepContextAfterNode(currentNode, stepCtxRef.current);
       setVariables(Object.fromEntries(stepCtxRef.current.variables.entries()));
 
@@ -1319,6 +1394,7 @@ function App() {
     addLog,
     clearRunningNodes,
     clearVariables,
+    confirmUndeclaredCapabilities,
     edges,
     exportWorkflow,
     nodes,
@@ -1673,6 +1749,10 @@ function App() {
       return;
     }
 
+    if (!confirmUndeclaredCapabilities()) {
+      return;
+    }
+
     const selectedNode = nodes.find((node) => node.id === selectedNodeId);
     if (!selectedNode) {
       addLog("warn", "请先选中一个节点，再开始连续单步。");
@@ -1715,7 +1795,7 @@ function App() {
 
         addLog("info", `连续单步执行：${currentNode.data.label}`);
         setRunningNode(currentNode.id);
-        await runWorkflow(toBackendGraph(stepFile));
+        await runWorkflow(toBackendGraph(stepFile), useSettingsStore.getState().safeMode, useSettingsStore.getState().runRecording);
         updateStepContextAfterNode(currentNode, stepCtxRef.current);
         setVariables(
           Object.fromEntries(stepCtxRef.current.variables.entries()),
@@ -1768,6 +1848,7 @@ function App() {
     addLog,
     clearRunningNodes,
     clearVariables,
+    confirmUndeclaredCapabilities,
     exportWorkflow,
     nodes,
     pickNextNodeId,
@@ -2166,6 +2247,7 @@ function App() {
         break;
       case "运行":
         if (running) return;
+        if (!confirmUndeclaredCapabilities()) return;
         clearRunningNodes();
         stepNextNodeIdRef.current = null;
         stepCtxRef.current = {
@@ -2183,7 +2265,7 @@ function App() {
           const workflowFile = exportWorkflow();
           const graph = toBackendGraph(workflowFile);
           addLog("info", `开始执行：${workflowFile.graph.name}`);
-          const message = await runWorkflow(graph);
+          const message = await runWorkflow(graph, useSettingsStore.getState().safeMode, useSettingsStore.getState().runRecording);
           addLog("success", message);
           announceWorkflowCompleted({
             body: `${workflowFile.graph.name} 已执行完成。`,
@@ -2231,6 +2313,18 @@ function App() {
       case "键鼠预设":
         setInputRecordingSettingsOpen(true);
         break;
+      case "密钥管理":
+        setSecretsOpen(true);
+        break;
+      case "工作流权限":
+        setCapabilitiesOpen(true);
+        break;
+      case "导出预设":
+        setPresetTransferMode("export");
+        break;
+      case "导入预设":
+        setPresetTransferMode("import");
+        break;
       default:
         console.log(`点击了 ${item}`);
     }
@@ -2338,6 +2432,10 @@ function App() {
       return;
     }
 
+    if (!confirmUndeclaredCapabilities()) {
+      return;
+    }
+
     setPackageLaunching(true);
     try {
       const report = packageEnvironmentReport ?? (await refreshPackagingEnvironment());
@@ -2391,6 +2489,7 @@ function App() {
     }
   }, [
     addLog,
+    confirmUndeclaredCapabilities,
     exportWorkflow,
     isTauriRuntime,
     packageBuildConfig,
@@ -2958,6 +3057,15 @@ function App() {
         open={llmSettingsOpen}
         onClose={() => setLlmSettingsOpen(false)}
       />
+      <SecretsModal open={secretsOpen} onClose={() => setSecretsOpen(false)} />
+      <WorkflowCapabilitiesModal
+        open={capabilitiesOpen}
+        onClose={() => setCapabilitiesOpen(false)}
+      />
+      <PresetTransferModal
+        mode={presetTransferMode}
+        onClose={() => setPresetTransferMode(null)}
+      />
       <InputRecordingSettingsModal
         open={inputRecordingSettingsOpen}
         onClose={() => setInputRecordingSettingsOpen(false)}
@@ -231,6 +231,11 @@ function InnerFlowEditor({ onPaneClick }: { onPaneClick?: () => void }) {
       inputPresetReplay: InputPresetReplayNode,
       imageMatch: ImageMatchNode,
       ocrMatch: OcrMatchNode,
+      ocrRead: OcrMatchNode,
+      pixelColor: ImageMatchNode,
+      screenChange: ImageMatchNode,
+      waitAny: ImageMatchNode,
+      visualAssert: ImageMatchNode,
       condition: ConditionNode,
       loop: LoopNode,
       whileLoop: LoopNode,
@@ -250,6 +255,7 @@ function InnerFlowEditor({ onPaneClick }: { onPaneClick?: () => void }) {
       pythonCode: ClickNode,
       clipboardRead: ClickNode,
       clipboardWrite: ClickNode,
+      imageOperation: ClickNode,
       showMessage: ClickNode,
       delay: ClickNode,
       systemOperation: ClickNode,
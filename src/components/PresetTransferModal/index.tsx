@@ -0,0 +1,279 @@
+import { useEffect, useState } from 'react'
+import { open as openDialog, save as saveDialog } from '@tauri-apps/plugin-dialog'
+import { useSettingsStore } from '../../stores/settingsStore'
+import {
+  exportPresets,
+  importPresets,
+  type PresetBundleKind,
+  type PresetImportReportPayload,
+  type PresetMergeStrategy,
+} from '../../utils/execution'
+
+export type PresetTransferMode = 'export' | 'import'
+
+interface PresetTransferModalProps {
+  mode: PresetTransferMode | null
+  onClose: () => void
+}
+
+const BUNDLE_FILTERS = [{ name: 'CommandFlow Presets', extensions: ['cfpreset'] }]
+const MIN_PASSPHRASE_LENGTH = 8
+
+const KIND_OPTIONS: Array<{ label: string; value: PresetBundleKind }> = [
+  { label: 'LLM 预设', value: 'llm' },
+  { label: '键鼠预设', value: 'inputRecording' },
+]
+
+const MERGE_STRATEGY_OPTIONS: Array<{ label: string; value: PresetMergeStrategy; description: string }> = [
+  { label: '跳过冲突项', value: 'skip', description: '保留本机已有预设，不导入同 id / 同名的预设。' },
+  { label: '覆盖本机', value: 'overwrite', description: '用导入的预设替换同 id / 同名的本机预设。' },
+  { label: '两者都保留', value: 'keepBoth', description: '冲突项以“（导入）”后缀另存为新预设。' },
+]
+
+const KIND_LABELS: Record<PresetBundleKind, string> = {
+  llm: 'LLM 预设',
+  inputRecording: '键鼠预设',
+}
+
+const RESOLUTION_LABELS: Record<string, string> = {
+  skipped: '已跳过',
+  overwritten: '已覆盖',
+  renamed: '已另存',
+}
+
+const inputClassName =
+  'w-full rounded-xl border border-slate-200 bg-white px-3 py-2 text-sm shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900'
+
+export default function PresetTransferModal({ mode, onClose }: PresetTransferModalProps) {
+  const reloadLlmPresets = useSettingsStore((state) => state.loadLlmPresets)
+  const reloadInputRecordingPresets = useSettingsStore((state) => state.loadInputRecordingPresets)
+
+  const [path, setPath] = useState('')
+  const [passphrase, setPassphrase] = useState('')
+  const [confirmPassphrase, setConfirmPassphrase] = useState('')
+  const [kinds, setKinds] = useState<PresetBundleKind[]>(['llm', 'inputRecording'])
+  const [mergeStrategy, setMergeStrategy] = useState<PresetMergeStrategy>('skip')
+  const [busy, setBusy] = useState(false)
+  const [error, setError] = useState('')
+  const [resultText, setResultText] = useState('')
+  const [importReport, setImportReport] = useState<PresetImportReportPayload | null>(null)
+
+  useEffect(() => {
+    if (!mode) return
+    setPath('')
+    setPassphrase('')
+    setConfirmPassphrase('')
+    setError('')
+    setResultText('')
+    setImportReport(null)
+  }, [mode])
+
+  if (!mode) return null
+
+  const isExport = mode === 'export'
+  const passphraseTooShort = passphrase.length > 0 && passphrase.length < MIN_PASSPHRASE_LENGTH
+  const passphraseMismatch = isExport && confirmPassphrase.length > 0 && confirmPassphrase !== passphrase
+  const canSubmit =
+    !busy &&
+    path.trim().length > 0 &&
+    passphrase.length >= MIN_PASSPHRASE_LENGTH &&
+    (!isExport || (confirmPassphrase === passphrase && kinds.length > 0))
+
+  const pickPath = async () => {
+    const selected = isExport
+      ? await saveDialog({ defaultPath: 'commandflow-presets.cfpreset', filters: BUNDLE_FILTERS })
+      : await openDialog({ multiple: false, filters: BUNDLE_FILTERS })
+    if (!selected || Array.isArray(selected)) return
+    setPath(selected)
+  }
+
+  const handleSubmit = async () => {
+    if (!canSubmit) return
+    setBusy(true)
+    setError('')
+    setResultText('')
+    setImportReport(null)
+    try {
+      if (isExport) {
+        const result = await exportPresets(path.trim(), passphrase, kinds)
+        setResultText(`已导出 ${result.llmCount} 个 LLM 预设、${result.inputRecordingCount} 个键鼠预设。`)
+      } else {
+        const report = await importPresets(path.trim(), passphrase, mergeStrategy)
+        setImportReport(report)
+        await Promise.all([reloadLlmPresets(), reloadInputRecordingPresets()])
+        setResultText('导入完成，预设列表已刷新。')
+      }
+      setPassphrase('')
+      setConfirmPassphrase('')
+    } catch (reason) {
+      setError(String(reason))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const renderSummary = (label: string, summary: PresetImportReportPayload['llm']) =>
+    summary ? (
+      <p className="text-[12px] text-slate-600 dark:text-slate-300">
+        {label}：新增 {summary.added}，覆盖 {summary.overwritten}，另存 {summary.renamed}，跳过 {summary.skipped}
+      </p>
+    ) : null
+
+  return (
+    <div
+      className="fixed inset-0 z-[360] flex items-center justify-center bg-black/55 backdrop-blur-sm"
+      onClick={(event) => {
+        if (event.target === event.currentTarget && !busy) {
+          onClose()
+        }
+      }}
+    >
+      <div className="flex max-h-[78vh] w-[560px] max-w-[92vw] flex-col overflow-hidden rounded-2xl border border-slate-200 bg-white shadow-2xl dark:border-neutral-700 dark:bg-neutral-900">
+        <div className="flex items-center justify-between border-b border-slate-200 px-6 py-4 dark:border-neutral-800">
+          <h3 className="text-sm font-bold text-slate-700 dark:text-slate-200">{isExport ? '导出预设' : '导入预设'}</h3>
+          <button
+            type="button"
+            onClick={onClose}
+            disabled={busy}
+            className="rounded-lg p-1.5 text-slate-400 transition-colors hover:bg-slate-100 hover:text-slate-700 dark:hover:bg-neutral-800"
+          >
+            <svg className="h-5 w-5" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+              <path d="M18 6L6 18M6 6l12 12" strokeLinecap="round" strokeLinejoin="round" />
+            </svg>
+          </button>
+        </div>
+
+        <div className="flex-1 space-y-4 overflow-y-auto p-6">
+          <p className="text-[11px] text-slate-500 dark:text-slate-400">
+            {isExport
+              ? '预设包使用口令加密（Argon2 + AES-GCM），可在其他电脑或同事之间共享。请通过其他渠道告知口令。'
+              : '导入时按 id 或名称匹配本机已有预设，冲突项按所选策略处理。'}
+          </p>
+
+          <div className="space-y-1.5">
+            <label className="text-[11px] font-semibold text-slate-500 dark:text-slate-400">预设包文件</label>
+            <div className="flex items-center gap-2">
+              <input
+                type="text"
+                value={path}
+                onChange={(event) => setPath(event.target.value)}
+                className={inputClassName}
+                placeholder={isExport ? '选择保存位置' : '选择要导入的 .cfpreset 文件'}
+              />
+              <button
+                type="button"
+                onClick={() => void pickPath()}
+                className="shrink-0 rounded-full border border-slate-300 px-4 py-2 text-xs font-semibold text-slate-600 transition-colors hover:border-cyan-400 hover:text-cyan-600 dark:border-neutral-600 dark:text-slate-300"
+              >
+                浏览…
+              </button>
+            </div>
+          </div>
+
+          {isExport ? (
+            <div className="space-y-1.5">
+              <label className="text-[11px] font-semibold text-slate-500 dark:text-slate-400">导出内容</label>
+              <div className="flex gap-4">
+                {KIND_OPTIONS.map((option) => (
+                  <label key={option.value} className="flex items-center gap-2 text-xs text-slate-600 dark:text-slate-300">
+                    <input
+                      type="checkbox"
+                      checked={kinds.includes(option.value)}
+                      onChange={(event) =>
+                        setKinds((state) =>
+                          event.target.checked ? [...state, option.value] : state.filter((item) => item !== option.value),
+                        )
+                      }
+                    />
+                    {option.label}
+                  </label>
+                ))}
+              </div>
+            </div>
+          ) : (
+            <div className="space-y-1.5">
+              <label className="text-[11px] font-semibold text-slate-500 dark:text-slate-400">冲突处理</label>
+              <select
+                value={mergeStrategy}
+                onChange={(event) => setMergeStrategy(event.target.value as PresetMergeStrategy)}
+                className={inputClassName}
+              >
+                {MERGE_STRATEGY_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+              </select>
+              <p className="text-[11px] text-slate-400">
+                {MERGE_STRATEGY_OPTIONS.find((option) => option.value === mergeStrategy)?.description}
+              </p>
+            </div>
+          )}
+
+          <div className="space-y-1.5">
+            <label className="text-[11px] font-semibold text-slate-500 dark:text-slate-400">口令</label>
+            <input
+              type="password"
+              value={passphrase}
+              autoComplete="new-password"
+              onChange={(event) => setPassphrase(event.target.value)}
+              className={inputClassName}
+              placeholder={`至少 ${MIN_PASSPHRASE_LENGTH} 个字符`}
+            />
+            {passphraseTooShort ? (
+              <p className="text-[11px] text-rose-500">口令至少需要 {MIN_PASSPHRASE_LENGTH} 个字符。</p>
+            ) : null}
+          </div>
+
+          {isExport ? (
+            <div className="space-y-1.5">
+              <label className="text-[11px] font-semibold text-slate-500 dark:text-slate-400">确认口令</label>
+              <input
+                type="password"
+                value={confirmPassphrase}
+                autoComplete="new-password"
+                onChange={(event) => setConfirmPassphrase(event.target.value)}
+                className={inputClassName}
+              />
+              {passphraseMismatch ? <p className="text-[11px] text-rose-500">两次输入的口令不一致。</p> : null}
+            </div>
+          ) : null}
+
+          {error ? <p className="text-[11px] text-rose-500">{error}</p> : null}
+          {resultText ? <p className="text-[12px] text-emerald-600 dark:text-emerald-400">{resultText}</p> : null}
+
+          {importReport ? (
+            <div className="space-y-2 rounded-xl border border-slate-200 p-3 dark:border-neutral-700">
+              {renderSummary('LLM 预设', importReport.llm)}
+              {renderSummary('键鼠预设', importReport.inputRecording)}
+              {importReport.conflicts.length > 0 ? (
+                <div className="space-y-1">
+                  <p className="text-[11px] font-semibold text-amber-600 dark:text-amber-400">
+                    冲突 {importReport.conflicts.length} 项：
+                  </p>
+                  {importReport.conflicts.map((conflict, index) => (
+                    <p key={`${conflict.kind}-${conflict.importedId}-${index}`} className="text-[11px] text-slate-500 dark:text-slate-400">
+                      [{KIND_LABELS[conflict.kind] ?? conflict.kind}] {conflict.importedName} ↔ 本机 {conflict.existingName}
+                      （按{conflict.matchedBy === 'id' ? ' id ' : '名称'}匹配，{RESOLUTION_LABELS[conflict.resolution] ?? conflict.resolution}）
+                    </p>
+                  ))}
+                </div>
+              ) : null}
+            </div>
+          ) : null}
+        </div>
+
+        <div className="flex justify-end border-t border-slate-200 px-6 py-4 dark:border-neutral-800">
+          <button
+            type="button"
+            disabled={!canSubmit}
+            onClick={() => void handleSubmit()}
+            className="rounded-full bg-cyan-600 px-5 py-2 text-xs font-semibold text-white transition-colors hover:bg-cyan-500 disabled:cursor-not-allowed disabled:opacity-50"
+          >
+            {busy ? '处理中…' : isExport ? '导出' : '导入'}
+          </button>
+        </div>
+      </div>
+    </div>
+  )
+}
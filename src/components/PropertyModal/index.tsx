@@ -96,11 +96,11 @@ const isVariableNameField = (kind: NodeKind, fieldKey: string) =>
   (kind === 'varDefine' || kind === 'varSet' || kind === 'varMath') && fieldKey === 'name'
 
 const isInputVariableField = (kind: NodeKind, fieldKey: string) =>
-  ((kind === 'clipboardWrite' || kind === 'fileOperation' || kind === 'showMessage') && fieldKey === 'inputVar') ||
+  ((kind === 'clipboardWrite' || kind === 'fileOperation' || kind === 'showMessage' || kind === 'imageOperation') && fieldKey === 'inputVar') ||
   (kind === 'clipboardWrite' && fieldKey === 'imageVar')
 
 const isOutputVariableField = (kind: NodeKind, fieldKey: string) =>
-  ((kind === 'clipboardRead' || kind === 'fileOperation') && fieldKey === 'outputVar') ||
+  ((kind === 'clipboardRead' || kind === 'fileOperation' || kind === 'imageOperation') && fieldKey === 'outputVar') ||
   (kind === 'clipboardRead' && (fieldKey === 'outputTextVar' || fieldKey === 'outputImageVar'))
 
 const isFilePathField = (kind: NodeKind, fieldKey: string) => {
@@ -110,12 +110,18 @@ const isFilePathField = (kind: NodeKind, fieldKey: string) => {
   if (kind === 'imageMatch' && (fieldKey === 'sourcePath' || fieldKey === 'templatePath')) {
     return true
   }
+  if (kind === 'visualAssert' && fieldKey === 'baselinePath') {
+    return true
+  }
   if (kind === 'screenshot' && fieldKey === 'saveDir') {
     return true
   }
   if (kind === 'clipboardWrite' && fieldKey === 'imagePath') {
     return true
   }
+  if (kind === 'imageOperation' && (fieldKey === 'source' || fieldKey === 'savePath')) {
+    return true
+  }
   return false
 }
 
@@ -128,6 +134,9 @@ const isTextFilePathField = (kind: NodeKind, fieldKey: string, params: Record<st
 const isClipboardImagePathField = (kind: NodeKind, fieldKey: string) =>
   kind === 'clipboardWrite' && fieldKey === 'imagePath'
 
+const isImageOperationSourceField = (kind: NodeKind, fieldKey: string) =>
+  kind === 'imageOperation' && fieldKey === 'source'
+
 const IMAGE_FILE_FILTERS = [
   { name: '图片文件', extensions: ['png', 'jpg', 'jpeg', 'bmp', 'webp'] },
 ]
@@ -744,13 +753,16 @@ export default function PropertyModal({ open, onClose }: PropertyModalProps) {
                       : (
                     isImageMatchImageField(selectedNode.data.kind, field.key) ||
                     isTextFilePathField(selectedNode.data.kind, field.key, selectedNode.data.params) ||
-                    isClipboardImagePathField(selectedNode.data.kind, field.key)
+                    isClipboardImagePathField(selectedNode.data.kind, field.key) ||
+                    isImageOperationSourceField(selectedNode.data.kind, field.key)
                       ? 'file'
                       : 'menu'
                       )
                   }
                   filters={
-                    isImageMatchImageField(selectedNode.data.kind, field.key) || isClipboardImagePathField(selectedNode.data.kind, field.key)
+                    isImageMatchImageField(selectedNode.data.kind, field.key) ||
+                    isClipboardImagePathField(selectedNode.data.kind, field.key) ||
+                    isImageOperationSourceField(selectedNode.data.kind, field.key)
                       ? IMAGE_FILE_FILTERS
                       : undefined
                   }
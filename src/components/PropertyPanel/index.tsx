@@ -2,7 +2,7 @@ import { useEffect, useMemo, useState } from 'react'
 import { useWorkflowStore } from '../../stores/workflowStore'
 import { useSettingsStore } from '../../stores/settingsStore'
 import { getKeyboardOperationKind, getNodeFields, getNodeMeta, getSystemOperationKind, getTriggerMode, type ParamField } from '../../utils/nodeMeta'
-import { listOpenWindowEntries, listRunningProcesses, listStartMenuApps, type OpenWindowEntryPayload, type RunningProcessEntryPayload, type StartMenuAppPayload } from '../../utils/execution'
+import { getSensitiveParamRules, isSensitiveParam, listOpenWindowEntries, listRunningProcesses, listStartMenuApps, type OpenWindowEntryPayload, type RunningProcessEntryPayload, type SensitiveParamRulesPayload, type StartMenuAppPayload } from '../../utils/execution'
 import { COMMAND_FLOW_REFRESH_ALL_EVENT } from '../../utils/refresh'
 import type { NodeKind } from '../../types/workflow'
 import { buildLaunchApplicationParams } from '../../utils/startMenuApp'
@@ -95,11 +95,11 @@ const isVariableNameField = (kind: NodeKind, fieldKey: string) =>
   (kind === 'varDefine' || kind === 'varSet' || kind === 'varMath' || kind === 'varGet') && fieldKey === 'name'
 
 const isInputVariableField = (kind: NodeKind, fieldKey: string) =>
-  ((kind === 'clipboardWrite' || kind === 'fileOperation' || kind === 'showMessage') && fieldKey === 'inputVar') ||
+  ((kind === 'clipboardWrite' || kind === 'fileOperation' || kind === 'showMessage' || kind === 'imageOperation') && fieldKey === 'inputVar') ||
   (kind === 'clipboardWrite' && fieldKey === 'imageVar')
 
 const isOutputVariableField = (kind: NodeKind, fieldKey: string) =>
-  ((kind === 'clipboardRead' || kind === 'fileOperation') && fieldKey === 'outputVar') ||
+  ((kind === 'clipboardRead' || kind === 'fileOperation' || kind === 'imageOperation') && fieldKey === 'outputVar') ||
   (kind === 'clipboardRead' && (fieldKey === 'outputTextVar' || fieldKey === 'outputImageVar'))
 
 const isFilePathField = (kind: NodeKind, fieldKey: string) => {
@@ -112,12 +112,18 @@ const isFilePathField = (kind: NodeKind, fieldKey: string) => {
   if (kind === 'ocrMatch' && fieldKey === 'sourcePath') {
     return true
   }
+  if (kind === 'visualAssert' && fieldKey === 'baselinePath') {
+    return true
+  }
   if (kind === 'screenshot' && fieldKey === 'saveDir') {
     return true
   }
   if (kind === 'clipboardWrite' && fieldKey === 'imagePath') {
     return true
   }
+  if (kind === 'imageOperation' && (fieldKey === 'source' || fieldKey === 'savePath')) {
+    return true
+  }
   return false
 }
 
@@ -132,6 +138,9 @@ const isTextFilePathField = (kind: NodeKind, fieldKey: string, params: Record<st
 const isClipboardImagePathField = (kind: NodeKind, fieldKey: string) =>
   kind === 'clipboardWrite' && fieldKey === 'imagePath'
 
+const isImageOperationSourceField = (kind: NodeKind, fieldKey: string) =>
+  kind === 'imageOperation' && fieldKey === 'source'
+
 const IMAGE_FILE_FILTERS = [
   { name: '图片文件', extensions: ['png', 'jpg', 'jpeg', 'bmp', 'webp'] },
 ]
@@ -167,6 +176,7 @@ export default function PropertyPanel({ expanded, onToggle }: PropertyPanelProps
   const [openWindows, setOpenWindows] = useState<OpenWindowEntryPayload[]>([])
   const [runningProcesses, setRunningProcesses] = useState<RunningProcessEntryPayload[]>([])
   const [startMenuApps, setStartMenuApps] = useState<StartMenuAppPayload[]>([])
+  const [sensitiveParamRules, setSensitiveParamRules] = useState<SensitiveParamRulesPayload | null>(null)
 
   const variableNames = useMemo(
     () =>
@@ -290,6 +300,17 @@ export default function PropertyPanel({ expanded, onToggle }: PropertyPanelProps
     }
   }, [expanded, selectedNode])
 
+  useEffect(() => {
+    let cancelled = false
+    void getSensitiveParamRules().then((rules) => {
+      if (!cancelled) setSensitiveParamRules(rules)
+    })
+
+    return () => {
+      cancelled = true
+    }
+  }, [])
+
   useEffect(() => {
     if (!selectedNode || !expanded) return
 
@@ -486,7 +507,7 @@ export default function PropertyPanel({ expanded, onToggle }: PropertyPanelProps
 
     if (selectedNode.data.kind === 'varDefine' || selectedNode.data.kind === 'varSet' || selectedNode.data.kind === 'constValue') {
       const valueType = String(nextParams.valueType ?? 'number')
-      if (valueType === 'string') {
+      if (valueType === 'string' || valueType === 'secret') {
         nextParams.value = String(nextParams.valueString ?? '')
       } else if (valueType === 'number') {
         nextParams.value = Number(nextParams.valueNumber ?? 0)
@@ -721,7 +742,8 @@ export default function PropertyPanel({ expanded, onToggle }: PropertyPanelProps
                     isImageMatchImageField(selectedNode.data.kind, field.key) ||
                     isOcrMatchImageField(selectedNode.data.kind, field.key) ||
                     isTextFilePathField(selectedNode.data.kind, field.key, selectedNode.data.params) ||
-                    isClipboardImagePathField(selectedNode.data.kind, field.key)
+                    isClipboardImagePathField(selectedNode.data.kind, field.key) ||
+                    isImageOperationSourceField(selectedNode.data.kind, field.key)
                       ? 'file'
                       : 'menu'
                       )
@@ -729,7 +751,8 @@ export default function PropertyPanel({ expanded, onToggle }: PropertyPanelProps
                   filters={
                     isImageMatchImageField(selectedNode.data.kind, field.key) ||
                     isOcrMatchImageField(selectedNode.data.kind, field.key) ||
-                    isClipboardImagePathField(selectedNode.data.kind, field.key)
+                    isClipboardImagePathField(selectedNode.data.kind, field.key) ||
+                    isImageOperationSourceField(selectedNode.data.kind, field.key)
                       ? IMAGE_FILE_FILTERS
                       : undefined
                   }
@@ -785,7 +808,7 @@ export default function PropertyPanel({ expanded, onToggle }: PropertyPanelProps
 
         return (
           <input
-            type="text"
+            type={field.key === 'valueString' && selectedNode.data.params.valueType === 'secret' ? 'password' : 'text'}
             value={String(currentValue ?? '')}
             placeholder={field.placeholder}
             disabled={isGuiAgentImageInputDisabled}
@@ -866,6 +889,9 @@ export default function PropertyPanel({ expanded, onToggle }: PropertyPanelProps
                       {field.description ? (
                         <p className="text-[11px] text-slate-400 dark:text-slate-500">{field.description}</p>
                       ) : null}
+                      {isSensitiveParam(sensitiveParamRules, selectedNode.data.kind, field.key, selectedNode.data.params) ? (
+                        <p className="text-[11px] text-amber-600 dark:text-amber-400">敏感参数：执行日志与事件中将以 ****** 显示。</p>
+                      ) : null}
                     </div>
                   ))}
                   {selectedNode.data.kind === 'guiAgent' && !Boolean(selectedNode.data.params.continuousMode ?? selectedMeta?.defaultParams.continuousMode ?? true) ? (
@@ -0,0 +1,177 @@
+import { useCallback, useEffect, useState } from 'react'
+import { deleteSecret, listSecrets, setSecret, type SecretSummaryPayload } from '../../utils/execution'
+
+interface SecretsModalProps {
+  open: boolean
+  onClose: () => void
+}
+
+const SECRET_NAME_PATTERN = /^[A-Za-z0-9_.-]+$/
+
+export default function SecretsModal({ open, onClose }: SecretsModalProps) {
+  const [secrets, setSecrets] = useState<SecretSummaryPayload[]>([])
+  const [name, setName] = useState('')
+  const [value, setValue] = useState('')
+  const [error, setError] = useState('')
+  const [saveHint, setSaveHint] = useState('')
+  const [busy, setBusy] = useState(false)
+
+  const refresh = useCallback(async () => {
+    try {
+      setSecrets(await listSecrets())
+      setError('')
+    } catch (reason) {
+      setError(`密钥列表读取失败：${String(reason)}`)
+    }
+  }, [])
+
+  useEffect(() => {
+    if (!open) return
+    setName('')
+    setValue('')
+    void refresh()
+  }, [open, refresh])
+
+  useEffect(() => {
+    if (!saveHint) return
+    const timer = window.setTimeout(() => setSaveHint(''), 1800)
+    return () => window.clearTimeout(timer)
+  }, [saveHint])
+
+  if (!open) return null
+
+  const trimmedName = name.trim()
+  const nameValid = SECRET_NAME_PATTERN.test(trimmedName)
+
+  const handleSave = async () => {
+    if (!nameValid || value.length === 0) return
+    setBusy(true)
+    try {
+      await setSecret(trimmedName, value)
+      setValue('')
+      setSaveHint(`已保存密钥 ${trimmedName}`)
+      await refresh()
+    } catch (reason) {
+      setError(`保存失败：${String(reason)}`)
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const handleDelete = async (secretName: string) => {
+    if (!window.confirm(`确定删除密钥「${secretName}」吗？引用它的工作流将无法执行。`)) return
+    try {
+      await deleteSecret(secretName)
+      await refresh()
+    } catch (reason) {
+      setError(`删除失败：${String(reason)}`)
+    }
+  }
+
+  return (
+    <div
+      className="fixed inset-0 z-[360] flex items-center justify-center bg-black/55 backdrop-blur-sm"
+      onClick={(event) => {
+        if (event.target === event.currentTarget) {
+          onClose()
+        }
+      }}
+    >
+      <div className="flex max-h-[78vh] w-[640px] max-w-[92vw] flex-col overflow-hidden rounded-2xl border border-slate-200 bg-white shadow-2xl dark:border-neutral-700 dark:bg-neutral-900">
+        <div className="flex items-center justify-between border-b border-slate-200 px-6 py-4 dark:border-neutral-800">
+          <h3 className="text-sm font-bold text-slate-700 dark:text-slate-200">密钥管理</h3>
+          <button
+            type="button"
+            onClick={onClose}
+            className="rounded-lg p-1.5 text-slate-400 transition-colors hover:bg-slate-100 hover:text-slate-700 dark:hover:bg-neutral-800"
+          >
+            <svg className="h-5 w-5" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+              <path d="M18 6L6 18M6 6l12 12" strokeLinecap="round" strokeLinejoin="round" />
+            </svg>
+          </button>
+        </div>
+
+        <div className="flex-1 space-y-5 overflow-y-auto p-6">
+          <p className="text-[11px] text-slate-500 dark:text-slate-400">
+            密钥经系统加密保存在本机，仅在执行时解析。在节点参数中使用{' '}
+            <code className="rounded bg-slate-100 px-1 font-mono text-cyan-600 dark:bg-neutral-800 dark:text-cyan-400">
+              {'{{secret:NAME}}'}
+            </code>{' '}
+            引用，工作流文件中不会保存明文。
+          </p>
+
+          <div className="space-y-3 rounded-xl border border-slate-200 p-4 dark:border-neutral-700">
+            <div className="space-y-1.5">
+              <label className="text-[11px] font-semibold text-slate-500 dark:text-slate-400">密钥名称</label>
+              <input
+                type="text"
+                value={name}
+                onChange={(event) => setName(event.target.value)}
+                className="w-full rounded-xl border border-slate-200 bg-white px-3 py-2 font-mono text-sm shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900"
+                placeholder="OPENAI_KEY"
+              />
+              {trimmedName && !nameValid ? (
+                <p className="text-[11px] text-rose-500">名称仅支持字母、数字、下划线、短横线和点。</p>
+              ) : null}
+            </div>
+            <div className="space-y-1.5">
+              <label className="text-[11px] font-semibold text-slate-500 dark:text-slate-400">密钥值</label>
+              <input
+                type="password"
+                value={value}
+                autoComplete="new-password"
+                onChange={(event) => setValue(event.target.value)}
+                className="w-full rounded-xl border border-slate-200 bg-white px-3 py-2 text-sm shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900"
+                placeholder="输入后保存，保存后不可查看"
+              />
+            </div>
+            <div className="flex items-center justify-between">
+              {saveHint ? <p className="text-[12px] text-emerald-600 dark:text-emerald-400">{saveHint}</p> : <span />}
+              <button
+                type="button"
+                disabled={busy || !nameValid || value.length === 0}
+                onClick={() => void handleSave()}
+                className="rounded-full bg-cyan-600 px-5 py-2 text-xs font-semibold text-white transition-colors hover:bg-cyan-500 disabled:cursor-not-allowed disabled:opacity-50"
+              >
+                {secrets.some((item) => item.name === trimmedName) ? '更新密钥' : '保存密钥'}
+              </button>
+            </div>
+          </div>
+
+          {error ? <p className="text-[11px] text-rose-500">{error}</p> : null}
+
+          {secrets.length === 0 ? (
+            <div className="rounded-xl border border-dashed border-slate-300 p-8 text-center text-sm text-slate-400 dark:border-neutral-700">
+              暂无已保存的密钥。
+            </div>
+          ) : (
+            <div className="space-y-1.5">
+              {secrets.map((secret) => (
+                <div
+                  key={secret.name}
+                  className="flex items-center justify-between rounded-xl border border-slate-200 px-3 py-2 dark:border-neutral-700"
+                >
+                  <button
+                    type="button"
+                    onClick={() => setName(secret.name)}
+                    className="min-w-0 text-left"
+                  >
+                    <div className="truncate font-mono text-xs font-semibold text-slate-700 dark:text-slate-200">{secret.name}</div>
+                    <div className="mt-0.5 text-[10px] text-slate-400 dark:text-slate-500">更新于 {secret.updatedAt}</div>
+                  </button>
+                  <button
+                    type="button"
+                    onClick={() => void handleDelete(secret.name)}
+                    className="rounded-full border border-rose-300 px-3 py-1 text-[11px] font-semibold text-rose-600 transition-colors hover:bg-rose-50 dark:border-rose-700 dark:text-rose-300 dark:hover:bg-rose-900/20"
+                  >
+                    删除
+                  </button>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+      </div>
+    </div>
+  )
+}
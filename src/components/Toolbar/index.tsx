@@ -1,14 +1,28 @@
 import { useExecutionStore } from "../../stores/executionStore";
 import { useSettingsStore } from "../../stores/settingsStore";
 import { useWorkflowStore } from "../../stores/workflowStore";
-import { runWorkflow, stopWorkflow } from "../../utils/execution";
+import {
+  purgeFileJournals,
+  runWorkflow,
+  stopWorkflow,
+  undoRunFileOperations,
+} from "../../utils/execution";
 import {
   announceWorkflowCompleted,
   announceWorkflowFailed,
 } from "../../utils/workflowCompletion";
 import { toBackendGraph } from "../../utils/workflowBridge";
 import { useState } from "react";
-import { Box, Moon, Play, Redo2, StepForward, Undo2 } from "lucide-react";
+import {
+  Box,
+  History,
+  Moon,
+  Play,
+  Redo2,
+  StepForward,
+  Trash2,
+  Undo2,
+} from "lucide-react";
 import CoordinatePicker from "../CoordinatePicker";
 
 interface ToolbarProps {
@@ -30,7 +44,8 @@ export default function Toolbar({
   coordinatePicking,
   elementPicking,
 }: ToolbarProps) {
-  const { running, setRunning, addLog, clearVariables } = useExecutionStore();
+  const { running, setRunning, addLog, clearVariables, lastRunId } =
+    useExecutionStore();
   const { zoom } = useSettingsStore();
   const { undo, redo, exportWorkflow, graphName, setGraphName } =
     useWorkflowStore();
@@ -47,7 +62,7 @@ export default function Toolbar({
     setRunning(true);
     addLog("info", `开始执行流程：${workflowFile.graph.name}`);
     try {
-      const message = await runWorkflow(graph);
+      const message = await runWorkflow(graph, useSettingsStore.getState().safeMode, useSettingsStore.getState().runRecording);
       addLog("success", message);
       announceWorkflowCompleted({
         body: `${workflowFile.graph.name} 已执行完成。`,
@@ -74,6 +89,48 @@ export default function Toolbar({
     setRunning(false);
   };
 
+  const undoFileOperations = async () => {
+    if (running || !lastRunId) return;
+    const confirmed = window.confirm(
+      `确定撤销最近一次运行（${lastRunId}）中以可撤销模式执行的文件操作吗？`,
+    );
+    if (!confirmed) return;
+
+    try {
+      const report = await undoRunFileOperations(lastRunId);
+      addLog(
+        report.failures.length > 0 ? "warn" : "success",
+        `已撤销文件操作 ${report.restored}/${report.total} 项（运行 ${report.runId}）。`,
+      );
+      report.failures.forEach((failure) => {
+        addLog("warn", `撤销失败：${failure}`);
+      });
+    } catch (error) {
+      addLog("error", `撤销文件操作失败：${String(error)}`);
+    }
+  };
+
+  const purgeFileJournalStore = async () => {
+    if (running) return;
+    const confirmed = window.confirm(
+      "确定清空所有历史运行的文件回收区吗？清空后这些运行的文件操作将无法撤销。",
+    );
+    if (!confirmed) return;
+
+    try {
+      const report = await purgeFileJournals();
+      addLog(
+        report.failures.length > 0 ? "warn" : "success",
+        `已清理 ${report.removedRuns} 个运行的文件回收区，释放 ${(report.freedBytes / 1024 / 1024).toFixed(1)} MB。`,
+      );
+      report.failures.forEach((failure) => {
+        addLog("warn", `清理失败：${failure}`);
+      });
+    } catch (error) {
+      addLog("error", `清理文件回收区失败：${String(error)}`);
+    }
+  };
+
   const buttonClass =
     "flex items-center gap-1.5 rounded-lg px-2.5 py-1.5 text-xs font-medium transition-all duration-150 hover:shadow-sm active:translate-y-[1px] disabled:opacity-50";
   const mutedActionButtonClass =
@@ -148,6 +205,26 @@ export default function Toolbar({
         >
           <Redo2 className="h-4 w-4" />
         </button>
+        <button
+          type="button"
+          onClick={undoFileOperations}
+          className={buttonClass}
+          title="撤销最近一次运行的文件操作"
+          aria-label="撤销文件操作"
+          disabled={running || !lastRunId}
+        >
+          <History className="h-4 w-4" />
+        </button>
+        <button
+          type="button"
+          onClick={purgeFileJournalStore}
+          className={buttonClass}
+          title="清空文件回收区（历史运行超过 7 天或总占用超过 2 GB 时也会在运行开始时自动清理）"
+          aria-label="清空文件回收区"
+          disabled={running}
+        >
+          <Trash2 className="h-4 w-4" />
+        </button>
       </div>
 
       <div className="mx-2 h-5 w-[1px] bg-slate-200 dark:bg-neutral-800" />
@@ -0,0 +1,211 @@
+import { useMemo } from 'react'
+import { useSettingsStore } from '../../stores/settingsStore'
+import { useWorkflowStore } from '../../stores/workflowStore'
+import type { WorkflowCapability } from '../../types/workflow'
+import { CAPABILITY_OPTIONS, detectRequiredCapabilities } from '../../utils/capabilities'
+
+interface WorkflowCapabilitiesModalProps {
+  open: boolean
+  onClose: () => void
+}
+
+export default function WorkflowCapabilitiesModal({ open, onClose }: WorkflowCapabilitiesModalProps) {
+  const nodes = useWorkflowStore((state) => state.nodes)
+  const capabilities = useWorkflowStore((state) => state.capabilities)
+  const setCapabilities = useWorkflowStore((state) => state.setCapabilities)
+  const safeMode = useSettingsStore((state) => state.safeMode)
+  const setSafeMode = useSettingsStore((state) => state.setSafeMode)
+  const runRecording = useSettingsStore((state) => state.runRecording)
+  const updateRunRecording = useSettingsStore((state) => state.updateRunRecording)
+
+  const required = useMemo(() => detectRequiredCapabilities(nodes), [nodes])
+
+  if (!open) return null
+
+  const declared = capabilities ?? []
+  const undeclared = required.filter((item) => !declared.includes(item))
+
+  const toggleCapability = (capability: WorkflowCapability, checked: boolean) => {
+    const next = checked ? [...declared, capability] : declared.filter((item) => item !== capability)
+    setCapabilities(CAPABILITY_OPTIONS.map((option) => option.value).filter((value) => next.includes(value)))
+  }
+
+  return (
+    <div
+      className="fixed inset-0 z-[360] flex items-center justify-center bg-black/55 backdrop-blur-sm"
+      onClick={(event) => {
+        if (event.target === event.currentTarget) {
+          onClose()
+        }
+      }}
+    >
+      <div className="flex max-h-[78vh] w-[560px] max-w-[92vw] flex-col overflow-hidden rounded-2xl border border-slate-200 bg-white shadow-2xl dark:border-neutral-700 dark:bg-neutral-900">
+        <div className="flex items-center justify-between border-b border-slate-200 px-6 py-4 dark:border-neutral-800">
+          <h3 className="text-sm font-bold text-slate-700 dark:text-slate-200">工作流权限</h3>
+          <button
+            type="button"
+            onClick={onClose}
+            className="rounded-lg p-1.5 text-slate-400 transition-colors hover:bg-slate-100 hover:text-slate-700 dark:hover:bg-neutral-800"
+          >
+            <svg className="h-5 w-5" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+              <path d="M18 6L6 18M6 6l12 12" strokeLinecap="round" strokeLinejoin="round" />
+            </svg>
+          </button>
+        </div>
+
+        <div className="flex-1 space-y-4 overflow-y-auto p-6">
+          <p className="text-[11px] text-slate-500 dark:text-slate-400">
+            {capabilities
+              ? '执行前会校验每个节点所需权限，未声明的权限将拒绝执行。'
+              : '当前工作流未声明权限，视为未授予任何权限；执行前会列出节点所需权限，确认后写入声明。'}
+          </p>
+
+          <div className="space-y-2">
+            {CAPABILITY_OPTIONS.map((option) => {
+              const missing = undeclared.includes(option.value)
+              return (
+                <label
+                  key={option.value}
+                  className={`flex items-start gap-3 rounded-xl border px-3 py-2 text-xs ${
+                    missing
+                      ? 'border-amber-300 bg-amber-50 dark:border-amber-700 dark:bg-amber-950/30'
+                      : 'border-slate-200 dark:border-neutral-700'
+                  }`}
+                >
+                  <input
+                    type="checkbox"
+                    className="mt-0.5"
+                    checked={declared.includes(option.value)}
+                    onChange={(event) => toggleCapability(option.value, event.target.checked)}
+                  />
+                  <span className="flex-1">
+                    <span className="font-semibold text-slate-700 dark:text-slate-200">{option.label}</span>
+                    {required.includes(option.value) ? (
+                      <span className="ml-2 text-[10px] text-cyan-600 dark:text-cyan-400">节点需要</span>
+                    ) : null}
+                    <span className="mt-0.5 block text-[11px] text-slate-500 dark:text-slate-400">{option.description}</span>
+                    {missing ? <span className="mt-0.5 block text-[11px] text-amber-600">已有节点需要此权限但尚未声明。</span> : null}
+                  </span>
+                </label>
+              )
+            })}
+          </div>
+
+          <div className="flex gap-2">
+            <button
+              type="button"
+              onClick={() => setCapabilities(required)}
+              className="rounded-full border border-slate-300 px-4 py-2 text-xs font-semibold text-slate-600 transition-colors hover:border-cyan-400 hover:text-cyan-600 dark:border-neutral-600 dark:text-slate-300"
+            >
+              按节点自动声明
+            </button>
+            <button
+              type="button"
+              disabled={!capabilities}
+              onClick={() => setCapabilities(null)}
+              className="rounded-full border border-slate-300 px-4 py-2 text-xs font-semibold text-slate-600 transition-colors hover:border-rose-400 hover:text-rose-600 disabled:cursor-not-allowed disabled:opacity-50 dark:border-neutral-600 dark:text-slate-300"
+            >
+              清除声明
+            </button>
+          </div>
+
+          <div className="space-y-1.5 rounded-xl border border-slate-200 p-3 dark:border-neutral-700">
+            <label className="flex items-center gap-2 text-xs font-semibold text-slate-700 dark:text-slate-200">
+              <input type="checkbox" checked={safeMode} onChange={(event) => setSafeMode(event.target.checked)} />
+              安全模式（全局）
+            </label>
+            <p className="text-[11px] text-slate-500 dark:text-slate-400">
+              开启后，电源操作与结束进程节点执行前需要逐个确认。
+            </p>
+          </div>
+
+          <div className="space-y-2 rounded-xl border border-slate-200 p-3 dark:border-neutral-700">
+            <label className="flex items-center gap-2 text-xs font-semibold text-slate-700 dark:text-slate-200">
+              <input
+                type="checkbox"
+                checked={runRecording.enabled}
+                onChange={(event) => updateRunRecording({ enabled: event.target.checked })}
+              />
+              运行录像（全局）
+            </label>
+            <p className="text-[11px] text-slate-500 dark:text-slate-400">
+              执行期间按帧率采样主显示器，标注当前节点与光标位置，仅保留最近一段画面；运行结束后按保存策略输出。
+            </p>
+            {runRecording.enabled ? (
+              <div className="grid grid-cols-2 gap-2 text-[11px] text-slate-500 dark:text-slate-400">
+                <label className="space-y-1">
+                  <span className="font-semibold">帧率 (FPS)</span>
+                  <input
+                    type="number"
+                    min={0.1}
+                    max={10}
+                    step={0.5}
+                    value={runRecording.fps}
+                    onChange={(event) => updateRunRecording({ fps: Number(event.target.value) })}
+                    className="w-full rounded-xl border border-slate-200 bg-white px-3 py-1.5 text-xs shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900"
+                  />
+                </label>
+                <label className="space-y-1">
+                  <span className="font-semibold">保留最近秒数</span>
+                  <input
+                    type="number"
+                    min={1}
+                    max={600}
+                    step={1}
+                    value={runRecording.bufferSeconds}
+                    onChange={(event) => updateRunRecording({ bufferSeconds: Number(event.target.value) })}
+                    className="w-full rounded-xl border border-slate-200 bg-white px-3 py-1.5 text-xs shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900"
+                  />
+                </label>
+                <label className="space-y-1">
+                  <span className="font-semibold">输出格式</span>
+                  <select
+                    value={runRecording.format}
+                    onChange={(event) => updateRunRecording({ format: event.target.value as 'gif' | 'pngSequence' })}
+                    className="w-full rounded-xl border border-slate-200 bg-white px-3 py-1.5 text-xs shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900"
+                  >
+                    <option value="gif">GIF 动图</option>
+                    <option value="pngSequence">PNG 序列</option>
+                  </select>
+                </label>
+                <label className="space-y-1">
+                  <span className="font-semibold">保存时机</span>
+                  <select
+                    value={runRecording.saveMode}
+                    onChange={(event) => updateRunRecording({ saveMode: event.target.value as 'onError' | 'always' })}
+                    className="w-full rounded-xl border border-slate-200 bg-white px-3 py-1.5 text-xs shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900"
+                  >
+                    <option value="onError">仅出错时</option>
+                    <option value="always">每次运行结束</option>
+                  </select>
+                </label>
+                <label className="space-y-1">
+                  <span className="font-semibold">最大宽度 (px)</span>
+                  <input
+                    type="number"
+                    min={160}
+                    max={3840}
+                    step={10}
+                    value={runRecording.maxWidth}
+                    onChange={(event) => updateRunRecording({ maxWidth: Number(event.target.value) })}
+                    className="w-full rounded-xl border border-slate-200 bg-white px-3 py-1.5 text-xs shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900"
+                  />
+                </label>
+                <label className="space-y-1">
+                  <span className="font-semibold">输出目录(留空=默认)</span>
+                  <input
+                    type="text"
+                    value={runRecording.outputDir}
+                    placeholder="C:\ProgramData\CommandFlow\recordings"
+                    onChange={(event) => updateRunRecording({ outputDir: event.target.value })}
+                    className="w-full rounded-xl border border-slate-200 bg-white px-3 py-1.5 text-xs shadow-sm focus:border-cyan-500 focus:outline-none dark:border-neutral-700 dark:bg-neutral-900"
+                  />
+                </label>
+              </div>
+            ) : null}
+          </div>
+        </div>
+      </div>
+    </div>
+  )
+}
@@ -1,5 +1,6 @@
 import { useEffect } from "react";
 import { useExecutionStore } from "../stores/executionStore";
+import { useSettingsStore } from "../stores/settingsStore";
 import { useWorkflowStore } from "../stores/workflowStore";
 import { triggerGlobalRefresh } from "../utils/refresh";
 import { runWorkflow } from "../utils/execution";
@@ -137,7 +138,7 @@ export const useShortcutBindings = () => {
         clearVariables();
         setRunning(true);
         addLog("info", `开始执行工作流：${workflowFile.graph.name}`);
-        void runWorkflow(graph)
+        void runWorkflow(graph, useSettingsStore.getState().safeMode, useSettingsStore.getState().runRecording)
           .then((message) => {
             addLog("success", message);
             announceWorkflowCompleted({
@@ -8,6 +8,7 @@ import { COMMAND_FLOW_REFRESH_ALL_EVENT } from '../utils/refresh'
 import { buildLaunchApplicationParams, filterStartMenuApps, getStartMenuAppDisplayName } from '../utils/startMenuApp'
 import {
   createParamInputHandleId,
+  getInputHandleValueType,
   getNodePortSpec,
 } from '../utils/nodePorts'
 import { useWorkflowStore } from '../stores/workflowStore'
@@ -53,6 +54,9 @@ const isFilePathField = (kind: NodeKind, fieldKey: string) => {
   if (kind === 'clipboardWrite' && fieldKey === 'imagePath') {
     return true
   }
+  if (kind === 'imageOperation' && (fieldKey === 'source' || fieldKey === 'savePath')) {
+    return true
+  }
 
   return false
 }
@@ -64,7 +68,10 @@ const isTextFilePathField = (kind: NodeKind, fieldKey: string, params: Record<st
   kind === 'fileOperation' && fieldKey === 'path' && (params.operation === 'readText' || params.operation === 'writeText')
 
 const isStrictFilePathField = (kind: NodeKind, fieldKey: string, params: Record<string, unknown> = {}) =>
-  isImageMatchImageField(kind, fieldKey) || isTextFilePathField(kind, fieldKey, params) || (kind === 'clipboardWrite' && fieldKey === 'imagePath')
+  isImageMatchImageField(kind, fieldKey) ||
+  isTextFilePathField(kind, fieldKey, params) ||
+  (kind === 'clipboardWrite' && fieldKey === 'imagePath') ||
+  (kind === 'imageOperation' && fieldKey === 'source')
 
 const IMAGE_FILE_FILTERS = [
   { name: '图片文件', extensions: ['png', 'jpg', 'jpeg', 'bmp', 'webp'] },
@@ -79,11 +86,11 @@ const isVariableNameField = (kind: NodeKind, fieldKey: string) =>
   (kind === 'varDefine' || kind === 'varSet' || kind === 'varMath' || kind === 'varGet') && fieldKey === 'name'
 
 const isInputVariableField = (kind: NodeKind, fieldKey: string) =>
-  ((kind === 'clipboardWrite' || kind === 'fileOperation' || kind === 'showMessage') && fieldKey === 'inputVar') ||
+  ((kind === 'clipboardWrite' || kind === 'fileOperation' || kind === 'showMessage' || kind === 'imageOperation') && fieldKey === 'inputVar') ||
   (kind === 'clipboardWrite' && fieldKey === 'imageVar')
 
 const isOutputVariableField = (kind: NodeKind, fieldKey: string) =>
-  ((kind === 'clipboardRead' || kind === 'fileOperation') && fieldKey === 'outputVar') ||
+  ((kind === 'clipboardRead' || kind === 'fileOperation' || kind === 'imageOperation') && fieldKey === 'outputVar') ||
   (kind === 'clipboardRead' && (fieldKey === 'outputTextVar' || fieldKey === 'outputImageVar'))
 
 const isWindowLookupNode = (kind: NodeKind, params: Record<string, unknown> = {}) =>
@@ -116,6 +123,7 @@ const describeHandleValueType = (valueType?: string) => {
   if (valueType === 'number') return '数字'
   if (valueType === 'string') return '字符串'
   if (valueType === 'json') return 'JSON'
+  if (valueType === 'image') return '图像'
   if (valueType === 'boolean') return '布尔'
   if (valueType === 'any') return '任意类型'
   return '通用'
@@ -567,7 +575,7 @@ export default function BaseNode({ id, data, tone = 'action', selected = false }
 
     if (data.kind === 'varDefine' || data.kind === 'varSet' || data.kind === 'constValue') {
       const valueType = String(nextParams.valueType ?? 'number')
-      if (valueType === 'string') {
+      if (valueType === 'string' || valueType === 'secret') {
         nextParams.value = String(nextParams.valueString ?? '')
       } else if (valueType === 'number') {
         nextParams.value = Number(nextParams.valueNumber ?? 0)
@@ -913,7 +921,7 @@ export default function BaseNode({ id, data, tone = 'action', selected = false }
               handleId: createParamInputHandleId(field.key),
               fieldKey: field.key,
               label: field.label,
-              valueType: field.type === 'number' ? 'number' : field.type === 'json' ? 'json' : 'string',
+              valueType: getInputHandleValueType(data.kind, createParamInputHandleId(field.key), params) ?? 'string',
             })}
           />
 
@@ -1025,7 +1033,7 @@ export default function BaseNode({ id, data, tone = 'action', selected = false }
               handleId: createParamInputHandleId(field.key),
               fieldKey: field.key,
               label: field.label,
-              valueType: field.type === 'number' ? 'number' : field.type === 'json' ? 'json' : 'string',
+              valueType: getInputHandleValueType(data.kind, createParamInputHandleId(field.key), params) ?? 'string',
             })}
           />
 
@@ -1435,7 +1443,7 @@ export default function BaseNode({ id, data, tone = 'action', selected = false }
             handleId: createParamInputHandleId(field.key),
             fieldKey: field.key,
             label: field.label,
-            valueType: field.type === 'number' ? 'number' : field.type === 'json' ? 'json' : 'string',
+            valueType: getInputHandleValueType(data.kind, createParamInputHandleId(field.key), params) ?? 'string',
           })}
         />
         {errors[field.key] ? (
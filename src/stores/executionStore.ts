@@ -12,11 +12,13 @@ interface ExecutionState {
   statusText: string
   logs: ExecutionLogItem[]
   variables: Record<string, unknown>
+  lastRunId: string | null
   setRunning: (running: boolean) => void
   addLog: (level: ExecutionLogItem['level'], message: string) => void
   clearLogs: () => void
   setVariables: (variables: Record<string, unknown>) => void
   clearVariables: () => void
+  setLastRunId: (runId: string | null) => void
 }
 
 const now = () => new Date().toISOString()
@@ -25,6 +27,7 @@ export const useExecutionStore = create<ExecutionState>((set) => ({
   running: false,
   statusText: '就绪',
   variables: {},
+  lastRunId: null,
   logs: [
     {
       id: crypto.randomUUID(),
@@ -53,4 +56,5 @@ export const useExecutionStore = create<ExecutionState>((set) => ({
   clearLogs: () => set(() => ({ logs: [] })),
   setVariables: (variables) => set(() => ({ variables })),
   clearVariables: () => set(() => ({ variables: {} })),
+  setLastRunId: (lastRunId) => set(() => ({ lastRunId })),
 }))
@@ -7,11 +7,14 @@ import {
   type InputRecordingActionPayload,
   type InputRecordingOptionsPayload,
   type InputRecordingPresetPayload,
+  type RunRecordingOptionsPayload,
 } from '../utils/execution'
 
 type ThemeMode = 'light' | 'dark' | 'system'
 type CoordinateMode = 'virtualScreen' | 'activeWindow'
 
+export type RunRecordingSettings = RunRecordingOptionsPayload
+
 export interface LlmPreset {
   id: string
   name: string
@@ -40,6 +43,8 @@ interface SettingsState {
   theme: ThemeMode
   zoom: number
   coordinateMode: CoordinateMode
+  safeMode: boolean
+  runRecording: RunRecordingSettings
   llmPresets: LlmPreset[]
   inputRecordingPresets: InputRecordingPreset[]
   loadLlmPresets: () => Promise<void>
@@ -47,6 +52,8 @@ interface SettingsState {
   setTheme: (theme: ThemeMode) => void
   setZoom: (zoom: number) => void
   setCoordinateMode: (mode: CoordinateMode) => void
+  setSafeMode: (enabled: boolean) => void
+  updateRunRecording: (patch: Partial<RunRecordingSettings>) => void
   addLlmPreset: (preset: Omit<LlmPreset, 'id'>) => string
   updateLlmPreset: (id: string, patch: Partial<Omit<LlmPreset, 'id'>>) => void
   deleteLlmPreset: (id: string) => void
@@ -57,6 +64,8 @@ interface SettingsState {
 }
 
 const THEME_KEY = 'commandflow.theme'
+const SAFE_MODE_KEY = 'commandflow.safeMode'
+const RUN_RECORDING_KEY = 'commandflow.runRecording'
 const LLM_PRESETS_KEY = 'commandflow.llmPresets'
 const INPUT_RECORDING_PRESETS_KEY = 'commandflow.inputRecordingPresets'
 const isTauriRuntime = () => '__TAURI_INTERNALS__' in window
@@ -75,6 +84,34 @@ const getSavedTheme = (): ThemeMode => {
   return 'system'
 }
 
+const getSavedSafeMode = (): boolean => localStorage.getItem(SAFE_MODE_KEY) === 'true'
+
+const clampNumber = (value: unknown, min: number, max: number, fallback: number) => {
+  const parsed = typeof value === 'number' ? value : Number(value)
+  return Number.isFinite(parsed) ? Math.min(max, Math.max(min, parsed)) : fallback
+}
+
+const sanitizeRunRecording = (raw: unknown): RunRecordingSettings => {
+  const value = (raw && typeof raw === 'object' ? raw : {}) as Partial<RunRecordingSettings>
+  return {
+    enabled: value.enabled === true,
+    fps: clampNumber(value.fps, 0.1, 10, 2),
+    bufferSeconds: Math.round(clampNumber(value.bufferSeconds, 1, 600, 30)),
+    format: value.format === 'pngSequence' ? 'pngSequence' : 'gif',
+    saveMode: value.saveMode === 'always' ? 'always' : 'onError',
+    outputDir: typeof value.outputDir === 'string' ? value.outputDir : '',
+    maxWidth: Math.round(clampNumber(value.maxWidth, 160, 3840, 960)),
+  }
+}
+
+const getSavedRunRecording = (): RunRecordingSettings => {
+  try {
+    return sanitizeRunRecording(JSON.parse(localStorage.getItem(RUN_RECORDING_KEY) ?? 'null'))
+  } catch {
+    return sanitizeRunRecording(null)
+  }
+}
+
 const getDefaultPreset = (): LlmPreset => ({
   id: `preset-${Date.now()}`,
   name: '默认 OpenAI',
@@ -227,6 +264,8 @@ export const useSettingsStore = create<SettingsState>((set) => ({
   theme: initialTheme,
   zoom: 1,
   coordinateMode: 'virtualScreen',
+  safeMode: getSavedSafeMode(),
+  runRecording: getSavedRunRecording(),
   llmPresets: initialLlmPresets,
   inputRecordingPresets: initialInputRecordingPresets,
   loadLlmPresets: async () => {
@@ -286,6 +325,16 @@ export const useSettingsStore = create<SettingsState>((set) => ({
     applyTheme(theme)
     set(() => ({ theme }))
   },
+  setSafeMode: (enabled) => {
+    localStorage.setItem(SAFE_MODE_KEY, enabled ? 'true' : 'false')
+    set(() => ({ safeMode: enabled }))
+  },
+  updateRunRecording: (patch) =>
+    set((state) => {
+      const runRecording = sanitizeRunRecording({ ...state.runRecording, ...patch })
+      localStorage.setItem(RUN_RECORDING_KEY, JSON.stringify(runRecording))
+      return { runRecording }
+    }),
   setZoom: (zoom) =>
     set((state) => {
       if (state.zoom === zoom) return state
@@ -7,7 +7,14 @@ import {
   type NodeChange,
 } from '@xyflow/react'
 import { create } from 'zustand'
-import type { CoordinatePoint, NodeKind, WorkflowEdge, WorkflowFile, WorkflowNode } from '../types/workflow'
+import type {
+  CoordinatePoint,
+  NodeKind,
+  WorkflowCapability,
+  WorkflowEdge,
+  WorkflowFile,
+  WorkflowNode,
+} from '../types/workflow'
 import { getNodeDisplayLabel, getNodeMeta } from '../utils/nodeMeta'
 import {
   getInputHandleValueType,
@@ -29,6 +36,7 @@ interface Snapshot {
 interface WorkflowState {
   graphId: string
   graphName: string
+  capabilities: WorkflowCapability[] | null
   nodes: WorkflowNode[]
   edges: WorkflowEdge[]
   runningNodeIds: string[]
@@ -59,6 +67,7 @@ interface WorkflowState {
   duplicateSelectedNode: () => void
   updateNodeParams: (id: string, params: Record<string, unknown>) => void
   setGraphName: (name: string) => void
+  setCapabilities: (capabilities: WorkflowCapability[] | null) => void
   exportWorkflow: () => WorkflowFile
   importWorkflow: (file: WorkflowFile, fileName?: string) => void
   resetWorkflow: () => void
@@ -163,6 +172,11 @@ const legacyFileKindToOperation = {
   fileDelete: 'delete',
   fileReadText: 'readText',
   fileWriteText: 'writeText',
+  listFiles: 'listFiles',
+  fileInfo: 'fileInfo',
+  waitForFile: 'waitForFile',
+  archiveCreate: 'archiveCreate',
+  archiveExtract: 'archiveExtract',
 } as const
 
 const legacyTriggerKindToMode = {
@@ -353,6 +367,7 @@ const applyConnectionWithReplacement = (
 export const useWorkflowStore = create<WorkflowState>((set, get) => ({
   graphId: crypto.randomUUID(),
   graphName: '未命名工作流',
+  capabilities: null,
   nodes: makeInitialNodes(),
   edges: [],
   runningNodeIds: [],
@@ -617,6 +632,10 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
     set(() => ({
       graphName: name,
     })),
+  setCapabilities: (capabilities) =>
+    set(() => ({
+      capabilities,
+    })),
   exportWorkflow: () => {
     const state = get()
     return {
@@ -628,6 +647,7 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
         name: state.graphName,
         nodes: state.nodes,
         edges: state.edges,
+        ...(state.capabilities ? { capabilities: state.capabilities } : {}),
       },
     }
   },
@@ -637,6 +657,7 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
       future: [],
       graphId: file.graph.id,
       graphName: fileName ?? file.graph.name,
+      capabilities: Array.isArray(file.graph.capabilities) ? file.graph.capabilities : null,
       nodes: normalizeImportedNodes(file.graph.nodes),
       edges: file.graph.edges,
       selectedNodeId: null,
@@ -648,6 +669,7 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
       future: [],
       graphId: crypto.randomUUID(),
       graphName: '未命名工作流',
+      capabilities: null,
       nodes: makeInitialNodes(),
       edges: [],
       selectedNodeId: null,
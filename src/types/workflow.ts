@@ -15,12 +15,24 @@ export type ActionNodeKind =
   | 'pythonCode'
   | 'clipboardRead'
   | 'clipboardWrite'
+  | 'imageOperation'
   | 'showMessage'
   | 'delay'
   | 'systemOperation'
   | 'guiAgent'
   | 'guiAgentActionParser'
-export type ControlNodeKind = 'condition' | 'loop' | 'whileLoop' | 'imageMatch' | 'ocrMatch' | 'tryCatch'
+export type ControlNodeKind =
+  | 'condition'
+  | 'loop'
+  | 'whileLoop'
+  | 'imageMatch'
+  | 'ocrMatch'
+  | 'ocrRead'
+  | 'pixelColor'
+  | 'screenChange'
+  | 'waitAny'
+  | 'visualAssert'
+  | 'tryCatch'
 export type DataNodeKind = 'varDefine' | 'varSet' | 'varMath' | 'varGet' | 'constValue'
   | 'currentTime'
   | 'jsonExtract'
@@ -35,6 +47,15 @@ export interface WorkflowNodeData {
   description?: string
 }
 
+export type WorkflowCapability =
+  | 'input'
+  | 'fileSystemWrite'
+  | 'processKill'
+  | 'power'
+  | 'network'
+  | 'shell'
+  | 'llm'
+
 export type WorkflowNode = Node<WorkflowNodeData>
 export type WorkflowEdge = Edge
 
@@ -43,6 +64,7 @@ export interface WorkflowGraph {
   name: string
   nodes: WorkflowNode[]
   edges: WorkflowEdge[]
+  capabilities?: WorkflowCapability[]
 }
 
 export interface WorkflowFile {
@@ -0,0 +1,96 @@
+import type { WorkflowCapability, WorkflowNode } from '../types/workflow'
+
+export const CAPABILITY_OPTIONS: Array<{ value: WorkflowCapability; label: string; description: string }> = [
+  { value: 'input', label: '键鼠输入', description: '模拟鼠标、键盘操作、键鼠预设回放，以及命中后点击与快捷键切换窗口。' },
+  { value: 'fileSystemWrite', label: '文件写入', description: '复制、移动、删除、写入文件与压缩解压（预演模式除外），以及保存截图、图像处理结果和视觉断言基准图。' },
+  { value: 'processKill', label: '结束进程', description: '强制结束指定进程。' },
+  { value: 'power', label: '电源操作', description: '关机、重启、睡眠、休眠、锁定与注销。' },
+  { value: 'network', label: '网络开关', description: '切换 WiFi、蓝牙与网络适配器。' },
+  { value: 'shell', label: '命令/程序执行', description: '启动应用、执行命令与运行 Python 代码。' },
+  { value: 'llm', label: 'LLM 调用', description: 'GUI Agent 调用大模型接口。' },
+]
+
+const normalizeOperation = (value: unknown, fallback: string) =>
+  (typeof value === 'string' ? value : fallback).replace(/[-_\s]/g, '').toLowerCase()
+
+const POWER_OPERATIONS = new Set(['shutdown', 'restart', 'sleep', 'hibernate', 'lock', 'signout'])
+const NETWORK_OPERATIONS = new Set(['wifiswitch', 'bluetoothswitch', 'networkadapterswitch'])
+const READONLY_FILE_OPERATIONS = new Set(['readtext', 'listfiles', 'fileinfo', 'waitforfile'])
+
+const when = (condition: boolean, capability: WorkflowCapability): WorkflowCapability[] => (condition ? [capability] : [])
+
+// 与后端 workflow::capability::required_capabilities 保持一致；每种节点显式归类，新增节点未登记时无法通过类型检查
+export const getNodeCapabilities = (node: WorkflowNode): WorkflowCapability[] => {
+  const params = node.data.params ?? {}
+  const kind = node.data.kind
+  switch (kind) {
+    case 'mouseOperation':
+    case 'keyboardOperation':
+    case 'inputPresetReplay':
+      return ['input']
+    case 'imageMatch':
+    case 'ocrMatch':
+    case 'waitAny':
+      return when(Boolean(params.clickOnMatch ?? false), 'input')
+    case 'windowActivate':
+      return when(normalizeOperation(params.switchMode, 'title') === 'shortcut', 'input')
+    case 'guiAgent':
+      return ['llm', 'input']
+    case 'terminateProcess':
+      return ['processKill']
+    case 'launchApplication':
+    case 'pythonCode':
+      return ['shell']
+    case 'systemOperation': {
+      const operation = normalizeOperation(params.operation, 'shutdown')
+      if (POWER_OPERATIONS.has(operation)) return ['power']
+      if (NETWORK_OPERATIONS.has(operation)) return ['network']
+      if (operation === 'runcommand') return ['shell']
+      return []
+    }
+    case 'fileOperation': {
+      const safetyMode = normalizeOperation(params.safetyMode, 'journal')
+      if (safetyMode === 'preview' || safetyMode === 'plan') return []
+      return READONLY_FILE_OPERATIONS.has(normalizeOperation(params.operation, 'copy')) ? [] : ['fileSystemWrite']
+    }
+    case 'screenshot':
+      return when(Boolean(params.shouldSave ?? true), 'fileSystemWrite')
+    case 'imageOperation':
+      return when(String(params.savePath ?? '').trim().length > 0, 'fileSystemWrite')
+    case 'visualAssert':
+      return ['fileSystemWrite']
+    case 'trigger':
+    case 'uiaElement':
+    case 'getMousePosition':
+    case 'clipboardRead':
+    case 'clipboardWrite':
+    case 'showMessage':
+    case 'delay':
+    case 'guiAgentActionParser':
+    case 'condition':
+    case 'loop':
+    case 'whileLoop':
+    case 'tryCatch':
+    case 'ocrRead':
+    case 'pixelColor':
+    case 'screenChange':
+    case 'varDefine':
+    case 'varSet':
+    case 'varMath':
+    case 'varGet':
+    case 'constValue':
+    case 'currentTime':
+    case 'jsonExtract':
+      return []
+    default: {
+      const unclassified: never = kind
+      return unclassified
+    }
+  }
+}
+
+export const detectRequiredCapabilities = (nodes: WorkflowNode[]): WorkflowCapability[] => {
+  const required = new Set<WorkflowCapability>()
+  nodes.forEach((node) => getNodeCapabilities(node).forEach((capability) => required.add(capability)))
+  return CAPABILITY_OPTIONS.map((option) => option.value).filter((value) => required.has(value))
+}
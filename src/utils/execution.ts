@@ -190,11 +190,25 @@ export const invalidateDynamicOptionCaches = () => {
   startMenuIconPromises.clear()
 }
 
-export const runWorkflow = async (graph: BackendWorkflowGraph): Promise<string> => {
+export interface RunRecordingOptionsPayload {
+  enabled: boolean
+  fps: number
+  bufferSeconds: number
+  format: 'gif' | 'pngSequence'
+  saveMode: 'onError' | 'always'
+  outputDir: string
+  maxWidth: number
+}
+
+export const runWorkflow = async (
+  graph: BackendWorkflowGraph,
+  safeMode = false,
+  recording?: RunRecordingOptionsPayload,
+): Promise<string> => {
   if (!isTauriRuntime()) {
     return '当前为浏览器预览模式，未连接 Tauri 后端，已跳过真实执行。'
   }
-  return invoke<string>('run_workflow', { graph })
+  return invoke<string>('run_workflow', { graph, safeMode, recording: recording?.enabled ? recording : null })
 }
 
 export const startPackageWorkflowAsExe = async (
@@ -225,6 +239,33 @@ export const checkPackagingEnvironment = async (): Promise<PackagingEnvironmentR
   return invoke<PackagingEnvironmentReportPayload>('check_packaging_environment')
 }
 
+export interface UndoRunFileOperationsPayload {
+  runId: string
+  total: number
+  restored: number
+  failures: string[]
+}
+
+export const undoRunFileOperations = async (runId: string): Promise<UndoRunFileOperationsPayload> => {
+  if (!isTauriRuntime()) {
+    throw new Error('当前为浏览器预览模式，未连接 Tauri 后端，无法撤销文件操作。')
+  }
+  return invoke<UndoRunFileOperationsPayload>('undo_run_file_operations', { runId })
+}
+
+export interface PurgeFileJournalsPayload {
+  removedRuns: number
+  freedBytes: number
+  failures: string[]
+}
+
+export const purgeFileJournals = async (): Promise<PurgeFileJournalsPayload> => {
+  if (!isTauriRuntime()) {
+    throw new Error('当前为浏览器预览模式，未连接 Tauri 后端，无法清理文件回收区。')
+  }
+  return invoke<PurgeFileJournalsPayload>('purge_file_journals')
+}
+
 export const stopWorkflow = async (): Promise<string> => {
   if (!isTauriRuntime()) {
     return '当前为浏览器预览模式，未连接 Tauri 后端。'
@@ -438,6 +479,190 @@ export const saveLlmPresets = async (presets: LlmPresetPayload[]): Promise<void>
   await invoke('save_llm_presets', { presets })
 }
 
+export interface SecretSummaryPayload {
+  name: string
+  updatedAt: string
+}
+
+export const listSecrets = async (): Promise<SecretSummaryPayload[]> => {
+  if (!isTauriRuntime()) {
+    return []
+  }
+  return invoke<SecretSummaryPayload[]>('list_secrets')
+}
+
+export const setSecret = async (name: string, value: string): Promise<SecretSummaryPayload> => {
+  if (!isTauriRuntime()) {
+    throw new Error('当前为浏览器预览模式，未连接 Tauri 后端，无法保存密钥。')
+  }
+  return invoke<SecretSummaryPayload>('set_secret', { name, value })
+}
+
+export const deleteSecret = async (name: string): Promise<boolean> => {
+  if (!isTauriRuntime()) {
+    return false
+  }
+  return invoke<boolean>('delete_secret', { name })
+}
+
+export interface SensitiveParamRulesPayload {
+  keyMarkers: string[]
+  nodeParams: Array<{ kind: string; params: string[] }>
+  secretValueType: string
+  secretValueParams: string[]
+}
+
+let sensitiveParamRulesPromise: Promise<SensitiveParamRulesPayload | null> | null = null
+
+// 敏感参数规则由后端统一维护，前端只读取用于提示，避免两边各写一份
+export const getSensitiveParamRules = async (): Promise<SensitiveParamRulesPayload | null> => {
+  if (!isTauriRuntime()) {
+    return null
+  }
+
+  if (!sensitiveParamRulesPromise) {
+    sensitiveParamRulesPromise = invoke<SensitiveParamRulesPayload>('get_sensitive_param_rules').catch(() => {
+      sensitiveParamRulesPromise = null
+      return null
+    })
+  }
+
+  return sensitiveParamRulesPromise
+}
+
+export const isSensitiveParam = (
+  rules: SensitiveParamRulesPayload | null,
+  kind: string,
+  key: string,
+  params: Record<string, unknown>,
+) => {
+  if (!rules) return false
+  const lowered = key.toLowerCase()
+  if (rules.keyMarkers.some((marker) => lowered.includes(marker))) return true
+  if (rules.nodeParams.some((entry) => entry.kind === kind && entry.params.includes(key))) return true
+  return (
+    (kind === 'varDefine' || kind === 'varSet' || kind === 'constValue') &&
+    String(params.valueType ?? '').toLowerCase() === rules.secretValueType &&
+    rules.secretValueParams.includes(key)
+  )
+}
+
+export type PresetBundleKind = 'llm' | 'inputRecording'
+export type PresetMergeStrategy = 'skip' | 'overwrite' | 'keepBoth'
+
+export interface PresetExportResultPayload {
+  path: string
+  llmCount: number
+  inputRecordingCount: number
+}
+
+export interface PresetConflictPayload {
+  kind: PresetBundleKind
+  importedId: string
+  importedName: string
+  existingId: string
+  existingName: string
+  matchedBy: 'id' | 'name'
+  resolution: 'skipped' | 'overwritten' | 'renamed'
+}
+
+export interface PresetMergeSummaryPayload {
+  added: number
+  overwritten: number
+  renamed: number
+  skipped: number
+}
+
+export interface PresetImportReportPayload {
+  llm: PresetMergeSummaryPayload | null
+  inputRecording: PresetMergeSummaryPayload | null
+  conflicts: PresetConflictPayload[]
+}
+
+export const exportPresets = async (
+  path: string,
+  passphrase: string,
+  kinds: PresetBundleKind[],
+): Promise<PresetExportResultPayload> => {
+  if (!isTauriRuntime()) {
+    throw new Error('当前为浏览器预览模式，未连接 Tauri 后端，无法导出预设。')
+  }
+  return invoke<PresetExportResultPayload>('export_presets', { path, passphrase, kinds })
+}
+
+export const importPresets = async (
+  path: string,
+  passphrase: string,
+  mergeStrategy: PresetMergeStrategy,
+): Promise<PresetImportReportPayload> => {
+  if (!isTauriRuntime()) {
+    throw new Error('当前为浏览器预览模式，未连接 Tauri 后端，无法导入预设。')
+  }
+  return invoke<PresetImportReportPayload>('import_presets', { path, passphrase, mergeStrategy })
+}
+
+export type OcrLanguageCode = 'ch' | 'en' | 'japan' | 'korean'
+export type OcrModelComponent = 'det' | 'cls' | 'rec' | 'dict'
+
+export interface OcrModelEntryPayload {
+  language: OcrLanguageCode
+  label: string
+  directory: string
+  det: string | null
+  cls: string | null
+  rec: string | null
+  dict: string | null
+  ready: boolean
+}
+
+export interface OcrModelImportResultPayload {
+  language: OcrLanguageCode
+  component: OcrModelComponent
+  path: string
+}
+
+export const listOcrModels = async (modelDir?: string): Promise<OcrModelEntryPayload[]> => {
+  if (!isTauriRuntime()) {
+    return []
+  }
+  return invoke<OcrModelEntryPayload[]>('list_ocr_models', { modelDir: modelDir || null })
+}
+
+export const importOcrModel = async (
+  sourcePath: string,
+  language: OcrLanguageCode,
+  component?: OcrModelComponent,
+  modelDir?: string,
+): Promise<OcrModelImportResultPayload> => {
+  if (!isTauriRuntime()) {
+    throw new Error('当前为浏览器预览模式，未连接 Tauri 后端，无法导入 OCR 模型。')
+  }
+  return invoke<OcrModelImportResultPayload>('import_ocr_model', {
+    sourcePath,
+    language,
+    component: component ?? null,
+    modelDir: modelDir || null,
+  })
+}
+
+export interface MonitorInfoPayload {
+  index: number
+  name: string
+  x: number
+  y: number
+  width: number
+  height: number
+  scaleFactor: number
+  isPrimary: boolean
+}
+
+export const listMonitors = async (): Promise<MonitorInfoPayload[]> => {
+  if (!isTauriRuntime()) {
+    return []
+  }
+  return invoke<MonitorInfoPayload[]>('list_monitors')
+}
+
 export const loadInputRecordingPresets = async (): Promise<InputRecordingPresetPayload[]> => {
   if (!isTauriRuntime()) {
     return []
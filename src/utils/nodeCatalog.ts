@@ -6,15 +6,21 @@ import {
   Clipboard,
   Clock3,
   Code2,
+  Crop,
   FileText,
   GitBranch,
+  GitFork,
+  ImageDown,
   Keyboard,
   MessageSquare,
   Monitor,
   MousePointerClick,
+  Pipette,
   Play,
   Repeat,
   Rocket,
+  ScanEye,
+  ScanText,
   Search,
   Settings,
   type LucideIcon,
@@ -45,6 +51,11 @@ const categories: NodePaletteCategory[] = [
       { label: 'try/catch/finally', kind: 'tryCatch', color: 'bg-amber-600', icon: GitBranch, category: '触发与流程' },
       { label: '图像匹配', kind: 'imageMatch', color: 'bg-teal-500', icon: Search, category: '触发与流程' },
       { label: 'OCR 文字匹配', kind: 'ocrMatch', color: 'bg-emerald-500', icon: Search, category: '触发与流程' },
+      { label: 'OCR 文字读取', kind: 'ocrRead', color: 'bg-emerald-600', icon: ScanText, category: '触发与流程' },
+      { label: '像素取色', kind: 'pixelColor', color: 'bg-lime-600', icon: Pipette, category: '触发与流程' },
+      { label: '画面变化检测', kind: 'screenChange', color: 'bg-cyan-600', icon: ScanEye, category: '触发与流程' },
+      { label: '多目标等待', kind: 'waitAny', color: 'bg-teal-600', icon: GitFork, category: '触发与流程' },
+      { label: '视觉断言', kind: 'visualAssert', color: 'bg-rose-500', icon: ImageDown, category: '触发与流程' },
     ],
   },
   {
@@ -69,6 +80,7 @@ const categories: NodePaletteCategory[] = [
       { label: '执行 Python', kind: 'pythonCode', color: 'bg-blue-600', icon: Code2, category: '系统与文件' },
       { label: '读取剪贴板', kind: 'clipboardRead', color: 'bg-emerald-500', icon: Clipboard, category: '系统与文件' },
       { label: '写入剪贴板', kind: 'clipboardWrite', color: 'bg-teal-500', icon: Clipboard, category: '系统与文件' },
+      { label: '图像处理', kind: 'imageOperation', color: 'bg-teal-500', icon: Crop, category: '系统与文件' },
       { label: '弹窗提示', kind: 'showMessage', color: 'bg-orange-500', icon: MessageSquare, category: '系统与文件' },
       { label: '等待延时', kind: 'delay', color: 'bg-purple-500', icon: Clock3, category: '系统与文件' },
       { label: 'GUI Agent', kind: 'guiAgent', color: 'bg-violet-600', icon: Bot, category: '系统与文件' },
@@ -25,7 +25,31 @@ export type SystemOperationKind =
   | 'openSettings'
   | 'runCommand'
 
-export type FileOperationKind = 'copy' | 'move' | 'delete' | 'readText' | 'writeText'
+export type FileOperationKind =
+  | 'copy'
+  | 'move'
+  | 'delete'
+  | 'readText'
+  | 'writeText'
+  | 'listFiles'
+  | 'fileInfo'
+  | 'waitForFile'
+  | 'archiveCreate'
+  | 'archiveExtract'
+
+export type ImageOperationKind =
+  | 'load'
+  | 'crop'
+  | 'resize'
+  | 'rotate'
+  | 'grayscale'
+  | 'threshold'
+  | 'invert'
+  | 'drawBox'
+  | 'drawLabel'
+  | 'blur'
+  | 'redact'
+  | 'encode'
 
 export type MouseOperationKind = 'click' | 'move' | 'drag' | 'wheel' | 'down' | 'up'
 
@@ -109,6 +133,258 @@ export const FILE_OPERATION_OPTIONS: Array<{ label: string; value: FileOperation
   { label: '删除文件/文件夹', value: 'delete' },
   { label: '读取文本文件', value: 'readText' },
   { label: '写入文本文件', value: 'writeText' },
+  { label: '列出文件', value: 'listFiles' },
+  { label: '获取文件信息', value: 'fileInfo' },
+  { label: '等待文件', value: 'waitForFile' },
+  { label: '压缩为 ZIP', value: 'archiveCreate' },
+  { label: '解压 ZIP', value: 'archiveExtract' },
+]
+
+export const IMAGE_OPERATION_OPTIONS: Array<{ label: string; value: ImageOperationKind }> = [
+  { label: '加载图像', value: 'load' },
+  { label: '裁剪', value: 'crop' },
+  { label: '缩放', value: 'resize' },
+  { label: '旋转/翻转', value: 'rotate' },
+  { label: '灰度化', value: 'grayscale' },
+  { label: '二值化', value: 'threshold' },
+  { label: '反色', value: 'invert' },
+  { label: '绘制矩形框', value: 'drawBox' },
+  { label: '绘制文字标签', value: 'drawLabel' },
+  { label: '区域模糊', value: 'blur' },
+  { label: '区域遮盖', value: 'redact' },
+  { label: '编码为 PNG/JPEG/base64', value: 'encode' },
+]
+
+export const IMAGE_ROTATION_OPTIONS: Array<{ label: string; value: string }> = [
+  { label: '顺时针 90°', value: '90' },
+  { label: '旋转 180°', value: '180' },
+  { label: '逆时针 90°', value: '270' },
+  { label: '水平翻转', value: 'flipH' },
+  { label: '垂直翻转', value: 'flipV' },
+]
+
+export const IMAGE_RESIZE_FILTER_OPTIONS: Array<{ label: string; value: string }> = [
+  { label: 'Lanczos3（清晰）', value: 'lanczos3' },
+  { label: 'CatmullRom', value: 'catmullRom' },
+  { label: '双线性', value: 'triangle' },
+  { label: '最近邻（像素风）', value: 'nearest' },
+]
+
+export const FILE_LIST_SORT_OPTIONS: Array<{ label: string; value: string }> = [
+  { label: '按名称', value: 'name' },
+  { label: '按修改时间', value: 'modified' },
+  { label: '按大小', value: 'size' },
+]
+
+export const TEXT_ENCODING_OPTIONS: Array<{ label: string; value: string }> = [
+  { label: '自动识别', value: 'auto' },
+  { label: 'UTF-8', value: 'utf-8' },
+  { label: 'UTF-8 (BOM)', value: 'utf-8-bom' },
+  { label: 'UTF-16 LE', value: 'utf-16le' },
+  { label: 'UTF-16 BE', value: 'utf-16be' },
+  { label: 'GBK', value: 'gbk' },
+  { label: 'GB18030', value: 'gb18030' },
+  { label: 'Shift_JIS', value: 'shift_jis' },
+  { label: 'Windows-1252', value: 'windows-1252' },
+]
+
+// 特征匹配（ORB/AKAZE）不使用的模板匹配参数
+const IMAGE_TEMPLATE_ONLY_FIELD_KEYS = [
+  'threshold',
+  'colorMode',
+  'matchMethod',
+  'useAlphaMask',
+  'matchMode',
+  'sortBy',
+  'maxMatches',
+  'matchIndex',
+  'multiScale',
+  'scaleMin',
+  'scaleMax',
+  'scaleStep',
+]
+
+const WAIT_FOR_FIELD: ParamField = {
+  key: 'waitFor',
+  label: '等待条件',
+  type: 'select',
+  options: [
+    { label: '等待出现', value: 'appear' },
+    { label: '等待消失', value: 'disappear' },
+  ],
+  description: '等待消失时，连续「确认帧数」帧未检测到目标即走 true 分支，适合等待加载动画或进度框关闭。',
+}
+
+const SEARCH_REGION_DEFAULT_PARAMS = {
+  regionMode: 'fullScreen',
+  regionX: 0,
+  regionY: 0,
+  regionWidth: 0,
+  regionHeight: 0,
+  windowMatchTarget: 'title',
+  windowTitle: '',
+  windowProgram: '',
+  windowMatchMode: 'contains',
+  anchorX: 0,
+  anchorY: 0,
+}
+
+const SEARCH_REGION_FIELDS: ParamField[] = [
+  {
+    key: 'regionMode',
+    label: '搜索区域',
+    type: 'select',
+    options: [
+      { label: '全屏', value: 'fullScreen' },
+      { label: '绝对区域', value: 'absolute' },
+      { label: '相对窗口', value: 'window' },
+      { label: '相对锚点', value: 'anchor' },
+    ],
+    description: '限定搜索范围可减少误匹配并提升速度；输出坐标始终为屏幕坐标。',
+  },
+  {
+    key: 'windowMatchTarget',
+    label: '窗口匹配目标',
+    type: 'select',
+    options: [
+      { label: '窗口标题', value: 'title' },
+      { label: '窗口程序', value: 'program' },
+    ],
+  },
+  { key: 'windowTitle', label: '窗口标题', type: 'string', placeholder: 'Untitled - Notepad' },
+  { key: 'windowProgram', label: '窗口程序', type: 'string', placeholder: 'notepad.exe' },
+  {
+    key: 'windowMatchMode',
+    label: '窗口匹配方式',
+    type: 'select',
+    options: [
+      { label: '包含', value: 'contains' },
+      { label: '完全匹配', value: 'exact' },
+    ],
+  },
+  {
+    key: 'anchorX',
+    label: '锚点 X',
+    type: 'number',
+    step: 1,
+    description: '通常连接上一个匹配节点的 matchX 输出。',
+  },
+  {
+    key: 'anchorY',
+    label: '锚点 Y',
+    type: 'number',
+    step: 1,
+    description: '通常连接上一个匹配节点的 matchY 输出。',
+  },
+  { key: 'regionX', label: '区域 X 偏移', type: 'number', step: 1 },
+  { key: 'regionY', label: '区域 Y 偏移', type: 'number', step: 1 },
+  {
+    key: 'regionWidth',
+    label: '区域宽度',
+    type: 'number',
+    min: 0,
+    step: 1,
+    description: '相对窗口时填 0 表示延伸到窗口边缘。',
+  },
+  { key: 'regionHeight', label: '区域高度', type: 'number', min: 0, step: 1 },
+]
+
+const SEARCH_REGION_FIELD_KEYS = SEARCH_REGION_FIELDS.map((field) => field.key)
+
+const OCR_MODEL_DEFAULT_PARAMS = {
+  ocrLanguage: 'ch',
+  ocrModelDir: '',
+}
+
+const OCR_MODEL_FIELDS: ParamField[] = [
+  {
+    key: 'ocrLanguage',
+    label: '识别语言',
+    type: 'select',
+    options: [
+      { label: '中文', value: 'ch' },
+      { label: '英文', value: 'en' },
+      { label: '日文', value: 'japan' },
+      { label: '韩文', value: 'korean' },
+    ],
+    description: '非中文语言需先导入对应的本地识别模型与字典。',
+  },
+  {
+    key: 'ocrModelDir',
+    label: 'OCR 模型目录(留空=默认)',
+    type: 'string',
+    placeholder: 'C:\\ProgramData\\CommandFlow\\ocr_models',
+    description: '目录下 common 存放 det.onnx/cls.onnx，各语言子目录存放 rec.onnx/dict.txt；本地模型齐全时不再联网下载。',
+  },
+]
+
+const MONITOR_DEFAULT_PARAMS = {
+  monitorMode: 'primary',
+  monitorIndex: 1,
+  monitorName: '',
+  monitorWindowTarget: 'title',
+  monitorWindowTitle: '',
+  monitorWindowProgram: '',
+  monitorWindowMatchMode: 'contains',
+}
+
+const MONITOR_FIELDS: ParamField[] = [
+  {
+    key: 'monitorMode',
+    label: '截图来源',
+    type: 'select',
+    options: [
+      { label: '主显示器', value: 'primary' },
+      { label: '按序号', value: 'index' },
+      { label: '按名称', value: 'name' },
+      { label: '窗口所在显示器', value: 'window' },
+      { label: '指定窗口画面', value: 'windowCapture' },
+      { label: '整个虚拟桌面', value: 'virtual' },
+    ],
+    description:
+      '非主显示器时坐标均为虚拟屏幕坐标（主显示器左上角为原点，左侧或上方的显示器为负值）；指定窗口画面只截取该窗口，被遮挡时也能截到。',
+  },
+  { key: 'monitorIndex', label: '显示器序号', type: 'number', min: 1, step: 1, description: '从 1 开始，与显示器列表顺序一致。' },
+  { key: 'monitorName', label: '显示器名称', type: 'string', placeholder: '\\\\.\\DISPLAY2' },
+  {
+    key: 'monitorWindowTarget',
+    label: '窗口匹配目标',
+    type: 'select',
+    options: [
+      { label: '窗口标题', value: 'title' },
+      { label: '窗口程序', value: 'program' },
+    ],
+  },
+  { key: 'monitorWindowTitle', label: '窗口标题', type: 'string', placeholder: 'Untitled - Notepad' },
+  { key: 'monitorWindowProgram', label: '窗口程序', type: 'string', placeholder: 'notepad.exe' },
+  {
+    key: 'monitorWindowMatchMode',
+    label: '窗口匹配方式',
+    type: 'select',
+    options: [
+      { label: '包含', value: 'contains' },
+      { label: '完全匹配', value: 'exact' },
+    ],
+  },
+]
+
+const MONITOR_FIELD_KEYS = MONITOR_FIELDS.map((field) => field.key)
+
+export const LINE_ENDING_OPTIONS: Array<{ label: string; value: string }> = [
+  { label: '保持原样', value: 'keep' },
+  { label: 'LF (\\n)', value: 'lf' },
+  { label: 'CRLF (\\r\\n)', value: 'crlf' },
+]
+
+export const FILE_SAFETY_MODE_OPTIONS: Array<{ label: string; value: string }> = [
+  { label: '可撤销（备份到回收区，默认）', value: 'journal' },
+  { label: '直接执行（不可撤销）', value: 'direct' },
+  { label: '仅预演（不修改磁盘）', value: 'preview' },
+]
+
+export const FILE_WAIT_MODE_OPTIONS: Array<{ label: string; value: string }> = [
+  { label: '文件出现', value: 'exists' },
+  { label: '文件停止增长', value: 'stable' },
 ]
 
 export const MOUSE_OPERATION_OPTIONS: Array<{ label: string; value: MouseOperationKind }> = [
@@ -185,13 +461,47 @@ const SYSTEM_OPERATION_FIELD_KEYS: Record<SystemOperationKind, string[]> = {
 }
 
 const FILE_OPERATION_FIELD_KEYS: Record<FileOperationKind, string[]> = {
-  copy: ['sourcePath', 'targetPath', 'overwrite', 'recursive'],
-  move: ['sourcePath', 'targetPath', 'overwrite'],
-  delete: ['path', 'recursive'],
-  readText: ['path', 'outputVar'],
-  writeText: ['path', 'inputMode', 'inputText', 'inputVar', 'append', 'createParentDir'],
+  copy: ['sourcePath', 'targetPath', 'overwrite', 'recursive', 'safetyMode'],
+  move: ['sourcePath', 'targetPath', 'overwrite', 'safetyMode'],
+  delete: ['path', 'recursive', 'safetyMode'],
+  readText: ['path', 'encoding', 'lineEnding', 'outputVar'],
+  writeText: [
+    'path',
+    'inputMode',
+    'inputText',
+    'inputVar',
+    'encoding',
+    'lineEnding',
+    'append',
+    'createParentDir',
+    'safetyMode',
+  ],
+  listFiles: ['path', 'patterns', 'recursive', 'includeDirs', 'sortBy', 'descending', 'limit', 'listOutputVar'],
+  fileInfo: ['path'],
+  waitForFile: ['path', 'waitMode', 'stableMs', 'timeoutMs', 'pollMs', 'failOnTimeout'],
+  archiveCreate: ['sourcePath', 'targetPath', 'patterns', 'recursive', 'compressionLevel', 'overwrite'],
+  archiveExtract: ['sourcePath', 'targetPath', 'patterns', 'password', 'overwrite'],
+}
+
+const IMAGE_RECT_FIELD_KEYS = ['rectX', 'rectY', 'rectWidth', 'rectHeight']
+
+const IMAGE_OPERATION_FIELD_KEYS: Record<ImageOperationKind, string[]> = {
+  load: [],
+  crop: IMAGE_RECT_FIELD_KEYS,
+  resize: ['resizeWidth', 'resizeHeight', 'resizeFilter'],
+  rotate: ['rotation'],
+  grayscale: [],
+  threshold: ['thresholdMode', 'thresholdValue'],
+  invert: [],
+  drawBox: [...IMAGE_RECT_FIELD_KEYS, 'color', 'lineWidth'],
+  drawLabel: ['rectX', 'rectY', 'labelText', 'fontSize', 'color'],
+  blur: [...IMAGE_RECT_FIELD_KEYS, 'blurSigma'],
+  redact: [...IMAGE_RECT_FIELD_KEYS, 'redactMode', 'color', 'pixelSize'],
+  encode: ['encodeFormat', 'encodeOutput'],
 }
 
+const IMAGE_OPERATION_COMMON_FIELD_KEYS = ['operation', 'inputMode', 'savePath', 'jpegQuality', 'outputVar']
+
 const MOUSE_OPERATION_FIELD_KEYS: Record<MouseOperationKind, string[]> = {
   click: ['x', 'y', 'times'],
   move: ['x', 'y'],
@@ -229,6 +539,16 @@ export const getFileOperationKind = (
     : defaultOperation
 }
 
+export const getImageOperationKind = (
+  params: Record<string, unknown>,
+  defaultOperation: ImageOperationKind = 'grayscale',
+): ImageOperationKind => {
+  const operation = String(params.operation ?? defaultOperation)
+  return IMAGE_OPERATION_OPTIONS.some((item) => item.value === operation)
+    ? (operation as ImageOperationKind)
+    : defaultOperation
+}
+
 export const getMouseOperationKind = (
   params: Record<string, unknown>,
   defaultOperation: MouseOperationKind = 'click',
@@ -325,6 +645,15 @@ export const getFileOperationLabel = (
   return FILE_OPERATION_OPTIONS.find((item) => item.value === operation)?.label ?? '文件操作'
 }
 
+export const getImageOperationLabel = (
+  params: Record<string, unknown>,
+  defaultOperation: ImageOperationKind = 'grayscale',
+): string => {
+  const operation = getImageOperationKind(params, defaultOperation)
+  const label = IMAGE_OPERATION_OPTIONS.find((item) => item.value === operation)?.label
+  return label ? `图像处理 · ${label}` : '图像处理'
+}
+
 export const getMouseOperationLabel = (
   params: Record<string, unknown>,
   defaultOperation: MouseOperationKind = 'click',
@@ -371,6 +700,10 @@ export const getNodeDisplayLabel = (
     return getFileOperationLabel(params, getFileOperationKind(getNodeMeta(kind).defaultParams))
   }
 
+  if (kind === 'imageOperation') {
+    return getImageOperationLabel(params, getImageOperationKind(getNodeMeta(kind).defaultParams))
+  }
+
   if (kind === 'mouseOperation') {
     return getMouseOperationLabel(params, getMouseOperationKind(getNodeMeta(kind).defaultParams))
   }
@@ -440,6 +773,10 @@ export const isNodeFieldVisible = (
       return false
     }
 
+    if (field.key === 'stableMs' && operation === 'waitForFile') {
+      return String(params.waitMode ?? defaultParams.waitMode ?? 'exists') === 'stable'
+    }
+
     if ((field.key === 'inputText' || field.key === 'inputVar') && operation === 'writeText') {
       const inputMode = String(params.inputMode ?? defaultParams.inputMode ?? 'literal')
       if (field.key === 'inputText') return inputMode === 'literal'
@@ -449,6 +786,32 @@ export const isNodeFieldVisible = (
     return true
   }
 
+  if (kind === 'imageOperation') {
+    if (field.key === 'source' || field.key === 'inputVar') {
+      const inputMode = String(params.inputMode ?? defaultParams.inputMode ?? 'literal')
+      return field.key === 'source' ? inputMode === 'literal' : inputMode === 'var'
+    }
+    if (IMAGE_OPERATION_COMMON_FIELD_KEYS.includes(field.key)) return true
+
+    const operation = getImageOperationKind(
+      params,
+      getImageOperationKind(defaultParams, 'grayscale'),
+    )
+    if (!IMAGE_OPERATION_FIELD_KEYS[operation].includes(field.key)) {
+      return false
+    }
+    if (field.key === 'thresholdValue') {
+      return String(params.thresholdMode ?? defaultParams.thresholdMode ?? 'otsu') === 'fixed'
+    }
+    if (field.key === 'pixelSize') {
+      return String(params.redactMode ?? defaultParams.redactMode ?? 'fill') === 'pixelate'
+    }
+    if (field.key === 'color' && operation === 'redact') {
+      return String(params.redactMode ?? defaultParams.redactMode ?? 'fill') === 'fill'
+    }
+    return true
+  }
+
   if (kind === 'mouseOperation') {
     if (field.key === 'operation') return true
     const operation = getMouseOperationKind(
@@ -511,6 +874,71 @@ export const isNodeFieldVisible = (
     return false
   }
 
+  if (kind === 'imageMatch') {
+    const algorithm = String(params.algorithm ?? defaultParams.algorithm ?? 'template')
+    if (field.key === 'minInliers') return algorithm !== 'template'
+    if (algorithm !== 'template' && IMAGE_TEMPLATE_ONLY_FIELD_KEYS.includes(field.key)) return false
+  }
+
+  if (kind === 'imageMatch' && ['scaleMin', 'scaleMax', 'scaleStep'].includes(field.key)) {
+    return Boolean(params.multiScale ?? defaultParams.multiScale ?? false)
+  }
+
+  if (kind === 'imageMatch' && ['sortBy', 'maxMatches', 'matchIndex'].includes(field.key)) {
+    return String(params.matchMode ?? defaultParams.matchMode ?? 'best') === 'all'
+  }
+
+  if (kind === 'pixelColor' && ['timeoutMs', 'pollMs'].includes(field.key)) {
+    return Boolean(params.waitUntilMatch ?? defaultParams.waitUntilMatch ?? false)
+  }
+
+  if ((kind === 'imageMatch' || kind === 'ocrMatch') && ['clickOnMatch', 'clickTimes'].includes(field.key)) {
+    return String(params.waitFor ?? defaultParams.waitFor ?? 'appear') !== 'disappear'
+  }
+
+  if (kind === 'ocrMatch' && ['minSimilarity', 'similarityAlgorithm'].includes(field.key)) {
+    const useRegex = Boolean(params.useRegex ?? defaultParams.useRegex ?? false)
+    return !useRegex && String(params.matchMode ?? defaultParams.matchMode ?? 'contains') === 'fuzzy'
+  }
+
+  if (kind === 'ocrMatch' && ['normalizeWhitespace', 'normalizeWidth', 'normalizeConfusables'].includes(field.key)) {
+    return !Boolean(params.useRegex ?? defaultParams.useRegex ?? false)
+  }
+
+  if (kind === 'visualAssert' && field.key === 'pollMs') {
+    return Number(params.timeoutMs ?? defaultParams.timeoutMs ?? 0) > 0
+  }
+
+  if (kind === 'screenChange' && field.key === 'stableMs') {
+    return String(params.mode ?? defaultParams.mode ?? 'change') === 'stable'
+  }
+
+  if (['screenshot', 'imageMatch', 'ocrMatch', 'guiAgent', 'visualAssert'].includes(kind) && MONITOR_FIELD_KEYS.includes(field.key)) {
+    if (kind === 'guiAgent' && !Boolean(params.continuousMode ?? defaultParams.continuousMode ?? true)) return false
+    const monitorMode = String(params.monitorMode ?? defaultParams.monitorMode ?? 'primary')
+    if (field.key === 'monitorMode') return true
+    if (field.key === 'monitorIndex') return monitorMode === 'index'
+    if (field.key === 'monitorName') return monitorMode === 'name'
+    const byWindow = monitorMode === 'window' || monitorMode === 'windowCapture'
+    if (field.key === 'monitorWindowTitle' || field.key === 'monitorWindowProgram') {
+      const target = String(params.monitorWindowTarget ?? defaultParams.monitorWindowTarget ?? 'title')
+      return byWindow && field.key === (target === 'program' ? 'monitorWindowProgram' : 'monitorWindowTitle')
+    }
+    return byWindow
+  }
+
+  if (['imageMatch', 'ocrMatch', 'ocrRead', 'screenChange', 'visualAssert'].includes(kind) && SEARCH_REGION_FIELD_KEYS.includes(field.key)) {
+    const regionMode = String(params.regionMode ?? defaultParams.regionMode ?? 'fullScreen')
+    if (field.key === 'regionMode') return true
+    if (['anchorX', 'anchorY'].includes(field.key)) return regionMode === 'anchor'
+    if (['windowMatchTarget', 'windowMatchMode'].includes(field.key)) return regionMode === 'window'
+    if (field.key === 'windowTitle' || field.key === 'windowProgram') {
+      const target = String(params.windowMatchTarget ?? defaultParams.windowMatchTarget ?? 'title')
+      return regionMode === 'window' && field.key === (target === 'program' ? 'windowProgram' : 'windowTitle')
+    }
+    return regionMode !== 'fullScreen'
+  }
+
   if (kind === 'clipboardRead') {
     if (field.key === 'readMode') return true
     return ['outputVar', 'outputTextVar', 'outputImageVar'].includes(field.key)
@@ -581,7 +1009,7 @@ export const isNodeFieldVisible = (
   if ((kind === 'varDefine' || kind === 'varSet' || kind === 'constValue') && field.key.startsWith('value')) {
     const valueType = String(params.valueType ?? defaultParams.valueType ?? 'number')
     if (field.key === 'valueType') return true
-    if (field.key === 'valueString') return valueType === 'string'
+    if (field.key === 'valueString') return valueType === 'string' || valueType === 'secret'
     if (field.key === 'valueNumber') return valueType === 'number'
     if (field.key === 'valueBoolean') return valueType === 'boolean'
     if (field.key === 'valueJson') return valueType === 'json'
@@ -719,28 +1147,65 @@ const resolveFileOperationField = (
   if (field.key === 'sourcePath') {
     return {
       ...field,
-      label: '源路径',
-      placeholder: operation === 'move' ? 'C:\\input\\folder-a' : 'C:\\input\\a.txt',
+      label:
+        operation === 'archiveCreate'
+          ? '待压缩文件或目录'
+          : operation === 'archiveExtract'
+            ? 'ZIP 文件路径'
+            : '源路径',
+      placeholder:
+        operation === 'move' || operation === 'archiveCreate'
+          ? 'C:\\input\\folder-a'
+          : operation === 'archiveExtract'
+            ? 'D:\\upload\\daily.zip'
+            : 'C:\\input\\a.txt',
     }
   }
 
   if (field.key === 'targetPath') {
     return {
       ...field,
-      label: '目标路径',
-      placeholder: operation === 'move' ? 'D:\\output\\folder-a' : 'D:\\output\\a.txt',
+      label:
+        operation === 'archiveCreate'
+          ? 'ZIP 输出路径'
+          : operation === 'archiveExtract'
+            ? '解压到目录'
+            : '目标路径',
+      placeholder:
+        operation === 'move' || operation === 'archiveExtract'
+          ? 'D:\\output\\folder-a'
+          : operation === 'archiveCreate'
+            ? 'D:\\upload\\daily.zip'
+            : 'D:\\output\\a.txt',
     }
   }
 
+  if (field.key === 'patterns' && operation === 'archiveExtract') {
+    return {
+      ...field,
+      label: '仅解压匹配项',
+      placeholder: '*.csv; reports/*',
+      description: '留空表示解压全部条目；按压缩包内的相对路径匹配。',
+    }
+  }
+
+  if (field.key === 'overwrite' && operation === 'archiveCreate') {
+    return { ...field, label: '覆盖已存在的压缩包' }
+  }
+
   if (field.key === 'path') {
     return {
       ...field,
       label:
         operation === 'delete'
           ? '路径'
-          : operation === 'readText' || operation === 'writeText'
+          : operation === 'readText' || operation === 'writeText' || operation === 'fileInfo'
             ? '文件路径'
-            : '路径',
+            : operation === 'listFiles'
+              ? '目录路径'
+              : operation === 'waitForFile'
+                ? '文件路径(文件名支持通配符)'
+                : '路径',
       placeholder:
         operation === 'delete'
           ? 'D:\\temp\\old-folder'
@@ -748,14 +1213,40 @@ const resolveFileOperationField = (
             ? 'C:\\temp\\note.txt'
             : operation === 'writeText'
               ? 'D:\\output\\result.txt'
-              : 'D:\\temp\\path',
+              : operation === 'listFiles'
+                ? 'C:\\Users\\me\\Downloads'
+                : operation === 'waitForFile'
+                  ? 'C:\\Users\\me\\Downloads\\export-*.xlsx'
+                  : 'D:\\temp\\path',
     }
   }
 
   if (field.key === 'recursive') {
     return {
       ...field,
-      label: operation === 'delete' ? '目录递归删除' : '目录递归复制',
+      label:
+        operation === 'delete'
+          ? '目录递归删除'
+          : operation === 'listFiles' || operation === 'archiveCreate'
+            ? '包含子目录'
+            : '目录递归复制',
+    }
+  }
+
+  if (field.key === 'encoding') {
+    return operation === 'writeText'
+      ? {
+          ...field,
+          label: '写入编码',
+          options: TEXT_ENCODING_OPTIONS.filter((item) => item.value !== 'auto'),
+        }
+      : { ...field, label: '读取编码' }
+  }
+
+  if (field.key === 'lineEnding') {
+    return {
+      ...field,
+      label: operation === 'writeText' ? '写入换行符' : '换行符转换',
     }
   }
 
@@ -1084,13 +1575,23 @@ const metas: Record<NodeKind, NodeMeta> = {
   screenshot: {
     label: '屏幕截图',
     description: '执行屏幕截图，可选择是否保存到本地文件夹，并输出截图(base64)。',
-    defaultParams: { shouldSave: true, saveDir: '', fullscreen: false, startX: 0, startY: 0, width: 320, height: 240 },
+    defaultParams: {
+      shouldSave: true,
+      saveDir: '',
+      fullscreen: false,
+      startX: 0,
+      startY: 0,
+      width: 320,
+      height: 240,
+      ...MONITOR_DEFAULT_PARAMS,
+    },
     fields: [
       { key: 'shouldSave', label: '是否保存', type: 'boolean' },
       { key: 'saveDir', label: '保存文件夹', type: 'string', placeholder: 'D:\\captures' },
-      { key: 'fullscreen', label: '是否全屏', type: 'boolean' },
-      { key: 'startX', label: '起点 X', type: 'number', min: 0, step: 1 },
-      { key: 'startY', label: '起点 Y', type: 'number', min: 0, step: 1 },
+      { key: 'fullscreen', label: '是否全屏', type: 'boolean', description: '全屏时截取截图来源的完整画面；指定窗口画面时区域坐标相对窗口左上角。' },
+      ...MONITOR_FIELDS,
+      { key: 'startX', label: '起点 X', type: 'number', step: 1 },
+      { key: 'startY', label: '起点 Y', type: 'number', step: 1 },
       { key: 'width', label: '宽度', type: 'number', min: 1, step: 1 },
       { key: 'height', label: '高度', type: 'number', min: 1, step: 1 },
     ],
@@ -1103,6 +1604,7 @@ const metas: Record<NodeKind, NodeMeta> = {
       maxSteps: 20,
       llmPresetId: '',
       imageInput: '',
+      ...MONITOR_DEFAULT_PARAMS,
       stripThink: true,
       instruction: '请根据截图执行下一步操作。',
       systemPrompt: `You are a GUI agent. You are given a task and your action history, with screenshots. You need to perform the next action to complete the task.
@@ -1157,6 +1659,7 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
         placeholder: '连接图像，或输入base64',
         description: '非连续模式需手动提供图片输入。',
       },
+      ...MONITOR_FIELDS,
       {
         key: 'instruction',
         label: '指令',
@@ -1323,7 +1826,7 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
   },
   fileOperation: {
     label: '文件操作',
-    description: '统一的文件操作节点；先选择复制、移动、删除、读取文本或写入文本，再按需填写对应参数。',
+    description: '统一的文件操作节点；先选择复制、移动、删除、读写文本、列出文件、获取文件信息、等待文件或 ZIP 压缩解压，再按需填写对应参数。',
     defaultParams: {
       operation: 'copy',
       sourcePath: '',
@@ -1337,6 +1840,22 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
       outputVar: 'fileText',
       append: false,
       createParentDir: true,
+      safetyMode: 'journal',
+      encoding: 'auto',
+      lineEnding: 'keep',
+      patterns: '*',
+      includeDirs: false,
+      compressionLevel: 6,
+      password: '',
+      sortBy: 'name',
+      descending: false,
+      limit: 0,
+      listOutputVar: 'fileList',
+      waitMode: 'exists',
+      stableMs: 2000,
+      timeoutMs: 30000,
+      pollMs: 500,
+      failOnTimeout: true,
     },
     fields: [
       {
@@ -1366,9 +1885,64 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
         placeholder: '支持多行文本，也支持 {{变量名}} 模板占位。',
       },
       { key: 'inputVar', label: '变量名', type: 'string', placeholder: 'fileText' },
+      { key: 'encoding', label: '编码', type: 'select', options: TEXT_ENCODING_OPTIONS },
+      { key: 'lineEnding', label: '换行符', type: 'select', options: LINE_ENDING_OPTIONS },
       { key: 'outputVar', label: '输出变量名', type: 'string', placeholder: 'fileText' },
       { key: 'append', label: '追加写入', type: 'boolean' },
       { key: 'createParentDir', label: '自动创建父目录', type: 'boolean' },
+      {
+        key: 'safetyMode',
+        label: '安全模式',
+        type: 'select',
+        options: FILE_SAFETY_MODE_OPTIONS,
+        description: '默认可撤销：被覆盖或删除的内容备份到本次运行的回收区，可在工具栏撤销；未设置时同样按可撤销执行。回收区保留 7 天，总占用超过 2 GB 时自动清理最旧的运行。直接执行不留备份，预演模式只记录将要执行的变更。',
+      },
+      {
+        key: 'patterns',
+        label: '匹配模式',
+        type: 'string',
+        placeholder: '*.xlsx; report-*.csv',
+        description: '支持 * ? [] 通配符，多个模式用分号分隔；包含 / 时按相对路径匹配。',
+      },
+      { key: 'includeDirs', label: '包含文件夹', type: 'boolean' },
+      {
+        key: 'compressionLevel',
+        label: '压缩级别',
+        type: 'number',
+        min: 0,
+        max: 9,
+        step: 1,
+        description: '0 表示仅存储不压缩，9 为最高压缩率。',
+      },
+      {
+        key: 'password',
+        label: '解压密码',
+        type: 'string',
+        placeholder: '无密码留空',
+      },
+      { key: 'sortBy', label: '排序方式', type: 'select', options: FILE_LIST_SORT_OPTIONS },
+      { key: 'descending', label: '降序排列', type: 'boolean' },
+      {
+        key: 'limit',
+        label: '最多返回条数',
+        type: 'number',
+        min: 0,
+        step: 1,
+        description: '0 表示不限制；配合按修改时间降序可取最新文件。',
+      },
+      { key: 'listOutputVar', label: '输出变量名', type: 'string', placeholder: 'fileList' },
+      { key: 'waitMode', label: '等待条件', type: 'select', options: FILE_WAIT_MODE_OPTIONS },
+      {
+        key: 'stableMs',
+        label: '稳定时长(ms)',
+        type: 'number',
+        min: 0,
+        step: 100,
+        description: '文件大小在该时长内保持不变才视为写入完成。',
+      },
+      { key: 'timeoutMs', label: '超时(ms)', type: 'number', min: 0, step: 100 },
+      { key: 'pollMs', label: '轮询间隔(ms)', type: 'number', min: 1, step: 50 },
+      { key: 'failOnTimeout', label: '超时视为失败', type: 'boolean' },
     ],
   },
   pythonCode: {
@@ -1425,9 +1999,156 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
       },
     ],
   },
+  imageOperation: {
+    label: '图像处理',
+    description: '对图像值做裁剪、缩放、旋转、灰度化、二值化、反色、画框/标注、模糊/遮盖或编码；输入可为文件路径、Data URL 或截图等节点输出的图像，输出图像可直接连到 OCR、找图或写入剪贴板。',
+    defaultParams: {
+      operation: 'grayscale',
+      inputMode: 'literal',
+      source: '',
+      inputVar: 'image',
+      rectX: 0,
+      rectY: 0,
+      rectWidth: 0,
+      rectHeight: 0,
+      resizeWidth: 0,
+      resizeHeight: 0,
+      resizeFilter: 'lanczos3',
+      rotation: '90',
+      thresholdMode: 'otsu',
+      thresholdValue: 128,
+      color: '#FF0000',
+      lineWidth: 2,
+      labelText: '',
+      fontSize: 16,
+      blurSigma: 4,
+      redactMode: 'fill',
+      pixelSize: 12,
+      encodeFormat: 'png',
+      encodeOutput: 'dataUrl',
+      jpegQuality: 90,
+      savePath: '',
+      outputVar: 'image',
+    },
+    fields: [
+      {
+        key: 'operation',
+        label: '操作类型',
+        type: 'select',
+        options: IMAGE_OPERATION_OPTIONS,
+      },
+      {
+        key: 'inputMode',
+        label: '输入来源',
+        type: 'select',
+        options: [
+          { label: '路径 / Data URL / 端口输入', value: 'literal' },
+          { label: '变量', value: 'var' },
+        ],
+      },
+      {
+        key: 'source',
+        label: '输入图像',
+        type: 'string',
+        placeholder: 'D:\\screens\\current.png 或 {{image}}',
+        description: '支持图片文件路径、Data URL、纯 base64，也可连接截图、读取剪贴板或上一个图像处理节点的图像输出。',
+      },
+      { key: 'inputVar', label: '图像变量名', type: 'string', placeholder: 'image' },
+      { key: 'rectX', label: '区域 X', type: 'number', min: 0 },
+      { key: 'rectY', label: '区域 Y', type: 'number', min: 0 },
+      {
+        key: 'rectWidth',
+        label: '区域宽度',
+        type: 'number',
+        min: 0,
+        description: '0 表示延伸到图像右边缘。',
+      },
+      {
+        key: 'rectHeight',
+        label: '区域高度',
+        type: 'number',
+        min: 0,
+        description: '0 表示延伸到图像下边缘。',
+      },
+      {
+        key: 'resizeWidth',
+        label: '目标宽度',
+        type: 'number',
+        min: 0,
+        description: '宽高其中一项为 0 时按比例缩放。',
+      },
+      { key: 'resizeHeight', label: '目标高度', type: 'number', min: 0 },
+      { key: 'resizeFilter', label: '缩放算法', type: 'select', options: IMAGE_RESIZE_FILTER_OPTIONS },
+      { key: 'rotation', label: '旋转方式', type: 'select', options: IMAGE_ROTATION_OPTIONS },
+      {
+        key: 'thresholdMode',
+        label: '阈值方式',
+        type: 'select',
+        options: [
+          { label: '自动 (Otsu)', value: 'otsu' },
+          { label: '固定阈值', value: 'fixed' },
+        ],
+      },
+      { key: 'thresholdValue', label: '阈值', type: 'number', min: 0, max: 255 },
+      { key: 'color', label: '颜色', type: 'string', placeholder: '#FF0000' },
+      { key: 'lineWidth', label: '线宽', type: 'number', min: 1 },
+      {
+        key: 'labelText',
+        label: '标签文字',
+        type: 'string',
+        placeholder: '匹配结果 {{ocrText}}',
+        description: '绘制在区域 X/Y 位置，文字颜色自动与底色对比。',
+      },
+      { key: 'fontSize', label: '字号', type: 'number', min: 6 },
+      { key: 'blurSigma', label: '模糊强度', type: 'number', min: 0.5, step: 0.5 },
+      {
+        key: 'redactMode',
+        label: '遮盖方式',
+        type: 'select',
+        options: [
+          { label: '纯色填充', value: 'fill' },
+          { label: '马赛克', value: 'pixelate' },
+        ],
+      },
+      { key: 'pixelSize', label: '马赛克块大小', type: 'number', min: 2 },
+      {
+        key: 'encodeFormat',
+        label: '编码格式',
+        type: 'select',
+        options: [
+          { label: 'PNG', value: 'png' },
+          { label: 'JPEG', value: 'jpeg' },
+        ],
+      },
+      {
+        key: 'encodeOutput',
+        label: '编码输出',
+        type: 'select',
+        options: [
+          { label: 'Data URL', value: 'dataUrl' },
+          { label: '纯 base64', value: 'base64' },
+        ],
+      },
+      { key: 'jpegQuality', label: 'JPEG 质量', type: 'number', min: 1, max: 100 },
+      {
+        key: 'savePath',
+        label: '保存路径(可选)',
+        type: 'string',
+        placeholder: 'D:\\output\\processed.png',
+        description: '按扩展名保存为 PNG 或 JPEG；留空时图像保留在内存中。',
+      },
+      {
+        key: 'outputVar',
+        label: '输出变量名',
+        type: 'string',
+        placeholder: 'image',
+        description: '写入图像对象：包含 type / image 或 path / width / height / format。',
+      },
+    ],
+  },
   clipboardWrite: {
     label: '写入剪贴板',
-    description: '向系统剪贴板写入文本或图片。图片支持 base64 / Data URL / 文件路径 / 变量。',
+    description: '向系统剪贴板写入文本或图片。图片支持 base64 / Data URL / 文件路径 / 变量，也可直接连接图像处理节点的图像输出。',
     defaultParams: {
       contentType: 'text',
       inputMode: 'literal',
@@ -1745,12 +2466,28 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
     defaultParams: {
       sourcePath: '',
       templatePath: '',
+      algorithm: 'template',
+      minInliers: 12,
       threshold: 0.99,
       timeoutMs: 10000,
       pollMs: 16,
       confirmFrames: 2,
+      waitFor: 'appear',
+      colorMode: 'gray',
+      matchMethod: 'ssd',
+      useAlphaMask: true,
       clickOnMatch: false,
       clickTimes: 1,
+      multiScale: false,
+      scaleMin: 0.5,
+      scaleMax: 2,
+      scaleStep: 0.1,
+      matchMode: 'best',
+      sortBy: 'score',
+      maxMatches: 50,
+      matchIndex: 1,
+      ...SEARCH_REGION_DEFAULT_PARAMS,
+      ...MONITOR_DEFAULT_PARAMS,
     },
     fields: [
       {
@@ -1758,6 +2495,7 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
         label: '源图路径(留空=实时截图)',
         type: 'string',
         placeholder: 'D:\\screens\\current.png',
+        description: '也可连接截图或图像处理节点输出的图像，预处理后再识别更准确。',
       },
       {
         key: 'templatePath',
@@ -1765,6 +2503,25 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
         type: 'string',
         placeholder: 'D:\\templates\\button.png',
       },
+      {
+        key: 'algorithm',
+        label: '匹配方式',
+        type: 'select',
+        options: [
+          { label: '模板匹配', value: 'template' },
+          { label: 'ORB 特征匹配', value: 'orb' },
+          { label: 'AKAZE 特征匹配', value: 'akaze' },
+        ],
+        description: '特征匹配可识别旋转、缩放、部分遮挡或字体渲染变化后的目标，需以 opencv-support 特性编译。',
+      },
+      {
+        key: 'minInliers',
+        label: '最少内点数',
+        type: 'number',
+        min: 4,
+        step: 1,
+        description: '单应性估计后一致的特征点数不少于该值才判定命中；越大越严格。',
+      },
       {
         key: 'threshold',
         label: '阈值(0~1)',
@@ -1790,6 +2547,81 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
         step: 1,
         description: '连续命中达到该帧数才判定成功。',
       },
+      WAIT_FOR_FIELD,
+      {
+        key: 'colorMode',
+        label: '颜色模式',
+        type: 'select',
+        options: [
+          { label: '灰度', value: 'gray' },
+          { label: '彩色(RGB)', value: 'rgb' },
+        ],
+        description: '需要区分红/绿等同亮度颜色时选择彩色，耗时约为灰度的 3 倍。',
+      },
+      {
+        key: 'matchMethod',
+        label: '匹配算法',
+        type: 'select',
+        options: [
+          { label: '平方差 SSD', value: 'ssd' },
+          { label: '归一化互相关 NCC', value: 'ncc' },
+          { label: '零均值归一化互相关 ZNCC', value: 'zncc' },
+        ],
+        description: 'ZNCC 对整体亮度/对比度变化不敏感；仅 SSD 且模板不透明时可使用 GPU 加速。',
+      },
+      {
+        key: 'useAlphaMask',
+        label: '忽略模板透明区域',
+        type: 'boolean',
+        description: '模板 PNG 含透明像素时，透明部分不参与比对，适合非矩形图标；启用掩码的模板只能使用 CPU 计算。',
+      },
+      {
+        key: 'matchMode',
+        label: '匹配模式',
+        type: 'select',
+        options: [
+          { label: '最佳匹配', value: 'best' },
+          { label: '查找全部', value: 'all' },
+        ],
+        description: '查找全部时输出所有超过阈值的位置（已去重），可用于计数或遍历重复图标。',
+      },
+      {
+        key: 'sortBy',
+        label: '结果排序',
+        type: 'select',
+        options: [
+          { label: '按相似度', value: 'score' },
+          { label: '按阅读顺序(从上到下、从左到右)', value: 'readingOrder' },
+        ],
+      },
+      { key: 'maxMatches', label: '最多结果数', type: 'number', min: 1, step: 1 },
+      {
+        key: 'matchIndex',
+        label: '取第 N 个结果',
+        type: 'number',
+        min: 1,
+        step: 1,
+        description: 'matchX/matchY 与自动点击使用排序后的第 N 个结果；结果不足 N 个时视为未命中。',
+      },
+      {
+        key: 'multiScale',
+        label: '多尺度匹配',
+        type: 'boolean',
+        description: '模板与屏幕缩放比例（DPI、浏览器缩放）不一致时开启，按比例范围逐级搜索。',
+      },
+      { key: 'scaleMin', label: '最小缩放比例', type: 'number', min: 0.1, max: 4, step: 0.05 },
+      { key: 'scaleMax', label: '最大缩放比例', type: 'number', min: 0.1, max: 4, step: 0.05 },
+      {
+        key: 'scaleStep',
+        label: '缩放步长',
+        type: 'number',
+        min: 0.01,
+        max: 1,
+        step: 0.01,
+        description: '步长越小越精确，但每帧匹配耗时越长。',
+      },
+      ...SEARCH_REGION_FIELDS,
+      ...MONITOR_FIELDS,
       { key: 'clickOnMatch', label: '匹配成功后自动点击', type: 'boolean' },
       { key: 'clickTimes', label: '点击次数', type: 'number', min: 1, step: 1 },
     ],
@@ -1803,12 +2635,21 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
       matchMode: 'contains',
       caseSensitive: false,
       useRegex: false,
+      minSimilarity: 0.8,
+      similarityAlgorithm: 'levenshtein',
+      normalizeWhitespace: false,
+      normalizeWidth: false,
+      normalizeConfusables: false,
       minConfidence: 0.5,
       timeoutMs: 10000,
       pollMs: 120,
       confirmFrames: 2,
+      waitFor: 'appear',
       clickOnMatch: false,
       clickTimes: 1,
+      ...SEARCH_REGION_DEFAULT_PARAMS,
+      ...MONITOR_DEFAULT_PARAMS,
+      ...OCR_MODEL_DEFAULT_PARAMS,
     },
     fields: [
       {
@@ -1816,6 +2657,7 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
         label: '源图路径(留空=实时截图)',
         type: 'string',
         placeholder: 'D:\\screens\\current.png',
+        description: '也可连接截图或图像处理节点输出的图像，预处理后再识别更准确。',
       },
       {
         key: 'targetText',
@@ -1830,10 +2672,37 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
         options: [
           { label: '包含', value: 'contains' },
           { label: '完全匹配', value: 'exact' },
+          { label: '模糊匹配', value: 'fuzzy' },
         ],
       },
       { key: 'caseSensitive', label: '区分大小写', type: 'boolean' },
       { key: 'useRegex', label: '将目标文本当作正则表达式', type: 'boolean' },
+      {
+        key: 'minSimilarity',
+        label: '最小相似度(0~1)',
+        type: 'number',
+        min: 0,
+        max: 1,
+        step: 0.01,
+        description: '识别文本中与目标最接近的片段达到该相似度即视为命中。',
+      },
+      {
+        key: 'similarityAlgorithm',
+        label: '相似度算法',
+        type: 'select',
+        options: [
+          { label: '编辑距离(Levenshtein)', value: 'levenshtein' },
+          { label: 'Jaro-Winkler', value: 'jaroWinkler' },
+        ],
+      },
+      { key: 'normalizeWhitespace', label: '忽略空白字符', type: 'boolean' },
+      { key: 'normalizeWidth', label: '全角转半角', type: 'boolean' },
+      {
+        key: 'normalizeConfusables',
+        label: '归并易混淆字符',
+        type: 'boolean',
+        description: '将 O/o→0、I/l/|→1、Z→2、S→5、B→8 等 OCR 常见混淆字符视为相同。',
+      },
       {
         key: 'minConfidence',
         label: '最小置信度(0~1)',
@@ -1859,10 +2728,265 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
         step: 1,
         description: '连续命中达到该帧数才判定成功。',
       },
+      WAIT_FOR_FIELD,
+      ...SEARCH_REGION_FIELDS,
+      ...MONITOR_FIELDS,
+      ...OCR_MODEL_FIELDS,
       { key: 'clickOnMatch', label: '匹配成功后自动点击', type: 'boolean' },
       { key: 'clickTimes', label: '点击次数', type: 'number', min: 1, step: 1 },
     ],
   },
+  ocrRead: {
+    label: 'OCR 文字读取',
+    description: '识别截图或源图中的全部文字，按阅读顺序输出文本和各文本块位置。',
+    defaultParams: {
+      sourcePath: '',
+      minConfidence: 0.5,
+      grouping: 'lines',
+      ...SEARCH_REGION_DEFAULT_PARAMS,
+      ...OCR_MODEL_DEFAULT_PARAMS,
+    },
+    fields: [
+      {
+        key: 'sourcePath',
+        label: '源图路径(留空=实时截图)',
+        type: 'string',
+        placeholder: 'D:\\screens\\current.png',
+        description: '也可连接截图或图像处理节点输出的图像，预处理后再识别更准确。',
+      },
+      {
+        key: 'minConfidence',
+        label: '最小置信度(0~1)',
+        type: 'number',
+        min: 0,
+        max: 1,
+        step: 0.01,
+      },
+      {
+        key: 'grouping',
+        label: '文本拼接方式',
+        type: 'select',
+        options: [
+          { label: '按文本块', value: 'blocks' },
+          { label: '按行', value: 'lines' },
+          { label: '按段落', value: 'paragraphs' },
+        ],
+        description: 'blocks 输出包含每个文本块的 text、confidence、中心点 x/y、外接矩形和四角坐标 quad。',
+      },
+      ...SEARCH_REGION_FIELDS,
+      ...OCR_MODEL_FIELDS,
+    ],
+  },
+  pixelColor: {
+    label: '像素取色',
+    description: '读取屏幕指定坐标的颜色，并与目标颜色比较；可等待颜色变化。',
+    defaultParams: {
+      x: 0,
+      y: 0,
+      sampleRadius: 0,
+      targetColor: '',
+      tolerance: 16,
+      waitUntilMatch: false,
+      timeoutMs: 10000,
+      pollMs: 50,
+    },
+    fields: [
+      { key: 'x', label: 'X 坐标', type: 'number', step: 1 },
+      { key: 'y', label: 'Y 坐标', type: 'number', step: 1 },
+      {
+        key: 'sampleRadius',
+        label: '取样半径(px)',
+        type: 'number',
+        min: 0,
+        max: 16,
+        step: 1,
+        description: '0 为单个像素；大于 0 时取以坐标为中心的方形区域平均色，可抵消抗锯齿与噪点。',
+      },
+      {
+        key: 'targetColor',
+        label: '目标颜色',
+        type: 'string',
+        placeholder: '#22C55E',
+        description: '留空时仅取色并走 true 分支。',
+      },
+      {
+        key: 'tolerance',
+        label: '容差(0~255)',
+        type: 'number',
+        min: 0,
+        max: 255,
+        step: 1,
+        description: 'R/G/B 各通道与目标色的最大允许差值。',
+      },
+      {
+        key: 'waitUntilMatch',
+        label: '等待颜色匹配',
+        type: 'boolean',
+        description: '开启后持续读取实时帧，直到颜色匹配或超时。',
+      },
+      { key: 'timeoutMs', label: '超时(ms)', type: 'number', min: 0, step: 100 },
+      { key: 'pollMs', label: '轮询间隔(ms)', type: 'number', min: 1, step: 1 },
+    ],
+  },
+  screenChange: {
+    label: '画面变化检测',
+    description: '监视屏幕区域，等待画面发生变化或保持稳定，适合等待页面渲染完成。',
+    defaultParams: {
+      mode: 'change',
+      changeThreshold: 1,
+      pixelThreshold: 24,
+      stableMs: 1000,
+      timeoutMs: 10000,
+      pollMs: 50,
+      ...SEARCH_REGION_DEFAULT_PARAMS,
+    },
+    fields: [
+      {
+        key: 'mode',
+        label: '检测模式',
+        type: 'select',
+        options: [
+          { label: '等待变化', value: 'change' },
+          { label: '等待稳定', value: 'stable' },
+        ],
+        description: '等待变化与节点开始时的首帧比较；等待稳定逐帧比较，持续无变化达到指定时长即通过。',
+      },
+      {
+        key: 'changeThreshold',
+        label: '变化阈值(%)',
+        type: 'number',
+        min: 0,
+        max: 100,
+        step: 0.1,
+        description: '变化像素占区域的百分比超过该值视为发生变化。',
+      },
+      {
+        key: 'pixelThreshold',
+        label: '像素灰度阈值(0~255)',
+        type: 'number',
+        min: 0,
+        max: 255,
+        step: 1,
+        description: '单个像素灰度差超过该值才计为变化，用于过滤噪点。',
+      },
+      { key: 'stableMs', label: '稳定时长(ms)', type: 'number', min: 0, step: 100 },
+      { key: 'timeoutMs', label: '超时(ms)', type: 'number', min: 0, step: 100 },
+      { key: 'pollMs', label: '轮询间隔(ms)', type: 'number', min: 1, step: 1 },
+      ...SEARCH_REGION_FIELDS,
+    ],
+  },
+  waitAny: {
+    label: '多目标等待',
+    description: '同时等待多个图像/文字目标，哪个先出现就从对应分支继续。',
+    defaultParams: {
+      targets: [
+        { name: 'success', type: 'image', templatePath: '', threshold: 0.95 },
+        { name: 'error', type: 'ocr', text: '错误', matchMode: 'contains' },
+      ],
+      timeoutMs: 10000,
+      pollMs: 50,
+      confirmFrames: 1,
+      clickOnMatch: false,
+      clickTimes: 1,
+      ...OCR_MODEL_DEFAULT_PARAMS,
+    },
+    fields: [
+      {
+        key: 'targets',
+        label: '目标列表(JSON)',
+        type: 'json',
+        description:
+          '每项需唯一 name，并生成同名输出分支。图像目标：type=image、templatePath、threshold；文字目标：type=ocr、text、matchMode(contains/exact/fuzzy)、caseSensitive、useRegex、minConfidence，模糊匹配另可设 minSimilarity、similarityAlgorithm、normalizeWhitespace、normalizeWidth、normalizeConfusables。同一帧多个命中时取列表中靠前者。',
+      },
+      { key: 'timeoutMs', label: '超时(ms)', type: 'number', min: 0, step: 100 },
+      { key: 'pollMs', label: '轮询间隔(ms)', type: 'number', min: 1, step: 1 },
+      {
+        key: 'confirmFrames',
+        label: '确认帧数',
+        type: 'number',
+        min: 1,
+        step: 1,
+        description: '同一目标连续命中达到该帧数才判定出现。',
+      },
+      { key: 'clickOnMatch', label: '命中后自动点击', type: 'boolean' },
+      { key: 'clickTimes', label: '点击次数', type: 'number', min: 1, step: 1 },
+      ...OCR_MODEL_FIELDS,
+    ],
+  },
+  visualAssert: {
+    label: '视觉断言',
+    description: '截取屏幕区域或窗口并与基准图逐像素比较，用于桌面应用界面冒烟测试；未通过时输出差异热力图。',
+    defaultParams: {
+      baselinePath: '',
+      baselineMode: 'auto',
+      tolerance: 16,
+      maxDiffPercent: 0.1,
+      ignoreRegions: [],
+      failAction: 'branch',
+      timeoutMs: 0,
+      pollMs: 200,
+      ...MONITOR_DEFAULT_PARAMS,
+      ...SEARCH_REGION_DEFAULT_PARAMS,
+    },
+    fields: [
+      { key: 'baselinePath', label: '基准图路径', type: 'string', placeholder: 'D:\\qa\\baselines\\login.png' },
+      {
+        key: 'baselineMode',
+        label: '基准模式',
+        type: 'select',
+        options: [
+          { label: '自动（缺失时录制）', value: 'auto' },
+          { label: '仅比较', value: 'compare' },
+          { label: '录制基准', value: 'record' },
+        ],
+        description: '录制基准会用当前截图覆盖基准图并走 true 分支；仅比较在基准图缺失时报错。',
+      },
+      {
+        key: 'tolerance',
+        label: '像素容差(0~255)',
+        type: 'number',
+        min: 0,
+        max: 255,
+        step: 1,
+        description: 'RGB 任一通道差值超过该值才计为差异像素，用于忽略抗锯齿与轻微渲染差异。',
+      },
+      {
+        key: 'maxDiffPercent',
+        label: '允许差异(%)',
+        type: 'number',
+        min: 0,
+        max: 100,
+        step: 0.01,
+        description: '差异像素占参与比较像素的百分比不超过该值即通过。',
+      },
+      {
+        key: 'ignoreRegions',
+        label: '忽略区域(JSON)',
+        type: 'json',
+        description: '数组，每项为 {"x","y","width","height"}，坐标相对截取区域左上角；基准图中完全透明的像素同样忽略。',
+      },
+      {
+        key: 'failAction',
+        label: '未通过时',
+        type: 'select',
+        options: [
+          { label: '走 false 分支', value: 'branch' },
+          { label: '报错（可被异常捕获）', value: 'error' },
+        ],
+      },
+      {
+        key: 'timeoutMs',
+        label: '重试超时(ms)',
+        type: 'number',
+        min: 0,
+        step: 100,
+        description: '大于 0 时在超时前反复截图比较，适合等待界面动画结束；为 0 只比较一次。',
+      },
+      { key: 'pollMs', label: '重试间隔(ms)', type: 'number', min: 1, step: 1 },
+      ...MONITOR_FIELDS,
+      ...SEARCH_REGION_FIELDS,
+    ],
+  },
   varDefine: {
     label: '变量定义',
     description: '定义一个变量并给初值。',
@@ -1886,6 +3010,7 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
           { label: '数字', value: 'number' },
           { label: '布尔', value: 'boolean' },
           { label: 'JSON', value: 'json' },
+          { label: '密钥(日志隐藏)', value: 'secret' },
         ],
       },
       { key: 'valueString', label: '初始值(字符串)', type: 'string', placeholder: 'hello' },
@@ -1925,6 +3050,7 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
           { label: '数字', value: 'number' },
           { label: '布尔', value: 'boolean' },
           { label: 'JSON', value: 'json' },
+          { label: '密钥(日志隐藏)', value: 'secret' },
         ],
       },
       { key: 'valueString', label: '新值(字符串)', type: 'string', placeholder: 'world' },
@@ -2077,6 +3203,7 @@ finished(content='xxx') # Use escape characters \\', \\\" and \\n in content par
           { label: '数字', value: 'number' },
           { label: '布尔', value: 'boolean' },
           { label: 'JSON', value: 'json' },
+          { label: '密钥(日志隐藏)', value: 'secret' },
         ],
       },
       { key: 'valueString', label: '常量值(字符串)', type: 'string', placeholder: 'hello' },
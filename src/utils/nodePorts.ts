@@ -10,7 +10,7 @@ import {
   getTriggerMode,
 } from './nodeMeta'
 
-export type HandleValueType = 'control' | 'string' | 'number' | 'json' | 'any'
+export type HandleValueType = 'control' | 'string' | 'number' | 'json' | 'image' | 'any'
 
 export interface NodePort {
   id: string
@@ -35,6 +35,18 @@ const PARAM_OUTPUT_SUFFIX = ':out'
 
 const isConnectableFieldType = (type: string) => type !== 'boolean'
 
+// 这些参数既接受路径 / Data URL 字符串，也接受结构化图像值
+const IMAGE_INPUT_FIELD_KEYS: Partial<Record<NodeKind, string[]>> = {
+  imageMatch: ['sourcePath', 'templatePath'],
+  ocrMatch: ['sourcePath'],
+  ocrRead: ['sourcePath'],
+  clipboardWrite: ['imageData', 'imagePath'],
+  imageOperation: ['source'],
+}
+
+const isImageInputField = (kind: NodeKind, fieldKey: string) =>
+  IMAGE_INPUT_FIELD_KEYS[kind]?.includes(fieldKey) ?? false
+
 const toHandleValueType = (fieldType: string): HandleValueType => {
   if (fieldType === 'number') return 'number'
   if (fieldType === 'json') return 'json'
@@ -132,22 +144,99 @@ const getKeyboardOperationDynamicOutputs = (params: Record<string, unknown> = {}
   return [{ id: 'key', label: 'key', maxConnections: MANY, valueType: 'string' }]
 }
 
+const getFileSafetyOutputs = (params: Record<string, unknown> = {}): NodePort[] => {
+  const safetyMode = String(params.safetyMode ?? 'journal')
+
+  if (safetyMode === 'preview' || safetyMode === 'plan') {
+    return [{ id: 'plan', label: 'plan', maxConnections: MANY, valueType: 'json' }]
+  }
+
+  if (safetyMode === 'direct') {
+    return []
+  }
+
+  return [
+    { id: 'runId', label: 'runId', maxConnections: MANY, valueType: 'string' },
+    { id: 'backupPath', label: 'backupPath', maxConnections: MANY, valueType: 'string' },
+  ]
+}
+
 const getFileOperationDynamicOutputs = (params: Record<string, unknown> = {}): NodePort[] => {
   const operation = getFileOperationKind(params)
 
   if (operation === 'delete') {
-    return [{ id: 'path', label: 'path', maxConnections: MANY, valueType: 'string' }]
+    return [
+      { id: 'path', label: 'path', maxConnections: MANY, valueType: 'string' },
+      ...getFileSafetyOutputs(params),
+    ]
   }
 
   if (operation === 'readText') {
-    return [{ id: 'text', label: 'text', maxConnections: MANY, valueType: 'string' }]
+    return [
+      { id: 'text', label: 'text', maxConnections: MANY, valueType: 'string' },
+      { id: 'encoding', label: 'encoding', maxConnections: MANY, valueType: 'string' },
+    ]
   }
 
   if (operation === 'writeText') {
-    return [{ id: 'path', label: 'path', maxConnections: MANY, valueType: 'string' }]
+    return [
+      { id: 'path', label: 'path', maxConnections: MANY, valueType: 'string' },
+      ...getFileSafetyOutputs(params),
+    ]
   }
 
-  return [{ id: 'targetPath', label: 'targetPath', maxConnections: MANY, valueType: 'string' }]
+  if (operation === 'listFiles') {
+    return [
+      { id: 'files', label: 'files', maxConnections: MANY, valueType: 'json' },
+      { id: 'paths', label: 'paths', maxConnections: MANY, valueType: 'json' },
+      { id: 'count', label: 'count', maxConnections: MANY, valueType: 'number' },
+      { id: 'first', label: 'first', maxConnections: MANY, valueType: 'string' },
+    ]
+  }
+
+  if (operation === 'fileInfo') {
+    return [
+      { id: 'info', label: 'info', maxConnections: MANY, valueType: 'json' },
+      { id: 'exists', label: 'exists', maxConnections: MANY, valueType: 'any' },
+      { id: 'path', label: 'path', maxConnections: MANY, valueType: 'string' },
+      { id: 'fileType', label: 'fileType', maxConnections: MANY, valueType: 'string' },
+      { id: 'size', label: 'size', maxConnections: MANY, valueType: 'number' },
+      { id: 'modifiedMs', label: 'modifiedMs', maxConnections: MANY, valueType: 'number' },
+      { id: 'createdMs', label: 'createdMs', maxConnections: MANY, valueType: 'number' },
+      { id: 'accessedMs', label: 'accessedMs', maxConnections: MANY, valueType: 'number' },
+    ]
+  }
+
+  if (operation === 'archiveCreate') {
+    return [
+      { id: 'targetPath', label: 'targetPath', maxConnections: MANY, valueType: 'string' },
+      { id: 'entries', label: 'entries', maxConnections: MANY, valueType: 'json' },
+      { id: 'count', label: 'count', maxConnections: MANY, valueType: 'number' },
+    ]
+  }
+
+  if (operation === 'archiveExtract') {
+    return [
+      { id: 'targetPath', label: 'targetPath', maxConnections: MANY, valueType: 'string' },
+      { id: 'paths', label: 'paths', maxConnections: MANY, valueType: 'json' },
+      { id: 'count', label: 'count', maxConnections: MANY, valueType: 'number' },
+    ]
+  }
+
+  if (operation === 'waitForFile') {
+    return [
+      { id: 'found', label: 'found', maxConnections: MANY, valueType: 'any' },
+      { id: 'path', label: 'path', maxConnections: MANY, valueType: 'string' },
+      { id: 'files', label: 'files', maxConnections: MANY, valueType: 'json' },
+      { id: 'size', label: 'size', maxConnections: MANY, valueType: 'number' },
+      { id: 'elapsedMs', label: 'elapsedMs', maxConnections: MANY, valueType: 'number' },
+    ]
+  }
+
+  return [
+    { id: 'targetPath', label: 'targetPath', maxConnections: MANY, valueType: 'string' },
+    ...getFileSafetyOutputs(params),
+  ]
 }
 
 const getLaunchApplicationDynamicOutputs = (params: Record<string, unknown> = {}): NodePort[] => {
@@ -173,6 +262,30 @@ const getSystemOperationDynamicOutputs = (params: Record<string, unknown> = {}):
   ]
 }
 
+const WAIT_ANY_HANDLE_PREFIX = 'target:'
+
+const getWaitAnyDynamicOutputs = (params: Record<string, unknown> = {}): NodePort[] => {
+  let targets: unknown = params.targets
+  if (typeof targets === 'string') {
+    try {
+      targets = JSON.parse(targets)
+    } catch {
+      return []
+    }
+  }
+  if (!Array.isArray(targets)) return []
+
+  const names = targets
+    .map((target) => (target && typeof target === 'object' ? String((target as { name?: unknown }).name ?? '').trim() : ''))
+    .filter((name, index, all) => name && all.indexOf(name) === index)
+  return names.map((name) => ({
+    id: `${WAIT_ANY_HANDLE_PREFIX}${name}`,
+    label: name,
+    maxConnections: ONE,
+    valueType: 'control' as const,
+  }))
+}
+
 const getTriggerDynamicOutputs = (params: Record<string, unknown> = {}): NodePort[] => {
   const triggerMode = getTriggerMode(params)
 
@@ -199,6 +312,9 @@ export const isHandleValueTypeCompatible = (
   if (sourceType === 'any' || targetType === 'any') {
     return true
   }
+  if (targetType === 'image') {
+    return sourceType === 'image' || sourceType === 'string'
+  }
   return sourceType === targetType
 }
 
@@ -272,6 +388,9 @@ const specs: Record<NodeKind, NodePortSpec> = {
       ...singleOut(),
       { id: 'path', label: 'path', maxConnections: MANY, valueType: 'string' },
       { id: 'screenshot', label: '截图', maxConnections: MANY, valueType: 'string' },
+      { id: 'image', label: 'image', maxConnections: MANY, valueType: 'image' },
+      { id: 'offsetX', label: 'offsetX', maxConnections: MANY, valueType: 'number' },
+      { id: 'offsetY', label: 'offsetY', maxConnections: MANY, valueType: 'number' },
     ],
   },
   guiAgent: {
@@ -338,6 +457,17 @@ const specs: Record<NodeKind, NodePortSpec> = {
     inputs: singleIn(),
     outputs: singleOut(),
   },
+  imageOperation: {
+    inputs: singleIn(),
+    outputs: [
+      ...singleOut(),
+      { id: 'image', label: 'image', maxConnections: MANY, valueType: 'image' },
+      { id: 'width', label: 'width', maxConnections: MANY, valueType: 'number' },
+      { id: 'height', label: 'height', maxConnections: MANY, valueType: 'number' },
+      { id: 'path', label: 'path', maxConnections: MANY, valueType: 'string' },
+      { id: 'encoded', label: 'encoded', maxConnections: MANY, valueType: 'string' },
+    ],
+  },
   showMessage: {
     inputs: singleIn(),
     outputs: [...singleOut(), { id: 'message', label: 'message', maxConnections: MANY, valueType: 'string' }],
@@ -391,6 +521,11 @@ const specs: Record<NodeKind, NodePortSpec> = {
       { id: 'matchX', label: 'matchX', maxConnections: MANY, valueType: 'number' },
       { id: 'matchY', label: 'matchY', maxConnections: MANY, valueType: 'number' },
       { id: 'similarity', label: 'similarity', maxConnections: MANY, valueType: 'number' },
+      { id: 'matchScale', label: 'matchScale', maxConnections: MANY, valueType: 'number' },
+      { id: 'count', label: 'count', maxConnections: MANY, valueType: 'number' },
+      { id: 'matches', label: 'matches', maxConnections: MANY, valueType: 'json' },
+      { id: 'inliers', label: 'inliers', maxConnections: MANY, valueType: 'number' },
+      { id: 'matchQuad', label: 'matchQuad', maxConnections: MANY, valueType: 'json' },
     ],
   },
   ocrMatch: {
@@ -402,6 +537,63 @@ const specs: Record<NodeKind, NodePortSpec> = {
       { id: 'matchY', label: 'matchY', maxConnections: MANY, valueType: 'number' },
       { id: 'matchedText', label: 'matchedText', maxConnections: MANY, valueType: 'string' },
       { id: 'confidence', label: 'confidence', maxConnections: MANY, valueType: 'number' },
+      { id: 'similarity', label: 'similarity', maxConnections: MANY, valueType: 'number' },
+    ],
+  },
+  ocrRead: {
+    inputs: singleIn(),
+    outputs: [
+      ...singleOut(),
+      { id: 'text', label: 'text', maxConnections: MANY, valueType: 'string' },
+      { id: 'blocks', label: 'blocks', maxConnections: MANY, valueType: 'json' },
+      { id: 'count', label: 'count', maxConnections: MANY, valueType: 'number' },
+    ],
+  },
+  pixelColor: {
+    inputs: singleIn(),
+    outputs: [
+      { id: 'true', label: 'true', maxConnections: ONE, valueType: 'control' },
+      { id: 'false', label: 'false', maxConnections: ONE, valueType: 'control' },
+      { id: 'hex', label: 'hex', maxConnections: MANY, valueType: 'string' },
+      { id: 'r', label: 'r', maxConnections: MANY, valueType: 'number' },
+      { id: 'g', label: 'g', maxConnections: MANY, valueType: 'number' },
+      { id: 'b', label: 'b', maxConnections: MANY, valueType: 'number' },
+    ],
+  },
+  screenChange: {
+    inputs: singleIn(),
+    outputs: [
+      { id: 'true', label: 'true', maxConnections: ONE, valueType: 'control' },
+      { id: 'false', label: 'false', maxConnections: ONE, valueType: 'control' },
+      { id: 'difference', label: 'difference', maxConnections: MANY, valueType: 'number' },
+      { id: 'changeX', label: 'changeX', maxConnections: MANY, valueType: 'number' },
+      { id: 'changeY', label: 'changeY', maxConnections: MANY, valueType: 'number' },
+      { id: 'changeWidth', label: 'changeWidth', maxConnections: MANY, valueType: 'number' },
+      { id: 'changeHeight', label: 'changeHeight', maxConnections: MANY, valueType: 'number' },
+    ],
+  },
+  waitAny: {
+    inputs: singleIn(),
+    outputs: [
+      { id: 'false', label: 'timeout', maxConnections: ONE, valueType: 'control' },
+      { id: 'matchedName', label: 'matchedName', maxConnections: MANY, valueType: 'string' },
+      { id: 'matchX', label: 'matchX', maxConnections: MANY, valueType: 'number' },
+      { id: 'matchY', label: 'matchY', maxConnections: MANY, valueType: 'number' },
+    ],
+  },
+  visualAssert: {
+    inputs: singleIn(),
+    outputs: [
+      { id: 'true', label: 'true', maxConnections: ONE, valueType: 'control' },
+      { id: 'false', label: 'false', maxConnections: ONE, valueType: 'control' },
+      { id: 'diffPercent', label: 'diffPercent', maxConnections: MANY, valueType: 'number' },
+      { id: 'mismatchedPixels', label: 'mismatchedPixels', maxConnections: MANY, valueType: 'number' },
+      { id: 'diffX', label: 'diffX', maxConnections: MANY, valueType: 'number' },
+      { id: 'diffY', label: 'diffY', maxConnections: MANY, valueType: 'number' },
+      { id: 'diffWidth', label: 'diffWidth', maxConnections: MANY, valueType: 'number' },
+      { id: 'diffHeight', label: 'diffHeight', maxConnections: MANY, valueType: 'number' },
+      { id: 'diffPath', label: 'diffPath', maxConnections: MANY, valueType: 'string' },
+      { id: 'recorded', label: 'recorded', maxConnections: MANY, valueType: 'any' },
     ],
   },
   varDefine: {
@@ -481,6 +673,8 @@ export const getNodePortSpec = (kind: NodeKind, params: Record<string, unknown>
               ? getLaunchApplicationDynamicOutputs(params)
               : kind === 'systemOperation'
                 ? getSystemOperationDynamicOutputs(params)
+                : kind === 'waitAny'
+                  ? getWaitAnyDynamicOutputs(params)
           : []
 
   const merged: NodePortSpec = {
@@ -490,7 +684,7 @@ export const getNodePortSpec = (kind: NodeKind, params: Record<string, unknown>
         id: createParamInputHandleId(field.key),
         label: field.label,
         maxConnections: MANY,
-        valueType: toHandleValueType(field.type),
+        valueType: isImageInputField(kind, field.key) ? 'image' : toHandleValueType(field.type),
       })),
     ],
     outputs: [...base.outputs, ...dynamicOutputs],
@@ -1,4 +1,4 @@
-import type { WorkflowFile } from '../types/workflow'
+import type { WorkflowCapability, WorkflowFile } from '../types/workflow'
 import { useSettingsStore } from '../stores/settingsStore'
 
 interface BackendWorkflowNode {
@@ -23,6 +23,7 @@ export interface BackendWorkflowGraph {
   name: string
   nodes: BackendWorkflowNode[]
   edges: BackendWorkflowEdge[]
+  capabilities?: WorkflowCapability[] | null
 }
 
 const resolveGuiAgentPresetParams = (params: Record<string, unknown>): Record<string, unknown> => {
@@ -43,6 +44,7 @@ const resolveGuiAgentPresetParams = (params: Record<string, unknown>): Record<st
 export const toBackendGraph = (file: WorkflowFile): BackendWorkflowGraph => ({
   id: file.graph.id,
   name: file.graph.name,
+  capabilities: file.graph.capabilities ?? null,
   nodes: file.graph.nodes.map((node) => ({
     id: node.id,
     label: node.data.label,